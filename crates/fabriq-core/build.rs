@@ -1,14 +1,62 @@
+// Model messages derive serde so they can be rendered as JSON outside of gRPC (eg. CloudEvents).
+const SERDE_MESSAGES: &[&str] = &[
+    ".fabriq.admin.Bundle",
+    ".fabriq.assignment.AssignmentMessage",
+    ".fabriq.audit.AuditEntryMessage",
+    ".fabriq.audit.DeletedModelMessage",
+    ".fabriq.common.ModelMetadata",
+    ".fabriq.config.ConfigMessage",
+    ".fabriq.deployment.DeploymentMessage",
+    ".fabriq.host.HostMessage",
+    ".fabriq.rbac.RoleBindingMessage",
+    ".fabriq.snapshot.Snapshot",
+    ".fabriq.target.TargetMessage",
+    ".fabriq.template.TemplateMessage",
+    ".fabriq.workload.WorkloadMessage",
+];
+
+// prost's Timestamp doesn't derive serde, so these are rendered as RFC 3339 strings instead.
+const SERDE_TIMESTAMP_FIELDS: &[&str] = &[
+    ".fabriq.audit.AuditEntryMessage.timestamp",
+    ".fabriq.audit.DeletedModelMessage.deleted_at",
+    ".fabriq.common.ModelMetadata.created_at",
+    ".fabriq.common.ModelMetadata.updated_at",
+];
+
+fn compile_protos(proto: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = tonic_build::configure();
+
+    for message in SERDE_MESSAGES {
+        builder = builder.type_attribute(
+            message,
+            "#[derive(serde::Serialize, serde::Deserialize)] #[serde(default)]",
+        );
+    }
+
+    for field in SERDE_TIMESTAMP_FIELDS {
+        builder = builder.field_attribute(field, "#[serde(with = \"crate::serde_timestamp\")]");
+    }
+
+    builder.compile(&[proto], &["proto"])?;
+
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::compile_protos("proto/common.proto")?;
-
-    tonic_build::compile_protos("proto/assignment.proto")?;
-    tonic_build::compile_protos("proto/config.proto")?;
-    tonic_build::compile_protos("proto/deployment.proto")?;
-    tonic_build::compile_protos("proto/event.proto")?;
-    tonic_build::compile_protos("proto/host.proto")?;
-    tonic_build::compile_protos("proto/target.proto")?;
-    tonic_build::compile_protos("proto/template.proto")?;
-    tonic_build::compile_protos("proto/workload.proto")?;
+    compile_protos("proto/common.proto")?;
+
+    compile_protos("proto/admin.proto")?;
+    compile_protos("proto/assignment.proto")?;
+    compile_protos("proto/audit.proto")?;
+    compile_protos("proto/config.proto")?;
+    compile_protos("proto/deployment.proto")?;
+    compile_protos("proto/event.proto")?;
+    compile_protos("proto/host.proto")?;
+    compile_protos("proto/rbac.proto")?;
+    compile_protos("proto/snapshot.proto")?;
+    compile_protos("proto/target.proto")?;
+    compile_protos("proto/template.proto")?;
+    compile_protos("proto/workload.proto")?;
 
     Ok(())
 }
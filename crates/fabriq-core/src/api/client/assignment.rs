@@ -0,0 +1,59 @@
+use tokio::sync::Mutex;
+use tonic::{codegen::InterceptedService, transport::Channel, Request, Response, Status};
+
+use crate::{
+    assignment::assignment_client::AssignmentClient,
+    common::{AssignmentIdRequest, DeploymentIdRequest},
+    AssignmentMessage, AssignmentTrait, ListAssignmentsRequest, ListAssignmentsResponse,
+    OperationId,
+};
+
+use super::interceptor::{ClientInterceptor, ClientToken};
+
+pub struct WrappedAssignmentClient {
+    inner: Mutex<AssignmentClient<InterceptedService<Channel, ClientInterceptor>>>,
+}
+
+impl WrappedAssignmentClient {
+    pub fn new(channel: Channel, token: impl Into<ClientToken>) -> Self {
+        let inner = AssignmentClient::with_interceptor(channel, ClientInterceptor::new(token));
+        let inner = Mutex::new(inner);
+
+        WrappedAssignmentClient { inner }
+    }
+}
+
+#[tonic::async_trait]
+impl AssignmentTrait for WrappedAssignmentClient {
+    async fn upsert(
+        &self,
+        request: Request<AssignmentMessage>,
+    ) -> Result<Response<OperationId>, Status> {
+        let mut inner = self.inner.lock().await;
+        inner.upsert(request).await
+    }
+
+    async fn delete(
+        &self,
+        request: Request<AssignmentIdRequest>,
+    ) -> Result<Response<OperationId>, Status> {
+        let mut inner = self.inner.lock().await;
+        inner.delete(request).await
+    }
+
+    async fn get_by_deployment_id(
+        &self,
+        request: Request<DeploymentIdRequest>,
+    ) -> Result<Response<ListAssignmentsResponse>, Status> {
+        let mut inner = self.inner.lock().await;
+        inner.get_by_deployment_id(request).await
+    }
+
+    async fn list(
+        &self,
+        request: Request<ListAssignmentsRequest>,
+    ) -> Result<Response<ListAssignmentsResponse>, Status> {
+        let mut inner = self.inner.lock().await;
+        inner.list(request).await
+    }
+}
@@ -1,26 +1,21 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tonic::{
-    codegen::InterceptedService,
-    metadata::{Ascii, MetadataValue},
-    transport::Channel,
-    Request, Response, Status,
-};
+use tonic::{codegen::InterceptedService, transport::Channel, Request, Response, Status};
 
 use crate::{
     config::config_client::ConfigClient, ConfigIdRequest, ConfigMessage, ConfigTrait, OperationId,
     QueryConfigRequest, QueryConfigResponse,
 };
 
-use super::interceptor::ClientInterceptor;
+use super::interceptor::{ClientInterceptor, ClientToken};
 
 pub struct WrappedConfigClient {
     inner: Arc<Mutex<ConfigClient<InterceptedService<Channel, ClientInterceptor>>>>,
 }
 
 impl WrappedConfigClient {
-    pub fn new(channel: Channel, token: MetadataValue<Ascii>) -> Self {
-        let inner = ConfigClient::with_interceptor(channel, ClientInterceptor { token });
+    pub fn new(channel: Channel, token: impl Into<ClientToken>) -> Self {
+        let inner = ConfigClient::with_interceptor(channel, ClientInterceptor::new(token));
         let inner = Arc::new(Mutex::new(inner));
 
         WrappedConfigClient { inner }
@@ -1,27 +1,22 @@
 use tokio::sync::Mutex;
-use tonic::{
-    codegen::InterceptedService,
-    metadata::{Ascii, MetadataValue},
-    transport::Channel,
-    Request, Response, Status,
-};
+use tonic::{codegen::InterceptedService, transport::Channel, Request, Response, Status};
 
 use crate::{
     common::{DeploymentIdRequest, TemplateIdRequest},
     deployment::deployment_client::DeploymentClient,
-    DeploymentMessage, DeploymentTrait, ListDeploymentsRequest, ListDeploymentsResponse,
-    OperationId, WorkloadIdRequest,
+    DeleteDeploymentRequest, DeploymentMessage, DeploymentTrait, ListDeploymentsRequest,
+    ListDeploymentsResponse, OperationId, WorkloadIdRequest,
 };
 
-use super::interceptor::ClientInterceptor;
+use super::interceptor::{ClientInterceptor, ClientToken};
 
 pub struct WrappedDeploymentClient {
     inner: Mutex<DeploymentClient<InterceptedService<Channel, ClientInterceptor>>>,
 }
 
 impl WrappedDeploymentClient {
-    pub fn new(channel: Channel, token: MetadataValue<Ascii>) -> Self {
-        let inner = DeploymentClient::with_interceptor(channel, ClientInterceptor { token });
+    pub fn new(channel: Channel, token: impl Into<ClientToken>) -> Self {
+        let inner = DeploymentClient::with_interceptor(channel, ClientInterceptor::new(token));
         let inner = Mutex::new(inner);
 
         WrappedDeploymentClient { inner }
@@ -40,7 +35,7 @@ impl DeploymentTrait for WrappedDeploymentClient {
 
     async fn delete(
         &self,
-        request: Request<DeploymentIdRequest>,
+        request: Request<DeleteDeploymentRequest>,
     ) -> Result<Response<OperationId>, Status> {
         let mut inner = self.inner.lock().await;
         inner.delete(request).await
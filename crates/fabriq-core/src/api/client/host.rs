@@ -0,0 +1,54 @@
+use tokio::sync::Mutex;
+use tonic::{codegen::InterceptedService, transport::Channel, Request, Response, Status};
+
+use crate::{
+    common::HostIdRequest, host::host_client::HostClient, DeleteHostRequest, HostMessage,
+    HostTrait, ListHostsRequest, ListHostsResponse, OperationId,
+};
+
+use super::interceptor::{ClientInterceptor, ClientToken};
+
+pub struct WrappedHostClient {
+    inner: Mutex<HostClient<InterceptedService<Channel, ClientInterceptor>>>,
+}
+
+impl WrappedHostClient {
+    pub fn new(channel: Channel, token: impl Into<ClientToken>) -> Self {
+        let inner = HostClient::with_interceptor(channel, ClientInterceptor::new(token));
+        let inner = Mutex::new(inner);
+
+        WrappedHostClient { inner }
+    }
+}
+
+#[tonic::async_trait]
+impl HostTrait for WrappedHostClient {
+    async fn upsert(&self, request: Request<HostMessage>) -> Result<Response<OperationId>, Status> {
+        let mut inner = self.inner.lock().await;
+        inner.upsert(request).await
+    }
+
+    async fn delete(
+        &self,
+        request: Request<DeleteHostRequest>,
+    ) -> Result<Response<OperationId>, Status> {
+        let mut inner = self.inner.lock().await;
+        inner.delete(request).await
+    }
+
+    async fn get_by_id(
+        &self,
+        request: Request<HostIdRequest>,
+    ) -> Result<Response<HostMessage>, Status> {
+        let mut inner = self.inner.lock().await;
+        inner.get_by_id(request).await
+    }
+
+    async fn list(
+        &self,
+        request: Request<ListHostsRequest>,
+    ) -> Result<Response<ListHostsResponse>, Status> {
+        let mut inner = self.inner.lock().await;
+        inner.list(request).await
+    }
+}
@@ -1,18 +1,119 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 use tonic::{
     metadata::{Ascii, MetadataValue},
     service::Interceptor,
     Status,
 };
 
+// how long a token read from a file is used before the file is read again.
+const TOKEN_FILE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+pub enum ClientToken {
+    Static(MetadataValue<Ascii>),
+    // re-read every TOKEN_FILE_REFRESH_INTERVAL so that rotated tokens (eg. projected workload
+    // identity tokens, which are refreshed well before they expire) are used.
+    File(PathBuf),
+}
+
+impl From<MetadataValue<Ascii>> for ClientToken {
+    fn from(token: MetadataValue<Ascii>) -> Self {
+        ClientToken::Static(token)
+    }
+}
+
 pub struct ClientInterceptor {
-    pub token: MetadataValue<Ascii>,
+    pub token: ClientToken,
+
+    // the last token read from a ClientToken::File and when it was read.
+    file_token: Option<(MetadataValue<Ascii>, Instant)>,
+}
+
+impl ClientInterceptor {
+    pub fn new(token: impl Into<ClientToken>) -> Self {
+        ClientInterceptor {
+            token: token.into(),
+            file_token: None,
+        }
+    }
 }
 
 impl Interceptor for ClientInterceptor {
     fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
-        request
-            .metadata_mut()
-            .insert("authorization", self.token.clone());
+        let token = match &self.token {
+            ClientToken::Static(token) => token.clone(),
+            ClientToken::File(path) => match &self.file_token {
+                Some((token, read_at)) if read_at.elapsed() < TOKEN_FILE_REFRESH_INTERVAL => {
+                    token.clone()
+                }
+                _ => {
+                    let token = fs::read_to_string(path).map_err(|err| {
+                        Status::new(
+                            tonic::Code::Unauthenticated,
+                            format!("failed to read token from {}: {err}", path.display()),
+                        )
+                    })?;
+
+                    let token: MetadataValue<Ascii> =
+                        format!("Bearer {}", token.trim()).parse().map_err(|_| {
+                            Status::new(
+                                tonic::Code::Unauthenticated,
+                                format!("token in {} is malformed", path.display()),
+                            )
+                        })?;
+
+                    self.file_token = Some((token.clone(), Instant::now()));
+
+                    token
+                }
+            },
+        };
+
+        request.metadata_mut().insert("authorization", token);
         Ok(request)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_token_is_cached() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("fabriq-token-{}", uuid::Uuid::new_v4()));
+        fs::write(&path, "first-token\n")?;
+
+        let mut interceptor = ClientInterceptor::new(ClientToken::File(path.clone()));
+
+        let request = interceptor.call(tonic::Request::new(()))?;
+        assert_eq!(
+            request.metadata().get("authorization").unwrap(),
+            "Bearer first-token"
+        );
+
+        // a rotated token isn't read again until the refresh interval has passed.
+        fs::write(&path, "second-token\n")?;
+        let request = interceptor.call(tonic::Request::new(()))?;
+        assert_eq!(
+            request.metadata().get("authorization").unwrap(),
+            "Bearer first-token"
+        );
+
+        interceptor.file_token = interceptor
+            .file_token
+            .map(|(token, _)| (token, Instant::now() - TOKEN_FILE_REFRESH_INTERVAL));
+        let request = interceptor.call(tonic::Request::new(()))?;
+        assert_eq!(
+            request.metadata().get("authorization").unwrap(),
+            "Bearer second-token"
+        );
+
+        fs::remove_file(&path)?;
+
+        Ok(())
+    }
+}
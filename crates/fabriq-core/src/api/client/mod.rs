@@ -1,10 +1,15 @@
+mod assignment;
 mod config;
 mod deployment;
+mod host;
 mod interceptor;
 mod template;
 mod workload;
 
+pub use assignment::WrappedAssignmentClient;
 pub use config::WrappedConfigClient;
 pub use deployment::WrappedDeploymentClient;
+pub use host::WrappedHostClient;
+pub use interceptor::ClientToken;
 pub use template::WrappedTemplateClient;
 pub use workload::WrappedWorkloadClient;
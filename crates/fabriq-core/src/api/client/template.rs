@@ -1,26 +1,21 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tonic::{
-    codegen::InterceptedService,
-    metadata::{Ascii, MetadataValue},
-    transport::Channel,
-    Request, Response, Status,
-};
+use tonic::{codegen::InterceptedService, transport::Channel, Request, Response, Status};
 
 use crate::{
-    common::TemplateIdRequest, template::template_client::TemplateClient, ListTemplatesRequest,
-    ListTemplatesResponse, OperationId, TemplateMessage, TemplateTrait,
+    common::TemplateIdRequest, template::template_client::TemplateClient, DeleteTemplateRequest,
+    ListTemplatesRequest, ListTemplatesResponse, OperationId, TemplateMessage, TemplateTrait,
 };
 
-use super::interceptor::ClientInterceptor;
+use super::interceptor::{ClientInterceptor, ClientToken};
 
 pub struct WrappedTemplateClient {
     inner: Arc<Mutex<TemplateClient<InterceptedService<Channel, ClientInterceptor>>>>,
 }
 
 impl WrappedTemplateClient {
-    pub fn new(channel: Channel, token: MetadataValue<Ascii>) -> Self {
-        let inner = TemplateClient::with_interceptor(channel, ClientInterceptor { token });
+    pub fn new(channel: Channel, token: impl Into<ClientToken>) -> Self {
+        let inner = TemplateClient::with_interceptor(channel, ClientInterceptor::new(token));
         let inner = Arc::new(Mutex::new(inner));
 
         WrappedTemplateClient { inner }
@@ -39,7 +34,7 @@ impl TemplateTrait for WrappedTemplateClient {
 
     async fn delete(
         &self,
-        request: Request<TemplateIdRequest>,
+        request: Request<DeleteTemplateRequest>,
     ) -> Result<Response<OperationId>, Status> {
         let mut inner = self.inner.lock().await;
         inner.delete(request).await
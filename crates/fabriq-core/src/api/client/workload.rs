@@ -1,27 +1,23 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tonic::{
-    codegen::InterceptedService,
-    metadata::{Ascii, MetadataValue},
-    transport::Channel,
-    Request, Response, Status,
-};
+use tonic::{codegen::InterceptedService, transport::Channel, Request, Response, Status};
 
 use crate::{
     common::{TemplateIdRequest, WorkloadIdRequest},
     workload::workload_client::WorkloadClient,
-    ListWorkloadsRequest, ListWorkloadsResponse, OperationId, WorkloadMessage, WorkloadTrait,
+    DeleteWorkloadRequest, ListWorkloadsRequest, ListWorkloadsResponse, OperationId,
+    WorkloadMessage, WorkloadTrait,
 };
 
-use super::interceptor::ClientInterceptor;
+use super::interceptor::{ClientInterceptor, ClientToken};
 
 pub struct WrappedWorkloadClient {
     inner: Arc<Mutex<WorkloadClient<InterceptedService<Channel, ClientInterceptor>>>>,
 }
 
 impl WrappedWorkloadClient {
-    pub fn new(channel: Channel, token: MetadataValue<Ascii>) -> Self {
-        let inner = WorkloadClient::with_interceptor(channel, ClientInterceptor { token });
+    pub fn new(channel: Channel, token: impl Into<ClientToken>) -> Self {
+        let inner = WorkloadClient::with_interceptor(channel, ClientInterceptor::new(token));
         let inner = Arc::new(Mutex::new(inner));
 
         WrappedWorkloadClient { inner }
@@ -40,7 +36,7 @@ impl WorkloadTrait for WrappedWorkloadClient {
 
     async fn delete(
         &self,
-        request: Request<WorkloadIdRequest>,
+        request: Request<DeleteWorkloadRequest>,
     ) -> Result<Response<OperationId>, Status> {
         let mut inner = self.inner.lock().await;
         inner.delete(request).await
@@ -0,0 +1,47 @@
+use tonic::{Request, Response, Status};
+
+use crate::{
+    common::{AssignmentIdRequest, DeploymentIdRequest},
+    test::get_assignment_fixture,
+    AssignmentMessage, AssignmentTrait, ListAssignmentsRequest, ListAssignmentsResponse,
+    OperationId,
+};
+
+pub struct MockAssignmentClient {}
+
+#[tonic::async_trait]
+impl AssignmentTrait for MockAssignmentClient {
+    async fn upsert(
+        &self,
+        _request: Request<AssignmentMessage>,
+    ) -> Result<Response<OperationId>, Status> {
+        Ok(Response::new(OperationId::create()))
+    }
+
+    async fn delete(
+        &self,
+        _request: Request<AssignmentIdRequest>,
+    ) -> Result<Response<OperationId>, Status> {
+        Ok(Response::new(OperationId::create()))
+    }
+
+    async fn get_by_deployment_id(
+        &self,
+        _request: Request<DeploymentIdRequest>,
+    ) -> Result<Response<ListAssignmentsResponse>, Status> {
+        Ok(Response::new(ListAssignmentsResponse {
+            assignments: vec![get_assignment_fixture(None)],
+            next_page_token: "".to_string(),
+        }))
+    }
+
+    async fn list(
+        &self,
+        _request: Request<ListAssignmentsRequest>,
+    ) -> Result<Response<ListAssignmentsResponse>, Status> {
+        Ok(Response::new(ListAssignmentsResponse {
+            assignments: vec![get_assignment_fixture(None)],
+            next_page_token: "".to_string(),
+        }))
+    }
+}
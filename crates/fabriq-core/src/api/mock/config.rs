@@ -25,8 +25,29 @@ impl ConfigTrait for MockConfigClient {
 
     async fn query(
         &self,
-        _request: Request<QueryConfigRequest>,
+        request: Request<QueryConfigRequest>,
     ) -> Result<Response<QueryConfigResponse>, Status> {
+        let query = request.into_inner();
+
+        if query.model_name == ConfigMessage::HOST_OWNER {
+            let owning_model =
+                ConfigMessage::make_owning_model(ConfigMessage::HOST_OWNER, &query.model_id)
+                    .unwrap();
+
+            let configs = vec![ConfigMessage {
+                id: ConfigMessage::make_id(&owning_model, "clusterName"),
+                owning_model,
+                key: "clusterName".to_owned(),
+                value: format!("{}-cluster", query.model_id),
+
+                value_type: ConfigValueType::StringType as i32,
+                resource_version: 0,
+                metadata: None,
+            }];
+
+            return Ok(Response::new(QueryConfigResponse { configs }));
+        }
+
         let workspace_id = "workspace-fixture";
         let workload_name = "workload-fixture";
         let workload_id = WorkloadMessage::make_id(workspace_id, workload_name);
@@ -42,6 +63,8 @@ impl ConfigTrait for MockConfigClient {
                 value: "5".to_owned(),
 
                 value_type: ConfigValueType::StringType as i32,
+                resource_version: 0,
+                metadata: None,
             },
             ConfigMessage {
                 id: ConfigMessage::make_id(&deployment_id, "labels"),
@@ -51,6 +74,8 @@ impl ConfigTrait for MockConfigClient {
                 value: "cloud=azure;region=eastus2".to_owned(),
 
                 value_type: ConfigValueType::KeyValueType as i32,
+                resource_version: 0,
+                metadata: None,
             },
             ConfigMessage {
                 id: ConfigMessage::make_id(&workload_id, "port"),
@@ -59,6 +84,8 @@ impl ConfigTrait for MockConfigClient {
                 value: "80".to_owned(),
 
                 value_type: ConfigValueType::StringType as i32,
+                resource_version: 0,
+                metadata: None,
             },
             ConfigMessage {
                 id: ConfigMessage::make_id(&workload_id, "image"),
@@ -69,6 +96,8 @@ impl ConfigTrait for MockConfigClient {
                     .to_owned(),
 
                 value_type: ConfigValueType::StringType as i32,
+                resource_version: 0,
+                metadata: None,
             },
             ConfigMessage {
                 id: ConfigMessage::make_id(&workload_id, "metricsEndpoint"),
@@ -77,6 +106,8 @@ impl ConfigTrait for MockConfigClient {
                 value: "/metrics".to_owned(),
 
                 value_type: ConfigValueType::StringType as i32,
+                resource_version: 0,
+                metadata: None,
             },
             ConfigMessage {
                 id: ConfigMessage::make_id(&workload_id, "healthEndpoint"),
@@ -85,6 +116,8 @@ impl ConfigTrait for MockConfigClient {
                 value: "/healthz".to_owned(),
 
                 value_type: ConfigValueType::StringType as i32,
+                resource_version: 0,
+                metadata: None,
             },
             ConfigMessage {
                 id: ConfigMessage::make_id(&workload_id, "cpu"),
@@ -93,6 +126,8 @@ impl ConfigTrait for MockConfigClient {
                 value: "1000m".to_owned(),
 
                 value_type: ConfigValueType::StringType as i32,
+                resource_version: 0,
+                metadata: None,
             },
             ConfigMessage {
                 id: ConfigMessage::make_id(&workload_id, "memory"),
@@ -101,6 +136,8 @@ impl ConfigTrait for MockConfigClient {
                 value: "128M".to_owned(),
 
                 value_type: ConfigValueType::StringType as i32,
+                resource_version: 0,
+                metadata: None,
             },
         ];
 
@@ -1,20 +1,22 @@
+use std::collections::HashMap;
 use tonic::{Request, Response, Status};
 
 use crate::{
     common::{DeploymentIdRequest, TemplateIdRequest},
-    DeploymentMessage, DeploymentTrait, ListDeploymentsRequest, ListDeploymentsResponse,
-    OperationId, WorkloadIdRequest, WorkloadMessage,
+    test::get_team_fixture,
+    DeleteDeploymentRequest, DeploymentMessage, DeploymentTrait, ListDeploymentsRequest,
+    ListDeploymentsResponse, OperationId, WorkloadIdRequest, WorkloadMessage,
 };
 
 pub struct MockDeploymentClient {}
 
 impl MockDeploymentClient {
     pub fn get_deployment_fixture() -> DeploymentMessage {
-        let workspace_id = "workspace-fixture";
+        let team_id = get_team_fixture();
         let workload_name = "workload-fixture";
         let template_id = "template-fixture";
         let target_id = "target-fixture";
-        let workload_id = WorkloadMessage::make_id(workspace_id, workload_name);
+        let workload_id = WorkloadMessage::make_id(&team_id, workload_name);
         let deployment_name = "deployment-fixture";
         let deployment_id = DeploymentMessage::make_id(&workload_id, deployment_name);
 
@@ -25,6 +27,10 @@ impl MockDeploymentClient {
             target_id: target_id.to_string(),
             template_id: Some(template_id.to_string()),
             host_count: 2,
+            resource_version: 0,
+            metadata: None,
+            labels: vec![],
+            annotations: HashMap::new(),
         }
     }
 }
@@ -40,7 +46,7 @@ impl DeploymentTrait for MockDeploymentClient {
 
     async fn delete(
         &self,
-        _request: Request<DeploymentIdRequest>,
+        _request: Request<DeleteDeploymentRequest>,
     ) -> Result<Response<OperationId>, Status> {
         Ok(Response::new(OperationId::create()))
     }
@@ -58,6 +64,7 @@ impl DeploymentTrait for MockDeploymentClient {
     ) -> Result<Response<ListDeploymentsResponse>, Status> {
         Ok(Response::new(ListDeploymentsResponse {
             deployments: vec![MockDeploymentClient::get_deployment_fixture()],
+            next_page_token: "".to_string(),
         }))
     }
 
@@ -67,6 +74,7 @@ impl DeploymentTrait for MockDeploymentClient {
     ) -> Result<Response<ListDeploymentsResponse>, Status> {
         Ok(Response::new(ListDeploymentsResponse {
             deployments: vec![MockDeploymentClient::get_deployment_fixture()],
+            next_page_token: "".to_string(),
         }))
     }
 
@@ -76,6 +84,7 @@ impl DeploymentTrait for MockDeploymentClient {
     ) -> Result<Response<ListDeploymentsResponse>, Status> {
         Ok(Response::new(ListDeploymentsResponse {
             deployments: vec![MockDeploymentClient::get_deployment_fixture()],
+            next_page_token: "".to_string(),
         }))
     }
 }
@@ -0,0 +1,44 @@
+use tonic::{Request, Response, Status};
+
+use crate::{
+    common::HostIdRequest, test::get_host_fixture, DeleteHostRequest, HostMessage, HostTrait,
+    ListHostsRequest, ListHostsResponse, OperationId,
+};
+
+pub struct MockHostClient {}
+
+#[tonic::async_trait]
+impl HostTrait for MockHostClient {
+    async fn upsert(
+        &self,
+        _request: Request<HostMessage>,
+    ) -> Result<Response<OperationId>, Status> {
+        Ok(Response::new(OperationId::create()))
+    }
+
+    async fn delete(
+        &self,
+        _request: Request<DeleteHostRequest>,
+    ) -> Result<Response<OperationId>, Status> {
+        Ok(Response::new(OperationId::create()))
+    }
+
+    async fn get_by_id(
+        &self,
+        request: Request<HostIdRequest>,
+    ) -> Result<Response<HostMessage>, Status> {
+        Ok(Response::new(get_host_fixture(Some(
+            &request.into_inner().host_id,
+        ))))
+    }
+
+    async fn list(
+        &self,
+        _request: Request<ListHostsRequest>,
+    ) -> Result<Response<ListHostsResponse>, Status> {
+        Ok(Response::new(ListHostsResponse {
+            hosts: vec![get_host_fixture(None)],
+            next_page_token: "".to_string(),
+        }))
+    }
+}
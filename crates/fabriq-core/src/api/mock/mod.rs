@@ -1,9 +1,13 @@
+mod assignment;
 mod config;
 mod deployment;
+mod host;
 mod template;
 mod workload;
 
+pub use assignment::MockAssignmentClient;
 pub use config::MockConfigClient;
 pub use deployment::MockDeploymentClient;
+pub use host::MockHostClient;
 pub use template::MockTemplateClient;
 pub use workload::MockWorkloadClient;
@@ -1,8 +1,9 @@
+use std::collections::HashMap;
 use tonic::{Request, Response, Status};
 
 use crate::{
-    common::TemplateIdRequest, ListTemplatesRequest, ListTemplatesResponse, OperationId,
-    TemplateMessage, TemplateTrait,
+    common::TemplateIdRequest, DeleteTemplateRequest, ListTemplatesRequest, ListTemplatesResponse,
+    OperationId, TemplateMessage, TemplateTrait,
 };
 
 pub struct MockTemplateClient {}
@@ -18,7 +19,7 @@ impl TemplateTrait for MockTemplateClient {
 
     async fn delete(
         &self,
-        _request: Request<TemplateIdRequest>,
+        _request: Request<DeleteTemplateRequest>,
     ) -> Result<Response<OperationId>, Status> {
         Ok(Response::new(OperationId::create()))
     }
@@ -32,6 +33,10 @@ impl TemplateTrait for MockTemplateClient {
             repository: "git@github.com:timfpark/deployment-templates".to_owned(),
             git_ref: "main".to_owned(),
             path: "external-service".to_owned(),
+            resource_version: 0,
+            metadata: None,
+            labels: vec![],
+            annotations: HashMap::new(),
         }))
     }
 
@@ -44,10 +49,15 @@ impl TemplateTrait for MockTemplateClient {
             repository: "git@github.com:timfpark/deployment-templates".to_owned(),
             git_ref: "main".to_owned(),
             path: "external-service".to_owned(),
+            resource_version: 0,
+            metadata: None,
+            labels: vec![],
+            annotations: HashMap::new(),
         };
 
         Ok(Response::new(ListTemplatesResponse {
             templates: vec![template],
+            next_page_token: "".to_string(),
         }))
     }
 }
@@ -3,7 +3,8 @@ use tonic::{Request, Response, Status};
 use crate::{
     common::{TemplateIdRequest, WorkloadIdRequest},
     test::get_workload_fixture,
-    ListWorkloadsRequest, ListWorkloadsResponse, OperationId, WorkloadMessage, WorkloadTrait,
+    DeleteWorkloadRequest, ListWorkloadsRequest, ListWorkloadsResponse, OperationId,
+    WorkloadMessage, WorkloadTrait,
 };
 
 pub struct MockWorkloadClient {}
@@ -19,7 +20,7 @@ impl WorkloadTrait for MockWorkloadClient {
 
     async fn delete(
         &self,
-        _request: Request<WorkloadIdRequest>,
+        _request: Request<DeleteWorkloadRequest>,
     ) -> Result<Response<OperationId>, Status> {
         Ok(Response::new(OperationId::create()))
     }
@@ -37,6 +38,7 @@ impl WorkloadTrait for MockWorkloadClient {
     ) -> Result<Response<ListWorkloadsResponse>, Status> {
         Ok(Response::new(ListWorkloadsResponse {
             workloads: vec![get_workload_fixture(None)],
+            next_page_token: "".to_string(),
         }))
     }
 
@@ -46,6 +48,7 @@ impl WorkloadTrait for MockWorkloadClient {
     ) -> Result<Response<ListWorkloadsResponse>, Status> {
         Ok(Response::new(ListWorkloadsResponse {
             workloads: vec![get_workload_fixture(None)],
+            next_page_token: "".to_string(),
         }))
     }
 }
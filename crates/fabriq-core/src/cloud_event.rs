@@ -0,0 +1,308 @@
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AssignmentMessage, ConfigMessage, DeploymentMessage, Event, EventType, HostMessage, ModelType,
+    RoleBindingMessage, TargetMessage, TemplateMessage, WorkloadMessage,
+};
+
+pub const CLOUD_EVENTS_SPEC_VERSION: &str = "1.0";
+pub const CLOUD_EVENTS_TYPE_PREFIX: &str = "cloud.fabriq";
+
+pub const CLOUD_EVENTS_STRUCTURED_CONTENT_TYPE: &str = "application/cloudevents+json";
+pub const CLOUD_EVENTS_DATA_CONTENT_TYPE: &str = "application/json";
+
+const BINARY_HEADER_PREFIX: &str = "ce-";
+
+// A CloudEvents 1.0 (https://github.com/cloudevents/spec) representation of a fabriq Event.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CloudEvent {
+    pub specversion: String,
+    pub id: String,
+    pub source: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datacontenttype: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operationid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+// The binary content mode (https://github.com/cloudevents/spec/blob/v1.0/http-protocol-binding.md)
+// carries the context attributes as ce- prefixed headers and the data as the HTTP body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BinaryCloudEvent {
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl CloudEvent {
+    pub fn from_event(event: &Event, source: &str) -> anyhow::Result<Self> {
+        let model_type = ModelType::from(event.model_type);
+        let event_type = EventType::from(event.event_type);
+
+        // deleted events only carry the previous model, so fall back to it for the data.
+        let serialized_model = match &event.serialized_current_model {
+            Some(serialized_current_model) => Some(serialized_current_model),
+            None => event.serialized_previous_model.as_ref(),
+        };
+
+        let (subject, data) = match serialized_model {
+            Some(serialized_model) => {
+                let (subject, data) = decode_model(model_type, serialized_model)?;
+                (Some(subject), Some(data))
+            }
+            None => (None, None),
+        };
+
+        let time = match &event.timestamp {
+            Some(timestamp) => {
+                let naive_time =
+                    NaiveDateTime::from_timestamp_opt(timestamp.seconds, timestamp.nanos as u32)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("invalid event timestamp {:?}", timestamp)
+                        })?;
+
+                Some(
+                    DateTime::<Utc>::from_utc(naive_time, Utc)
+                        .to_rfc3339_opts(SecondsFormat::Secs, true),
+                )
+            }
+            None => None,
+        };
+
+        Ok(CloudEvent {
+            specversion: CLOUD_EVENTS_SPEC_VERSION.to_string(),
+            id: event.id.clone(),
+            source: source.to_string(),
+            event_type: make_cloud_event_type(model_type, event_type),
+            subject,
+            time,
+            datacontenttype: data
+                .as_ref()
+                .map(|_| CLOUD_EVENTS_DATA_CONTENT_TYPE.to_string()),
+            operationid: event
+                .operation_id
+                .as_ref()
+                .map(|operation_id| operation_id.id.clone()),
+            data,
+        })
+    }
+
+    pub fn from_structured(json: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(json)?)
+    }
+
+    pub fn to_structured(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn to_binary(&self) -> anyhow::Result<BinaryCloudEvent> {
+        let mut headers = vec![
+            header("specversion", &self.specversion),
+            header("id", &self.id),
+            header("source", &self.source),
+            header("type", &self.event_type),
+        ];
+
+        if let Some(subject) = &self.subject {
+            headers.push(header("subject", subject));
+        }
+
+        if let Some(time) = &self.time {
+            headers.push(header("time", time));
+        }
+
+        if let Some(operation_id) = &self.operationid {
+            headers.push(header("operationid", operation_id));
+        }
+
+        // in binary mode datacontenttype maps to the standard Content-Type header.
+        if let Some(datacontenttype) = &self.datacontenttype {
+            headers.push(("content-type".to_string(), datacontenttype.clone()));
+        }
+
+        let body = match &self.data {
+            Some(data) => serde_json::to_vec(data)?,
+            None => vec![],
+        };
+
+        Ok(BinaryCloudEvent { headers, body })
+    }
+}
+
+fn header(attribute: &str, value: &str) -> (String, String) {
+    (
+        format!("{BINARY_HEADER_PREFIX}{attribute}"),
+        value.to_string(),
+    )
+}
+
+pub fn make_cloud_event_type(model_type: ModelType, event_type: EventType) -> String {
+    let model = match model_type {
+        ModelType::Assignment => "assignment",
+        ModelType::Config => "config",
+        ModelType::Deployment => "deployment",
+        ModelType::Host => "host",
+        ModelType::RoleBinding => "rolebinding",
+        ModelType::Target => "target",
+        ModelType::Template => "template",
+        ModelType::Workload => "workload",
+        ModelType::Workspace => "workspace",
+    };
+
+    let action = match event_type {
+        EventType::Created => "created",
+        EventType::Updated => "updated",
+        EventType::Deleted => "deleted",
+    };
+
+    format!("{CLOUD_EVENTS_TYPE_PREFIX}.{model}.{action}")
+}
+
+fn to_json<ModelMessage: Serialize>(
+    id: String,
+    message: &ModelMessage,
+) -> anyhow::Result<(String, serde_json::Value)> {
+    Ok((id, serde_json::to_value(message)?))
+}
+
+// Decodes a serialized model of model_type into its id and JSON form.
+pub fn decode_model(
+    model_type: ModelType,
+    serialized_model: &[u8],
+) -> anyhow::Result<(String, serde_json::Value)> {
+    match model_type {
+        ModelType::Assignment => {
+            let message = AssignmentMessage::decode(serialized_model)?;
+            to_json(message.id.clone(), &message)
+        }
+        ModelType::Config => {
+            let message = ConfigMessage::decode(serialized_model)?;
+            to_json(message.id.clone(), &message)
+        }
+        ModelType::Deployment => {
+            let message = DeploymentMessage::decode(serialized_model)?;
+            to_json(message.id.clone(), &message)
+        }
+        ModelType::Host => {
+            let message = HostMessage::decode(serialized_model)?;
+            to_json(message.id.clone(), &message)
+        }
+        ModelType::RoleBinding => {
+            let message = RoleBindingMessage::decode(serialized_model)?;
+            to_json(message.id.clone(), &message)
+        }
+        ModelType::Target => {
+            let message = TargetMessage::decode(serialized_model)?;
+            to_json(message.id.clone(), &message)
+        }
+        ModelType::Template => {
+            let message = TemplateMessage::decode(serialized_model)?;
+            to_json(message.id.clone(), &message)
+        }
+        ModelType::Workload => {
+            let message = WorkloadMessage::decode(serialized_model)?;
+            to_json(message.id.clone(), &message)
+        }
+        ModelType::Workspace => Err(anyhow::anyhow!(
+            "workspace models are not supported in cloud events"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_event,
+        test::{get_deployment_fixture, get_host_fixture},
+        OperationId,
+    };
+
+    const SOURCE: &str = "https://api.fabriq.cloud";
+
+    #[test]
+    fn test_structured_cloud_event() -> anyhow::Result<()> {
+        let deployment = get_deployment_fixture(None);
+        let operation_id = OperationId::create();
+
+        let event = create_event(
+            &None,
+            &Some(deployment.clone()),
+            EventType::Created,
+            ModelType::Deployment,
+            &operation_id,
+        );
+
+        let cloud_event = CloudEvent::from_event(&event, SOURCE)?;
+
+        assert_eq!(cloud_event.specversion, "1.0");
+        assert_eq!(cloud_event.id, event.id);
+        assert_eq!(cloud_event.source, SOURCE);
+        assert_eq!(cloud_event.event_type, "cloud.fabriq.deployment.created");
+        assert_eq!(cloud_event.subject, Some(deployment.id.clone()));
+        assert_eq!(cloud_event.operationid, Some(operation_id.id));
+        assert!(cloud_event.time.as_ref().unwrap().ends_with('Z'));
+
+        let json = cloud_event.to_structured()?;
+        let value: serde_json::Value = serde_json::from_slice(&json)?;
+
+        assert_eq!(value["type"], "cloud.fabriq.deployment.created");
+        assert_eq!(value["datacontenttype"], "application/json");
+        assert_eq!(value["data"]["id"], deployment.id);
+        assert_eq!(value["data"]["target_id"], deployment.target_id);
+
+        let round_tripped = CloudEvent::from_structured(&json)?;
+        assert_eq!(round_tripped, CloudEvent::from_event(&event, SOURCE)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_cloud_event_for_deleted_model() -> anyhow::Result<()> {
+        let host = get_host_fixture(None);
+
+        let event = create_event(
+            &Some(host.clone()),
+            &None,
+            EventType::Deleted,
+            ModelType::Host,
+            &OperationId::create(),
+        );
+
+        let binary = CloudEvent::from_event(&event, SOURCE)?.to_binary()?;
+
+        let header_value = |name: &str| {
+            binary
+                .headers
+                .iter()
+                .find(|(header_name, _)| header_name == name)
+                .map(|(_, value)| value.clone())
+        };
+
+        assert_eq!(header_value("ce-specversion"), Some("1.0".to_string()));
+        assert_eq!(
+            header_value("ce-type"),
+            Some("cloud.fabriq.host.deleted".to_string())
+        );
+        assert_eq!(header_value("ce-subject"), Some(host.id.clone()));
+        assert_eq!(header_value("ce-source"), Some(SOURCE.to_string()));
+        assert_eq!(
+            header_value("content-type"),
+            Some("application/json".to_string())
+        );
+
+        let body: HostMessage = serde_json::from_slice(&binary.body)?;
+        assert_eq!(body, host);
+
+        Ok(())
+    }
+}
@@ -4,17 +4,32 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use super::{ClonedGitRepo, GitRepo};
+use super::{ClonedGitRepo, GitRepo, GitSignature};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemoryCommit {
+    pub author: GitSignature,
+    pub committer: GitSignature,
+    pub message: String,
+}
 
 #[derive(Debug)]
 pub struct MemoryClonedGitRepo {
     pub files: Mutex<HashMap<String, Vec<u8>>>, // path -> contents
+    pub committed_files: Mutex<HashMap<String, Vec<u8>>>, // files as of the last commit
+    pub commits: Mutex<Vec<MemoryCommit>>,
+    pub branches: Mutex<HashMap<String, Vec<MemoryCommit>>>,
+    pub pushed_branches: Mutex<Vec<String>>,
 }
 
 impl MemoryClonedGitRepo {
     pub fn new() -> Self {
         MemoryClonedGitRepo {
             files: Mutex::new(HashMap::new()),
+            committed_files: Mutex::new(HashMap::new()),
+            commits: Mutex::new(Vec::new()),
+            branches: Mutex::new(HashMap::new()),
+            pushed_branches: Mutex::new(Vec::new()),
         }
     }
 }
@@ -35,6 +50,38 @@ impl MemoryGitRepo {
             cloned_repo: Arc::new(MemoryClonedGitRepo::new()),
         })
     }
+
+    // Every commit made to any clone, oldest first.
+    pub fn get_commits(&self) -> Vec<MemoryCommit> {
+        self.cloned_repo.commits.lock().unwrap().clone()
+    }
+
+    // The last commit moved onto branch, if any.
+    pub fn get_branch_commit(&self, branch: &str) -> Option<MemoryCommit> {
+        self.get_branch_commits(branch).pop()
+    }
+
+    // Every commit on branch, oldest first.
+    pub fn get_branch_commits(&self, branch: &str) -> Vec<MemoryCommit> {
+        self.cloned_repo
+            .branches
+            .lock()
+            .unwrap()
+            .get(branch)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn get_pushed_branches(&self) -> Vec<String> {
+        self.cloned_repo.pushed_branches.lock().unwrap().clone()
+    }
+
+    pub fn get_commit_messages(&self) -> Vec<String> {
+        self.get_commits()
+            .into_iter()
+            .map(|commit| commit.message)
+            .collect()
+    }
 }
 
 impl GitRepo for MemoryGitRepo {
@@ -50,7 +97,60 @@ impl ClonedGitRepo for MemoryClonedGitRepo {
         Ok(())
     }
 
-    fn commit(&self, _name: &str, _email: &str, _message: &str) -> anyhow::Result<()> {
+    fn commit(
+        &self,
+        author: &GitSignature,
+        committer: &GitSignature,
+        message: &str,
+    ) -> anyhow::Result<()> {
+        *self.committed_files.lock().unwrap() = self.files.lock().unwrap().clone();
+
+        self.commits.lock().unwrap().push(MemoryCommit {
+            author: author.clone(),
+            committer: committer.clone(),
+            message: message.to_string(),
+        });
+
+        Ok(())
+    }
+
+    // Files aren't versioned, so only the commit itself is moved.
+    fn move_last_commit(&self, branch: &str) -> anyhow::Result<()> {
+        let commit = match self.commits.lock().unwrap().pop() {
+            Some(commit) => commit,
+            None => return Err(anyhow::anyhow!("no commit to move to branch {branch}")),
+        };
+
+        self.branches
+            .lock()
+            .unwrap()
+            .insert(branch.to_string(), vec![commit]);
+
+        Ok(())
+    }
+
+    fn append_last_commit(&self, branch: &str) -> anyhow::Result<()> {
+        if !self
+            .pushed_branches
+            .lock()
+            .unwrap()
+            .contains(&branch.to_string())
+        {
+            return self.move_last_commit(branch);
+        }
+
+        let commit = match self.commits.lock().unwrap().pop() {
+            Some(commit) => commit,
+            None => return Err(anyhow::anyhow!("no commit to append to branch {branch}")),
+        };
+
+        self.branches
+            .lock()
+            .unwrap()
+            .entry(branch.to_string())
+            .or_default()
+            .push(commit);
+
         Ok(())
     }
 
@@ -58,6 +158,15 @@ impl ClonedGitRepo for MemoryClonedGitRepo {
         Ok(())
     }
 
+    fn push_branch(&self, branch: &str) -> anyhow::Result<()> {
+        self.pushed_branches
+            .lock()
+            .unwrap()
+            .push(branch.to_string());
+
+        Ok(())
+    }
+
     fn list(&self, repo_path: PathBuf) -> anyhow::Result<Vec<PathBuf>> {
         let files = self.files.lock().unwrap();
 
@@ -72,6 +181,27 @@ impl ClonedGitRepo for MemoryClonedGitRepo {
         Ok(path_files)
     }
 
+    fn has_changes(&self) -> anyhow::Result<bool> {
+        Ok(*self.files.lock().unwrap() != *self.committed_files.lock().unwrap())
+    }
+
+    fn list_files(&self, repo_path: &str) -> anyhow::Result<Vec<String>> {
+        let directory_prefix = format!("{}/", repo_path.trim_end_matches('/'));
+
+        let mut file_paths: Vec<String> = self
+            .files
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|path| path.starts_with(&directory_prefix))
+            .cloned()
+            .collect();
+
+        file_paths.sort();
+
+        Ok(file_paths)
+    }
+
     fn read_file(&self, repo_path: PathBuf) -> anyhow::Result<Vec<u8>> {
         let files = self.files.lock().unwrap();
         let file_contents = files.get(&repo_path.to_string_lossy().to_string());
@@ -1,7 +1,11 @@
 pub mod memory;
 pub mod remote;
 mod repo;
+pub mod template_cache;
 
 pub use memory::MemoryGitRepo;
 pub use remote::RemoteGitRepo;
-pub use repo::{ClonedGitRepo, GitRepo, GitRepoFactory};
+pub use repo::{ClonedGitRepo, GitRepo, GitRepoFactory, GitSignature};
+pub use template_cache::{
+    MemoryTemplateCache, RemoteTemplateCache, TemplateCache, TemplateSnapshot,
+};
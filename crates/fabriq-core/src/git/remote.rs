@@ -1,15 +1,22 @@
 use git2::{
-    Cred, Direction, Index, ObjectType, PushOptions, RemoteCallbacks, Repository, Signature,
+    build::CheckoutBuilder, Cred, Direction, ErrorCode, FetchOptions, Index, ObjectType,
+    PushOptions, RebaseOptions, RemoteCallbacks, Repository, Signature,
 };
 use std::{
+    cell::RefCell,
     fmt::Debug,
     fs,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    thread,
+    time::Duration,
 };
 use tempfile::TempDir;
 
-use super::{ClonedGitRepo, GitRepo, GitRepoFactory};
+use super::{ClonedGitRepo, GitRepo, GitRepoFactory, GitSignature};
+
+const MAX_PUSH_ATTEMPTS: u32 = 5;
+const PUSH_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
 
 pub struct RemoteClonedGitRepo {
     pub branch: String,
@@ -17,25 +24,54 @@ pub struct RemoteClonedGitRepo {
 
     pub index: Mutex<Index>,
     pub repository: Repository,
-    pub local_path: TempDir,
+    pub local_path: PathBuf,
 }
 
+// A remote repo and the long-lived local clone of it that each clone_repo fetches into and resets,
+// instead of cloning from scratch. Clones share that directory, so only one should be in use at a
+// time.
 pub struct RemoteGitRepo {
     pub branch: String,
     pub private_ssh_key: String,
     pub repo_url: String,
+
+    pub local_path: PathBuf,
+    _temp_dir: Option<TempDir>,
 }
 
 impl RemoteGitRepo {
+    // Keeps the local clone in a temporary directory that lasts as long as the RemoteGitRepo.
     pub fn new(repo_url: &str, branch: &str, private_ssh_key: &str) -> anyhow::Result<Self> {
+        let temp_dir = tempfile::tempdir()?;
+
+        Ok(Self {
+            branch: branch.to_string(),
+            private_ssh_key: private_ssh_key.to_string(),
+            repo_url: repo_url.to_string(),
+
+            local_path: temp_dir.path().to_path_buf(),
+            _temp_dir: Some(temp_dir),
+        })
+    }
+
+    // Keeps the local clone at local_path, so that it survives restarts.
+    pub fn with_local_path(
+        repo_url: &str,
+        branch: &str,
+        private_ssh_key: &str,
+        local_path: &Path,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
             branch: branch.to_string(),
             private_ssh_key: private_ssh_key.to_string(),
             repo_url: repo_url.to_string(),
+
+            local_path: local_path.to_path_buf(),
+            _temp_dir: None,
         })
     }
 
-    fn get_auth_callback(private_ssh_key: &str) -> RemoteCallbacks {
+    pub(crate) fn get_auth_callback(private_ssh_key: &str) -> RemoteCallbacks {
         let mut auth_callback = RemoteCallbacks::new();
 
         auth_callback.credentials(|_url, username_from_url, _allowed_types| {
@@ -49,23 +85,27 @@ impl RemoteGitRepo {
 
         auth_callback
     }
-}
 
-impl GitRepo for RemoteGitRepo {
-    fn clone_repo(&self) -> anyhow::Result<Arc<dyn ClonedGitRepo>> {
-        let local_path = tempfile::tempdir()?;
-        let auth_callback = RemoteGitRepo::get_auth_callback(&self.private_ssh_key);
-
-        let mut fetch_options = git2::FetchOptions::new();
-        fetch_options.remote_callbacks(auth_callback);
+    fn open_or_clone(&self) -> anyhow::Result<(Repository, bool)> {
+        if let Ok(repository) = Repository::open(&self.local_path) {
+            return Ok((repository, true));
+        }
 
-        // Prepare builder.
-        let mut repo_builder = git2::build::RepoBuilder::new();
-        repo_builder.fetch_options(fetch_options);
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(RemoteGitRepo::get_auth_callback(&self.private_ssh_key));
 
-        let repository = repo_builder
+        let repository = git2::build::RepoBuilder::new()
+            .fetch_options(fetch_options)
             .branch(&self.branch)
-            .clone(&self.repo_url, local_path.path())?;
+            .clone(&self.repo_url, &self.local_path)?;
+
+        Ok((repository, false))
+    }
+}
+
+impl GitRepo for RemoteGitRepo {
+    fn clone_repo(&self) -> anyhow::Result<Arc<dyn ClonedGitRepo>> {
+        let (repository, existing) = self.open_or_clone()?;
 
         let cloned_git_repo = RemoteClonedGitRepo {
             branch: self.branch.clone(),
@@ -73,12 +113,16 @@ impl GitRepo for RemoteGitRepo {
 
             index: Mutex::new(repository.index()?),
             repository,
-            local_path,
+            local_path: self.local_path.clone(),
         };
 
-        let boxed_cloned_git_repo = Arc::new(cloned_git_repo);
+        // Throws away anything left behind by the last clone, eg. commits whose push failed.
+        if existing {
+            cloned_git_repo.fetch()?;
+            cloned_git_repo.reset_to_origin()?;
+        }
 
-        Ok(boxed_cloned_git_repo)
+        Ok(Arc::new(cloned_git_repo))
     }
 }
 
@@ -108,6 +152,165 @@ impl Debug for RemoteClonedGitRepo {
     }
 }
 
+impl RemoteClonedGitRepo {
+    fn origin_ref(&self) -> String {
+        format!("refs/remotes/origin/{}", self.branch)
+    }
+
+    fn fetch(&self) -> anyhow::Result<()> {
+        let mut remote = self.repository.find_remote("origin")?;
+
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(RemoteGitRepo::get_auth_callback(&self.private_ssh_key));
+
+        let ref_spec = format!("+refs/heads/{}:{}", self.branch, self.origin_ref());
+        remote.fetch(&[ref_spec], Some(&mut fetch_options), None)?;
+
+        Ok(())
+    }
+
+    // Points the branch, index and working tree at the last fetched origin commit, dropping any
+    // local commits and changes.
+    fn reset_to_origin(&self) -> anyhow::Result<()> {
+        let origin_oid = self.repository.refname_to_id(&self.origin_ref())?;
+        self.reset_to(origin_oid)
+    }
+
+    fn reset_to(&self, oid: git2::Oid) -> anyhow::Result<()> {
+        let mut index = self.index.lock().unwrap();
+
+        // Checked out directly rather than through a hard reset, which would leave behind files
+        // that were written but never committed.
+        let commit = self.repository.find_object(oid, Some(ObjectType::Commit))?;
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force().remove_untracked(true);
+        self.repository
+            .checkout_tree(&commit, Some(&mut checkout))?;
+
+        let branch_ref = format!("refs/heads/{}", self.branch);
+        self.repository
+            .reference(&branch_ref, oid, true, "fabriq: reset")?;
+        self.repository.set_head(&branch_ref)?;
+
+        index.read(true)?;
+
+        Ok(())
+    }
+
+    // Replays the local commits on top of the last fetched origin commit. Fails, leaving the
+    // branch as it was, if any of them conflict.
+    fn rebase_onto_origin(&self) -> anyhow::Result<()> {
+        let head_oid = self.repository.refname_to_id("HEAD")?;
+        let origin_oid = self.repository.refname_to_id(&self.origin_ref())?;
+
+        let branch = self.repository.find_annotated_commit(head_oid)?;
+        let upstream = self.repository.find_annotated_commit(origin_oid)?;
+
+        let mut rebase_options = RebaseOptions::new();
+        rebase_options.inmemory(true);
+
+        let mut rebase = self.repository.rebase(
+            Some(&branch),
+            Some(&upstream),
+            None,
+            Some(&mut rebase_options),
+        )?;
+
+        let mut rebased_oid = origin_oid;
+
+        while let Some(operation) = rebase.next() {
+            let commit = self.repository.find_commit(operation?.id())?;
+            let committer = commit.committer().to_owned();
+
+            if rebase.inmemory_index()?.has_conflicts() {
+                rebase.abort()?;
+                return Err(anyhow::anyhow!(
+                    "commit {} conflicts with changes pushed to branch {}",
+                    commit.id(),
+                    self.branch
+                ));
+            }
+
+            match rebase.commit(None, &committer, None) {
+                Ok(oid) => rebased_oid = oid,
+                // the same change was already pushed, so there is nothing left to replay.
+                Err(err) if err.code() == ErrorCode::Applied => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        rebase.finish(None)?;
+
+        self.reset_to(rebased_oid)
+    }
+
+    // Fetches branch from origin, returning the commit it points at, or None if it hasn't been
+    // pushed.
+    fn fetch_branch(&self, branch: &str) -> anyhow::Result<Option<git2::Oid>> {
+        let mut remote = self.repository.find_remote("origin")?;
+
+        let connect_auth_callback = RemoteGitRepo::get_auth_callback(&self.private_ssh_key);
+        remote.connect_auth(Direction::Fetch, Some(connect_auth_callback), None)?;
+
+        let branch_ref = format!("refs/heads/{branch}");
+        let is_pushed = remote.list()?.iter().any(|head| head.name() == branch_ref);
+        remote.disconnect()?;
+
+        if !is_pushed {
+            return Ok(None);
+        }
+
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(RemoteGitRepo::get_auth_callback(&self.private_ssh_key));
+
+        let origin_branch_ref = format!("refs/remotes/origin/{branch}");
+        let ref_spec = format!("+{branch_ref}:{origin_branch_ref}");
+        remote.fetch(&[ref_spec], Some(&mut fetch_options), None)?;
+
+        Ok(Some(self.repository.refname_to_id(&origin_branch_ref)?))
+    }
+
+    // Returns false if the push was rejected because the remote branch has moved on.
+    fn try_push(&self, ref_spec: &str) -> anyhow::Result<bool> {
+        let mut remote = self.repository.find_remote("origin")?;
+
+        let connect_auth_callback = RemoteGitRepo::get_auth_callback(&self.private_ssh_key);
+        remote.connect_auth(Direction::Push, Some(connect_auth_callback), None)?;
+
+        // Servers report rejected updates through this callback rather than failing the push.
+        let rejection: RefCell<Option<String>> = RefCell::new(None);
+
+        let mut push_callback = RemoteGitRepo::get_auth_callback(&self.private_ssh_key);
+        push_callback.push_update_reference(|_ref_name, status| {
+            if let Some(status) = status {
+                rejection.replace(Some(status.to_string()));
+            }
+
+            Ok(())
+        });
+
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(push_callback);
+
+        let pushed = remote.push(&[ref_spec], Some(&mut push_options));
+        drop(push_options);
+
+        match pushed {
+            Ok(()) => {}
+            Err(err) if err.code() == ErrorCode::NotFastForward => return Ok(false),
+            Err(err) => return Err(err.into()),
+        }
+
+        match rejection.into_inner() {
+            Some(status) => {
+                tracing::warn!("push to branch {} rejected: {}", self.branch, status);
+                Ok(false)
+            }
+            None => Ok(true),
+        }
+    }
+}
+
 impl ClonedGitRepo for RemoteClonedGitRepo {
     #[tracing::instrument(skip_all)]
     fn add_path(&self, repo_path: PathBuf) -> anyhow::Result<()> {
@@ -117,11 +320,17 @@ impl ClonedGitRepo for RemoteClonedGitRepo {
     }
 
     #[tracing::instrument(skip_all)]
-    fn commit(&self, name: &str, email: &str, message: &str) -> anyhow::Result<()> {
+    fn commit(
+        &self,
+        author: &GitSignature,
+        committer: &GitSignature,
+        message: &str,
+    ) -> anyhow::Result<()> {
         let mut index = self.index.lock().unwrap();
         let oid = index.write_tree()?;
 
-        let signature = Signature::now(name, email)?;
+        let author = Signature::now(&author.name, &author.email)?;
+        let committer = Signature::now(&committer.name, &committer.email)?;
 
         let obj = self
             .repository
@@ -137,8 +346,8 @@ impl ClonedGitRepo for RemoteClonedGitRepo {
 
         self.repository.commit(
             Some("HEAD"),
-            &signature,
-            &signature,
+            &author,
+            &committer,
             message,
             &tree,
             &[&parent_commit],
@@ -150,21 +359,106 @@ impl ClonedGitRepo for RemoteClonedGitRepo {
     }
 
     #[tracing::instrument(skip_all)]
-    fn push(&self) -> anyhow::Result<()> {
-        let mut remote = self.repository.find_remote("origin")?;
+    fn has_changes(&self) -> anyhow::Result<bool> {
+        let index = self.index.lock().unwrap();
+        let head_tree = self.repository.head()?.peel_to_tree()?;
 
-        let connect_auth_callback = RemoteGitRepo::get_auth_callback(&self.private_ssh_key);
-        remote.connect_auth(Direction::Push, Some(connect_auth_callback), None)?;
+        let diff = self
+            .repository
+            .diff_tree_to_index(Some(&head_tree), Some(&index), None)?;
 
+        Ok(diff.deltas().len() > 0)
+    }
+
+    // Pushes the branch, and if someone else has pushed in the meantime, fetches and replays the
+    // local commits on top of theirs before trying again with backoff.
+    #[tracing::instrument(skip_all)]
+    fn push(&self) -> anyhow::Result<()> {
         let ref_spec = format!("refs/heads/{}:refs/heads/{}", self.branch, self.branch);
 
-        let push_auth_callback = RemoteGitRepo::get_auth_callback(&self.private_ssh_key);
-        let mut push_options = PushOptions::new();
-        push_options.remote_callbacks(push_auth_callback);
+        for attempt in 0..MAX_PUSH_ATTEMPTS {
+            if attempt > 0 {
+                thread::sleep(PUSH_RETRY_BASE_DELAY * 2u32.pow(attempt - 1));
+
+                self.fetch()?;
+                self.rebase_onto_origin()?;
+            }
+
+            if self.try_push(&ref_spec)? {
+                tracing::info!("push completed on branch {}", self.branch);
+                return Ok(());
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "push to branch {} rejected after {} attempts",
+            self.branch,
+            MAX_PUSH_ATTEMPTS
+        ))
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn move_last_commit(&self, branch: &str) -> anyhow::Result<()> {
+        let last_commit = self.repository.head()?.peel_to_commit()?;
+        self.repository.branch(branch, &last_commit, true)?;
+
+        self.reset_to(last_commit.parent_id(0)?)
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn append_last_commit(&self, branch: &str) -> anyhow::Result<()> {
+        let branch_oid = match self.fetch_branch(branch)? {
+            Some(branch_oid) => branch_oid,
+            None => return self.move_last_commit(branch),
+        };
+
+        let last_commit = self.repository.head()?.peel_to_commit()?;
+        let branch_commit = self.repository.find_commit(branch_oid)?;
+
+        let mut index = self
+            .repository
+            .cherrypick_commit(&last_commit, &branch_commit, 0, None)?;
+
+        if index.has_conflicts() {
+            return Err(anyhow::anyhow!(
+                "commit {} conflicts with changes pushed to branch {branch}",
+                last_commit.id()
+            ));
+        }
+
+        let tree_oid = index.write_tree_to(&self.repository)?;
+
+        // the branch already has these changes, eg. from an earlier attempt at the same events.
+        let appended_oid = if tree_oid == branch_commit.tree_id() {
+            branch_oid
+        } else {
+            self.repository.commit(
+                None,
+                &last_commit.author(),
+                &last_commit.committer(),
+                last_commit.message().unwrap_or_default(),
+                &self.repository.find_tree(tree_oid)?,
+                &[&branch_commit],
+            )?
+        };
 
-        remote.push(&[ref_spec], Some(&mut push_options))?;
+        self.repository
+            .branch(branch, &self.repository.find_commit(appended_oid)?, true)?;
 
-        tracing::info!("push completed on branch {}", self.branch);
+        self.reset_to(last_commit.parent_id(0)?)
+    }
+
+    // The local branch is either appended to from what was pushed, or replaces it outright, so
+    // it is pushed over whatever is there.
+    #[tracing::instrument(skip_all)]
+    fn push_branch(&self, branch: &str) -> anyhow::Result<()> {
+        let ref_spec = format!("+refs/heads/{branch}:refs/heads/{branch}");
+
+        if !self.try_push(&ref_spec)? {
+            return Err(anyhow::anyhow!("push to branch {branch} rejected"));
+        }
+
+        tracing::info!("push completed on branch {}", branch);
 
         Ok(())
     }
@@ -183,7 +477,7 @@ impl ClonedGitRepo for RemoteClonedGitRepo {
 
     #[tracing::instrument(skip_all)]
     fn list(&self, repo_path: PathBuf) -> anyhow::Result<Vec<PathBuf>> {
-        let file_path = self.local_path.path().join(repo_path);
+        let file_path = self.local_path.join(repo_path);
         let directory = fs::read_dir(file_path)?;
 
         let mut entries = vec![];
@@ -194,9 +488,23 @@ impl ClonedGitRepo for RemoteClonedGitRepo {
         Ok(entries)
     }
 
+    #[tracing::instrument(skip_all)]
+    fn list_files(&self, repo_path: &str) -> anyhow::Result<Vec<String>> {
+        let directory_prefix = format!("{}/", repo_path.trim_end_matches('/'));
+        let index = self.index.lock().unwrap();
+
+        let file_paths = index
+            .iter()
+            .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+            .filter(|path| path.starts_with(&directory_prefix))
+            .collect();
+
+        Ok(file_paths)
+    }
+
     #[tracing::instrument(skip_all)]
     fn read_file(&self, repo_path: PathBuf) -> anyhow::Result<Vec<u8>> {
-        let file_path = self.local_path.path().join(repo_path);
+        let file_path = self.local_path.join(repo_path);
         let contents = fs::read(file_path)?;
 
         Ok(contents)
@@ -204,7 +512,7 @@ impl ClonedGitRepo for RemoteClonedGitRepo {
 
     #[tracing::instrument(skip_all)]
     fn write_file(&self, repo_path: &str, contents: &[u8]) -> anyhow::Result<()> {
-        let file_path = self.local_path.path().join(repo_path);
+        let file_path = self.local_path.join(repo_path);
         let directory_path = file_path.parent().unwrap();
 
         fs::create_dir_all(directory_path)?;
@@ -283,14 +591,243 @@ mod tests {
 
         assert_eq!(data, String::from_utf8(contents_read).unwrap());
 
+        let signature = GitSignature {
+            name: "Tim Park".to_string(),
+            email: "timfpark@gmail.com".to_string(),
+        };
+
         cloned_repo
-            .commit(
-                "Tim Park",
-                "timfpark@gmail.com",
-                "Create azure-eastus2-1 host",
-            )
+            .commit(&signature, &signature, "Create azure-eastus2-1 host")
             .unwrap();
 
         cloned_repo.push().unwrap();
     }
+
+    // A bare repo with one commit on main, standing in for the remote.
+    fn make_bare_repo() -> anyhow::Result<TempDir> {
+        let bare_path = tempfile::tempdir()?;
+        let repository = Repository::init_bare(bare_path.path())?;
+
+        let readme = repository.blob(b"gitops repo")?;
+        let mut tree_builder = repository.treebuilder(None)?;
+        tree_builder.insert("README.md", readme, 0o100644)?;
+        let tree = repository.find_tree(tree_builder.write()?)?;
+
+        let signature = Signature::now("Fabriq", "fabriq@example.com")?;
+        repository.commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )?;
+        repository.set_head("refs/heads/main")?;
+
+        Ok(bare_path)
+    }
+
+    fn commit_file(
+        cloned_repo: &Arc<dyn ClonedGitRepo>,
+        path: &str,
+        contents: &str,
+    ) -> anyhow::Result<()> {
+        cloned_repo.write_file(path, contents.as_bytes())?;
+        cloned_repo.add_path(path.into())?;
+        let signature = GitSignature {
+            name: "Fabriq".to_string(),
+            email: "fabriq@example.com".to_string(),
+        };
+
+        cloned_repo.commit(&signature, &signature, &format!("Update {path}"))
+    }
+
+    #[test]
+    fn test_clone_repo_resets_local_clone() -> anyhow::Result<()> {
+        let bare_path = make_bare_repo()?;
+        let repo_url = bare_path.path().to_string_lossy();
+
+        let gitops_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let other_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+
+        // Left unpushed, so dropped by the next clone.
+        commit_file(&gitops_repo.clone_repo()?, "hosts/a.yaml", "a")?;
+
+        let other_cloned_repo = other_repo.clone_repo()?;
+        commit_file(&other_cloned_repo, "hosts/b.yaml", "b")?;
+        other_cloned_repo.push()?;
+
+        let cloned_repo = gitops_repo.clone_repo()?;
+        assert!(cloned_repo.read_file("hosts/a.yaml".into()).is_err());
+        assert_eq!(cloned_repo.read_file("hosts/b.yaml".into())?, b"b");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_after_concurrent_push() -> anyhow::Result<()> {
+        let bare_path = make_bare_repo()?;
+        let repo_url = bare_path.path().to_string_lossy();
+
+        let gitops_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let other_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+
+        let cloned_repo = gitops_repo.clone_repo()?;
+        let other_cloned_repo = other_repo.clone_repo()?;
+
+        commit_file(&other_cloned_repo, "hosts/b.yaml", "b")?;
+        other_cloned_repo.push()?;
+
+        commit_file(&cloned_repo, "hosts/a.yaml", "a")?;
+        cloned_repo.push()?;
+
+        let verify_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let verify_repo = verify_repo.clone_repo()?;
+        assert_eq!(verify_repo.read_file("hosts/a.yaml".into())?, b"a");
+        assert_eq!(verify_repo.read_file("hosts/b.yaml".into())?, b"b");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_last_commit() -> anyhow::Result<()> {
+        let bare_path = make_bare_repo()?;
+        let repo_url = bare_path.path().to_string_lossy();
+
+        let gitops_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let cloned_repo = gitops_repo.clone_repo()?;
+
+        commit_file(&cloned_repo, "hosts/a.yaml", "a")?;
+        commit_file(&cloned_repo, "hosts/b.yaml", "b")?;
+        cloned_repo.move_last_commit("fabriq/operation")?;
+        assert!(cloned_repo.read_file("hosts/b.yaml".into()).is_err());
+
+        cloned_repo.push()?;
+        cloned_repo.push_branch("fabriq/operation")?;
+
+        let verify_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let verify_cloned_repo = verify_repo.clone_repo()?;
+        assert_eq!(verify_cloned_repo.read_file("hosts/a.yaml".into())?, b"a");
+        assert!(verify_cloned_repo.read_file("hosts/b.yaml".into()).is_err());
+
+        let verify_branch_repo = RemoteGitRepo::new(&repo_url, "fabriq/operation", "")?;
+        let verify_branch_cloned_repo = verify_branch_repo.clone_repo()?;
+        assert_eq!(
+            verify_branch_cloned_repo.read_file("hosts/b.yaml".into())?,
+            b"b"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_last_commit() -> anyhow::Result<()> {
+        let bare_path = make_bare_repo()?;
+        let repo_url = bare_path.path().to_string_lossy();
+
+        let gitops_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+
+        let cloned_repo = gitops_repo.clone_repo()?;
+        commit_file(&cloned_repo, "deployments/a.yaml", "a")?;
+        cloned_repo.append_last_commit("fabriq/operation")?;
+        cloned_repo.push_branch("fabriq/operation")?;
+
+        // a later batch of the same operation, rendered from a fresh clone of main.
+        let cloned_repo = gitops_repo.clone_repo()?;
+        commit_file(&cloned_repo, "hosts/b.yaml", "b")?;
+        cloned_repo.append_last_commit("fabriq/operation")?;
+        assert!(cloned_repo.read_file("hosts/b.yaml".into()).is_err());
+        cloned_repo.push_branch("fabriq/operation")?;
+
+        let verify_branch_repo = RemoteGitRepo::new(&repo_url, "fabriq/operation", "")?;
+        let verify_branch_cloned_repo = verify_branch_repo.clone_repo()?;
+        assert_eq!(
+            verify_branch_cloned_repo.read_file("deployments/a.yaml".into())?,
+            b"a"
+        );
+        assert_eq!(
+            verify_branch_cloned_repo.read_file("hosts/b.yaml".into())?,
+            b"b"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_changes() -> anyhow::Result<()> {
+        let bare_path = make_bare_repo()?;
+        let repo_url = bare_path.path().to_string_lossy();
+
+        let gitops_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let cloned_repo = gitops_repo.clone_repo()?;
+
+        commit_file(&cloned_repo, "hosts/a.yaml", "a")?;
+        assert!(!cloned_repo.has_changes()?);
+
+        // rendering the same contents again changes nothing.
+        cloned_repo.write_file("hosts/a.yaml", b"a")?;
+        cloned_repo.add_path("hosts/a.yaml".into())?;
+        assert!(!cloned_repo.has_changes()?);
+
+        cloned_repo.write_file("hosts/a.yaml", b"b")?;
+        cloned_repo.add_path("hosts/a.yaml".into())?;
+        assert!(cloned_repo.has_changes()?);
+
+        commit_file(&cloned_repo, "hosts/a.yaml", "b")?;
+        cloned_repo.remove_dir("hosts")?;
+        assert!(cloned_repo.has_changes()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_files() -> anyhow::Result<()> {
+        let bare_path = make_bare_repo()?;
+        let repo_url = bare_path.path().to_string_lossy();
+
+        let gitops_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let cloned_repo = gitops_repo.clone_repo()?;
+
+        commit_file(&cloned_repo, "hosts/host-a/kustomization.yaml", "a")?;
+        commit_file(&cloned_repo, "hosts/host-b/kustomization.yaml", "b")?;
+        commit_file(&cloned_repo, "deployments/deployment.yaml", "d")?;
+
+        cloned_repo.remove_dir("hosts/host-b")?;
+        cloned_repo.write_file("hosts/host-c/kustomization.yaml", b"c")?;
+        cloned_repo.add_path("hosts/host-c/kustomization.yaml".into())?;
+
+        assert_eq!(
+            cloned_repo.list_files("hosts")?,
+            vec![
+                "hosts/host-a/kustomization.yaml".to_string(),
+                "hosts/host-c/kustomization.yaml".to_string()
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_conflict() -> anyhow::Result<()> {
+        let bare_path = make_bare_repo()?;
+        let repo_url = bare_path.path().to_string_lossy();
+
+        let gitops_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let other_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+
+        let cloned_repo = gitops_repo.clone_repo()?;
+        let other_cloned_repo = other_repo.clone_repo()?;
+
+        commit_file(&other_cloned_repo, "hosts/a.yaml", "theirs")?;
+        other_cloned_repo.push()?;
+
+        commit_file(&cloned_repo, "hosts/a.yaml", "ours")?;
+        assert!(cloned_repo.push().is_err());
+
+        let verify_repo = RemoteGitRepo::new(&repo_url, "main", "")?;
+        let verify_repo = verify_repo.clone_repo()?;
+        assert_eq!(verify_repo.read_file("hosts/a.yaml".into())?, b"theirs");
+
+        Ok(())
+    }
 }
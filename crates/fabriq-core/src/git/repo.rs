@@ -2,15 +2,46 @@ use std::fmt::Debug;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+// Who authored or committed a commit.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitSignature {
+    pub name: String,
+    pub email: String,
+}
+
 pub trait GitRepo {
     fn clone_repo(&self) -> anyhow::Result<Arc<dyn ClonedGitRepo>>;
 }
 
 pub trait ClonedGitRepo: Debug {
     fn add_path(&self, repo_path: PathBuf) -> anyhow::Result<()>;
-    fn commit(&self, name: &str, email: &str, message: &str) -> anyhow::Result<()>;
+    fn commit(
+        &self,
+        author: &GitSignature,
+        committer: &GitSignature,
+        message: &str,
+    ) -> anyhow::Result<()>;
+
+    // Whether the staged index differs from HEAD, ie. whether committing would change anything.
+    fn has_changes(&self) -> anyhow::Result<bool>;
+
     fn list(&self, repo_path: PathBuf) -> anyhow::Result<Vec<PathBuf>>;
+
+    // The repo relative paths of every file staged under the repo_path directory.
+    fn list_files(&self, repo_path: &str) -> anyhow::Result<Vec<String>>;
+
+    // Moves the last commit onto branch, leaving the cloned branch as it was before the commit.
+    fn move_last_commit(&self, branch: &str) -> anyhow::Result<()>;
+
+    // Like move_last_commit, but if branch has already been pushed, the last commit's changes are
+    // replayed on top of it, so that the branch keeps what was pushed to it before.
+    fn append_last_commit(&self, branch: &str) -> anyhow::Result<()>;
+
     fn push(&self) -> anyhow::Result<()>;
+
+    // Pushes branch, overwriting it if it already exists.
+    fn push_branch(&self, branch: &str) -> anyhow::Result<()>;
+
     fn read_file(&self, repo_path: PathBuf) -> anyhow::Result<Vec<u8>>;
     fn remove_dir(&self, path: &str) -> anyhow::Result<()>;
     fn remove_file(&self, path: &str) -> anyhow::Result<()>;
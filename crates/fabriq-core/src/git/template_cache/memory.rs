@@ -0,0 +1,69 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use super::{lock, TemplateCache, TemplateSnapshot};
+
+#[derive(Debug, Default)]
+pub struct MemoryTemplateCache {
+    // (repository, git_ref) -> commit
+    snapshots: Mutex<HashMap<(String, String), TemplateSnapshot>>,
+}
+
+impl MemoryTemplateCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds a file at path in the repo to the commit git_ref points at.
+    pub fn write_file(
+        &self,
+        repository: &str,
+        git_ref: &str,
+        sha: &str,
+        path: &str,
+        contents: &[u8],
+    ) {
+        let mut snapshots = self.snapshots.lock().unwrap();
+
+        let snapshot = snapshots
+            .entry((repository.to_string(), git_ref.to_string()))
+            .or_default();
+
+        snapshot.sha = sha.to_string();
+        snapshot
+            .files
+            .insert(PathBuf::from(path), contents.to_vec());
+    }
+}
+
+impl TemplateCache for MemoryTemplateCache {
+    fn get(
+        &self,
+        repository: &str,
+        git_ref: &str,
+        path: &str,
+    ) -> anyhow::Result<Arc<TemplateSnapshot>> {
+        let snapshots = lock(&self.snapshots)?;
+
+        let snapshot = snapshots
+            .get(&(repository.to_string(), git_ref.to_string()))
+            .ok_or_else(|| anyhow::anyhow!("ref {git_ref} not found in {repository}"))?;
+
+        let files = snapshot
+            .files
+            .iter()
+            .filter_map(|(file_path, contents)| {
+                let relative_path = file_path.strip_prefix(Path::new(path)).ok()?;
+                Some((relative_path.to_path_buf(), contents.clone()))
+            })
+            .collect();
+
+        Ok(Arc::new(TemplateSnapshot {
+            sha: snapshot.sha.clone(),
+            files,
+        }))
+    }
+}
@@ -0,0 +1,36 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+mod memory;
+mod remote;
+
+pub use memory::MemoryTemplateCache;
+pub use remote::RemoteTemplateCache;
+
+// The files under a template's path at one commit, keyed by their path relative to it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TemplateSnapshot {
+    pub sha: String,
+    pub files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+pub trait TemplateCache: Debug + Send + Sync {
+    // Resolves git_ref of repository to a commit and returns the files under path at it.
+    fn get(
+        &self,
+        repository: &str,
+        git_ref: &str,
+        path: &str,
+    ) -> anyhow::Result<Arc<TemplateSnapshot>>;
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> anyhow::Result<MutexGuard<'_, T>> {
+    match mutex.lock() {
+        Ok(locked) => Ok(locked),
+        Err(_) => Err(anyhow::anyhow!("failed to acquire lock")),
+    }
+}
@@ -0,0 +1,354 @@
+use git2::{FetchOptions, ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tempfile::TempDir;
+
+use super::{lock, TemplateCache, TemplateSnapshot};
+use crate::git::RemoteGitRepo;
+
+const FETCH_REF_SPECS: [&str; 2] = [
+    "+refs/heads/*:refs/remotes/origin/*",
+    "+refs/tags/*:refs/tags/*",
+];
+
+#[derive(Debug)]
+struct ResolvedRef {
+    sha: String,
+    resolved_at: Instant,
+}
+
+// Keeps a bare mirror of each template repository, fetching into it rather than cloning, and
+// reads templates from the commit their ref resolves to. A ref is resolved again at most once per
+// refresh_interval, while a commit sha is never fetched again once the mirror has it. Snapshots
+// are cached by commit, so every deployment of a template at the same commit shares one, and are
+// evicted once a ref moves and no resolved ref points at their commit any more.
+pub struct RemoteTemplateCache {
+    pub private_ssh_key: String,
+    pub refresh_interval: Duration,
+
+    pub local_path: PathBuf,
+    _temp_dir: Option<TempDir>,
+
+    // (repository, git_ref) -> commit
+    resolved_refs: Mutex<HashMap<(String, String), ResolvedRef>>,
+
+    // (repository, sha, path) -> snapshot
+    snapshots: Mutex<HashMap<(String, String, String), Arc<TemplateSnapshot>>>,
+}
+
+impl Debug for RemoteTemplateCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RemoteTemplateCache {}", self.local_path.display())
+    }
+}
+
+impl RemoteTemplateCache {
+    // Keeps the mirrors in a temporary directory that lasts as long as the cache.
+    pub fn new(private_ssh_key: &str, refresh_interval: Duration) -> anyhow::Result<Self> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut cache = Self::with_local_path(private_ssh_key, refresh_interval, temp_dir.path());
+        cache._temp_dir = Some(temp_dir);
+
+        Ok(cache)
+    }
+
+    pub fn with_local_path(
+        private_ssh_key: &str,
+        refresh_interval: Duration,
+        local_path: &Path,
+    ) -> Self {
+        RemoteTemplateCache {
+            private_ssh_key: private_ssh_key.to_string(),
+            refresh_interval,
+
+            local_path: local_path.to_path_buf(),
+            _temp_dir: None,
+
+            resolved_refs: Mutex::new(HashMap::new()),
+            snapshots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn open_mirror(&self, repository: &str) -> anyhow::Result<Repository> {
+        let mirror_path = self
+            .local_path
+            .join(urlencoding::encode(repository).as_ref());
+
+        if let Ok(mirror) = Repository::open_bare(&mirror_path) {
+            return Ok(mirror);
+        }
+
+        let mirror = Repository::init_bare(&mirror_path)?;
+        mirror.remote("origin", repository)?;
+
+        Ok(mirror)
+    }
+
+    fn fetch(&self, mirror: &Repository) -> anyhow::Result<()> {
+        let mut remote = mirror.find_remote("origin")?;
+
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(RemoteGitRepo::get_auth_callback(&self.private_ssh_key));
+
+        remote.fetch(&FETCH_REF_SPECS, Some(&mut fetch_options), None)?;
+
+        Ok(())
+    }
+
+    // git_ref may name a branch, a tag or a commit.
+    fn find_commit(mirror: &Repository, git_ref: &str) -> anyhow::Result<Oid> {
+        for ref_name in [
+            format!("refs/remotes/origin/{git_ref}"),
+            format!("refs/tags/{git_ref}"),
+        ] {
+            if let Ok(reference) = mirror.find_reference(&ref_name) {
+                return Ok(reference.peel_to_commit()?.id());
+            }
+        }
+
+        Ok(mirror.revparse_single(git_ref)?.peel_to_commit()?.id())
+    }
+
+    fn resolve(&self, mirror: &Repository, repository: &str, git_ref: &str) -> anyhow::Result<Oid> {
+        let key = (repository.to_string(), git_ref.to_string());
+
+        if let Some(resolved_ref) = lock(&self.resolved_refs)?.get(&key) {
+            if resolved_ref.resolved_at.elapsed() < self.refresh_interval {
+                return Ok(Oid::from_str(&resolved_ref.sha)?);
+            }
+        }
+
+        let pinned_commit = Oid::from_str(git_ref)
+            .ok()
+            .filter(|oid| git_ref.len() == 40 && mirror.find_commit(*oid).is_ok());
+
+        let commit = match pinned_commit {
+            Some(commit) => commit,
+            None => {
+                self.fetch(mirror)?;
+                Self::find_commit(mirror, git_ref)?
+            }
+        };
+
+        let mut resolved_refs = lock(&self.resolved_refs)?;
+        let previous_ref = resolved_refs.insert(
+            key,
+            ResolvedRef {
+                sha: commit.to_string(),
+                resolved_at: Instant::now(),
+            },
+        );
+
+        if previous_ref.is_some_and(|previous_ref| previous_ref.sha != commit.to_string()) {
+            self.evict_snapshots(&resolved_refs)?;
+        }
+
+        Ok(commit)
+    }
+
+    // Drops the snapshots of commits that no resolved ref points at.
+    fn evict_snapshots(
+        &self,
+        resolved_refs: &HashMap<(String, String), ResolvedRef>,
+    ) -> anyhow::Result<()> {
+        lock(&self.snapshots)?.retain(|(repository, sha, _), _| {
+            resolved_refs
+                .iter()
+                .any(|((resolved_repository, _), resolved_ref)| {
+                    resolved_repository == repository && &resolved_ref.sha == sha
+                })
+        });
+
+        Ok(())
+    }
+
+    fn read_snapshot(
+        mirror: &Repository,
+        commit: Oid,
+        path: &str,
+    ) -> anyhow::Result<TemplateSnapshot> {
+        let root_tree = mirror.find_commit(commit)?.tree()?;
+
+        let tree = match path.trim_matches('/') {
+            "" | "." => root_tree,
+            path => root_tree
+                .get_path(Path::new(path))?
+                .to_object(mirror)?
+                .peel_to_tree()?,
+        };
+
+        let mut blobs = vec![];
+
+        tree.walk(TreeWalkMode::PreOrder, |directory, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                if let Some(name) = entry.name() {
+                    blobs.push((Path::new(directory).join(name), entry.id()));
+                }
+            }
+
+            TreeWalkResult::Ok
+        })?;
+
+        let mut files = BTreeMap::new();
+
+        for (file_path, blob_id) in blobs {
+            files.insert(file_path, mirror.find_blob(blob_id)?.content().to_vec());
+        }
+
+        Ok(TemplateSnapshot {
+            sha: commit.to_string(),
+            files,
+        })
+    }
+}
+
+impl TemplateCache for RemoteTemplateCache {
+    #[tracing::instrument(skip_all)]
+    fn get(
+        &self,
+        repository: &str,
+        git_ref: &str,
+        path: &str,
+    ) -> anyhow::Result<Arc<TemplateSnapshot>> {
+        let mirror = self.open_mirror(repository)?;
+        let commit = self.resolve(&mirror, repository, git_ref)?;
+
+        let key = (repository.to_string(), commit.to_string(), path.to_string());
+
+        if let Some(snapshot) = lock(&self.snapshots)?.get(&key) {
+            return Ok(Arc::clone(snapshot));
+        }
+
+        let snapshot = Arc::new(Self::read_snapshot(&mirror, commit, path)?);
+        lock(&self.snapshots)?.insert(key, Arc::clone(&snapshot));
+
+        tracing::info!(
+            "cached template {} path {} at {}",
+            repository,
+            path,
+            snapshot.sha
+        );
+
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::{IndexAddOption, Signature};
+    use std::fs;
+
+    use super::*;
+
+    // Commits files to a repo standing in for the template repository, returning the commit sha.
+    fn commit_files(repo_path: &Path, files: &[(&str, &str)]) -> anyhow::Result<String> {
+        let repository = match Repository::open(repo_path) {
+            Ok(repository) => repository,
+            Err(_) => Repository::init(repo_path)?,
+        };
+
+        for (path, contents) in files {
+            let file_path = repo_path.join(path);
+            fs::create_dir_all(file_path.parent().unwrap())?;
+            fs::write(file_path, contents)?;
+        }
+
+        let mut index = repository.index()?;
+        index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+        index.write()?;
+        let tree = repository.find_tree(index.write_tree()?)?;
+
+        let signature = Signature::now("Fabriq", "fabriq@example.com")?;
+        let parent = repository
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+
+        let commit = repository.commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            "Update templates",
+            &tree,
+            &parents,
+        )?;
+        repository.set_head("refs/heads/main")?;
+
+        Ok(commit.to_string())
+    }
+
+    #[test]
+    fn test_get() -> anyhow::Result<()> {
+        let template_repo_path = tempfile::tempdir()?;
+        let repository = template_repo_path.path().to_string_lossy();
+
+        let first_sha = commit_files(
+            template_repo_path.path(),
+            &[
+                ("external-service/deployment.yaml", "replicas: 1"),
+                ("external-service/config/service.yaml", "port: 80"),
+                ("other-service/deployment.yaml", "replicas: 2"),
+            ],
+        )?;
+
+        let cache = RemoteTemplateCache::new("", Duration::from_secs(60))?;
+
+        let snapshot = cache.get(&repository, "main", "external-service")?;
+        assert_eq!(snapshot.sha, first_sha);
+        assert_eq!(
+            snapshot.files.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("config/service.yaml"),
+                Path::new("deployment.yaml")
+            ]
+        );
+        assert_eq!(snapshot.files[Path::new("deployment.yaml")], b"replicas: 1");
+
+        let second_sha = commit_files(
+            template_repo_path.path(),
+            &[("external-service/deployment.yaml", "replicas: 3")],
+        )?;
+
+        // main was resolved within the refresh interval, so isn't fetched again yet.
+        assert!(Arc::ptr_eq(
+            &cache.get(&repository, "main", "external-service")?,
+            &snapshot
+        ));
+
+        let cache = RemoteTemplateCache {
+            refresh_interval: Duration::ZERO,
+            ..cache
+        };
+
+        let snapshot = cache.get(&repository, "main", "external-service")?;
+        assert_eq!(snapshot.sha, second_sha);
+        assert_eq!(snapshot.files[Path::new("deployment.yaml")], b"replicas: 3");
+
+        // main has moved on, so nothing points at the first commit's snapshot any more.
+        assert_eq!(
+            cache
+                .snapshots
+                .lock()
+                .unwrap()
+                .keys()
+                .map(|(_, sha, _)| sha.clone())
+                .collect::<Vec<_>>(),
+            vec![second_sha.clone()]
+        );
+
+        let pinned_snapshot = cache.get(&repository, &first_sha, "external-service")?;
+        assert_eq!(pinned_snapshot.sha, first_sha);
+        assert_eq!(
+            pinned_snapshot.files[Path::new("deployment.yaml")],
+            b"replicas: 1"
+        );
+
+        Ok(())
+    }
+}
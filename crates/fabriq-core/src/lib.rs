@@ -1,7 +1,9 @@
 pub mod api;
+pub mod cloud_event;
 mod event_stream;
 pub mod git;
 mod protobufs;
+mod serde_timestamp;
 pub mod test;
 
 pub use event_stream::EventStream;
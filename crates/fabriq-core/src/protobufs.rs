@@ -9,7 +9,9 @@ pub mod common {
     tonic::include_proto!("fabriq.common");
 }
 
-pub use common::{DeploymentIdRequest, OperationId, TargetIdRequest, WorkloadIdRequest};
+pub use common::{
+    DeleteMode, DeploymentIdRequest, ModelMetadata, OperationId, TargetIdRequest, WorkloadIdRequest,
+};
 
 impl OperationId {
     pub fn create() -> Self {
@@ -26,6 +28,20 @@ impl OperationId {
     }
 }
 
+// admin protobufs
+
+pub mod admin {
+    tonic::include_proto!("fabriq.admin");
+}
+
+pub use admin::admin_server::{Admin as AdminTrait, AdminServer};
+pub use admin::{Bundle, ExportRequest, ExportResponse, ImportRequest, ImportResponse};
+
+impl Bundle {
+    // Bumped whenever a bundle written by this version can't be imported by earlier ones.
+    pub const VERSION: u32 = 1;
+}
+
 // assignment protobufs
 
 pub mod assignment {
@@ -46,6 +62,15 @@ impl AssignmentMessage {
     }
 }
 
+// audit protobufs
+
+pub mod audit {
+    tonic::include_proto!("fabriq.audit");
+}
+
+pub use audit::audit_server::{Audit as AuditTrait, AuditServer};
+pub use audit::{AuditEntryMessage, DeletedModelMessage, QueryAuditRequest, QueryAuditResponse};
+
 // config protobufs
 
 pub mod config {
@@ -72,6 +97,7 @@ impl ConfigMessage {
     pub const OWNING_MODEL_SEPARATOR: char = ':';
 
     pub const DEPLOYMENT_OWNER: &str = "deployment";
+    pub const HOST_OWNER: &str = "host";
     pub const TEMPLATE_OWNER: &str = "template";
     pub const WORKLOAD_OWNER: &str = "workload";
 
@@ -86,6 +112,7 @@ impl ConfigMessage {
         match owning_model_type {
             ConfigMessage::WORKLOAD_OWNER
             | ConfigMessage::DEPLOYMENT_OWNER
+            | ConfigMessage::HOST_OWNER
             | ConfigMessage::TEMPLATE_OWNER => Ok(format!(
                 "{owning_model_type}{}{owning_model_id}",
                 ConfigMessage::OWNING_MODEL_SEPARATOR,
@@ -155,7 +182,9 @@ pub mod deployment {
 }
 
 pub use deployment::deployment_server::{Deployment as DeploymentTrait, DeploymentServer};
-pub use deployment::{DeploymentMessage, ListDeploymentsRequest, ListDeploymentsResponse};
+pub use deployment::{
+    DeleteDeploymentRequest, DeploymentMessage, ListDeploymentsRequest, ListDeploymentsResponse,
+};
 
 impl DeploymentMessage {
     const DEPLOYMENT_ID_SEPARATOR: char = '/';
@@ -230,6 +259,7 @@ pub fn create_event<ModelMessage: prost::Message>(
         serialized_current_model,
         event_type: event_type as i32,
         timestamp: Some(timestamp),
+        actor: String::new(),
     }
 }
 
@@ -257,6 +287,7 @@ impl From<i32> for ModelType {
             5 => ModelType::Workload,
             6 => ModelType::Workspace,
             7 => ModelType::Config,
+            8 => ModelType::RoleBinding,
             _ => panic!("invalid model type"),
         }
     }
@@ -271,6 +302,45 @@ pub mod host {
 pub use host::host_server::{Host as HostTrait, HostServer};
 pub use host::{DeleteHostRequest, HostMessage, ListHostsRequest, ListHostsResponse};
 
+// rbac protobufs
+
+pub mod rbac {
+    tonic::include_proto!("fabriq.rbac");
+}
+
+pub use rbac::rbac_server::{Rbac as RbacTrait, RbacServer};
+pub use rbac::{
+    ListRoleBindingsRequest, ListRoleBindingsResponse, RoleBindingIdRequest, RoleBindingMessage,
+};
+
+impl RoleBindingMessage {
+    pub const PLATFORM_ADMIN_ROLE: &str = "platform-admin";
+    pub const TEAM_ADMIN_ROLE: &str = "team-admin";
+    pub const VIEWER_ROLE: &str = "viewer";
+
+    pub const ROLES: &[&str] = &[
+        RoleBindingMessage::PLATFORM_ADMIN_ROLE,
+        RoleBindingMessage::TEAM_ADMIN_ROLE,
+        RoleBindingMessage::VIEWER_ROLE,
+    ];
+
+    pub fn make_team_id(role: &str, team_id: &str) -> String {
+        format!("{role}:team:{team_id}")
+    }
+
+    pub fn make_subject_id(role: &str, subject: &str) -> String {
+        format!("{role}:subject:{subject}")
+    }
+}
+
+// snapshot protobufs
+
+pub mod snapshot {
+    tonic::include_proto!("fabriq.snapshot");
+}
+
+pub use snapshot::Snapshot;
+
 // target protobufs
 
 pub mod target {
@@ -278,7 +348,7 @@ pub mod target {
 }
 
 pub use target::target_server::{Target as TargetTrait, TargetServer};
-pub use target::{ListTargetsRequest, ListTargetsResponse, TargetMessage};
+pub use target::{DeleteTargetRequest, ListTargetsRequest, ListTargetsResponse, TargetMessage};
 
 // template protobufs
 
@@ -287,7 +357,9 @@ pub mod template {
 }
 
 pub use template::template_server::{Template as TemplateTrait, TemplateServer};
-pub use template::{ListTemplatesRequest, ListTemplatesResponse, TemplateMessage};
+pub use template::{
+    DeleteTemplateRequest, ListTemplatesRequest, ListTemplatesResponse, TemplateMessage,
+};
 
 // workload protobufs
 
@@ -296,7 +368,9 @@ pub mod workload {
 }
 
 pub use workload::workload_server::{Workload as WorkloadTrait, WorkloadServer};
-pub use workload::{ListWorkloadsRequest, ListWorkloadsResponse, WorkloadMessage};
+pub use workload::{
+    DeleteWorkloadRequest, ListWorkloadsRequest, ListWorkloadsResponse, WorkloadMessage,
+};
 
 impl WorkloadMessage {
     pub const TEAM_ID_SEPARATOR: char = '/';
@@ -313,6 +387,21 @@ impl WorkloadMessage {
         format!("{org_id}{}{team_id}", WorkloadMessage::TEAM_ID_SEPARATOR)
     }
 
+    pub fn split_id(id: &str) -> anyhow::Result<(String, String)> {
+        let id_parts = id
+            .split(WorkloadMessage::WORKLOAD_ID_SEPARATOR)
+            .collect::<Vec<_>>();
+
+        if id_parts.len() != 3 {
+            return Err(anyhow::anyhow!("split_id: invalid workload id"));
+        }
+
+        Ok((
+            WorkloadMessage::make_team_id(id_parts[0], id_parts[1]),
+            id_parts[2].to_string(),
+        ))
+    }
+
     pub fn split_team_id(team_id: &str) -> anyhow::Result<(String, String)> {
         let team_id_parts = team_id
             .split(WorkloadMessage::TEAM_ID_SEPARATOR)
@@ -355,6 +444,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_workload_split_id() -> anyhow::Result<()> {
+        let workload_id = WorkloadMessage::make_id("fabriq-cloud/fabriq", "api");
+
+        let (team_id, workload_name) = WorkloadMessage::split_id(&workload_id)?;
+
+        assert_eq!(team_id, "fabriq-cloud/fabriq");
+        assert_eq!(workload_name, "api");
+
+        assert!(WorkloadMessage::split_id("fabriq-cloud/fabriq").is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_workload_split_team_id() -> anyhow::Result<()> {
         let team_id = WorkloadMessage::make_team_id("fabriq-cloud", "fabriq");
@@ -377,6 +480,8 @@ mod tests {
             owning_model: "deployment:test".to_owned(),
             value: "A=postgres%3A%2F%2Fpostgres%3A%5Beuro4sure%5D%40fabriq.postgres.database.azure.com%2Ffabriq%3Fsslmode%3Drequire;B=postgres%3A%2F%2Fpostgres%3A%5Beuro4sure%5D%40fabriq.postgres.database.azure.com%2Ffabriq%3Fsslmode%3Drequire".to_owned(),
             value_type: ConfigValueType::KeyValueType as i32,
+            resource_version: 0,
+            metadata: None,
         };
 
         let kv = config.deserialize_keyvalue_pairs()?;
@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use crate::{
     AssignmentMessage, ConfigMessage, ConfigValueType, DeploymentMessage, HostMessage,
-    TargetMessage, TemplateMessage, WorkloadMessage,
+    RoleBindingMessage, TargetMessage, TemplateMessage, WorkloadMessage,
 };
 
 pub fn get_assignment_fixture(id: Option<&str>) -> AssignmentMessage {
@@ -14,6 +16,8 @@ pub fn get_assignment_fixture(id: Option<&str>) -> AssignmentMessage {
         id,
         host_id: host.id,
         deployment_id: deployment.id,
+        resource_version: 0,
+        metadata: None,
     }
 }
 
@@ -28,6 +32,8 @@ pub fn get_keyvalue_config_fixture() -> ConfigMessage {
         value: "key1=value1;key2=value2".to_owned(),
 
         value_type: ConfigValueType::KeyValueType as i32,
+        resource_version: 0,
+        metadata: None,
     }
 }
 
@@ -42,6 +48,8 @@ pub fn get_string_config_fixture() -> ConfigMessage {
         value: "100m".to_owned(),
 
         value_type: ConfigValueType::StringType as i32,
+        resource_version: 0,
+        metadata: None,
     }
 }
 
@@ -60,6 +68,10 @@ pub fn get_deployment_fixture(name: Option<&str>) -> DeploymentMessage {
         workload_id: workload.id,
         template_id: Some(template.id),
         host_count: 2,
+        resource_version: 0,
+        metadata: None,
+        labels: vec!["stage:production".to_string()],
+        annotations: HashMap::from([("replicas".to_string(), "3".to_string())]),
     }
 }
 
@@ -69,6 +81,21 @@ pub fn get_host_fixture(name: Option<&str>) -> HostMessage {
     HostMessage {
         id,
         labels: vec!["region:eastus2".to_string(), "cloud:azure".to_string()],
+        resource_version: 0,
+        metadata: None,
+    }
+}
+
+pub fn get_role_binding_fixture(role: Option<&str>) -> RoleBindingMessage {
+    let role = role.unwrap_or(RoleBindingMessage::VIEWER_ROLE).to_string();
+    let team_id = get_team_fixture();
+
+    RoleBindingMessage {
+        id: RoleBindingMessage::make_team_id(&role, &team_id),
+        role,
+        team_id,
+        subject: "".to_owned(),
+        resource_version: 0,
     }
 }
 
@@ -78,6 +105,8 @@ pub fn get_target_fixture(name: Option<&str>) -> TargetMessage {
     TargetMessage {
         id,
         labels: vec!["region:eastus2".to_string()],
+        resource_version: 0,
+        metadata: None,
     }
 }
 
@@ -89,6 +118,10 @@ pub fn get_template_fixture(name: Option<&str>) -> TemplateMessage {
         repository: "git@github.com:timfpark/deployment-templates".to_owned(),
         git_ref: "main".to_owned(),
         path: "external-service".to_owned(),
+        resource_version: 0,
+        metadata: None,
+        labels: vec![],
+        annotations: HashMap::new(),
     }
 }
 
@@ -104,6 +137,10 @@ pub fn get_workload_fixture(name: Option<&str>) -> WorkloadMessage {
         name: workload_name,
         template_id: template.id,
         team_id,
+        resource_version: 0,
+        metadata: None,
+        labels: vec!["tier:critical".to_string()],
+        annotations: HashMap::from([("owner".to_string(), "alice".to_string())]),
     }
 }
 
@@ -69,6 +69,8 @@ mod tests {
         let host = HostMessage {
             id: "azure-eastus2-1".to_owned(),
             labels: vec!["location:eastus2".to_string(), "cloud:azure".to_string()],
+            resource_version: 0,
+            metadata: None,
         };
 
         let host_stream = MemoryEventStream::new().unwrap();
@@ -90,6 +92,7 @@ mod tests {
             serialized_previous_model: None,
             event_type: EventType::Created as i32,
             timestamp: Some(timestamp),
+            actor: String::new(),
         };
 
         host_stream.send(&create_host_event).await.unwrap();
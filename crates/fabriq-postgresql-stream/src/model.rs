@@ -14,6 +14,8 @@ pub struct PostgreSQLEvent {
     pub serialized_previous_model: Option<Vec<u8>>,
 
     pub event_type: i32,
+
+    pub actor: String,
 }
 
 impl From<PostgreSQLEvent> for Event {
@@ -35,6 +37,7 @@ impl From<PostgreSQLEvent> for Event {
             serialized_current_model: model.serialized_current_model,
             serialized_previous_model: model.serialized_previous_model,
             event_type: model.event_type,
+            actor: model.actor,
         }
     }
 }
@@ -29,9 +29,11 @@ impl PostgresqlEventStream {
                      serialized_current_model,
                      serialized_previous_model,
 
-                     event_type)
+                     event_type,
+
+                     actor)
                 VALUES
-                    ($1, $2, $3, $4, $5, $6, $7, $8)
+                    ($1, $2, $3, $4, $5, $6, $7, $8, $9)
                 ON CONFLICT (id) DO NOTHING
                 "#,
             event.id,
@@ -41,7 +43,8 @@ impl PostgresqlEventStream {
             event.model_type,
             event.serialized_current_model,
             event.serialized_previous_model,
-            event.event_type
+            event.event_type,
+            event.actor
         )
         .execute(&*self.db)
         .await?;
@@ -120,6 +123,8 @@ impl EventStream for PostgresqlEventStream {
 
                     serialized_current_model: event.serialized_current_model.clone(),
                     serialized_previous_model: event.serialized_previous_model.clone(),
+
+                    actor: event.actor.clone(),
                 }
             })
             .collect();
@@ -194,6 +199,8 @@ mod tests {
         let host = HostMessage {
             id: "azure-eastus2-1".to_owned(),
             labels: vec!["location:eastus2".to_string(), "cloud:azure".to_string()],
+            resource_version: 0,
+            metadata: None,
         };
 
         let database_url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
@@ -234,6 +241,7 @@ mod tests {
             serialized_previous_model: None,
             event_type: EventType::Created as i32,
             timestamp: Some(timestamp),
+            actor: "octocat".to_string(),
         };
 
         event_stream.send(&create_host_event).await.unwrap();
@@ -248,6 +256,7 @@ mod tests {
 
         assert_eq!(received_event.event_type, EventType::Created as i32);
         assert_eq!(received_event.model_type, ModelType::Host as i32);
+        assert_eq!(received_event.actor, "octocat");
 
         let decoded_host: HostMessage = HostMessage::decode(
             received_event
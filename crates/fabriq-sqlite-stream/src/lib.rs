@@ -0,0 +1,4 @@
+mod model;
+mod sqlite;
+
+pub use sqlite::{connect, SqliteEventStream};
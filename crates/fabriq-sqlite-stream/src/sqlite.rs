@@ -0,0 +1,252 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use std::{str::FromStr, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use fabriq_core::{Event, EventStream};
+
+use crate::model::SqliteEvent;
+
+// Opens the database at database_url (eg. sqlite://fabriq.db), creating it if it doesn't exist.
+// The api and gitops processes share the database file, so it is opened in WAL mode and writers
+// wait for each other rather than failing with SQLITE_BUSY.
+pub async fn connect(database_url: &str) -> anyhow::Result<SqlitePool> {
+    let options = SqliteConnectOptions::from_str(database_url)?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(Duration::from_secs(30));
+
+    let db = SqlitePoolOptions::new()
+        .max_connections(5)
+        .connect_with(options)
+        .await?;
+
+    Ok(db)
+}
+
+#[derive(Debug)]
+pub struct SqliteEventStream {
+    pub db: Arc<SqlitePool>,
+    pub subscribers: Vec<String>,
+}
+
+impl SqliteEventStream {
+    async fn upsert(&self, event: &SqliteEvent) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+                INSERT INTO events
+                    (id,
+
+                     event_timestamp,
+                     consumer_id,
+                     operation_id,
+                     model_type,
+
+                     serialized_current_model,
+                     serialized_previous_model,
+
+                     event_type,
+
+                     actor)
+                VALUES
+                    (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                ON CONFLICT (id) DO NOTHING
+                "#,
+        )
+        .bind(&event.id)
+        .bind(event.event_timestamp)
+        .bind(&event.consumer_id)
+        .bind(&event.operation_id)
+        .bind(event.model_type)
+        .bind(&event.serialized_current_model)
+        .bind(&event.serialized_previous_model)
+        .bind(event.event_type)
+        .bind(&event.actor)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn upsert_many(&self, events: &[SqliteEvent]) -> anyhow::Result<u64> {
+        let mut total_rows_affected = 0;
+
+        for event in events {
+            let rows_affected = self.upsert(event).await?;
+            total_rows_affected += rows_affected;
+        }
+
+        Ok(total_rows_affected)
+    }
+}
+
+fn prost_to_naive_timestamp(timestamp: &prost_types::Timestamp) -> NaiveDateTime {
+    NaiveDateTime::from_timestamp_opt(timestamp.seconds, timestamp.nanos as u32).unwrap()
+}
+
+#[async_trait]
+impl EventStream for SqliteEventStream {
+    async fn send(&self, event: &Event) -> anyhow::Result<()> {
+        if event.operation_id.is_none() {
+            return Err(anyhow::anyhow!("operation_id not provided"));
+        }
+
+        let operation_id = event.operation_id.as_ref().unwrap();
+
+        let models: Vec<SqliteEvent> = self
+            .subscribers
+            .iter()
+            .map(|consumer_id| {
+                let event_timestamp =
+                    prost_to_naive_timestamp(&event.timestamp.as_ref().unwrap().clone());
+
+                SqliteEvent {
+                    id: Uuid::new_v4().to_string(), // create new id for each subscriber
+                    event_timestamp,
+                    consumer_id: consumer_id.to_string(),
+
+                    operation_id: operation_id.id.clone(),
+
+                    model_type: event.model_type,
+                    event_type: event.event_type,
+
+                    serialized_current_model: event.serialized_current_model.clone(),
+                    serialized_previous_model: event.serialized_previous_model.clone(),
+
+                    actor: event.actor.clone(),
+                }
+            })
+            .collect();
+
+        self.upsert_many(&models).await?;
+
+        Ok(())
+    }
+
+    async fn send_many(&self, events: &[Event]) -> anyhow::Result<()> {
+        for event in events {
+            self.send(event).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, event: &Event, _consumer_id: &str) -> anyhow::Result<u64> {
+        if event.operation_id.is_none() {
+            return Err(anyhow::anyhow!("operation_id is not supported"));
+        }
+
+        let result = sqlx::query("DELETE FROM events WHERE id = ?1")
+            .bind(&event.id)
+            .execute(&*self.db)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn receive(&self, consumer_id: &str) -> anyhow::Result<Vec<Event>> {
+        let rows = sqlx::query_as::<_, SqliteEvent>(
+            r#"
+                SELECT * FROM events WHERE consumer_id = ?1
+            "#,
+        )
+        .bind(consumer_id)
+        .fetch_all(&*self.db)
+        .await?;
+
+        let models = rows.into_iter().map(Event::from).collect::<Vec<Event>>();
+
+        Ok(models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use prost_types::Timestamp;
+    use std::time::SystemTime;
+
+    use fabriq_core::{EventType, HostMessage, ModelType, OperationId};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_send_create_host_event() {
+        const RECONCILER_CONSUMER_ID: &str = "reconciler";
+        const GITOPS_CONSUMER_ID: &str = "gitops";
+
+        let host = HostMessage {
+            id: "azure-eastus2-1".to_owned(),
+            labels: vec!["location:eastus2".to_string(), "cloud:azure".to_string()],
+            resource_version: 0,
+            metadata: None,
+        };
+
+        // every connection to an in-memory database gets its own database, so only use one.
+        let db = Arc::new(
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await
+                .unwrap(),
+        );
+
+        sqlx::migrate!("../../migrations/sqlite")
+            .run(&*db)
+            .await
+            .unwrap();
+
+        let event_stream = SqliteEventStream {
+            db,
+            subscribers: vec![
+                RECONCILER_CONSUMER_ID.to_string(),
+                GITOPS_CONSUMER_ID.to_string(),
+            ],
+        };
+
+        let create_host_event = Event {
+            id: Uuid::new_v4().to_string(),
+            operation_id: Some(OperationId::create()),
+            model_type: ModelType::Host as i32,
+            serialized_current_model: Some(host.encode_to_vec()),
+            serialized_previous_model: None,
+            event_type: EventType::Created as i32,
+            timestamp: Some(Timestamp::from(SystemTime::now())),
+            actor: "octocat".to_string(),
+        };
+
+        event_stream.send(&create_host_event).await.unwrap();
+
+        let received_events = event_stream.receive(RECONCILER_CONSUMER_ID).await.unwrap();
+        assert_eq!(received_events.len(), 1);
+
+        let received_event = received_events.first().unwrap();
+        assert_eq!(received_event.event_type, EventType::Created as i32);
+        assert_eq!(received_event.model_type, ModelType::Host as i32);
+        assert_eq!(received_event.actor, "octocat");
+        assert_eq!(received_event.timestamp, create_host_event.timestamp);
+
+        let decoded_host = HostMessage::decode(
+            received_event
+                .serialized_current_model
+                .as_ref()
+                .unwrap()
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(decoded_host.id, host.id);
+
+        event_stream
+            .delete(received_event, RECONCILER_CONSUMER_ID)
+            .await
+            .unwrap();
+
+        let received_events = event_stream.receive(RECONCILER_CONSUMER_ID).await.unwrap();
+        assert!(received_events.is_empty());
+
+        // deleting the reconciler's copy leaves the gitops consumer's copy of the event
+        let received_events = event_stream.receive(GITOPS_CONSUMER_ID).await.unwrap();
+        assert_eq!(received_events.len(), 1);
+    }
+}
@@ -0,0 +1,7 @@
+CREATE TABLE role_bindings (
+  id         TEXT  PRIMARY KEY,
+
+  role       TEXT  NOT NULL,
+  team_id    TEXT  NOT NULL,
+  subject    TEXT  NOT NULL
+);
@@ -0,0 +1,8 @@
+ALTER TABLE assignments DROP COLUMN resource_version;
+ALTER TABLE configs DROP COLUMN resource_version;
+ALTER TABLE deployments DROP COLUMN resource_version;
+ALTER TABLE hosts DROP COLUMN resource_version;
+ALTER TABLE role_bindings DROP COLUMN resource_version;
+ALTER TABLE targets DROP COLUMN resource_version;
+ALTER TABLE templates DROP COLUMN resource_version;
+ALTER TABLE workloads DROP COLUMN resource_version;
@@ -0,0 +1,8 @@
+ALTER TABLE assignments ADD COLUMN resource_version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE configs ADD COLUMN resource_version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE deployments ADD COLUMN resource_version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE hosts ADD COLUMN resource_version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE role_bindings ADD COLUMN resource_version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE targets ADD COLUMN resource_version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE templates ADD COLUMN resource_version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE workloads ADD COLUMN resource_version BIGINT NOT NULL DEFAULT 1;
@@ -0,0 +1,16 @@
+CREATE TABLE audit_entries (
+  id              TEXT         PRIMARY KEY,
+  actor           TEXT         NOT NULL,
+  recorded_at     TIMESTAMPTZ  NOT NULL,
+  operation_id    TEXT         NOT NULL,
+
+  event_type      TEXT         NOT NULL,
+  model_type      TEXT         NOT NULL,
+  model_id        TEXT         NOT NULL,
+
+  previous_model  JSONB,
+  current_model   JSONB
+);
+
+CREATE INDEX audit_entries_model_idx ON audit_entries (model_type, model_id);
+CREATE INDEX audit_entries_recorded_at_idx ON audit_entries (recorded_at);
@@ -0,0 +1,35 @@
+ALTER TABLE assignments
+  ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN created_by TEXT NOT NULL DEFAULT 'system',
+  ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN updated_by TEXT NOT NULL DEFAULT 'system';
+ALTER TABLE configs
+  ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN created_by TEXT NOT NULL DEFAULT 'system',
+  ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN updated_by TEXT NOT NULL DEFAULT 'system';
+ALTER TABLE deployments
+  ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN created_by TEXT NOT NULL DEFAULT 'system',
+  ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN updated_by TEXT NOT NULL DEFAULT 'system';
+ALTER TABLE hosts
+  ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN created_by TEXT NOT NULL DEFAULT 'system',
+  ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN updated_by TEXT NOT NULL DEFAULT 'system';
+ALTER TABLE targets
+  ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN created_by TEXT NOT NULL DEFAULT 'system',
+  ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN updated_by TEXT NOT NULL DEFAULT 'system';
+ALTER TABLE templates
+  ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN created_by TEXT NOT NULL DEFAULT 'system',
+  ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN updated_by TEXT NOT NULL DEFAULT 'system';
+ALTER TABLE workloads
+  ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN created_by TEXT NOT NULL DEFAULT 'system',
+  ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN updated_by TEXT NOT NULL DEFAULT 'system';
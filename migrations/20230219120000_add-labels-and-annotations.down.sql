@@ -0,0 +1,3 @@
+ALTER TABLE deployments DROP COLUMN labels, DROP COLUMN annotations;
+ALTER TABLE templates DROP COLUMN labels, DROP COLUMN annotations;
+ALTER TABLE workloads DROP COLUMN labels, DROP COLUMN annotations;
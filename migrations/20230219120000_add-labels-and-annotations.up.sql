@@ -0,0 +1,13 @@
+ALTER TABLE deployments
+  ADD COLUMN labels TEXT[] NOT NULL DEFAULT '{}',
+  ADD COLUMN annotations JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE templates
+  ADD COLUMN labels TEXT[] NOT NULL DEFAULT '{}',
+  ADD COLUMN annotations JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE workloads
+  ADD COLUMN labels TEXT[] NOT NULL DEFAULT '{}',
+  ADD COLUMN annotations JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX deployments_labels on deployments USING GIN(labels);
+CREATE INDEX templates_labels on templates USING GIN(labels);
+CREATE INDEX workloads_labels on workloads USING GIN(labels);
@@ -0,0 +1,10 @@
+CREATE TABLE deleted_models (
+  model_type  TEXT         NOT NULL,
+  model_id    TEXT         NOT NULL,
+  deleted_at  TIMESTAMPTZ  NOT NULL,
+  deleted_by  TEXT         NOT NULL,
+
+  model       JSONB        NOT NULL,
+
+  PRIMARY KEY (model_type, model_id, deleted_at)
+);
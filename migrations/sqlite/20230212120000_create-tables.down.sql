@@ -0,0 +1,10 @@
+DROP TABLE events;
+DROP TABLE audit_entries;
+DROP TABLE role_bindings;
+DROP TABLE configs;
+DROP TABLE assignments;
+DROP TABLE deployments;
+DROP TABLE targets;
+DROP TABLE workloads;
+DROP TABLE hosts;
+DROP TABLE templates;
@@ -0,0 +1,149 @@
+-- SQLite has no arrays, so labels are stored as JSON arrays of strings, and timestamps as RFC 3339
+-- text.
+
+CREATE TABLE templates (
+  id                TEXT     PRIMARY KEY,
+
+  repository        TEXT     NOT NULL,
+  git_ref           TEXT     NOT NULL,
+  path              TEXT     NOT NULL,
+
+  resource_version  INTEGER  NOT NULL DEFAULT 1,
+  created_at        TEXT     NOT NULL,
+  created_by        TEXT     NOT NULL,
+  updated_at        TEXT     NOT NULL,
+  updated_by        TEXT     NOT NULL
+);
+
+CREATE TABLE hosts (
+  id                TEXT     PRIMARY KEY,
+
+  labels            TEXT     NOT NULL,
+
+  resource_version  INTEGER  NOT NULL DEFAULT 1,
+  created_at        TEXT     NOT NULL,
+  created_by        TEXT     NOT NULL,
+  updated_at        TEXT     NOT NULL,
+  updated_by        TEXT     NOT NULL
+);
+
+CREATE TABLE workloads (
+  id                TEXT     PRIMARY KEY,
+
+  name              TEXT     NOT NULL,
+  team_id           TEXT     NOT NULL,
+  template_id       TEXT     NOT NULL,
+
+  resource_version  INTEGER  NOT NULL DEFAULT 1,
+  created_at        TEXT     NOT NULL,
+  created_by        TEXT     NOT NULL,
+  updated_at        TEXT     NOT NULL,
+  updated_by        TEXT     NOT NULL
+);
+
+CREATE TABLE targets (
+  id                TEXT     PRIMARY KEY,
+
+  labels            TEXT     NOT NULL,
+
+  resource_version  INTEGER  NOT NULL DEFAULT 1,
+  created_at        TEXT     NOT NULL,
+  created_by        TEXT     NOT NULL,
+  updated_at        TEXT     NOT NULL,
+  updated_by        TEXT     NOT NULL
+);
+
+CREATE TABLE deployments (
+  id                TEXT     PRIMARY KEY,
+
+  name              TEXT     NOT NULL,
+  workload_id       TEXT     NOT NULL,
+  target_id         TEXT     NOT NULL REFERENCES targets(id),
+  template_id       TEXT     REFERENCES templates(id),
+  host_count        INTEGER  NOT NULL,
+
+  resource_version  INTEGER  NOT NULL DEFAULT 1,
+  created_at        TEXT     NOT NULL,
+  created_by        TEXT     NOT NULL,
+  updated_at        TEXT     NOT NULL,
+  updated_by        TEXT     NOT NULL
+);
+
+CREATE INDEX deployments_target_id ON deployments (target_id);
+CREATE INDEX deployments_template_id ON deployments (template_id);
+CREATE INDEX deployments_workload_id ON deployments (workload_id);
+
+CREATE TABLE assignments (
+  id                TEXT     PRIMARY KEY,
+
+  deployment_id     TEXT     NOT NULL REFERENCES deployments(id),
+  host_id           TEXT     NOT NULL REFERENCES hosts(id),
+
+  resource_version  INTEGER  NOT NULL DEFAULT 1,
+  created_at        TEXT     NOT NULL,
+  created_by        TEXT     NOT NULL,
+  updated_at        TEXT     NOT NULL,
+  updated_by        TEXT     NOT NULL
+);
+
+CREATE INDEX assignments_deployment_id ON assignments (deployment_id);
+
+CREATE TABLE configs (
+  id                TEXT     PRIMARY KEY,
+
+  owning_model      TEXT     NOT NULL,
+  key               TEXT     NOT NULL,
+  value             TEXT     NOT NULL,
+  value_type        INTEGER  NOT NULL,
+
+  resource_version  INTEGER  NOT NULL DEFAULT 1,
+  created_at        TEXT     NOT NULL,
+  created_by        TEXT     NOT NULL,
+  updated_at        TEXT     NOT NULL,
+  updated_by        TEXT     NOT NULL
+);
+
+CREATE INDEX configs_owning_model ON configs (owning_model);
+
+CREATE TABLE role_bindings (
+  id                TEXT     PRIMARY KEY,
+
+  role              TEXT     NOT NULL,
+  team_id           TEXT     NOT NULL,
+  subject           TEXT     NOT NULL,
+
+  resource_version  INTEGER  NOT NULL DEFAULT 1
+);
+
+CREATE TABLE audit_entries (
+  id                TEXT     PRIMARY KEY,
+  actor             TEXT     NOT NULL,
+  recorded_at       TEXT     NOT NULL,
+  operation_id      TEXT     NOT NULL,
+
+  event_type        TEXT     NOT NULL,
+  model_type        TEXT     NOT NULL,
+  model_id          TEXT     NOT NULL,
+
+  previous_model    TEXT,
+  current_model     TEXT
+);
+
+CREATE INDEX audit_entries_model_idx ON audit_entries (model_type, model_id);
+CREATE INDEX audit_entries_recorded_at_idx ON audit_entries (recorded_at);
+
+CREATE TABLE events (
+  id                         TEXT     PRIMARY KEY,
+  event_timestamp            TEXT     NOT NULL,
+  consumer_id                TEXT     NOT NULL,
+
+  operation_id               TEXT     NOT NULL,
+  model_type                 INTEGER  NOT NULL,
+
+  serialized_current_model   BLOB,
+  serialized_previous_model  BLOB,
+
+  event_type                 INTEGER  NOT NULL
+);
+
+CREATE INDEX events_consumer_id ON events (consumer_id);
@@ -0,0 +1,6 @@
+ALTER TABLE deployments DROP COLUMN labels;
+ALTER TABLE deployments DROP COLUMN annotations;
+ALTER TABLE templates DROP COLUMN labels;
+ALTER TABLE templates DROP COLUMN annotations;
+ALTER TABLE workloads DROP COLUMN labels;
+ALTER TABLE workloads DROP COLUMN annotations;
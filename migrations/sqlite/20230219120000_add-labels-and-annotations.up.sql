@@ -0,0 +1,10 @@
+-- Annotations are stored as JSON objects of strings.
+
+ALTER TABLE deployments ADD COLUMN labels TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE deployments ADD COLUMN annotations TEXT NOT NULL DEFAULT '{}';
+
+ALTER TABLE templates ADD COLUMN labels TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE templates ADD COLUMN annotations TEXT NOT NULL DEFAULT '{}';
+
+ALTER TABLE workloads ADD COLUMN labels TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE workloads ADD COLUMN annotations TEXT NOT NULL DEFAULT '{}';
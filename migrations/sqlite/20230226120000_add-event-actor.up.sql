@@ -0,0 +1 @@
+ALTER TABLE events ADD COLUMN actor TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,10 @@
+CREATE TABLE deleted_models (
+  model_type  TEXT  NOT NULL,
+  model_id    TEXT  NOT NULL,
+  deleted_at  TEXT  NOT NULL,
+  deleted_by  TEXT  NOT NULL,
+
+  model       TEXT  NOT NULL,
+
+  PRIMARY KEY (model_type, model_id, deleted_at)
+);
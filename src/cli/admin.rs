@@ -0,0 +1,111 @@
+use clap::{arg, builder::PossibleValuesParser, Arg, ArgAction, Command};
+use fabriq_core::{admin::admin_client::AdminClient, Bundle, ExportRequest, ImportRequest};
+use std::path::Path;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::context::Context;
+
+const FORMATS: &[&str] = &["json", "yaml"];
+
+pub fn args() -> Command {
+    Command::new("admin")
+        .arg_required_else_help(true)
+        .about("Export and import the whole system")
+        .subcommand(
+            Command::new("export")
+                .about("Export every template, workload, deployment, target, host and config")
+                .arg(arg!([FILE] "File to write the bundle to (default stdout)"))
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .help("Bundle format (default from the file extension, else yaml)")
+                        .value_parser(PossibleValuesParser::new(FORMATS))
+                        .action(ArgAction::Set),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Import a bundle, skipping models that are unchanged")
+                .arg(arg!(<FILE> "JSON or YAML bundle to import"))
+                .arg_required_else_help(true),
+        )
+}
+
+fn is_json(format: Option<&String>, file: Option<&String>) -> bool {
+    match format {
+        Some(format) => format == "json",
+        None => file
+            .and_then(|file| Path::new(file).extension())
+            .is_some_and(|extension| extension == "json"),
+    }
+}
+
+pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyhow::Result<()> {
+    let endpoint: &str = Box::leak(Box::new(context.endpoint.clone()));
+    let channel = Channel::from_static(endpoint).connect().await?;
+
+    let token = context.make_token()?;
+
+    let mut client = AdminClient::with_interceptor(channel, move |mut req: Request<()>| {
+        req.metadata_mut().insert("authorization", token.clone());
+        Ok(req)
+    });
+
+    match model_match.subcommand() {
+        Some(("export", export_match)) => {
+            let request = tonic::Request::new(ExportRequest {});
+
+            let bundle = client
+                .export(request)
+                .await?
+                .into_inner()
+                .bundle
+                .unwrap_or_default();
+
+            let file = export_match.get_one::<String>("FILE");
+
+            let serialized_bundle = if is_json(export_match.get_one::<String>("format"), file) {
+                serde_json::to_string_pretty(&bundle)?
+            } else {
+                serde_yaml::to_string(&bundle)?
+            };
+
+            match file {
+                Some(file) => {
+                    std::fs::write(file, serialized_bundle)?;
+
+                    tracing::info!("bundle exported to '{file}'");
+                }
+                None => println!("{serialized_bundle}"),
+            }
+
+            Ok(())
+        }
+        Some(("import", import_match)) => {
+            let file = import_match
+                .get_one::<String>("FILE")
+                .expect("Bundle file expected");
+
+            // JSON is valid YAML, so either format parses as YAML.
+            let bundle: Bundle = serde_yaml::from_str(&std::fs::read_to_string(file)?)?;
+
+            let request = tonic::Request::new(ImportRequest {
+                bundle: Some(bundle),
+            });
+
+            let response = client.import(request).await?.into_inner();
+
+            tracing::info!(
+                "bundle '{file}' imported: {} created, {} updated, {} unchanged",
+                response.created,
+                response.updated,
+                response.unchanged
+            );
+
+            Ok(())
+        }
+        _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
+    }
+}
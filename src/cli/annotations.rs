@@ -0,0 +1,26 @@
+use clap::{Arg, ArgAction, ArgMatches};
+use std::collections::HashMap;
+
+pub fn arg() -> Arg {
+    Arg::new("annotation")
+        .short('a')
+        .long("annotation")
+        .help("key=value annotation to apply (repeatable)")
+        .action(ArgAction::Append)
+}
+
+pub fn get(matches: &ArgMatches) -> anyhow::Result<HashMap<String, String>> {
+    let annotations = match matches.get_many::<String>("annotation") {
+        Some(annotations) => annotations,
+        None => return Ok(HashMap::new()),
+    };
+
+    annotations
+        .map(|annotation| match annotation.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+            _ => Err(anyhow::anyhow!(
+                "invalid annotation '{annotation}': expected key=value"
+            )),
+        })
+        .collect()
+}
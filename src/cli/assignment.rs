@@ -7,7 +7,7 @@ use fabriq_core::{
 use tonic::transport::Channel;
 use tonic::Request;
 
-use crate::context::Context;
+use crate::{context::Context, list, resource_version};
 
 pub fn args() -> Command {
     Command::new("assignment")
@@ -33,10 +33,21 @@ pub fn args() -> Command {
         .subcommand(
             Command::new("delete")
                 .about("delete assignment")
+                .arg(resource_version::arg())
                 .arg(arg!(<ID> "assignment id"))
                 .arg_required_else_help(true),
         )
-        .subcommand(Command::new("list").about("List assignments"))
+        .subcommand(
+            list::args(Command::new("list").about("List assignments"))
+                .arg(list::filter_arg(
+                    "deployment",
+                    "only list assignments of this deployment id",
+                ))
+                .arg(list::filter_arg(
+                    "host",
+                    "only list assignments to this host id",
+                )),
+        )
 }
 
 pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyhow::Result<()> {
@@ -62,12 +73,14 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .expect("host id expected")
                 .to_string();
 
-            let id = format!("{}|{}", deployment_id, host_id);
+            let id = AssignmentMessage::make_id(&deployment_id, &host_id);
 
             let request = tonic::Request::new(AssignmentMessage {
                 id: id.clone(),
                 deployment_id,
                 host_id,
+                resource_version: resource_version::get(add_match),
+                metadata: None,
             });
 
             client.upsert(request).await?;
@@ -91,13 +104,18 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
 
             Ok(())
         }
-        Some(("list", _)) => {
-            let request = tonic::Request::new(ListAssignmentsRequest {});
+        Some(("list", list_match)) => {
+            let request = tonic::Request::new(ListAssignmentsRequest {
+                page_size: list::get_page_size(list_match),
+                page_token: list::get_string(list_match, "page-token"),
+                order_by: list::get_string(list_match, "order-by"),
+                deployment_id: list::get_string(list_match, "deployment"),
+                host_id: list::get_string(list_match, "host"),
+            });
 
-            let response = client.list(request).await?;
+            let response = client.list(request).await?.into_inner();
 
             let table_data: Vec<Vec<String>> = response
-                .into_inner()
                 .assignments
                 .into_iter()
                 .map(|assignment| {
@@ -105,6 +123,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                         assignment.id.to_string(),
                         assignment.deployment_id.clone(),
                         assignment.host_id,
+                        assignment.resource_version.to_string(),
+                        list::format_created(&assignment.metadata),
+                        list::format_updated(&assignment.metadata),
                     ]
                 })
                 .collect();
@@ -132,8 +153,25 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .set_header("HOST ID")
                 .set_align(Align::Left);
 
+            ascii_table
+                .column(3)
+                .set_header("VERSION")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(4)
+                .set_header("CREATED")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(5)
+                .set_header("UPDATED")
+                .set_align(Align::Left);
+
             ascii_table.print(table_data);
 
+            list::print_next_page(&response.next_page_token);
+
             Ok(())
         }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
@@ -0,0 +1,150 @@
+use ascii_table::{Align, AsciiTable};
+use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
+use fabriq_core::{audit::audit_client::AuditClient, QueryAuditRequest};
+use prost_types::Timestamp;
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::{context::Context, list};
+
+const MODEL_TYPES: &[&str] = &[
+    "assignment",
+    "config",
+    "deployment",
+    "host",
+    "rolebinding",
+    "target",
+    "template",
+    "workload",
+];
+
+pub fn args() -> Command {
+    Command::new("audit")
+        .about("show who changed what, newest first")
+        .arg(
+            Arg::new("model")
+                .short('m')
+                .long("model")
+                .help("only show changes to this type of model")
+                .value_parser(PossibleValuesParser::new(MODEL_TYPES))
+                .action(ArgAction::Set),
+        )
+        .arg(list::filter_arg(
+            "id",
+            "only show changes to the model with this id",
+        ))
+        .arg(list::filter_arg(
+            "actor",
+            "only show changes made by this token subject",
+        ))
+        .arg(time_arg(
+            "since",
+            "only show changes at or after this time (eg. 2023-01-29T12:00:00Z)",
+        ))
+        .arg(time_arg("until", "only show changes before this time"))
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .help("maximum number of changes to show (default 100)")
+                .value_parser(clap::value_parser!(i32))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("models")
+                .long("models")
+                .help("also show the model before and after each change")
+                .action(ArgAction::SetTrue),
+        )
+}
+
+fn time_arg(id: &'static str, help: &'static str) -> Arg {
+    Arg::new(id)
+        .long(id)
+        .help(help)
+        .value_parser(|value: &str| {
+            value
+                .parse::<Timestamp>()
+                .map_err(|err| format!("expected an RFC 3339 time: {err}"))
+        })
+        .action(ArgAction::Set)
+}
+
+fn get_time(matches: &ArgMatches, id: &str) -> Option<Timestamp> {
+    matches.get_one::<Timestamp>(id).cloned()
+}
+
+pub async fn handlers(audit_match: &ArgMatches, context: &Context) -> anyhow::Result<()> {
+    let endpoint: &str = Box::leak(Box::new(context.endpoint.clone()));
+    let channel = Channel::from_static(endpoint).connect().await?;
+
+    let token = context.make_token()?;
+
+    let mut client = AuditClient::with_interceptor(channel, move |mut req: Request<()>| {
+        req.metadata_mut().insert("authorization", token.clone());
+        Ok(req)
+    });
+
+    let request = tonic::Request::new(QueryAuditRequest {
+        model_type: list::get_string(audit_match, "model"),
+        model_id: list::get_string(audit_match, "id"),
+        actor: list::get_string(audit_match, "actor"),
+        since: get_time(audit_match, "since"),
+        until: get_time(audit_match, "until"),
+        limit: audit_match.get_one::<i32>("limit").copied().unwrap_or(0),
+    });
+
+    let response = client.query(request).await?.into_inner();
+
+    let show_models = audit_match.get_flag("models");
+
+    let table_data: Vec<Vec<String>> = response
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let mut row = vec![
+                entry
+                    .timestamp
+                    .map(|timestamp| timestamp.to_string())
+                    .unwrap_or_default(),
+                entry.actor,
+                entry.event_type,
+                entry.model_type,
+                entry.model_id,
+                entry.operation_id,
+            ];
+
+            if show_models {
+                row.push(entry.previous_model.unwrap_or_default());
+                row.push(entry.current_model.unwrap_or_default());
+            }
+
+            row
+        })
+        .collect();
+
+    if table_data.is_empty() {
+        tracing::info!("no changes found");
+
+        return Ok(());
+    }
+
+    let mut headers = vec!["TIME", "ACTOR", "CHANGE", "MODEL", "ID", "OPERATION"];
+
+    if show_models {
+        headers.push("BEFORE");
+        headers.push("AFTER");
+    }
+
+    let mut ascii_table = AsciiTable::default();
+
+    for (column, header) in headers.into_iter().enumerate() {
+        ascii_table
+            .column(column)
+            .set_header(header)
+            .set_align(Align::Left);
+    }
+
+    ascii_table.print(table_data);
+
+    Ok(())
+}
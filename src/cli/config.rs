@@ -7,7 +7,7 @@ use fabriq_core::{
 use tonic::transport::Channel;
 use tonic::Request;
 
-use crate::context::Context;
+use crate::{context::Context, list, resource_version};
 
 pub fn args() -> Command {
     Command::new("config")
@@ -24,6 +24,12 @@ pub fn args() -> Command {
                         .help("owning deployment id")
                         .action(ArgAction::Set),
                 )
+                .arg(
+                    Arg::new("host")
+                        .long("host")
+                        .help("owning host id")
+                        .action(ArgAction::Set),
+                )
                 .arg(
                     Arg::new("template")
                         .long("template")
@@ -48,6 +54,7 @@ pub fn args() -> Command {
                         .help("value of type (default 'string')")
                         .action(ArgAction::Set),
                 )
+                .arg(resource_version::arg())
                 .arg(arg!(<KEY> "Config key"))
                 .arg(arg!(<VALUE> "Config value"))
                 .arg_required_else_help(true),
@@ -68,6 +75,12 @@ pub fn args() -> Command {
                         .help("Deployment to query config for")
                         .action(ArgAction::Set),
                 )
+                .arg(
+                    Arg::new("host")
+                        .long("host")
+                        .help("Host to query config for")
+                        .action(ArgAction::Set),
+                )
                 .arg(
                     Arg::new("template")
                         .short('t')
@@ -111,6 +124,7 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
             let template_id = create_match.get_one::<String>("template");
             let workload_id = create_match.get_one::<String>("workload");
             let deployment_id = create_match.get_one::<String>("deployment");
+            let host_id = create_match.get_one::<String>("host");
             let value_type_option = create_match.get_one::<String>("type");
 
             let value_type = if let Some(value_type) = value_type_option {
@@ -135,9 +149,12 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                         Some(template_id) => {
                             ConfigMessage::make_owning_model("template", template_id)?
                         }
-                        None => {
-                            panic!("owning workload, template, or deployment id must be specified")
-                        }
+                        None => match host_id {
+                            Some(host_id) => ConfigMessage::make_owning_model("host", host_id)?,
+                            None => panic!(
+                                "owning workload, template, deployment, or host id must be specified"
+                            ),
+                        },
                     },
                 },
             };
@@ -152,6 +169,8 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 value,
 
                 value_type,
+                resource_version: resource_version::get(create_match),
+                metadata: None,
             });
 
             client.upsert(request).await?;
@@ -179,6 +198,7 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
             let deployment_id = list_match.get_one::<String>("deployment");
             let template_id = list_match.get_one::<String>("template");
             let workload_id = list_match.get_one::<String>("workload");
+            let host_id = list_match.get_one::<String>("host");
 
             let request = if let Some(deployment_id) = deployment_id {
                 tonic::Request::new(QueryConfigRequest {
@@ -195,8 +215,13 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                     model_name: "template".to_string(),
                     model_id: template_id.to_string(),
                 })
+            } else if let Some(host_id) = host_id {
+                tonic::Request::new(QueryConfigRequest {
+                    model_name: "host".to_string(),
+                    model_id: host_id.to_string(),
+                })
             } else {
-                panic!("owning workload, template, or deployment id must be specified")
+                panic!("owning workload, template, deployment, or host id must be specified")
             };
 
             let response = client.query(request).await?;
@@ -211,6 +236,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                         config.owning_model.to_string(),
                         config.key.to_string(),
                         config.value,
+                        config.resource_version.to_string(),
+                        list::format_created(&config.metadata),
+                        list::format_updated(&config.metadata),
                     ]
                 })
                 .collect();
@@ -243,6 +271,21 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .set_header("VALUE")
                 .set_align(Align::Left);
 
+            ascii_table
+                .column(4)
+                .set_header("VERSION")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(5)
+                .set_header("CREATED")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(6)
+                .set_header("UPDATED")
+                .set_align(Align::Left);
+
             ascii_table.print(table_data);
 
             Ok(())
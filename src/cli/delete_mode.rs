@@ -0,0 +1,18 @@
+use clap::{Arg, ArgAction, ArgMatches};
+use fabriq_core::DeleteMode;
+
+pub fn arg() -> Arg {
+    Arg::new("cascade")
+        .long("cascade")
+        .help("also delete the deployments, assignments and configs that depend on it")
+        .action(ArgAction::SetTrue)
+}
+
+// Without --cascade the api refuses to delete models that others still depend on.
+pub fn get(matches: &ArgMatches) -> i32 {
+    if matches.get_flag("cascade") {
+        DeleteMode::Cascade as i32
+    } else {
+        DeleteMode::Refuse as i32
+    }
+}
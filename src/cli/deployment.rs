@@ -1,13 +1,13 @@
 use ascii_table::{Align, AsciiTable};
 use clap::{arg, Arg, ArgAction, Command};
 use fabriq_core::{
-    deployment::deployment_client::DeploymentClient, DeploymentIdRequest, DeploymentMessage,
+    deployment::deployment_client::DeploymentClient, DeleteDeploymentRequest, DeploymentMessage,
     ListDeploymentsRequest, WorkloadMessage,
 };
 use tonic::transport::Channel;
 use tonic::Request;
 
-use crate::context::Context;
+use crate::{annotations, context::Context, delete_mode, list, resource_version};
 
 pub fn args() -> Command {
     Command::new("deployment")
@@ -47,6 +47,9 @@ pub fn args() -> Command {
                         .help("workload name for deployment")
                         .action(ArgAction::Set),
                 )
+                .arg(list::label_arg("label to apply to deployment (repeatable)"))
+                .arg(annotations::arg())
+                .arg(resource_version::arg())
                 .arg(arg!(<NAME> "deployment name"))
                 .arg_required_else_help(true),
         )
@@ -54,9 +57,25 @@ pub fn args() -> Command {
             Command::new("delete")
                 .about("delete deployment")
                 .arg(arg!(<ID> "deployment id"))
+                .arg(delete_mode::arg())
                 .arg_required_else_help(true),
         )
-        .subcommand(Command::new("list").about("List deployments"))
+        .subcommand(
+            list::args(Command::new("list").about("List deployments"))
+                .arg(list::filter_arg(
+                    "workload",
+                    "only list deployments of this workload id",
+                ))
+                .arg(list::filter_arg(
+                    "target",
+                    "only list deployments with this target id",
+                ))
+                .arg(list::filter_arg(
+                    "template",
+                    "only list deployments with this template override",
+                ))
+                .arg(list::label_arg("only list deployments with this label")),
+        )
 }
 
 pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyhow::Result<()> {
@@ -110,6 +129,10 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 target_id,
                 host_count,
                 template_id,
+                resource_version: resource_version::get(add_match),
+                labels: list::get_labels(add_match),
+                annotations: annotations::get(add_match)?,
+                metadata: None,
             });
 
             client.upsert(request).await?;
@@ -123,8 +146,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .get_one::<String>("ID")
                 .expect("deployment id expected");
 
-            let request = tonic::Request::new(DeploymentIdRequest {
+            let request = tonic::Request::new(DeleteDeploymentRequest {
                 deployment_id: id.to_string(),
+                mode: delete_mode::get(create_match),
             });
 
             client.delete(request).await?;
@@ -133,13 +157,20 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
 
             Ok(())
         }
-        Some(("list", _)) => {
-            let request = tonic::Request::new(ListDeploymentsRequest {});
+        Some(("list", list_match)) => {
+            let request = tonic::Request::new(ListDeploymentsRequest {
+                page_size: list::get_page_size(list_match),
+                page_token: list::get_string(list_match, "page-token"),
+                order_by: list::get_string(list_match, "order-by"),
+                labels: list::get_labels(list_match),
+                workload_id: list::get_string(list_match, "workload"),
+                target_id: list::get_string(list_match, "target"),
+                template_id: list::get_string(list_match, "template"),
+            });
 
-            let response = client.list(request).await?;
+            let response = client.list(request).await?.into_inner();
 
             let table_data: Vec<Vec<String>> = response
-                .into_inner()
                 .deployments
                 .into_iter()
                 .map(|deployment| {
@@ -158,6 +189,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                             .template_id
                             .unwrap_or_else(|| "(inherited)".to_string()),
                         host_count,
+                        deployment.resource_version.to_string(),
+                        list::format_created(&deployment.metadata),
+                        list::format_updated(&deployment.metadata),
                     ]
                 })
                 .collect();
@@ -200,8 +234,25 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .set_header("HOSTS")
                 .set_align(Align::Left);
 
+            ascii_table
+                .column(6)
+                .set_header("VERSION")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(7)
+                .set_header("CREATED")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(8)
+                .set_header("UPDATED")
+                .set_align(Align::Left);
+
             ascii_table.print(table_data);
 
+            list::print_next_page(&response.next_page_token);
+
             Ok(())
         }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
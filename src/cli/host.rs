@@ -6,7 +6,7 @@ use fabriq_core::{
 use tonic::transport::Channel;
 use tonic::Request;
 
-use crate::context::Context;
+use crate::{context::Context, delete_mode, list, resource_version};
 
 pub fn args() -> Command {
     Command::new("host")
@@ -23,6 +23,7 @@ pub fn args() -> Command {
                         .action(ArgAction::Set)
                         .num_args(1..),
                 )
+                .arg(resource_version::arg())
                 .arg(arg!(<ID> "host id"))
                 .arg_required_else_help(true),
         )
@@ -30,9 +31,13 @@ pub fn args() -> Command {
             Command::new("delete")
                 .about("delete host")
                 .arg(arg!(<ID> "host id"))
+                .arg(delete_mode::arg())
                 .arg_required_else_help(true),
         )
-        .subcommand(Command::new("list").about("list hosts"))
+        .subcommand(
+            list::args(Command::new("list").about("list hosts"))
+                .arg(list::label_arg("only list hosts with this label")),
+        )
 }
 
 pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyhow::Result<()> {
@@ -62,6 +67,8 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
             let request = tonic::Request::new(HostMessage {
                 id: id.clone(),
                 labels,
+                resource_version: resource_version::get(add_match),
+                metadata: None,
             });
 
             client.upsert(request).await?;
@@ -74,7 +81,10 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
             let id = create_match
                 .get_one::<String>("ID")
                 .expect("Host id expected");
-            let request = tonic::Request::new(DeleteHostRequest { id: id.to_string() });
+            let request = tonic::Request::new(DeleteHostRequest {
+                id: id.to_string(),
+                mode: delete_mode::get(create_match),
+            });
 
             client.delete(request).await?;
 
@@ -82,16 +92,28 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
 
             Ok(())
         }
-        Some(("list", _)) => {
-            let request = tonic::Request::new(ListHostsRequest {});
+        Some(("list", list_match)) => {
+            let request = tonic::Request::new(ListHostsRequest {
+                page_size: list::get_page_size(list_match),
+                page_token: list::get_string(list_match, "page-token"),
+                order_by: list::get_string(list_match, "order-by"),
+                labels: list::get_labels(list_match),
+            });
 
-            let response = client.list(request).await?;
+            let response = client.list(request).await?.into_inner();
 
             let table_data: Vec<Vec<String>> = response
-                .into_inner()
                 .hosts
                 .into_iter()
-                .map(|host| vec![host.id.to_string(), host.labels.join(", ")])
+                .map(|host| {
+                    vec![
+                        host.id.to_string(),
+                        host.labels.join(", "),
+                        host.resource_version.to_string(),
+                        list::format_created(&host.metadata),
+                        list::format_updated(&host.metadata),
+                    ]
+                })
                 .collect();
 
             if table_data.is_empty() {
@@ -112,8 +134,25 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .set_header("LABELS")
                 .set_align(Align::Left);
 
+            ascii_table
+                .column(2)
+                .set_header("VERSION")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(3)
+                .set_header("CREATED")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(4)
+                .set_header("UPDATED")
+                .set_align(Align::Left);
+
             ascii_table.print(table_data);
 
+            list::print_next_page(&response.next_page_token);
+
             Ok(())
         }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
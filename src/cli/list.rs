@@ -0,0 +1,82 @@
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use fabriq_core::ModelMetadata;
+use prost_types::Timestamp;
+
+// Adds the paging and ordering arguments shared by every list command.
+pub fn args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("page-size")
+                .long("page-size")
+                .help("maximum number of results to return (default 100)")
+                .value_parser(clap::value_parser!(i32))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("page-token")
+                .long("page-token")
+                .help("continue from a page token printed by a previous list")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("order-by")
+                .long("order-by")
+                .help("field to order by, optionally followed by asc or desc (eg. 'id desc')")
+                .action(ArgAction::Set),
+        )
+}
+
+pub fn filter_arg(id: &'static str, help: &'static str) -> Arg {
+    Arg::new(id).long(id).help(help).action(ArgAction::Set)
+}
+
+pub fn label_arg(help: &'static str) -> Arg {
+    Arg::new("label")
+        .short('l')
+        .long("label")
+        .help(help)
+        .action(ArgAction::Append)
+}
+
+pub fn get_page_size(matches: &ArgMatches) -> i32 {
+    matches.get_one::<i32>("page-size").copied().unwrap_or(0)
+}
+
+pub fn get_string(matches: &ArgMatches, id: &str) -> String {
+    matches.get_one::<String>(id).cloned().unwrap_or_default()
+}
+
+pub fn get_labels(matches: &ArgMatches) -> Vec<String> {
+    matches
+        .get_many::<String>("label")
+        .map(|labels| labels.cloned().collect())
+        .unwrap_or_default()
+}
+
+pub fn print_next_page(next_page_token: &str) {
+    if !next_page_token.is_empty() {
+        tracing::info!("more results available, list again with --page-token {next_page_token}");
+    }
+}
+
+// Formats when and by whom a model was created, eg. "2023-02-05T12:00:00Z by octocat".
+pub fn format_created(metadata: &Option<ModelMetadata>) -> String {
+    metadata
+        .as_ref()
+        .map(|metadata| format_change(&metadata.created_at, &metadata.created_by))
+        .unwrap_or_default()
+}
+
+pub fn format_updated(metadata: &Option<ModelMetadata>) -> String {
+    metadata
+        .as_ref()
+        .map(|metadata| format_change(&metadata.updated_at, &metadata.updated_by))
+        .unwrap_or_default()
+}
+
+fn format_change(timestamp: &Option<Timestamp>, actor: &str) -> String {
+    match timestamp {
+        Some(timestamp) => format!("{timestamp} by {actor}"),
+        None => actor.to_string(),
+    }
+}
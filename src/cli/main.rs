@@ -2,13 +2,20 @@ use std::env;
 
 use clap::Command;
 
+mod admin;
+mod annotations;
 mod assignment;
+mod audit;
 mod config;
 mod context;
+mod delete_mode;
 mod deployment;
 mod host;
+mod list;
 mod login;
 mod profile;
+mod rbac;
+mod resource_version;
 mod target;
 mod team;
 mod template;
@@ -23,11 +30,14 @@ fn cli() -> Command {
         .version("0.1.0")
         .subcommand_required(true)
         .author("Tim Park")
+        .subcommand(admin::args())
         .subcommand(assignment::args())
+        .subcommand(audit::args())
         .subcommand(config::args())
         .subcommand(deployment::args())
         .subcommand(host::args())
         .subcommand(login::args())
+        .subcommand(rbac::args())
         .subcommand(target::args())
         .subcommand(template::args())
         .subcommand(workload::args())
@@ -48,16 +58,21 @@ async fn main() -> anyhow::Result<()> {
 
     let context = Context::new(&endpoint);
 
-    match matches.subcommand() {
-        Some(("assignment", submatches)) => Ok(assignment::handlers(submatches, &context).await?),
-        Some(("config", submatches)) => Ok(config::handlers(submatches, &context).await?),
-        Some(("deployment", submatches)) => Ok(deployment::handlers(submatches, &context).await?),
-        Some(("host", submatches)) => Ok(host::handlers(submatches, &context).await?),
-        Some(("login", submatches)) => Ok(login::handlers(submatches, &context).await?),
-        Some(("target", submatches)) => Ok(target::handlers(submatches, &context).await?),
-        Some(("team", submatches)) => Ok(team::handlers(submatches, &context).await?),
-        Some(("template", submatches)) => Ok(template::handlers(submatches, &context).await?),
-        Some(("workload", submatches)) => Ok(workload::handlers(submatches, &context).await?),
+    let result = match matches.subcommand() {
+        Some(("admin", submatches)) => admin::handlers(submatches, &context).await,
+        Some(("assignment", submatches)) => assignment::handlers(submatches, &context).await,
+        Some(("audit", submatches)) => audit::handlers(submatches, &context).await,
+        Some(("config", submatches)) => config::handlers(submatches, &context).await,
+        Some(("deployment", submatches)) => deployment::handlers(submatches, &context).await,
+        Some(("host", submatches)) => host::handlers(submatches, &context).await,
+        Some(("login", submatches)) => login::handlers(submatches, &context).await,
+        Some(("rbac", submatches)) => rbac::handlers(submatches, &context).await,
+        Some(("target", submatches)) => target::handlers(submatches, &context).await,
+        Some(("team", submatches)) => team::handlers(submatches, &context).await,
+        Some(("template", submatches)) => template::handlers(submatches, &context).await,
+        Some(("workload", submatches)) => workload::handlers(submatches, &context).await,
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
-    }
+    };
+
+    resource_version::explain_conflict(result)
 }
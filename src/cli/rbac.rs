@@ -0,0 +1,185 @@
+use ascii_table::{Align, AsciiTable};
+use clap::{arg, builder::PossibleValuesParser, Arg, ArgAction, ArgGroup, Command};
+use fabriq_core::{
+    rbac::rbac_client::RbacClient, ListRoleBindingsRequest, RoleBindingIdRequest,
+    RoleBindingMessage,
+};
+use tonic::transport::Channel;
+use tonic::Request;
+
+use crate::{context::Context, list, resource_version};
+
+pub fn args() -> Command {
+    Command::new("rbac")
+        .arg_required_else_help(true)
+        .about("Manage role bindings")
+        .subcommand(
+            Command::new("create")
+                .about("Bind a role to a team or token subject")
+                .arg(
+                    Arg::new("team")
+                        .short('t')
+                        .long("team")
+                        .help("Team (eg. org/team) to bind the role to")
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("subject")
+                        .short('s')
+                        .long("subject")
+                        .help("Token subject (eg. GitHub login) to bind the role to")
+                        .action(ArgAction::Set),
+                )
+                .group(
+                    ArgGroup::new("principal")
+                        .args(["team", "subject"])
+                        .required(true),
+                )
+                .arg(resource_version::arg())
+                .arg(
+                    arg!(<ROLE> "Role to bind")
+                        .value_parser(PossibleValuesParser::new(RoleBindingMessage::ROLES)),
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("delete")
+                .about("Delete role binding")
+                .arg(arg!(<ID> "ID of role binding"))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            list::args(Command::new("list").about("List role bindings"))
+                .arg(list::filter_arg("role", "only list bindings of this role"))
+                .arg(list::filter_arg("team", "only list bindings to this team"))
+                .arg(list::filter_arg(
+                    "subject",
+                    "only list bindings to this token subject",
+                )),
+        )
+}
+
+pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyhow::Result<()> {
+    let endpoint: &str = Box::leak(Box::new(context.endpoint.clone()));
+    let channel = Channel::from_static(endpoint).connect().await?;
+
+    let token = context.make_token()?;
+
+    let mut client = RbacClient::with_interceptor(channel, move |mut req: Request<()>| {
+        req.metadata_mut().insert("authorization", token.clone());
+        Ok(req)
+    });
+
+    match model_match.subcommand() {
+        Some(("create", create_match)) => {
+            let role = create_match
+                .get_one::<String>("ROLE")
+                .expect("Role expected")
+                .to_string();
+
+            let team_id = create_match.get_one::<String>("team");
+            let subject = create_match.get_one::<String>("subject");
+
+            let id = match (team_id, subject) {
+                (Some(team_id), _) => RoleBindingMessage::make_team_id(&role, team_id),
+                (_, Some(subject)) => RoleBindingMessage::make_subject_id(&role, subject),
+                _ => unreachable!(), // clap requires one of team or subject
+            };
+
+            let request = tonic::Request::new(RoleBindingMessage {
+                id: id.clone(),
+                role,
+                team_id: team_id.cloned().unwrap_or_default(),
+                subject: subject.cloned().unwrap_or_default(),
+                resource_version: resource_version::get(create_match),
+            });
+
+            client.upsert(request).await?;
+
+            tracing::info!("role binding '{id}' created");
+
+            Ok(())
+        }
+        Some(("delete", delete_match)) => {
+            let id = delete_match
+                .get_one::<String>("ID")
+                .expect("Role binding ID expected");
+
+            let request = tonic::Request::new(RoleBindingIdRequest {
+                role_binding_id: id.to_string(),
+            });
+
+            client.delete(request).await?;
+
+            tracing::info!("role binding '{id}' deleted");
+
+            Ok(())
+        }
+        Some(("list", list_match)) => {
+            let request = tonic::Request::new(ListRoleBindingsRequest {
+                page_size: list::get_page_size(list_match),
+                page_token: list::get_string(list_match, "page-token"),
+                order_by: list::get_string(list_match, "order-by"),
+                role: list::get_string(list_match, "role"),
+                team_id: list::get_string(list_match, "team"),
+                subject: list::get_string(list_match, "subject"),
+            });
+
+            let response = client.list(request).await?.into_inner();
+
+            let table_data: Vec<Vec<String>> = response
+                .role_bindings
+                .into_iter()
+                .map(|role_binding| {
+                    vec![
+                        role_binding.id,
+                        role_binding.role,
+                        role_binding.team_id,
+                        role_binding.subject,
+                        role_binding.resource_version.to_string(),
+                    ]
+                })
+                .collect();
+
+            if table_data.is_empty() {
+                tracing::info!("no role bindings found");
+
+                return Ok(());
+            }
+
+            let mut ascii_table = AsciiTable::default();
+
+            ascii_table
+                .column(0)
+                .set_header("ID")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(1)
+                .set_header("ROLE")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(2)
+                .set_header("TEAM")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(3)
+                .set_header("SUBJECT")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(4)
+                .set_header("VERSION")
+                .set_align(Align::Left);
+
+            ascii_table.print(table_data);
+
+            list::print_next_page(&response.next_page_token);
+
+            Ok(())
+        }
+        _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
+    }
+}
@@ -0,0 +1,34 @@
+use clap::{Arg, ArgAction, ArgMatches};
+
+pub fn arg() -> Arg {
+    Arg::new("resource-version")
+        .long("resource-version")
+        .help("only update if the current resource version (see `list`) still matches")
+        .value_parser(clap::value_parser!(i64))
+        .action(ArgAction::Set)
+}
+
+// 0 asks the server to skip the concurrency check.
+pub fn get(matches: &ArgMatches) -> i64 {
+    matches
+        .get_one::<i64>("resource-version")
+        .copied()
+        .unwrap_or(0)
+}
+
+// The api rejects upserts with a stale resource version as Aborted: turn that into something a
+// person can act on.
+pub fn explain_conflict(result: anyhow::Result<()>) -> anyhow::Result<()> {
+    if let Err(err) = &result {
+        if let Some(status) = err.downcast_ref::<tonic::Status>() {
+            if status.code() == tonic::Code::Aborted {
+                return Err(anyhow::anyhow!(
+                    "conflict: {}. list it again to get the current resource version and retry.",
+                    status.message()
+                ));
+            }
+        }
+    }
+
+    result
+}
@@ -1,12 +1,12 @@
 use ascii_table::{Align, AsciiTable};
 use clap::{arg, Arg, ArgAction, Command};
 use fabriq_core::{
-    target::target_client::TargetClient, ListTargetsRequest, TargetIdRequest, TargetMessage,
+    target::target_client::TargetClient, DeleteTargetRequest, ListTargetsRequest, TargetMessage,
 };
 use tonic::transport::Channel;
 use tonic::Request;
 
-use crate::context::Context;
+use crate::{context::Context, delete_mode, list, resource_version};
 
 pub fn args() -> Command {
     Command::new("target")
@@ -23,6 +23,7 @@ pub fn args() -> Command {
                         .action(ArgAction::Set)
                         .num_args(1..),
                 )
+                .arg(resource_version::arg())
                 .arg(arg!(<ID> "Target ID"))
                 .arg_required_else_help(true),
         )
@@ -30,9 +31,13 @@ pub fn args() -> Command {
             Command::new("delete")
                 .about("Delete target")
                 .arg(arg!(<ID> "ID of target"))
+                .arg(delete_mode::arg())
                 .arg_required_else_help(true),
         )
-        .subcommand(Command::new("list").about("List targets"))
+        .subcommand(
+            list::args(Command::new("list").about("List targets"))
+                .arg(list::label_arg("only list targets with this label")),
+        )
 }
 
 pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyhow::Result<()> {
@@ -61,6 +66,8 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
             let request = tonic::Request::new(TargetMessage {
                 id: id.clone(),
                 labels,
+                resource_version: resource_version::get(add_match),
+                metadata: None,
             });
 
             client.upsert(request).await?;
@@ -74,8 +81,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .get_one::<String>("ID")
                 .expect("Target id expected");
 
-            let request = tonic::Request::new(TargetIdRequest {
+            let request = tonic::Request::new(DeleteTargetRequest {
                 target_id: id.to_string(),
+                mode: delete_mode::get(create_match),
             });
 
             client.delete(request).await?;
@@ -84,16 +92,28 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
 
             Ok(())
         }
-        Some(("list", _)) => {
-            let request = tonic::Request::new(ListTargetsRequest {});
+        Some(("list", list_match)) => {
+            let request = tonic::Request::new(ListTargetsRequest {
+                page_size: list::get_page_size(list_match),
+                page_token: list::get_string(list_match, "page-token"),
+                order_by: list::get_string(list_match, "order-by"),
+                labels: list::get_labels(list_match),
+            });
 
-            let response = client.list(request).await?;
+            let response = client.list(request).await?.into_inner();
 
             let table_data: Vec<Vec<String>> = response
-                .into_inner()
                 .targets
                 .into_iter()
-                .map(|target| vec![target.id.to_string(), target.labels.join(", ")])
+                .map(|target| {
+                    vec![
+                        target.id.to_string(),
+                        target.labels.join(", "),
+                        target.resource_version.to_string(),
+                        list::format_created(&target.metadata),
+                        list::format_updated(&target.metadata),
+                    ]
+                })
                 .collect();
 
             if table_data.is_empty() {
@@ -114,8 +134,25 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .set_header("LABELS")
                 .set_align(Align::Left);
 
+            ascii_table
+                .column(2)
+                .set_header("VERSION")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(3)
+                .set_header("CREATED")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(4)
+                .set_header("UPDATED")
+                .set_align(Align::Left);
+
             ascii_table.print(table_data);
 
+            list::print_next_page(&response.next_page_token);
+
             Ok(())
         }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
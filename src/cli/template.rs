@@ -1,13 +1,13 @@
 use ascii_table::{Align, AsciiTable};
 use clap::{arg, Arg, ArgAction, Command};
 use fabriq_core::{
-    common::TemplateIdRequest, template::template_client::TemplateClient, ListTemplatesRequest,
+    template::template_client::TemplateClient, DeleteTemplateRequest, ListTemplatesRequest,
     TemplateMessage,
 };
 use tonic::transport::Channel;
 use tonic::Request;
 
-use crate::context::Context;
+use crate::{annotations, context::Context, delete_mode, list, resource_version};
 
 pub fn args() -> Command {
     Command::new("template")
@@ -35,6 +35,9 @@ pub fn args() -> Command {
                         .help("Template git repo path to template")
                         .action(ArgAction::Set),
                 )
+                .arg(list::label_arg("label to apply to template (repeatable)"))
+                .arg(annotations::arg())
+                .arg(resource_version::arg())
                 .arg(arg!(<ID> "Template ID"))
                 .arg_required_else_help(true),
         )
@@ -42,9 +45,17 @@ pub fn args() -> Command {
             Command::new("delete")
                 .about("Delete template")
                 .arg(arg!(<ID> "ID of template"))
+                .arg(delete_mode::arg())
                 .arg_required_else_help(true),
         )
-        .subcommand(Command::new("list").about("List templates"))
+        .subcommand(
+            list::args(Command::new("list").about("List templates"))
+                .arg(list::filter_arg(
+                    "repo",
+                    "only list templates from this repository",
+                ))
+                .arg(list::label_arg("only list templates with this label")),
+        )
 }
 
 pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyhow::Result<()> {
@@ -82,6 +93,10 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 repository,
                 git_ref,
                 path,
+                resource_version: resource_version::get(add_match),
+                labels: list::get_labels(add_match),
+                annotations: annotations::get(add_match)?,
+                metadata: None,
             });
 
             client.upsert(request).await?;
@@ -94,8 +109,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
             let id = create_match
                 .get_one::<String>("ID")
                 .expect("Template id expected");
-            let request = tonic::Request::new(TemplateIdRequest {
+            let request = tonic::Request::new(DeleteTemplateRequest {
                 template_id: id.to_string(),
+                mode: delete_mode::get(create_match),
             });
 
             client.delete(request).await?;
@@ -104,13 +120,18 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
 
             Ok(())
         }
-        Some(("list", _)) => {
-            let request = tonic::Request::new(ListTemplatesRequest {});
+        Some(("list", list_match)) => {
+            let request = tonic::Request::new(ListTemplatesRequest {
+                page_size: list::get_page_size(list_match),
+                page_token: list::get_string(list_match, "page-token"),
+                order_by: list::get_string(list_match, "order-by"),
+                labels: list::get_labels(list_match),
+                repository: list::get_string(list_match, "repo"),
+            });
 
-            let response = client.list(request).await?;
+            let response = client.list(request).await?.into_inner();
 
             let table_data: Vec<Vec<String>> = response
-                .into_inner()
                 .templates
                 .into_iter()
                 .map(|template| {
@@ -119,6 +140,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                         template.repository.clone(),
                         template.git_ref.clone(),
                         template.path,
+                        template.resource_version.to_string(),
+                        list::format_created(&template.metadata),
+                        list::format_updated(&template.metadata),
                     ]
                 })
                 .collect();
@@ -151,8 +175,25 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .set_header("PATH")
                 .set_align(Align::Left);
 
+            ascii_table
+                .column(4)
+                .set_header("VERSION")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(5)
+                .set_header("CREATED")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(6)
+                .set_header("UPDATED")
+                .set_align(Align::Left);
+
             ascii_table.print(table_data);
 
+            list::print_next_page(&response.next_page_token);
+
             Ok(())
         }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
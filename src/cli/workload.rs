@@ -1,13 +1,13 @@
 use ascii_table::{Align, AsciiTable};
 use clap::{arg, Arg, ArgAction, Command};
 use fabriq_core::{
-    workload::workload_client::WorkloadClient, ListWorkloadsRequest, WorkloadIdRequest,
+    workload::workload_client::WorkloadClient, DeleteWorkloadRequest, ListWorkloadsRequest,
     WorkloadMessage,
 };
 use tonic::transport::Channel;
 use tonic::Request;
 
-use crate::context::Context;
+use crate::{annotations, context::Context, delete_mode, list, resource_version};
 
 pub fn args() -> Command {
     Command::new("workload")
@@ -30,6 +30,9 @@ pub fn args() -> Command {
                         .help("template this workload should use")
                         .action(ArgAction::Set),
                 )
+                .arg(list::label_arg("label to apply to workload (repeatable)"))
+                .arg(annotations::arg())
+                .arg(resource_version::arg())
                 .arg(arg!(<NAME> "workload name"))
                 .arg_required_else_help(true),
         )
@@ -37,6 +40,7 @@ pub fn args() -> Command {
             Command::new("delete")
                 .about("delete workload")
                 .arg(arg!(<ID> "id of workload"))
+                .arg(delete_mode::arg())
                 .arg_required_else_help(true),
         )
         .subcommand(
@@ -52,7 +56,18 @@ pub fn args() -> Command {
                 .arg(arg!(<ID> "Workload ID"))
                 .arg_required_else_help(true),
         )
-        .subcommand(Command::new("list").about("list workloads"))
+        .subcommand(
+            list::args(Command::new("list").about("list workloads"))
+                .arg(list::filter_arg(
+                    "team",
+                    "only list workloads owned by this team",
+                ))
+                .arg(list::filter_arg(
+                    "template",
+                    "only list workloads with this template id",
+                ))
+                .arg(list::label_arg("only list workloads with this label")),
+        )
 }
 
 pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyhow::Result<()> {
@@ -88,6 +103,10 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 name: workload_name.clone(),
                 team_id,
                 template_id,
+                resource_version: resource_version::get(add_match),
+                labels: list::get_labels(add_match),
+                annotations: annotations::get(add_match)?,
+                metadata: None,
             });
 
             client.upsert(request).await?;
@@ -143,8 +162,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
             let id = delete_match
                 .get_one::<String>("ID")
                 .expect("workload id expected");
-            let request = tonic::Request::new(WorkloadIdRequest {
+            let request = tonic::Request::new(DeleteWorkloadRequest {
                 workload_id: id.to_string(),
+                mode: delete_mode::get(delete_match),
             });
 
             client.delete(request).await?;
@@ -153,13 +173,19 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
 
             Ok(())
         }
-        Some(("list", _)) => {
-            let request = tonic::Request::new(ListWorkloadsRequest {});
+        Some(("list", list_match)) => {
+            let request = tonic::Request::new(ListWorkloadsRequest {
+                page_size: list::get_page_size(list_match),
+                page_token: list::get_string(list_match, "page-token"),
+                order_by: list::get_string(list_match, "order-by"),
+                labels: list::get_labels(list_match),
+                team_id: list::get_string(list_match, "team"),
+                template_id: list::get_string(list_match, "template"),
+            });
 
-            let response = client.list(request).await?;
+            let response = client.list(request).await?.into_inner();
 
             let table_data: Vec<Vec<String>> = response
-                .into_inner()
                 .workloads
                 .into_iter()
                 .map(|workload| {
@@ -168,6 +194,9 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                         workload.name.to_string(),
                         workload.team_id.clone(),
                         workload.template_id,
+                        workload.resource_version.to_string(),
+                        list::format_created(&workload.metadata),
+                        list::format_updated(&workload.metadata),
                     ]
                 })
                 .collect();
@@ -200,8 +229,25 @@ pub async fn handlers(model_match: &clap::ArgMatches, context: &Context) -> anyh
                 .set_header("TEMPLATE ID")
                 .set_align(Align::Left);
 
+            ascii_table
+                .column(4)
+                .set_header("VERSION")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(5)
+                .set_header("CREATED")
+                .set_align(Align::Left);
+
+            ascii_table
+                .column(6)
+                .set_header("UPDATED")
+                .set_align(Align::Left);
+
             ascii_table.print(table_data);
 
+            list::print_next_page(&response.next_page_token);
+
             Ok(())
         }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
@@ -1,10 +1,12 @@
+use fabriq_core::api::client::ClientToken;
+
 pub struct Context<'a> {
     pub endpoint: &'a str,
-    pub token: &'a str,
+    pub token: ClientToken,
 }
 
 impl<'a> Context<'a> {
-    pub fn new(endpoint: &'a str, token: &'a str) -> Self {
+    pub fn new(endpoint: &'a str, token: ClientToken) -> Self {
         Self { endpoint, token }
     }
 }
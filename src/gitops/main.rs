@@ -1,30 +1,41 @@
 use context::Context;
 use dotenvy::dotenv;
 use fabriq_core::{
-    git::{remote::RemoteGitRepoFactory, RemoteGitRepo},
+    api::client::ClientToken,
+    git::{RemoteGitRepo, RemoteTemplateCache},
     EventStream,
 };
 use fabriq_postgresql_stream::PostgresqlEventStream;
+use fabriq_sqlite_stream::SqliteEventStream;
 use opentelemetry::{
     sdk::{trace as sdktrace, Resource},
     KeyValue,
 };
 use opentelemetry_otlp::WithExportConfig;
 use processor::GitOpsProcessor;
+use pull_request::{
+    BranchPullRequestProvider, DeliveryPolicy, GitHubPullRequestProvider, PullRequestProvider,
+};
 use reqwest::Url;
 use sqlx::postgres::PgPoolOptions;
-use std::{env, sync::Arc};
-use tokio::time::Duration;
+use std::{env, path::Path, sync::Arc};
+use tokio::time::{Duration, Instant};
 use tonic::{
     metadata::{Ascii, MetadataValue},
     transport::Channel,
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use working_copy::{CommitIdentity, CommitMode};
 
 mod context;
 mod processor;
+mod pull_request;
+mod renderer;
+mod template_helpers;
+mod working_copy;
 
 const DEFAULT_GITOPS_CONSUMER_ID: &str = "gitops";
+const DEFAULT_TEMPLATE_CACHE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
 
 fn init_tracer() -> anyhow::Result<sdktrace::Tracer> {
     let opentelemetry_endpoint = env::var("OTEL_ENDPOINT").expect("OTEL_ENDPOINT expected");
@@ -66,31 +77,48 @@ async fn main() -> anyhow::Result<()> {
     let gitops_consumer_id =
         env::var("GITOPS_CONSUMER_ID").unwrap_or_else(|_| DEFAULT_GITOPS_CONSUMER_ID.to_string());
 
-    let database_url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
-
-    let db = Arc::new(
-        PgPoolOptions::new()
-            .max_connections(20)
-            .connect(&database_url)
-            .await
-            .expect("failed to connect to DATABASE_URL"),
-    );
-
-    sqlx::migrate!().run(&*db).await?;
-
     let subscribers: Vec<String> = dotenvy::var("SUBSCRIBERS")
         .unwrap_or_else(|_| "reconciler,gitops".to_string())
         .split(',')
         .map(|s| s.to_string())
         .collect();
 
-    let event_stream = PostgresqlEventStream {
-        db: Arc::clone(&db),
-        subscribers,
+    // the api and gitops share their DATABASE_URL, see the api for the schemes.
+    let database_url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let event_stream: Arc<dyn EventStream> = if database_url.starts_with("sqlite:") {
+        let db = Arc::new(
+            fabriq_sqlite_stream::connect(&database_url)
+                .await
+                .expect("failed to open DATABASE_URL"),
+        );
+
+        sqlx::migrate!("./migrations/sqlite").run(&*db).await?;
+
+        Arc::new(SqliteEventStream { db, subscribers })
+    } else {
+        let db = Arc::new(
+            PgPoolOptions::new()
+                .max_connections(20)
+                .connect(&database_url)
+                .await
+                .expect("failed to connect to DATABASE_URL"),
+        );
+
+        sqlx::migrate!().run(&*db).await?;
+
+        Arc::new(PostgresqlEventStream { db, subscribers })
     };
 
-    let access_token = env::var("GITOPS_ACCESS_TOKEN").expect("GITOPS_ACCESS_TOKEN must be set");
-    let access_token: &'static str = Box::leak(Box::new(access_token));
+    // a token file (eg. a projected workload identity token) takes precedence over a static token.
+    let access_token = match env::var("GITOPS_ACCESS_TOKEN_FILE") {
+        Ok(access_token_file) => ClientToken::File(access_token_file.into()),
+        Err(_) => {
+            let access_token = env::var("GITOPS_ACCESS_TOKEN")
+                .expect("GITOPS_ACCESS_TOKEN or GITOPS_ACCESS_TOKEN_FILE must be set");
+            let access_token: MetadataValue<Ascii> = access_token.parse()?;
+            ClientToken::Static(access_token)
+        }
+    };
 
     let api_endpoint = env::var("FABRIQ_API_ENDPOINT").expect("FABRIQ_API_ENDPOINT must be set");
     let api_endpoint: &'static str = Box::leak(Box::new(api_endpoint));
@@ -102,15 +130,26 @@ async fn main() -> anyhow::Result<()> {
         .expect("GITOPS_PRIVATE_SSH_KEY_BASE64 must be set");
     let private_ssh_key: String = String::from_utf8(base64::decode(&private_ssh_key_base64)?)?;
 
-    let gitops_repo = Arc::new(RemoteGitRepo::new(
-        &repo_url,
-        &repo_branch,
-        &private_ssh_key,
-    )?);
+    // the clone is kept between batches, and with a path set, between restarts as well.
+    let gitops_repo = match env::var("GITOPS_REPO_PATH") {
+        Ok(repo_path) => RemoteGitRepo::with_local_path(
+            &repo_url,
+            &repo_branch,
+            &private_ssh_key,
+            Path::new(&repo_path),
+        )?,
+        Err(_) => RemoteGitRepo::new(&repo_url, &repo_branch, &private_ssh_key)?,
+    };
+    let gitops_repo = Arc::new(gitops_repo);
 
     let context = Context::new(api_endpoint, access_token);
     let channel = Channel::from_static(context.endpoint).connect().await?;
-    let token: MetadataValue<Ascii> = context.token.parse()?;
+    let token = context.token;
+
+    let assignment_client = Arc::new(fabriq_core::api::client::WrappedAssignmentClient::new(
+        channel.clone(),
+        token.clone(),
+    ));
 
     let config_client = Arc::new(fabriq_core::api::client::WrappedConfigClient::new(
         channel.clone(),
@@ -122,6 +161,11 @@ async fn main() -> anyhow::Result<()> {
         token.clone(),
     ));
 
+    let host_client = Arc::new(fabriq_core::api::client::WrappedHostClient::new(
+        channel.clone(),
+        token.clone(),
+    ));
+
     let template_client = Arc::new(fabriq_core::api::client::WrappedTemplateClient::new(
         channel.clone(),
         token.clone(),
@@ -133,47 +177,116 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("starting");
 
-    let template_repo_factory = Arc::new(RemoteGitRepoFactory {});
+    let template_cache_refresh_interval = match env::var("GITOPS_TEMPLATE_CACHE_REFRESH_SECONDS") {
+        Ok(seconds) => Duration::from_secs(seconds.parse()?),
+        Err(_) => DEFAULT_TEMPLATE_CACHE_REFRESH_INTERVAL,
+    };
+
+    let template_cache = Arc::new(match env::var("GITOPS_TEMPLATE_CACHE_PATH") {
+        Ok(template_cache_path) => RemoteTemplateCache::with_local_path(
+            &private_ssh_key,
+            template_cache_refresh_interval,
+            Path::new(&template_cache_path),
+        ),
+        Err(_) => RemoteTemplateCache::new(&private_ssh_key, template_cache_refresh_interval)?,
+    });
+
+    let commit_mode = match env::var("GITOPS_COMMIT_MODE") {
+        Ok(commit_mode) => commit_mode.parse()?,
+        Err(_) => CommitMode::default(),
+    };
+
+    let pull_request_provider: Arc<dyn PullRequestProvider> =
+        match env::var("GITOPS_PULL_REQUEST_PROVIDER").as_deref() {
+            Ok("github") => {
+                let github_token =
+                    env::var("GITOPS_GITHUB_TOKEN").expect("GITOPS_GITHUB_TOKEN must be set");
+
+                Arc::new(GitHubPullRequestProvider::new(
+                    &repo_url,
+                    &repo_branch,
+                    &github_token,
+                )?)
+            }
+            Ok("branch") | Err(_) => Arc::new(BranchPullRequestProvider::default()),
+            Ok(provider) => {
+                return Err(anyhow::anyhow!(
+                    "unknown pull request provider '{provider}', expected github or branch"
+                ))
+            }
+        };
 
     let mut gitops_processor = GitOpsProcessor {
         gitops_repo,
-        private_ssh_key,
 
-        template_repo_factory,
+        template_cache,
 
+        assignment_client,
         config_client,
         deployment_client,
+        host_client,
         template_client,
         workload_client,
+
+        commit_mode,
+        commit_identity: CommitIdentity::from_env(),
+
+        delivery_policy: DeliveryPolicy::from_env(),
+        pull_request_provider,
+    };
+
+    // `gitops reconcile` reconciles the repo once, on demand, instead of processing events.
+    if env::args().nth(1).as_deref() == Some("reconcile") {
+        let changed = gitops_processor.reconcile_repo().await?;
+        tracing::info!("reconciled gitops repo, changed: {changed}");
+
+        return Ok(());
+    }
+
+    let reconcile_interval = match env::var("GITOPS_RECONCILE_INTERVAL_SECONDS") {
+        Ok(seconds) => Some(Duration::from_secs(seconds.parse()?)),
+        Err(_) => None,
     };
+    let mut last_reconciled: Option<Instant> = None;
 
     tracing::info!("starting event loop");
 
     loop {
+        if let Some(reconcile_interval) = reconcile_interval {
+            if last_reconciled
+                .is_none_or(|last_reconciled| last_reconciled.elapsed() >= reconcile_interval)
+            {
+                match gitops_processor.reconcile_repo().await {
+                    Ok(changed) => tracing::info!("reconciled gitops repo, changed: {changed}"),
+                    Err(err) => tracing::error!("gitops processor: failed to reconcile: {}", err),
+                }
+
+                last_reconciled = Some(Instant::now());
+            }
+        }
+
         tracing::info!("fetching events");
 
         let events = event_stream.receive(&gitops_consumer_id).await?;
 
         tracing::info!("event loop fetched {} events", events.len());
 
-        for event in events.iter() {
-            tracing::info!(
-                "event loop processing event id {} with type {} and model {}",
-                event.id,
-                event.event_type,
-                event.model_type
-            );
-            match gitops_processor.process(event).await {
-                Ok(_) => {
-                    tracing::info!("gitops processor: processed event successfully");
-                    event_stream.delete(event, &gitops_consumer_id).await?;
-                }
-                Err(err) => {
-                    tracing::error!("gitops processor: failed to process event: {}", err);
-                    tokio::time::sleep(Duration::from_millis(1000)).await;
-                    break;
-                }
-            };
+        let processed = match gitops_processor.process_batch(&events).await {
+            Ok(processed) => processed,
+            Err(err) => {
+                tracing::error!("gitops processor: failed to process batch: {}", err);
+                0
+            }
+        };
+
+        for event in &events[..processed] {
+            event_stream.delete(event, &gitops_consumer_id).await?;
+        }
+
+        tracing::info!("gitops processor: processed {processed} events");
+
+        if processed < events.len() {
+            tokio::time::sleep(Duration::from_millis(1000)).await;
         }
 
         if events.is_empty() {
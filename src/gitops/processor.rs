@@ -1,32 +1,56 @@
-use fabriq_core::git::ClonedGitRepo;
+use fabriq_core::git::{memory::MemoryClonedGitRepo, ClonedGitRepo};
 use handlebars::{to_json, Handlebars};
 use serde_json::value::{Map, Value as Json};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::path::Path;
 use std::sync::Arc;
-use std::{collections::HashMap, path::PathBuf};
 use tonic::Request;
 
+use crate::pull_request::{DeliveryPolicy, PullRequestProvider};
+use crate::renderer::{take_host_patches, TemplateFiles, TemplateManifest};
+use crate::working_copy::{CommitIdentity, CommitMode, WorkingCopy};
+
 use fabriq_core::{
-    common::TemplateIdRequest,
+    common::{HostIdRequest, TemplateIdRequest},
     get_current_or_previous_model,
-    git::{GitRepo, GitRepoFactory},
-    AssignmentMessage, ConfigMessage, ConfigTrait, ConfigValueType, DeploymentIdRequest,
-    DeploymentMessage, DeploymentTrait, Event, EventType, HostMessage, ModelType,
-    QueryConfigRequest, TargetMessage, TemplateMessage, TemplateTrait, WorkloadIdRequest,
-    WorkloadMessage, WorkloadTrait,
+    git::{GitRepo, TemplateCache},
+    AssignmentMessage, AssignmentTrait, ConfigMessage, ConfigTrait, ConfigValueType,
+    DeploymentIdRequest, DeploymentMessage, DeploymentTrait, Event, EventType, HostMessage,
+    HostTrait, ListAssignmentsRequest, ListDeploymentsRequest, ModelType, QueryConfigRequest,
+    TargetMessage, TemplateMessage, TemplateTrait, WorkloadIdRequest, WorkloadMessage,
+    WorkloadTrait,
 };
 
+// Written next to each rendered deployment, naming the template commit it was rendered from.
+const TEMPLATE_SOURCE_FILE_NAME: &str = ".fabriq-template.json";
+
+// The directories the processor renders into, and reconciliation owns outright.
+const RECONCILED_DIRECTORIES: [&str; 2] = ["deployments", "hosts"];
+
+// Reconciliation changes for reviewed targets are proposed from one branch, so that a pull
+// request left open is updated by the next reconciliation rather than joined by another.
+const RECONCILE_BRANCH: &str = "fabriq/reconcile";
+
+const RECONCILE_PAGE_SIZE: i32 = 1000;
+
 pub struct GitOpsProcessor {
     pub gitops_repo: Arc<dyn GitRepo>,
-    pub private_ssh_key: String,
 
-    pub template_repo_factory: Arc<dyn GitRepoFactory>,
+    pub template_cache: Arc<dyn TemplateCache>,
 
+    pub assignment_client: Arc<dyn AssignmentTrait>,
     pub config_client: Arc<dyn ConfigTrait>,
     pub deployment_client: Arc<dyn DeploymentTrait>,
+    pub host_client: Arc<dyn HostTrait>,
     pub template_client: Arc<dyn TemplateTrait>,
     pub workload_client: Arc<dyn WorkloadTrait>,
+
+    pub commit_mode: CommitMode,
+    pub commit_identity: CommitIdentity,
+
+    pub delivery_policy: DeliveryPolicy,
+    pub pull_request_provider: Arc<dyn PullRequestProvider>,
 }
 
 impl Debug for GitOpsProcessor {
@@ -36,33 +60,248 @@ impl Debug for GitOpsProcessor {
 }
 
 impl GitOpsProcessor {
+    // Renders events into one clone of the gitops repo, committing per commit_mode and pushing
+    // once. Returns how many events were processed, which is fewer than all of them if one fails:
+    // those before it are still pushed, so that only the rest need to be retried.
+    //
+    // Operations whose changes the delivery policy says need review are instead committed on a
+    // branch of their own, which is pushed and opened as a pull request once the rest are pushed.
+    // To keep them apart, each operation is committed separately whenever there is such a policy.
     #[tracing::instrument(skip_all)]
-    pub async fn process(&mut self, event: &Event) -> anyhow::Result<()> {
+    pub async fn process_batch(&mut self, events: &[Event]) -> anyhow::Result<usize> {
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        let working_copy =
+            WorkingCopy::new(self.gitops_repo.clone_repo()?, self.commit_identity.clone());
+        let mut committed = false;
+        let mut pull_requests = vec![];
+
+        let commit_per_operation =
+            self.commit_mode == CommitMode::Operation || self.delivery_policy.has_pull_requests();
+
+        for (index, event) in events.iter().enumerate() {
+            tracing::info!(
+                "processing event id {} with type {} and model {}",
+                event.id,
+                event.event_type,
+                event.model_type
+            );
+
+            if let Err(err) = self.process(event, &working_copy).await {
+                tracing::error!("failed to process event id {}: {}", event.id, err);
+
+                // The working copy may hold part of the failed event's changes, so the events
+                // before it are rendered again into a fresh clone.
+                return Box::pin(self.process_batch(&events[..index])).await;
+            }
+
+            working_copy.record_event(event)?;
+
+            let operation_ends = events
+                .get(index + 1)
+                .is_none_or(|next_event| next_event.operation_id != event.operation_id);
+
+            if commit_per_operation && operation_ends {
+                if working_copy.requires_pull_request(&self.delivery_policy) {
+                    pull_requests.extend(working_copy.propose()?);
+                } else {
+                    committed |= working_copy.commit()?;
+                }
+            }
+        }
+
+        committed |= working_copy.commit()?;
+
+        if committed {
+            working_copy.repo.push()?;
+        }
+
+        for pull_request in &pull_requests {
+            working_copy.repo.push_branch(&pull_request.branch)?;
+
+            let location = self.pull_request_provider.open(pull_request).await?;
+            tracing::info!("opened pull request {}", location);
+        }
+
+        Ok(events.len())
+    }
+
+    // Renders everything the api holds into a scratch repo and brings the gitops repo's
+    // deployments and hosts directories in line with it, in one commit. Files nothing renders
+    // any more, eg. those of deleted workloads or of events that were never processed, are
+    // removed. With a delivery policy set, the commit is proposed as a pull request instead.
+    // Returns whether anything changed.
+    #[tracing::instrument(skip_all)]
+    pub async fn reconcile_repo(&mut self) -> anyhow::Result<bool> {
+        let desired_repo: Arc<dyn ClonedGitRepo> = Arc::new(MemoryClonedGitRepo::new());
+        self.render_desired_repo(&desired_repo).await?;
+
+        let working_copy =
+            WorkingCopy::new(self.gitops_repo.clone_repo()?, self.commit_identity.clone());
+        let cloned_repo = &working_copy.repo;
+
+        for directory in RECONCILED_DIRECTORIES {
+            let desired_paths = desired_repo.list_files(directory)?;
+
+            for path in &desired_paths {
+                let contents = desired_repo.read_file(path.into())?;
+
+                if cloned_repo.read_file(path.into()).ok() != Some(contents.clone()) {
+                    cloned_repo.write_file(path, &contents)?;
+                    cloned_repo.add_path(path.into())?;
+                    working_copy.record(format!("Reconciled {path}"));
+                }
+            }
+
+            for path in cloned_repo.list_files(directory)? {
+                if !desired_paths.contains(&path) {
+                    cloned_repo.remove_file(&path)?;
+                    working_copy.record(format!("Removed orphaned {path}"));
+                }
+            }
+        }
+
+        if self.delivery_policy.has_pull_requests() {
+            let pull_request = match working_copy.propose_on(RECONCILE_BRANCH)? {
+                Some(pull_request) => pull_request,
+                None => return Ok(false),
+            };
+
+            working_copy.repo.push_branch(&pull_request.branch)?;
+
+            let location = self.pull_request_provider.open(&pull_request).await?;
+            tracing::info!(
+                "opened pull request {} to reconcile the gitops repo",
+                location
+            );
+
+            return Ok(true);
+        }
+
+        let committed = working_copy.commit()?;
+
+        if committed {
+            working_copy.repo.push()?;
+        }
+
+        Ok(committed)
+    }
+
+    async fn render_desired_repo(
+        &self,
+        desired_repo: &Arc<dyn ClonedGitRepo>,
+    ) -> anyhow::Result<()> {
+        let mut workloads: HashMap<String, Option<WorkloadMessage>> = HashMap::new();
+
+        for deployment in self.list_deployments().await? {
+            if !workloads.contains_key(&deployment.workload_id) {
+                let workload = self.get_workload(&deployment.workload_id).await?;
+                workloads.insert(deployment.workload_id.clone(), workload);
+            }
+
+            if let Some(workload) = &workloads[&deployment.workload_id] {
+                let configs = self.get_deployment_configs(&deployment.id).await?;
+
+                self.render_deployment(&configs, workload, &deployment, desired_repo)
+                    .await?;
+            }
+        }
+
+        for assignment in self.list_assignments("").await? {
+            self.render_assignment(&assignment.host_id, &assignment.deployment_id, desired_repo)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    // Lists the assignments of host_id, or every assignment if host_id is empty.
+    async fn list_assignments(&self, host_id: &str) -> anyhow::Result<Vec<AssignmentMessage>> {
+        let mut assignments = vec![];
+        let mut page_token = String::new();
+
+        loop {
+            let response = self
+                .assignment_client
+                .list(Request::new(ListAssignmentsRequest {
+                    page_size: RECONCILE_PAGE_SIZE,
+                    page_token,
+                    host_id: host_id.to_string(),
+                    ..Default::default()
+                }))
+                .await?
+                .into_inner();
+
+            assignments.extend(response.assignments);
+
+            if response.next_page_token.is_empty() {
+                return Ok(assignments);
+            }
+
+            page_token = response.next_page_token;
+        }
+    }
+
+    async fn list_deployments(&self) -> anyhow::Result<Vec<DeploymentMessage>> {
+        let mut deployments = vec![];
+        let mut page_token = String::new();
+
+        loop {
+            let response = self
+                .deployment_client
+                .list(Request::new(ListDeploymentsRequest {
+                    page_size: RECONCILE_PAGE_SIZE,
+                    page_token,
+                    ..Default::default()
+                }))
+                .await?
+                .into_inner();
+
+            deployments.extend(response.deployments);
+
+            if response.next_page_token.is_empty() {
+                return Ok(deployments);
+            }
+
+            page_token = response.next_page_token;
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn process(
+        &mut self,
+        event: &Event,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         let model_type = event.model_type;
 
         tracing::info!("model_type: {:?}", model_type);
 
         match model_type {
             model_type if model_type == ModelType::Assignment as i32 => {
-                self.process_assignment_event(event).await
+                self.process_assignment_event(event, working_copy).await
             }
             model_type if model_type == ModelType::Config as i32 => {
-                self.process_config_event(event).await
+                self.process_config_event(event, working_copy).await
             }
             model_type if model_type == ModelType::Deployment as i32 => {
-                self.process_deployment_event(event).await
+                self.process_deployment_event(event, working_copy).await
             }
             model_type if model_type == ModelType::Host as i32 => {
-                self.process_host_event(event).await
+                self.process_host_event(event, working_copy).await
             }
+            // role bindings only affect authorization, so there is nothing to reconcile.
+            model_type if model_type == ModelType::RoleBinding as i32 => Ok(()),
             model_type if model_type == ModelType::Target as i32 => {
                 self.process_target_event(event).await
             }
             model_type if model_type == ModelType::Template as i32 => {
-                self.process_template_event(event).await
+                self.process_template_event(event, working_copy).await
             }
             model_type if model_type == ModelType::Workload as i32 => {
-                self.process_workload_event(event).await
+                self.process_workload_event(event, working_copy).await
             }
             _ => {
                 let message = format!("Unknown model type: {}", model_type);
@@ -74,7 +313,11 @@ impl GitOpsProcessor {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn process_assignment_event(&self, event: &Event) -> anyhow::Result<()> {
+    async fn process_assignment_event(
+        &self,
+        event: &Event,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         let event_type = event.event_type;
         let assignment = get_current_or_previous_model::<AssignmentMessage>(event)?;
 
@@ -82,15 +325,18 @@ impl GitOpsProcessor {
 
         match event_type {
             event_type if event_type == EventType::Created as i32 => {
-                self.update_assignment(&assignment, true).await?;
+                self.update_assignment(&assignment, true, working_copy)
+                    .await?;
                 tracing::info!("assignment id {} created", assignment.id);
             }
             event_type if event_type == EventType::Updated as i32 => {
-                self.update_assignment(&assignment, true).await?;
+                self.update_assignment(&assignment, true, working_copy)
+                    .await?;
                 tracing::info!("assignment id {} updated", assignment.id);
             }
             event_type if event_type == EventType::Deleted as i32 => {
-                self.update_assignment(&assignment, false).await?;
+                self.update_assignment(&assignment, false, working_copy)
+                    .await?;
                 tracing::info!("assignment id {} deleted", assignment.id);
             }
             _ => {
@@ -102,7 +348,11 @@ impl GitOpsProcessor {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn process_config_event(&self, event: &Event) -> anyhow::Result<()> {
+    async fn process_config_event(
+        &self,
+        event: &Event,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         // Created
         // Updated
         // Deleted: Rerender all deployments that could be effected by this config change.
@@ -124,21 +374,26 @@ impl GitOpsProcessor {
                 ConfigMessage::DEPLOYMENT_OWNER => {
                     let deployment = self.get_deployment(model_id).await?;
                     if let Some(deployment) = deployment {
-                        self.update_deployment(&deployment, true).await?;
+                        self.update_deployment(&deployment, true, working_copy)
+                            .await?;
                     }
                 }
 
+                ConfigMessage::HOST_OWNER => {
+                    self.update_host(model_id, working_copy).await?;
+                }
+
                 ConfigMessage::TEMPLATE_OWNER => {
                     let template = self.get_template(model_id).await?;
                     if let Some(template) = template {
-                        self.update_template(&template).await?;
+                        self.update_template(&template, working_copy).await?;
                     }
                 }
 
                 ConfigMessage::WORKLOAD_OWNER => {
                     let workload = self.get_workload(model_id).await?;
                     if let Some(workload) = workload {
-                        self.update_workload(&workload).await?;
+                        self.update_workload(&workload, working_copy).await?;
                     }
                 }
 
@@ -152,7 +407,11 @@ impl GitOpsProcessor {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn process_deployment_event(&mut self, event: &Event) -> anyhow::Result<()> {
+    async fn process_deployment_event(
+        &mut self,
+        event: &Event,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         // Created, Updated: Render deployment in deployments directory.
         // Deleted: Remove deployment from deployments directory.
 
@@ -163,15 +422,18 @@ impl GitOpsProcessor {
 
         match event_type {
             event_type if event_type == EventType::Created as i32 => {
-                self.update_deployment(&deployment, true).await?;
+                self.update_deployment(&deployment, true, working_copy)
+                    .await?;
                 tracing::info!("deployment id {} created", deployment.id);
             }
             event_type if event_type == EventType::Updated as i32 => {
-                self.update_deployment(&deployment, true).await?;
+                self.update_deployment(&deployment, true, working_copy)
+                    .await?;
                 tracing::info!("deployment id {} updated", deployment.id);
             }
             event_type if event_type == EventType::Deleted as i32 => {
-                self.update_deployment(&deployment, false).await?;
+                self.update_deployment(&deployment, false, working_copy)
+                    .await?;
                 tracing::info!("deployment id {} deleted", deployment.id);
             }
             _ => {
@@ -183,7 +445,11 @@ impl GitOpsProcessor {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn process_host_event(&self, event: &Event) -> anyhow::Result<()> {
+    async fn process_host_event(
+        &self,
+        event: &Event,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         let event_type = event.event_type;
         let host = get_current_or_previous_model::<HostMessage>(event)?;
 
@@ -194,10 +460,18 @@ impl GitOpsProcessor {
                 tracing::info!("host id {} created (NOP)", host.id);
             }
             event_type if event_type == EventType::Updated as i32 => {
-                tracing::info!("host id {} updated (NOP)", host.id);
+                self.update_host(&host.id, working_copy).await?;
+                tracing::info!("host id {} updated", host.id);
             }
             event_type if event_type == EventType::Deleted as i32 => {
-                tracing::info!("host id {} deleted (NOP)", host.id);
+                working_copy
+                    .repo
+                    .remove_dir(&Self::make_host_directory(&host.id))?;
+                working_copy.record(format!("Deleted host {}", host.id));
+
+                // its assignments, and so the targets they were for, are already gone.
+                working_copy.record_unknown_target();
+                tracing::info!("host id {} deleted", host.id);
             }
             _ => {
                 tracing::error!("unsupported event type: {:?}", event);
@@ -233,7 +507,11 @@ impl GitOpsProcessor {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn process_template_event(&self, event: &Event) -> anyhow::Result<()> {
+    async fn process_template_event(
+        &self,
+        event: &Event,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         let event_type = event.event_type;
         let template = get_current_or_previous_model::<TemplateMessage>(event)?;
 
@@ -244,7 +522,7 @@ impl GitOpsProcessor {
                 tracing::info!("template id {} created (NOP)", template.id);
             }
             event_type if event_type == EventType::Updated as i32 => {
-                self.update_template(&template).await?;
+                self.update_template(&template, working_copy).await?;
                 tracing::info!("template id {} updated", template.id);
             }
             event_type if event_type == EventType::Deleted as i32 => {
@@ -259,7 +537,11 @@ impl GitOpsProcessor {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn process_workload_event(&self, event: &Event) -> anyhow::Result<()> {
+    async fn process_workload_event(
+        &self,
+        event: &Event,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         let event_type = event.event_type;
         let workload = get_current_or_previous_model::<WorkloadMessage>(event)?;
 
@@ -267,15 +549,27 @@ impl GitOpsProcessor {
 
         match event_type {
             event_type if event_type == EventType::Created as i32 => {
-                self.update_workload(&workload).await?;
+                self.update_workload(&workload, working_copy).await?;
                 tracing::info!("workload id {} created", workload.id);
             }
             event_type if event_type == EventType::Updated as i32 => {
-                self.update_workload(&workload).await?;
+                self.update_workload(&workload, working_copy).await?;
                 tracing::info!("workload id {} updated", workload.id);
             }
             event_type if event_type == EventType::Deleted as i32 => {
-                tracing::info!("workload id {} deleted (NOP)", workload.id);
+                let (organization_name, team_name) =
+                    WorkloadMessage::split_team_id(&workload.team_id)?;
+
+                working_copy.repo.remove_dir(&Self::make_workload_path(
+                    &organization_name,
+                    &team_name,
+                    &workload.name,
+                ))?;
+                working_copy.record(format!("Deleted workload {}", workload.id));
+
+                // its deployments, and so their targets, are already gone.
+                working_copy.record_unknown_target();
+                tracing::info!("workload id {} deleted", workload.id);
             }
             _ => {
                 tracing::error!("unsupported event type: {:?}", event);
@@ -289,21 +583,24 @@ impl GitOpsProcessor {
         &self,
         assignment: &AssignmentMessage,
         created: bool,
+        working_copy: &WorkingCopy,
     ) -> anyhow::Result<()> {
         let (team_id, workload_name, deployment_name) =
             DeploymentMessage::split_id(&assignment.deployment_id)?;
 
-        let cloned_repo = self.gitops_repo.clone_repo()?;
+        let cloned_repo = &working_copy.repo;
+
+        // only needed to decide whether the change needs review.
+        if self.delivery_policy.has_pull_requests() {
+            match self.get_deployment(&assignment.deployment_id).await? {
+                Some(deployment) => working_copy.record_target(&deployment.target_id),
+                None => working_copy.record_unknown_target(),
+            }
+        }
 
         if created {
-            self.render_assignment(
-                &assignment.host_id,
-                &team_id,
-                &workload_name,
-                &deployment_name,
-                &cloned_repo,
-            )
-            .await?;
+            self.render_assignment(&assignment.host_id, &assignment.deployment_id, cloned_repo)
+                .await?;
         } else {
             let (organization_name, team_name) = WorkloadMessage::split_team_id(&team_id)?;
 
@@ -318,13 +615,7 @@ impl GitOpsProcessor {
             cloned_repo.remove_dir(&assignment_path)?;
         }
 
-        // TODO: Add generic capability to handle commit
-        // TODO: Need to figure out how to plumb user effecting these changes here.
-
-        let message = format!("Updated assignment {}", assignment.id);
-
-        cloned_repo.commit("Tim Park", "timfpark@gmail.com", &message)?;
-        cloned_repo.push()?;
+        working_copy.record(format!("Updated assignment {}", assignment.id));
 
         Ok(())
     }
@@ -355,6 +646,27 @@ impl GitOpsProcessor {
         }
     }
 
+    async fn get_host(&self, host_id: &str) -> anyhow::Result<Option<HostMessage>> {
+        match self
+            .host_client
+            .get_by_id(Request::new(HostIdRequest {
+                host_id: host_id.to_string(),
+            }))
+            .await
+        {
+            Ok(response) => Ok(Some(response.into_inner())),
+            Err(err) => {
+                if err.code() == tonic::Code::NotFound {
+                    tracing::warn!("host not found (possibly deleted in the meantime), moving on");
+
+                    Ok(None)
+                } else {
+                    Err(anyhow::format_err!("error getting host: {}", err))
+                }
+            }
+        }
+    }
+
     async fn get_template(&self, template_id: &str) -> anyhow::Result<Option<TemplateMessage>> {
         match self
             .template_client
@@ -413,10 +725,20 @@ impl GitOpsProcessor {
         &self,
         deployment: &DeploymentMessage,
         create: bool,
+        working_copy: &WorkingCopy,
     ) -> anyhow::Result<()> {
-        let cloned_repo = self.gitops_repo.clone_repo()?;
+        let cloned_repo = &working_copy.repo;
 
-        cloned_repo.remove_dir(&deployment.id)?;
+        let (team_id, workload_name, deployment_name) =
+            DeploymentMessage::split_id(&deployment.id)?;
+        let (organization_name, team_name) = WorkloadMessage::split_team_id(&team_id)?;
+
+        cloned_repo.remove_dir(&Self::make_deployment_path(
+            &organization_name,
+            &team_name,
+            &workload_name,
+            &deployment_name,
+        ))?;
 
         if create {
             // check to make sure the deployment still exists, otherwise delete deployment.
@@ -425,31 +747,87 @@ impl GitOpsProcessor {
                 // check to make sure the workload still exists, otherwise delete deployment.
                 let workload = self.get_workload(&deployment.workload_id).await?;
                 if let Some(workload) = workload {
-                    let config_request = Request::new(QueryConfigRequest {
-                        model_name: "deployment".to_string(),
-                        model_id: deployment.id.clone(),
-                    });
+                    let configs = self.get_deployment_configs(&deployment.id).await?;
 
-                    let response = self.config_client.query(config_request).await?.into_inner();
-                    let configs = response.configs;
+                    self.render_deployment(&configs, &workload, &deployment, cloned_repo)
+                        .await?;
 
-                    self.render_deployment(&configs, &workload, &deployment, &cloned_repo)
+                    // the deployment's values are also rendered into the patches of each host, and
+                    // patches its template no longer has are removed from them.
+                    self.render_deployment_assignments(&deployment, cloned_repo)
                         .await?;
                 }
             }
         }
 
-        // TODO: Add generic capability to handle commit
-        // TODO: Need to figure out how to plumb user making these changes here.
-        let message = format!("Updated deployment {}", deployment.id);
+        working_copy.record(format!("Updated deployment {}", deployment.id));
+        working_copy.record_target(&deployment.target_id);
+
+        Ok(())
+    }
+
+    async fn get_deployment_configs(
+        &self,
+        deployment_id: &str,
+    ) -> anyhow::Result<Vec<ConfigMessage>> {
+        let config_request = Request::new(QueryConfigRequest {
+            model_name: "deployment".to_string(),
+            model_id: deployment_id.to_string(),
+        });
+
+        let response = self.config_client.query(config_request).await?.into_inner();
+
+        Ok(response.configs)
+    }
+
+    async fn get_host_configs(&self, host_id: &str) -> anyhow::Result<Vec<ConfigMessage>> {
+        let config_request = Request::new(QueryConfigRequest {
+            model_name: ConfigMessage::HOST_OWNER.to_string(),
+            model_id: host_id.to_string(),
+        });
+
+        let response = self.config_client.query(config_request).await?.into_inner();
+
+        Ok(response.configs)
+    }
+
+    async fn render_deployment_assignments(
+        &self,
+        deployment: &DeploymentMessage,
+        cloned_repo: &Arc<dyn ClonedGitRepo>,
+    ) -> anyhow::Result<()> {
+        let assignments = self
+            .assignment_client
+            .get_by_deployment_id(Request::new(DeploymentIdRequest {
+                deployment_id: deployment.id.clone(),
+            }))
+            .await?
+            .into_inner()
+            .assignments;
+
+        for assignment in assignments {
+            self.render_assignment(&assignment.host_id, &deployment.id, cloned_repo)
+                .await?;
+        }
+
+        Ok(())
+    }
 
-        cloned_repo.commit("Tim Park", "timfpark@gmail.com", &message)?;
-        cloned_repo.push()?;
+    // A host's labels and configs are rendered into the host patches of its assignments.
+    async fn update_host(&self, host_id: &str, working_copy: &WorkingCopy) -> anyhow::Result<()> {
+        for assignment in self.list_assignments(host_id).await? {
+            self.update_assignment(&assignment, true, working_copy)
+                .await?;
+        }
 
         Ok(())
     }
 
-    async fn update_template(&self, template: &TemplateMessage) -> anyhow::Result<()> {
+    async fn update_template(
+        &self,
+        template: &TemplateMessage,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         let workloads_by_template_id_request = Request::new(TemplateIdRequest {
             template_id: template.id.clone(),
         });
@@ -462,7 +840,7 @@ impl GitOpsProcessor {
             .workloads;
 
         for workload in workloads {
-            self.update_workload(&workload).await?;
+            self.update_workload(&workload, working_copy).await?;
         }
 
         let deployments_by_template_id_request = Request::new(TemplateIdRequest {
@@ -477,13 +855,18 @@ impl GitOpsProcessor {
             .deployments;
 
         for deployment in deployments {
-            self.update_deployment(&deployment, true).await?;
+            self.update_deployment(&deployment, true, working_copy)
+                .await?;
         }
 
         Ok(())
     }
 
-    async fn update_workload(&self, workload: &WorkloadMessage) -> anyhow::Result<()> {
+    async fn update_workload(
+        &self,
+        workload: &WorkloadMessage,
+        working_copy: &WorkingCopy,
+    ) -> anyhow::Result<()> {
         let deployments_by_workload_id_request = Request::new(WorkloadIdRequest {
             workload_id: workload.id.clone(),
         });
@@ -496,51 +879,91 @@ impl GitOpsProcessor {
             .deployments;
 
         for deployment in deployments {
-            self.update_deployment(&deployment, true).await?;
+            self.update_deployment(&deployment, true, working_copy)
+                .await?;
         }
 
         Ok(())
     }
 
+    // Renders the host's kustomization of the deployment, along with the deployment template's
+    // host patches, if it has any.
     #[tracing::instrument(skip_all)]
     async fn render_assignment(
         &self,
         host_id: &str,
-        team_id: &str,
-        workload_name: &str,
-        deployment_name: &str,
+        deployment_id: &str,
         cloned_repo: &Arc<dyn ClonedGitRepo>,
     ) -> anyhow::Result<()> {
-        let (organization_name, team_name) = WorkloadMessage::split_team_id(team_id)?;
+        let (team_id, workload_name, deployment_name) = DeploymentMessage::split_id(deployment_id)?;
+        let (organization_name, team_name) = WorkloadMessage::split_team_id(&team_id)?;
+
+        let assignment_directory = GitOpsProcessor::make_assignment_directory(
+            host_id,
+            &organization_name,
+            &team_name,
+            &workload_name,
+            &deployment_name,
+        );
 
         let assignment_path = GitOpsProcessor::make_assignment_path(
             host_id,
             &organization_name,
             &team_name,
-            workload_name,
-            deployment_name,
+            &workload_name,
+            &deployment_name,
         );
 
         let deployment_path = GitOpsProcessor::make_deployment_path(
             &organization_name,
             &team_name,
-            workload_name,
-            deployment_name,
+            &workload_name,
+            &deployment_name,
         );
 
         let host_relative_deployment_path = format!("../../../../../../{}", deployment_path);
 
+        let host_patches = self.render_host_patches(host_id, deployment_id).await?;
+        let mut rendered_paths = vec![assignment_path.clone()];
+
+        for (relative_path, contents) in &host_patches {
+            let patch_path = Path::new(&assignment_directory).join(relative_path);
+            cloned_repo.write_file(&patch_path.to_string_lossy(), contents)?;
+            cloned_repo.add_path(patch_path.clone())?;
+
+            rendered_paths.push(patch_path.to_string_lossy().to_string());
+        }
+
+        // patches the template no longer has are removed.
+        for path in cloned_repo.list_files(&assignment_directory)? {
+            if !rendered_paths.contains(&path) {
+                cloned_repo.remove_file(&path)?;
+            }
+        }
+
         let template_string = "apiVersion: kustomize.config.k8s.io/v1beta1\n\
             kind: Kustomization\n\
             resources:\n\
-            - {{relative_deployment_path}}";
+            - {{relative_deployment_path}}\
+            {{#if patches}}\n\
+            patches:\
+            {{#each patches}}\n\
+            - path: {{{this}}}\
+            {{/each}}\
+            {{/if}}";
 
         let mut handlebars = Handlebars::new();
         handlebars.register_template_string("assignment", template_string)?;
 
-        let key = "relative_deployment_path".to_owned();
-        let mut values: HashMap<&str, &str> = HashMap::new();
-        values.insert(&key, &host_relative_deployment_path);
+        let patch_paths: Vec<String> = host_patches
+            .keys()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+
+        let values = serde_json::json!({
+            "relative_deployment_path": host_relative_deployment_path,
+            "patches": patch_paths,
+        });
 
         let rendered_assignment = handlebars.render("assignment", &values)?;
 
@@ -550,6 +973,68 @@ impl GitOpsProcessor {
         Ok(())
     }
 
+    // Renders the host patches of the deployment's template with the deployment's values, the
+    // host's configs over its own, and the host's id and labels as host.id and host.labels.
+    async fn render_host_patches(
+        &self,
+        host_id: &str,
+        deployment_id: &str,
+    ) -> anyhow::Result<TemplateFiles> {
+        let deployment = match self.get_deployment(deployment_id).await? {
+            Some(deployment) => deployment,
+            None => return Ok(TemplateFiles::new()),
+        };
+
+        let workload = match self.get_workload(&deployment.workload_id).await? {
+            Some(workload) => workload,
+            None => return Ok(TemplateFiles::new()),
+        };
+
+        let template_id = deployment
+            .template_id
+            .clone()
+            .unwrap_or_else(|| workload.template_id.clone());
+
+        let template = match self.get_template(&template_id).await? {
+            Some(template) => template,
+            None => return Ok(TemplateFiles::new()),
+        };
+
+        let snapshot =
+            self.template_cache
+                .get(&template.repository, &template.git_ref, &template.path)?;
+
+        let mut template_files = snapshot.files.clone();
+        let manifest = TemplateManifest::take_from(&mut template_files)?;
+        let host_patches = take_host_patches(&mut template_files);
+
+        if host_patches.is_empty() {
+            return Ok(host_patches);
+        }
+
+        let host = match self.get_host(host_id).await? {
+            Some(host) => host,
+            None => return Ok(TemplateFiles::new()),
+        };
+
+        let mut configs = self.get_deployment_configs(&deployment.id).await?;
+        configs.extend(self.get_host_configs(&host.id).await?);
+
+        let mut values = Self::make_template_values(&configs, &deployment, &template, &workload)?;
+        values.insert("host".to_owned(), Self::make_host_value(&host));
+
+        manifest
+            .make_host_patch_renderer()
+            .render(&host_patches, &values)
+            .map_err(|err| {
+                anyhow::anyhow!("template {} for host {}: {}", template.id, host.id, err)
+            })
+    }
+
+    fn make_host_directory(host_id: &str) -> String {
+        format!("hosts/{}", host_id)
+    }
+
     fn make_assignment_directory(
         host_id: &str,
         organization_name: &str,
@@ -558,8 +1043,12 @@ impl GitOpsProcessor {
         deployment_name: &str,
     ) -> String {
         format!(
-            "hosts/{}/{}/{}/{}/{}",
-            host_id, organization_name, team_name, workload_name, deployment_name
+            "{}/{}/{}/{}/{}",
+            Self::make_host_directory(host_id),
+            organization_name,
+            team_name,
+            workload_name,
+            deployment_name
         )
     }
 
@@ -582,6 +1071,13 @@ impl GitOpsProcessor {
         )
     }
 
+    fn make_workload_path(organization_name: &str, team_name: &str, workload_name: &str) -> String {
+        format!(
+            "deployments/{}/{}/{}",
+            organization_name, team_name, workload_name
+        )
+    }
+
     fn make_deployment_path(
         organization_name: &str,
         team_name: &str,
@@ -589,96 +1085,88 @@ impl GitOpsProcessor {
         deployment_name: &str,
     ) -> String {
         format!(
-            "deployments/{}/{}/{}/{}",
-            organization_name, team_name, workload_name, deployment_name
+            "{}/{}",
+            Self::make_workload_path(organization_name, team_name, workload_name),
+            deployment_name
         )
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn render_template_path(
+    // Deployment annotations override workload annotations, which override template annotations.
+    fn merge_annotations(
+        template: &TemplateMessage,
+        workload: &WorkloadMessage,
+        deployment: &DeploymentMessage,
+    ) -> BTreeMap<String, String> {
+        let mut annotations = BTreeMap::new();
+
+        for model_annotations in [
+            &template.annotations,
+            &workload.annotations,
+            &deployment.annotations,
+        ] {
+            annotations.extend(model_annotations.clone());
+        }
+
+        annotations
+    }
+
+    // Host labels are key:value pairs, eg. region:eastus2, so that templates can use
+    // host.labels.region.
+    fn make_host_value(host: &HostMessage) -> Json {
+        let labels: BTreeMap<&str, &str> = host
+            .labels
+            .iter()
+            .map(|label| label.split_once(':').unwrap_or((label, "")))
+            .collect();
+
+        serde_json::json!({
+            "id": host.id,
+            "labels": labels,
+        })
+    }
+
+    fn make_template_values(
         configs: &[ConfigMessage],
-        template_repo: &dyn GitRepo,
-        template_path: PathBuf,
         deployment: &DeploymentMessage,
-        sub_template_path: &PathBuf,
         template: &TemplateMessage,
         workload: &WorkloadMessage,
-        cloned_repo: &Arc<dyn ClonedGitRepo>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Map<String, Json>> {
         let (organization_name, team_name) = WorkloadMessage::split_team_id(&workload.team_id)?;
-        let deployment_repo_path = Self::make_deployment_path(
-            &organization_name,
-            &team_name,
-            &workload.name,
-            &deployment.name,
-        );
 
-        let cloned_template_repo = template_repo.clone_repo()?;
-        let template_paths = cloned_template_repo.list(template_path)?;
-
-        for template_path in template_paths {
-            if template_path.is_dir() {
-                let sub_path = sub_template_path.join(template_path.file_name().unwrap());
-                GitOpsProcessor::render_template_path(
-                    configs,
-                    template_repo,
-                    template_path,
-                    deployment,
-                    &sub_path,
-                    template,
-                    workload,
-                    cloned_repo,
-                )?;
-            } else {
-                let template_bytes = cloned_template_repo.read_file(template_path.clone())?;
-                let template_string = String::from_utf8(template_bytes)?;
-
-                let mut handlebars = Handlebars::new();
-                handlebars.register_template_string(&template.id, template_string)?;
-
-                let file_name = template_path.file_name().unwrap();
-                let file_path = Path::new(&deployment_repo_path)
-                    .join(sub_template_path)
-                    .join(file_name);
-                let string_path = file_path.to_string_lossy();
-
-                let mut values: Map<String, Json> = Map::new();
-
-                for config in configs {
-                    match config.value_type {
-                        value_type if value_type == ConfigValueType::StringType as i32 => {
-                            values.insert(config.key.clone(), to_json(config.value.clone()));
-                        }
-
-                        value_type if value_type == ConfigValueType::KeyValueType as i32 => {
-                            let keyvalue_config = config.deserialize_keyvalue_pairs()?;
-                            values.insert(config.key.clone(), to_json(keyvalue_config));
-                        }
-
-                        _ => {
-                            tracing::error!("unsupported config type: {:?}", config);
-                        }
-                    }
-                }
+        let mut values: Map<String, Json> = Map::new();
 
-                values.insert(
-                    "organization".to_owned(),
-                    to_json(organization_name.clone()),
-                );
-                values.insert("team".to_owned(), to_json(team_name.clone()));
-                values.insert("workload".to_owned(), to_json(workload.name.clone()));
-                values.insert("deployment".to_owned(), to_json(deployment.name.clone()));
+        for config in configs {
+            match config.value_type {
+                value_type if value_type == ConfigValueType::StringType as i32 => {
+                    values.insert(config.key.clone(), to_json(config.value.clone()));
+                }
 
-                let rendered_template = handlebars.render(&template.id, &values)?;
+                value_type if value_type == ConfigValueType::KeyValueType as i32 => {
+                    let keyvalue_config = config.deserialize_keyvalue_pairs()?;
+                    values.insert(config.key.clone(), to_json(keyvalue_config));
+                }
 
-                cloned_repo.write_file(&string_path, rendered_template.as_bytes())?;
-                cloned_repo.add_path(file_path.clone())?;
+                _ => {
+                    tracing::error!("unsupported config type: {:?}", config);
+                }
             }
         }
 
-        Ok(())
+        values.insert("organization".to_owned(), to_json(organization_name));
+        values.insert("team".to_owned(), to_json(team_name));
+        values.insert("workload".to_owned(), to_json(workload.name.clone()));
+        values.insert("deployment".to_owned(), to_json(deployment.name.clone()));
+        values.insert(
+            "annotations".to_owned(),
+            to_json(Self::merge_annotations(template, workload, deployment)),
+        );
+
+        Ok(values)
     }
 
+    // Renders every file under the template's path at the commit its git_ref resolves to, with
+    // the renderer its manifest names, and records that commit alongside the rendered files. Host
+    // patches are left to each assignment.
     #[tracing::instrument(skip_all)]
     fn render_deployment_template(
         &self,
@@ -688,24 +1176,50 @@ impl GitOpsProcessor {
         workload: &WorkloadMessage,
         cloned_repo: &Arc<dyn ClonedGitRepo>,
     ) -> anyhow::Result<()> {
-        let template_repo = self.template_repo_factory.create(
-            &template.repository,
-            &template.git_ref,
-            &self.private_ssh_key,
-        )?;
+        let snapshot =
+            self.template_cache
+                .get(&template.repository, &template.git_ref, &template.path)?;
+
+        let (organization_name, team_name) = WorkloadMessage::split_team_id(&workload.team_id)?;
+        let deployment_repo_path = Self::make_deployment_path(
+            &organization_name,
+            &team_name,
+            &workload.name,
+            &deployment.name,
+        );
+
+        let values = Self::make_template_values(configs, deployment, template, workload)?;
+
+        let mut template_files = snapshot.files.clone();
+        let manifest = TemplateManifest::take_from(&mut template_files)?;
+        take_host_patches(&mut template_files);
+
+        let rendered_files = manifest
+            .make_renderer()
+            .render(&template_files, &values)
+            .map_err(|err| anyhow::anyhow!("template {}: {}", template.id, err))?;
+
+        for (relative_path, contents) in rendered_files {
+            let file_path = Path::new(&deployment_repo_path).join(relative_path);
+            cloned_repo.write_file(&file_path.to_string_lossy(), &contents)?;
+            cloned_repo.add_path(file_path)?;
+        }
+
+        let template_source = serde_json::json!({
+            "templateId": template.id,
+            "repository": template.repository,
+            "gitRef": template.git_ref,
+            "path": template.path,
+            "sha": snapshot.sha,
+            "renderer": manifest.renderer.as_str(),
+        });
 
-        let template_root_path: PathBuf = template.path.clone().into();
-
-        GitOpsProcessor::render_template_path(
-            configs,
-            &*template_repo,
-            template_root_path,
-            deployment,
-            &PathBuf::new(),
-            template,
-            workload,
-            cloned_repo,
+        let source_path = Path::new(&deployment_repo_path).join(TEMPLATE_SOURCE_FILE_NAME);
+        cloned_repo.write_file(
+            &source_path.to_string_lossy(),
+            serde_json::to_string_pretty(&template_source)?.as_bytes(),
         )?;
+        cloned_repo.add_path(source_path)?;
 
         Ok(())
     }
@@ -732,9 +1246,7 @@ impl GitOpsProcessor {
             .await?
             .into_inner();
 
-        self.render_deployment_template(configs, deployment, &template, workload, cloned_repo)?;
-
-        Ok(())
+        self.render_deployment_template(configs, deployment, &template, workload, cloned_repo)
     }
 }
 
@@ -742,13 +1254,14 @@ impl GitOpsProcessor {
 mod tests {
     use fabriq_core::{
         create_event,
-        git::{GitRepo, GitRepoFactory, MemoryGitRepo},
+        git::{GitRepo, MemoryGitRepo, MemoryTemplateCache},
         test::{
             get_assignment_fixture, get_deployment_fixture, get_host_fixture,
-            get_string_config_fixture, get_team_fixture, get_template_fixture,
+            get_string_config_fixture, get_target_fixture, get_team_fixture, get_template_fixture,
             get_workload_fixture,
         },
-        EventType, ModelType, OperationId, WorkloadMessage,
+        AssignmentMessage, DeploymentMessage, Event, EventType, ModelType, OperationId,
+        WorkloadMessage,
     };
 
     use std::{
@@ -759,30 +1272,26 @@ mod tests {
         sync::Arc,
     };
 
-    use super::GitOpsProcessor;
+    use super::{GitOpsProcessor, TEMPLATE_SOURCE_FILE_NAME};
+    use crate::pull_request::{BranchPullRequestProvider, DeliveryPolicy};
+    use crate::working_copy::{CommitIdentity, CommitMode};
 
-    #[derive(Debug)]
-    pub struct MockTemplateRepoFactory {}
+    const TEMPLATE_SHA: &str = "0123456789abcdef0123456789abcdef01234567";
 
-    impl GitRepoFactory for MockTemplateRepoFactory {
-        fn create(
-            &self,
-            _repository_url: &str,
-            _branch: &str,
-            _private_ssh_key: &str,
-        ) -> anyhow::Result<Box<dyn GitRepo>> {
-            let git_repo = MemoryGitRepo::new()?;
-
-            let cloned_repo = git_repo.clone_repo()?;
+    fn create_template_cache_fixture() -> anyhow::Result<MemoryTemplateCache> {
+        let template = get_template_fixture(None);
+        let template_cache = MemoryTemplateCache::new();
 
-            let deployment_contents = fs::read_to_string("tests/fixtures/deployment.yaml")?;
-            cloned_repo.write_file(
-                "external-service/deployment.yaml",
-                deployment_contents.as_bytes(),
-            )?;
+        let deployment_contents = fs::read_to_string("tests/fixtures/deployment.yaml")?;
+        template_cache.write_file(
+            &template.repository,
+            &template.git_ref,
+            TEMPLATE_SHA,
+            "external-service/deployment.yaml",
+            deployment_contents.as_bytes(),
+        );
 
-            Ok(Box::new(git_repo))
-        }
+        Ok(template_cache)
     }
 
     #[tokio::test]
@@ -961,6 +1470,16 @@ mod tests {
 
         assert_eq!(deployment_hash, 4035192254134204402);
 
+        let template_source: serde_json::Value = serde_json::from_slice(
+            &cloned_repo
+                .read_file(format!("{deployment_path}/{TEMPLATE_SOURCE_FILE_NAME}").into())
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(template_source["gitRef"], "main");
+        assert_eq!(template_source["sha"], TEMPLATE_SHA);
+
         cloned_repo
             .remove_file(&deployment_pathbuf.to_string_lossy())
             .unwrap();
@@ -987,21 +1506,30 @@ mod tests {
     async fn create_processor_fixture(
         gitops_repo: Arc<dyn GitRepo>,
     ) -> anyhow::Result<GitOpsProcessor> {
+        let assignment_client = Arc::new(fabriq_core::api::mock::MockAssignmentClient {});
         let config_client = Arc::new(fabriq_core::api::mock::MockConfigClient {});
         let deployment_client = Arc::new(fabriq_core::api::mock::MockDeploymentClient {});
+        let host_client = Arc::new(fabriq_core::api::mock::MockHostClient {});
         let template_client = Arc::new(fabriq_core::api::mock::MockTemplateClient {});
         let workload_client = Arc::new(fabriq_core::api::mock::MockWorkloadClient {});
 
         Ok(GitOpsProcessor {
             gitops_repo,
-            private_ssh_key: "unused".to_owned(),
 
-            template_repo_factory: Arc::new(MockTemplateRepoFactory {}),
+            template_cache: Arc::new(create_template_cache_fixture()?),
 
+            assignment_client,
             config_client,
             deployment_client,
+            host_client,
             template_client,
             workload_client,
+
+            commit_mode: CommitMode::Batch,
+            commit_identity: CommitIdentity::default(),
+
+            delivery_policy: DeliveryPolicy::default(),
+            pull_request_provider: Arc::new(BranchPullRequestProvider::default()),
         })
     }
 
@@ -1026,7 +1554,7 @@ mod tests {
             &operation_id,
         );
 
-        processor.process(&event).await.unwrap();
+        assert_eq!(processor.process_batch(&[event]).await.unwrap(), 1);
     }
 
     async fn create_and_process_config_event(
@@ -1050,7 +1578,7 @@ mod tests {
             &operation_id,
         );
 
-        processor.process(&event).await.unwrap();
+        assert_eq!(processor.process_batch(&[event]).await.unwrap(), 1);
     }
 
     async fn create_and_process_deployment_event(
@@ -1073,7 +1601,7 @@ mod tests {
             &operation_id,
         );
 
-        processor.process(&event).await.unwrap();
+        assert_eq!(processor.process_batch(&[event]).await.unwrap(), 1);
     }
 
     async fn create_and_process_template_event(
@@ -1097,7 +1625,409 @@ mod tests {
             &operation_id,
         );
 
-        processor.process(&event).await.unwrap();
+        assert_eq!(processor.process_batch(&[event]).await.unwrap(), 1);
+    }
+
+    fn make_assignment_event(host_id: &str, operation_id: &OperationId) -> Event {
+        let assignment = AssignmentMessage {
+            host_id: host_id.to_string(),
+            ..get_assignment_fixture(Some(host_id))
+        };
+
+        create_event(
+            &None,
+            &Some(assignment),
+            EventType::Created,
+            ModelType::Assignment,
+            operation_id,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_render_host_patches() {
+        let template = get_template_fixture(None);
+        let gitops_repo = Arc::new(MemoryGitRepo::new().unwrap());
+        let cloned_repo = gitops_repo.clone_repo().unwrap();
+        let mut processor = create_processor_fixture(Arc::clone(&gitops_repo) as Arc<dyn GitRepo>)
+            .await
+            .unwrap();
+
+        let template_cache = create_template_cache_fixture().unwrap();
+        template_cache.write_file(
+            &template.repository,
+            &template.git_ref,
+            TEMPLATE_SHA,
+            "external-service/host-patches/ingress.yaml",
+            b"host: {{clusterName}}.{{host.labels.region}}.example.com\nreplicas: {{replicas}}",
+        );
+        let template_cache = Arc::new(template_cache);
+        processor.template_cache = template_cache.clone();
+
+        let assignment_directory =
+            "hosts/host-fixture/fabriq-cloud/fabriq/workload-fixture/deployment-fixture";
+        let patch_path = format!("{assignment_directory}/ingress.yaml");
+        let kustomization_path = format!("{assignment_directory}/kustomization.yaml");
+
+        let operation_id = OperationId::create();
+        let host = get_host_fixture(None);
+        let events = [
+            create_event(
+                &None,
+                &Some(get_deployment_fixture(None)),
+                EventType::Created,
+                ModelType::Deployment,
+                &operation_id,
+            ),
+            create_event(
+                &Some(host.clone()),
+                &Some(host),
+                EventType::Updated,
+                ModelType::Host,
+                &operation_id,
+            ),
+        ];
+
+        // the deployment renders the patches of its assignments, and not into itself.
+        assert_eq!(processor.process_batch(&events[..1]).await.unwrap(), 1);
+
+        let patch = cloned_repo.read_file(patch_path.clone().into()).unwrap();
+        assert_eq!(
+            String::from_utf8(patch).unwrap(),
+            "host: host-fixture-cluster.eastus2.example.com\nreplicas: 5"
+        );
+
+        let kustomization = cloned_repo
+            .read_file(kustomization_path.clone().into())
+            .unwrap();
+        assert!(String::from_utf8(kustomization)
+            .unwrap()
+            .ends_with("/deployment-fixture\npatches:\n- path: ingress.yaml"));
+
+        assert!(cloned_repo
+            .list_files("deployments")
+            .unwrap()
+            .iter()
+            .all(|path| !path.contains("host-patches")));
+
+        // patches the template no longer has are removed when the deployment changes.
+        processor.template_cache = Arc::new(create_template_cache_fixture().unwrap());
+        assert_eq!(processor.process_batch(&events[..1]).await.unwrap(), 1);
+
+        assert!(cloned_repo.read_file(patch_path.clone().into()).is_err());
+        assert_eq!(
+            cloned_repo.list_files(assignment_directory).unwrap(),
+            vec![kustomization_path.clone()]
+        );
+
+        let kustomization = cloned_repo
+            .read_file(kustomization_path.clone().into())
+            .unwrap();
+        assert!(String::from_utf8(kustomization)
+            .unwrap()
+            .ends_with("/deployment-fixture"));
+
+        // and rendered again when the host changes.
+        processor.template_cache = template_cache;
+        assert_eq!(processor.process_batch(&events[1..]).await.unwrap(), 1);
+
+        assert!(cloned_repo.read_file(patch_path.into()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_process_batch() {
+        let gitops_repo = Arc::new(MemoryGitRepo::new().unwrap());
+        let mut processor = create_processor_fixture(Arc::clone(&gitops_repo) as Arc<dyn GitRepo>)
+            .await
+            .unwrap();
+
+        let first_operation_id = OperationId::create();
+        let second_operation_id = OperationId::create();
+        let events = vec![
+            make_assignment_event("host-a", &first_operation_id),
+            make_assignment_event("host-b", &first_operation_id),
+            make_assignment_event("host-c", &second_operation_id),
+        ];
+
+        assert_eq!(processor.process_batch(&events).await.unwrap(), 3);
+        assert_eq!(gitops_repo.get_commit_messages().len(), 1);
+        assert!(gitops_repo.get_commit_messages()[0].starts_with("3 changes"));
+
+        // rendering the same assignments again changes nothing, so there is nothing to commit.
+        assert_eq!(processor.process_batch(&events).await.unwrap(), 3);
+        assert_eq!(gitops_repo.get_commit_messages().len(), 1);
+
+        processor.commit_mode = CommitMode::Operation;
+
+        let events = vec![
+            make_assignment_event("host-d", &first_operation_id),
+            make_assignment_event("host-e", &first_operation_id),
+            make_assignment_event("host-f", &second_operation_id),
+        ];
+
+        assert_eq!(processor.process_batch(&events).await.unwrap(), 3);
+        assert_eq!(gitops_repo.get_commit_messages().len(), 3);
+        assert!(gitops_repo.get_commit_messages()[1].starts_with("2 changes"));
+        assert_eq!(
+            gitops_repo.get_commit_messages()[2],
+            format!(
+                "Updated assignment host-f\n\nFabriq-Operation-Id: {}\nFabriq-Model: assignment host-f",
+                second_operation_id.id
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_pull_requests() {
+        let gitops_repo = Arc::new(MemoryGitRepo::new().unwrap());
+        let mut processor = create_processor_fixture(Arc::clone(&gitops_repo) as Arc<dyn GitRepo>)
+            .await
+            .unwrap();
+
+        processor.delivery_policy = DeliveryPolicy {
+            pull_request_targets: vec![get_target_fixture(None).id],
+        };
+
+        let first_operation_id = OperationId::create();
+        let second_operation_id = OperationId::create();
+        let events = vec![
+            make_assignment_event("host-a", &first_operation_id),
+            make_assignment_event("host-b", &first_operation_id),
+            make_assignment_event("host-c", &second_operation_id),
+        ];
+
+        assert_eq!(processor.process_batch(&events).await.unwrap(), 3);
+        assert!(gitops_repo.get_commit_messages().is_empty());
+
+        let first_branch = format!("fabriq/{}", first_operation_id.id);
+        let second_branch = format!("fabriq/{}", second_operation_id.id);
+
+        assert_eq!(
+            gitops_repo.get_pushed_branches(),
+            vec![first_branch.clone(), second_branch]
+        );
+        assert!(gitops_repo
+            .get_branch_commit(&first_branch)
+            .unwrap()
+            .message
+            .starts_with("2 changes"));
+
+        processor.delivery_policy = DeliveryPolicy {
+            pull_request_targets: vec!["other-target".to_string()],
+        };
+
+        let events = vec![
+            make_assignment_event("host-d", &first_operation_id),
+            make_assignment_event("host-e", &first_operation_id),
+            make_assignment_event("host-f", &second_operation_id),
+        ];
+
+        assert_eq!(processor.process_batch(&events).await.unwrap(), 3);
+        assert_eq!(gitops_repo.get_commit_messages().len(), 2);
+        assert_eq!(gitops_repo.get_pushed_branches().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_process_operation_across_batches() {
+        let gitops_repo = Arc::new(MemoryGitRepo::new().unwrap());
+        let mut processor = create_processor_fixture(Arc::clone(&gitops_repo) as Arc<dyn GitRepo>)
+            .await
+            .unwrap();
+
+        processor.delivery_policy = DeliveryPolicy {
+            pull_request_targets: vec![get_target_fixture(None).id],
+        };
+
+        // a deployment, and then in a later batch the assignments the reconciler made for it.
+        let operation_id = OperationId::create();
+        let deployment_event = create_event(
+            &None,
+            &Some(get_deployment_fixture(None)),
+            EventType::Created,
+            ModelType::Deployment,
+            &operation_id,
+        );
+
+        assert_eq!(
+            processor.process_batch(&[deployment_event]).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            processor
+                .process_batch(&[make_assignment_event("host-a", &operation_id)])
+                .await
+                .unwrap(),
+            1
+        );
+
+        let branch = format!("fabriq/{}", operation_id.id);
+        let branch_commits = gitops_repo.get_branch_commits(&branch);
+
+        assert_eq!(branch_commits.len(), 2);
+        assert!(branch_commits[0].message.starts_with("Updated deployment"));
+        assert!(branch_commits[1]
+            .message
+            .starts_with("Updated assignment host-a"));
+        assert!(gitops_repo.get_commit_messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_process_delete_pull_requests() {
+        let gitops_repo = Arc::new(MemoryGitRepo::new().unwrap());
+        let mut processor = create_processor_fixture(Arc::clone(&gitops_repo) as Arc<dyn GitRepo>)
+            .await
+            .unwrap();
+
+        let host = get_host_fixture(None);
+        let cloned_repo = gitops_repo.clone_repo().unwrap();
+        cloned_repo
+            .write_file(&format!("hosts/{}/kustomization.yaml", host.id), b"host")
+            .unwrap();
+        let author = CommitIdentity::default().author;
+        cloned_repo.commit(&author, &author, "Add host").unwrap();
+
+        processor.delivery_policy = DeliveryPolicy {
+            pull_request_targets: vec!["*".to_string()],
+        };
+
+        // the host's assignments are already gone, so its removal could be for any target.
+        let operation_id = OperationId::create();
+        let event = create_event(
+            &Some(host),
+            &None,
+            EventType::Deleted,
+            ModelType::Host,
+            &operation_id,
+        );
+
+        assert_eq!(processor.process_batch(&[event]).await.unwrap(), 1);
+        assert_eq!(
+            gitops_repo.get_commit_messages(),
+            vec!["Add host".to_string()]
+        );
+        assert_eq!(
+            gitops_repo.get_pushed_branches(),
+            vec![format!("fabriq/{}", operation_id.id)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_repo() {
+        let deployment_path = "deployments/fabriq-cloud/fabriq/workload-fixture/deployment-fixture";
+        let stale_deployment_path =
+            "deployments/fabriq-cloud/fabriq/deleted-workload/deployment-fixture/deployment.yaml";
+        let stale_host_path = "hosts/deleted-host/kustomization.yaml";
+
+        let assignment = get_assignment_fixture(None);
+        let (team_id, workload_name, deployment_name) =
+            DeploymentMessage::split_id(&assignment.deployment_id).unwrap();
+        let (organization_name, team_name) = WorkloadMessage::split_team_id(&team_id).unwrap();
+        let assignment_path = GitOpsProcessor::make_assignment_path(
+            &assignment.host_id,
+            &organization_name,
+            &team_name,
+            &workload_name,
+            &deployment_name,
+        );
+
+        let gitops_repo = Arc::new(MemoryGitRepo::new().unwrap());
+        let cloned_repo = gitops_repo.clone_repo().unwrap();
+
+        cloned_repo
+            .write_file(stale_deployment_path, b"stale")
+            .unwrap();
+        cloned_repo.write_file(stale_host_path, b"stale").unwrap();
+        cloned_repo.write_file("README.md", b"unmanaged").unwrap();
+
+        let mut processor = create_processor_fixture(Arc::clone(&gitops_repo) as Arc<dyn GitRepo>)
+            .await
+            .unwrap();
+
+        assert!(processor.reconcile_repo().await.unwrap());
+
+        assert!(cloned_repo
+            .read_file(format!("{deployment_path}/deployment.yaml").into())
+            .is_ok());
+        assert!(cloned_repo.read_file(assignment_path.into()).is_ok());
+        assert!(cloned_repo.read_file(stale_deployment_path.into()).is_err());
+        assert!(cloned_repo.read_file(stale_host_path.into()).is_err());
+        assert!(cloned_repo.read_file("README.md".into()).is_ok());
+        assert_eq!(gitops_repo.get_commit_messages().len(), 1);
+
+        // the repo already matches, so there is nothing to commit.
+        assert!(!processor.reconcile_repo().await.unwrap());
+        assert_eq!(gitops_repo.get_commit_messages().len(), 1);
+
+        processor.delivery_policy = DeliveryPolicy {
+            pull_request_targets: vec!["*".to_string()],
+        };
+
+        // committed by someone else since.
+        let author = CommitIdentity::default().author;
+        cloned_repo.write_file(stale_host_path, b"stale").unwrap();
+        cloned_repo
+            .commit(&author, &author, "Add deleted host")
+            .unwrap();
+
+        assert!(processor.reconcile_repo().await.unwrap());
+        assert_eq!(gitops_repo.get_commit_messages().len(), 2);
+        assert_eq!(
+            gitops_repo.get_pushed_branches(),
+            vec!["fabriq/reconcile".to_string()]
+        );
+        assert_eq!(
+            gitops_repo
+                .get_branch_commit("fabriq/reconcile")
+                .unwrap()
+                .message,
+            format!("Removed orphaned {stale_host_path}")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_failure() {
+        let gitops_repo = Arc::new(MemoryGitRepo::new().unwrap());
+        let mut processor = create_processor_fixture(Arc::clone(&gitops_repo) as Arc<dyn GitRepo>)
+            .await
+            .unwrap();
+
+        let operation_id = OperationId::create();
+        let mut unknown_event = make_assignment_event("host-b", &operation_id);
+        unknown_event.model_type = -1;
+
+        let events = vec![
+            make_assignment_event("host-a", &operation_id),
+            unknown_event,
+            make_assignment_event("host-c", &operation_id),
+        ];
+
+        assert_eq!(processor.process_batch(&events).await.unwrap(), 1);
+        let commit_messages = gitops_repo.get_commit_messages();
+        assert_eq!(commit_messages.len(), 1);
+        assert!(commit_messages[0].starts_with("Updated assignment host-a\n\n"));
+    }
+
+    #[test]
+    fn test_merge_annotations() {
+        let mut template = get_template_fixture(None);
+        template
+            .annotations
+            .insert("owner".to_string(), "platform".to_string());
+        template
+            .annotations
+            .insert("tier".to_string(), "standard".to_string());
+
+        let workload = get_workload_fixture(None);
+
+        let mut deployment = get_deployment_fixture(None);
+        deployment
+            .annotations
+            .insert("tier".to_string(), "critical".to_string());
+
+        let annotations = GitOpsProcessor::merge_annotations(&template, &workload, &deployment);
+
+        assert_eq!(annotations["owner"], "alice");
+        assert_eq!(annotations["tier"], "critical");
+        assert_eq!(annotations["replicas"], "3");
     }
 
     async fn create_and_process_workload_event(
@@ -1121,6 +2051,6 @@ mod tests {
             &operation_id,
         );
 
-        processor.process(&event).await.unwrap();
+        assert_eq!(processor.process_batch(&[event]).await.unwrap(), 1);
     }
 }
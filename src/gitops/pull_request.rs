@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use std::{env, fmt::Debug};
+
+const ALL_TARGETS: &str = "*";
+const BRANCH_PREFIX: &str = "fabriq/";
+
+// Decides, per target, whether changes are pushed straight to the deploy branch or proposed in a
+// pull request.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeliveryPolicy {
+    // the ids of the targets whose changes need review, or * for all of them.
+    pub pull_request_targets: Vec<String>,
+}
+
+impl DeliveryPolicy {
+    pub fn from_env() -> Self {
+        let pull_request_targets = match env::var("GITOPS_PULL_REQUEST_TARGETS") {
+            Ok(targets) => targets
+                .split(',')
+                .map(|target| target.trim().to_string())
+                .filter(|target| !target.is_empty())
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        DeliveryPolicy {
+            pull_request_targets,
+        }
+    }
+
+    pub fn has_pull_requests(&self) -> bool {
+        !self.pull_request_targets.is_empty()
+    }
+
+    // Changes are proposed if any of the targets they were made for need review.
+    pub fn requires_pull_request(&self, target_ids: &[String]) -> bool {
+        target_ids.iter().any(|target_id| {
+            self.pull_request_targets
+                .iter()
+                .any(|target| target == ALL_TARGETS || target == target_id)
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PullRequest {
+    pub branch: String,
+    pub title: String,
+    pub body: String,
+}
+
+impl PullRequest {
+    pub fn make_branch(operation_id: &str) -> String {
+        format!("{BRANCH_PREFIX}{operation_id}")
+    }
+}
+
+#[async_trait]
+pub trait PullRequestProvider: Debug + Send + Sync {
+    // Opens pull_request from its branch, which has already been pushed, into the deploy branch.
+    // Returns where to find it.
+    async fn open(&self, pull_request: &PullRequest) -> anyhow::Result<String>;
+}
+
+// Leaves the pushed branch for someone to merge by hand, eg. when testing against a local repo.
+#[derive(Debug, Default)]
+pub struct BranchPullRequestProvider {}
+
+#[async_trait]
+impl PullRequestProvider for BranchPullRequestProvider {
+    async fn open(&self, pull_request: &PullRequest) -> anyhow::Result<String> {
+        Ok(pull_request.branch.clone())
+    }
+}
+
+pub struct GitHubPullRequestProvider {
+    pub owner: String,
+    pub repo: String,
+    pub base_branch: String,
+
+    octocrab: Octocrab,
+}
+
+impl Debug for GitHubPullRequestProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GitHubPullRequestProvider {}/{}", self.owner, self.repo)
+    }
+}
+
+impl GitHubPullRequestProvider {
+    pub fn new(repo_url: &str, base_branch: &str, token: &str) -> anyhow::Result<Self> {
+        let (owner, repo) = Self::split_repo_url(repo_url)?;
+
+        let octocrab = octocrab::OctocrabBuilder::new()
+            .personal_token(token.to_string())
+            .build()?;
+
+        Ok(GitHubPullRequestProvider {
+            owner,
+            repo,
+            base_branch: base_branch.to_string(),
+
+            octocrab,
+        })
+    }
+
+    // Accepts both ssh (git@github.com:owner/repo.git) and https (https://github.com/owner/repo)
+    // repo urls.
+    fn split_repo_url(repo_url: &str) -> anyhow::Result<(String, String)> {
+        let path = repo_url
+            .strip_prefix("git@github.com:")
+            .or_else(|| repo_url.strip_prefix("https://github.com/"))
+            .ok_or_else(|| anyhow::anyhow!("{repo_url} is not a GitHub repo url"))?;
+
+        let path = path.trim_end_matches('/').trim_end_matches(".git");
+
+        match path.split_once('/') {
+            Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() && !repo.contains('/') => {
+                Ok((owner.to_string(), repo.to_string()))
+            }
+            _ => Err(anyhow::anyhow!("{repo_url} is not a GitHub repo url")),
+        }
+    }
+}
+
+#[async_trait]
+impl PullRequestProvider for GitHubPullRequestProvider {
+    async fn open(&self, pull_request: &PullRequest) -> anyhow::Result<String> {
+        let created = self
+            .octocrab
+            .pulls(&self.owner, &self.repo)
+            .create(&pull_request.title, &pull_request.branch, &self.base_branch)
+            .body(&pull_request.body)
+            .send()
+            .await;
+
+        match created {
+            Ok(created) => Ok(created
+                .html_url
+                .map(|html_url| html_url.to_string())
+                .unwrap_or_else(|| pull_request.branch.clone())),
+
+            // an earlier attempt at the same operation already opened it, and pushing the branch
+            // again has updated it.
+            Err(err) if err.to_string().contains("already exists") => {
+                Ok(pull_request.branch.clone())
+            }
+
+            Err(err) => Err(anyhow::anyhow!(
+                "failed to open pull request for branch {}: {}",
+                pull_request.branch,
+                err
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requires_pull_request() {
+        let policy = DeliveryPolicy {
+            pull_request_targets: vec!["production".to_string()],
+        };
+
+        assert!(policy.requires_pull_request(&["staging".to_string(), "production".to_string()]));
+        assert!(!policy.requires_pull_request(&["staging".to_string()]));
+        assert!(!policy.requires_pull_request(&[]));
+
+        let policy = DeliveryPolicy {
+            pull_request_targets: vec![ALL_TARGETS.to_string()],
+        };
+
+        assert!(policy.requires_pull_request(&["staging".to_string()]));
+        assert!(!DeliveryPolicy::default().requires_pull_request(&["staging".to_string()]));
+    }
+
+    #[test]
+    fn test_split_repo_url() {
+        assert_eq!(
+            GitHubPullRequestProvider::split_repo_url("git@github.com:fabriq-cloud/gitops.git")
+                .unwrap(),
+            ("fabriq-cloud".to_string(), "gitops".to_string())
+        );
+        assert_eq!(
+            GitHubPullRequestProvider::split_repo_url("https://github.com/fabriq-cloud/gitops")
+                .unwrap(),
+            ("fabriq-cloud".to_string(), "gitops".to_string())
+        );
+        assert!(
+            GitHubPullRequestProvider::split_repo_url("https://gitlab.com/fabriq/gitops").is_err()
+        );
+    }
+}
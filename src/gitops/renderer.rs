@@ -0,0 +1,428 @@
+use handlebars::Handlebars;
+use serde::Deserialize;
+use serde_json::value::{Map, Value as Json};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::Debug,
+    path::{Path, PathBuf},
+};
+
+use crate::template_helpers;
+
+// A template's manifest, at the root of its path. Templates without one are rendered with
+// handlebars.
+pub const TEMPLATE_MANIFEST_FILE_NAME: &str = "fabriq-template.yaml";
+
+// Files under this directory of a template are kustomize patches, rendered once for each host
+// a deployment is assigned to rather than into the deployment itself.
+pub const HOST_PATCHES_DIRECTORY: &str = "host-patches";
+
+const HELM_VALUES_FILE_NAME: &str = "values.yaml";
+
+// Files of a template or of a rendered deployment, by path relative to its root.
+pub type TemplateFiles = BTreeMap<PathBuf, Vec<u8>>;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RendererKind {
+    #[default]
+    Handlebars,
+    Tera,
+    Raw,
+    HelmValues,
+}
+
+impl RendererKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RendererKind::Handlebars => "handlebars",
+            RendererKind::Tera => "tera",
+            RendererKind::Raw => "raw",
+            RendererKind::HelmValues => "helm-values",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct TemplateManifest {
+    #[serde(default)]
+    pub renderer: RendererKind,
+
+    // Fails handlebars renders that use a variable no config or model provides, rather than
+    // rendering it empty. Tera always does.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl TemplateManifest {
+    // Takes the manifest out of files, if there is one, so that it isn't rendered with them.
+    pub fn take_from(files: &mut TemplateFiles) -> anyhow::Result<Self> {
+        match files.remove(Path::new(TEMPLATE_MANIFEST_FILE_NAME)) {
+            Some(manifest) => serde_yaml::from_slice(&manifest).map_err(|err| {
+                anyhow::anyhow!("invalid template manifest {TEMPLATE_MANIFEST_FILE_NAME}: {err}")
+            }),
+            None => Ok(TemplateManifest::default()),
+        }
+    }
+
+    pub fn make_renderer(&self) -> Box<dyn Renderer> {
+        match self.renderer {
+            RendererKind::Handlebars => Box::new(HandlebarsRenderer {
+                strict: self.strict,
+            }),
+            RendererKind::Tera => Box::new(TeraRenderer {}),
+            RendererKind::Raw => Box::new(RawRenderer {}),
+            RendererKind::HelmValues => Box::new(HelmValuesRenderer {}),
+        }
+    }
+
+    // Host patches are small kustomize documents, so they are templated with tera for tera
+    // templates and with handlebars for every other renderer.
+    pub fn make_host_patch_renderer(&self) -> Box<dyn Renderer> {
+        match self.renderer {
+            RendererKind::Tera => Box::new(TeraRenderer {}),
+            _ => Box::new(HandlebarsRenderer {
+                strict: self.strict,
+            }),
+        }
+    }
+}
+
+// Takes the host patches out of files, by path relative to the host patches directory.
+pub fn take_host_patches(files: &mut TemplateFiles) -> TemplateFiles {
+    let patch_paths: Vec<PathBuf> = files
+        .keys()
+        .filter(|path| path.starts_with(HOST_PATCHES_DIRECTORY))
+        .cloned()
+        .collect();
+
+    let mut host_patches = TemplateFiles::new();
+
+    for path in patch_paths {
+        if let (Some(contents), Ok(relative_path)) = (
+            files.remove(&path),
+            path.strip_prefix(HOST_PATCHES_DIRECTORY),
+        ) {
+            host_patches.insert(relative_path.to_path_buf(), contents);
+        }
+    }
+
+    host_patches
+}
+
+// Renders a template's files with a deployment's values into the files of the deployment.
+pub trait Renderer: Debug + Send + Sync {
+    fn render(
+        &self,
+        files: &TemplateFiles,
+        values: &Map<String, Json>,
+    ) -> anyhow::Result<TemplateFiles>;
+}
+
+// Some template engines only say which file failed at the top level, and why further down.
+fn make_render_error(path: &Path, err: &dyn Error) -> anyhow::Error {
+    let mut message = format!("failed to render {}: {err}", path.display());
+
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+
+    anyhow::anyhow!(message)
+}
+
+// With the helpers in template_helpers, eg. `{{default "1" replicas}}`.
+#[derive(Debug, Default)]
+pub struct HandlebarsRenderer {
+    pub strict: bool,
+}
+
+impl Renderer for HandlebarsRenderer {
+    fn render(
+        &self,
+        files: &TemplateFiles,
+        values: &Map<String, Json>,
+    ) -> anyhow::Result<TemplateFiles> {
+        let mut rendered_files = TemplateFiles::new();
+
+        for (path, contents) in files {
+            let template_string = String::from_utf8(contents.clone())?;
+            let template_name = path.to_string_lossy();
+
+            let mut handlebars = Handlebars::new();
+            handlebars.set_strict_mode(self.strict);
+            template_helpers::register_helpers(&mut handlebars);
+
+            handlebars
+                .register_template_string(&template_name, template_string)
+                .map_err(|err| make_render_error(path, &err))?;
+
+            let rendered = handlebars
+                .render(&template_name, values)
+                .map_err(|err| make_render_error(path, &err))?;
+
+            rendered_files.insert(path.clone(), rendered.into_bytes());
+        }
+
+        Ok(rendered_files)
+    }
+}
+
+// Jinja style templates, eg. `{{ team | upper }}` and `{% for key, value in labels %}`.
+#[derive(Debug)]
+pub struct TeraRenderer {}
+
+impl Renderer for TeraRenderer {
+    fn render(
+        &self,
+        files: &TemplateFiles,
+        values: &Map<String, Json>,
+    ) -> anyhow::Result<TemplateFiles> {
+        let context = tera::Context::from_value(Json::Object(values.clone()))?;
+        let mut rendered_files = TemplateFiles::new();
+
+        for (path, contents) in files {
+            let template_string = String::from_utf8(contents.clone())?;
+            let template_name = path.to_string_lossy();
+
+            let mut tera = tera::Tera::default();
+            tera.autoescape_on(vec![]);
+            tera.add_raw_template(&template_name, &template_string)
+                .map_err(|err| make_render_error(path, &err))?;
+
+            let rendered = tera
+                .render(&template_name, &context)
+                .map_err(|err| make_render_error(path, &err))?;
+
+            rendered_files.insert(path.clone(), rendered.into_bytes());
+        }
+
+        Ok(rendered_files)
+    }
+}
+
+// Copies files as they are, eg. kustomize bases.
+#[derive(Debug)]
+pub struct RawRenderer {}
+
+impl Renderer for RawRenderer {
+    fn render(
+        &self,
+        files: &TemplateFiles,
+        _values: &Map<String, Json>,
+    ) -> anyhow::Result<TemplateFiles> {
+        Ok(files.clone())
+    }
+}
+
+// Copies a Helm chart as it is, and writes the deployment's values over the chart's own
+// values.yaml, for whatever installs the chart to render it.
+#[derive(Debug)]
+pub struct HelmValuesRenderer {}
+
+impl HelmValuesRenderer {
+    fn merge(base: &mut Json, overrides: &Json) {
+        match (base, overrides) {
+            (Json::Object(base), Json::Object(overrides)) => {
+                for (key, value) in overrides {
+                    match base.get_mut(key) {
+                        Some(base_value) => Self::merge(base_value, value),
+                        None => {
+                            base.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+            (base, overrides) => *base = overrides.clone(),
+        }
+    }
+}
+
+impl Renderer for HelmValuesRenderer {
+    fn render(
+        &self,
+        files: &TemplateFiles,
+        values: &Map<String, Json>,
+    ) -> anyhow::Result<TemplateFiles> {
+        let mut rendered_files = files.clone();
+        let values_path = PathBuf::from(HELM_VALUES_FILE_NAME);
+
+        let mut chart_values = match files.get(&values_path) {
+            Some(contents) => serde_yaml::from_slice::<Option<Json>>(contents)
+                .map_err(|err| make_render_error(&values_path, &err))?
+                .unwrap_or_else(|| Json::Object(Map::new())),
+            None => Json::Object(Map::new()),
+        };
+
+        Self::merge(&mut chart_values, &Json::Object(values.clone()));
+
+        rendered_files.insert(
+            values_path,
+            serde_yaml::to_string(&chart_values)?.into_bytes(),
+        );
+
+        Ok(rendered_files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn make_files(files: &[(&str, &str)]) -> TemplateFiles {
+        files
+            .iter()
+            .map(|(path, contents)| (PathBuf::from(path), contents.as_bytes().to_vec()))
+            .collect()
+    }
+
+    fn make_values() -> Map<String, Json> {
+        match json!({
+            "team": "fabriq",
+            "image": { "tag": "1.2.0" },
+        }) {
+            Json::Object(values) => values,
+            _ => unreachable!(),
+        }
+    }
+
+    fn read(files: &TemplateFiles, path: &str) -> String {
+        String::from_utf8(files[Path::new(path)].clone()).unwrap()
+    }
+
+    #[test]
+    fn test_take_manifest() {
+        let mut files = make_files(&[
+            (TEMPLATE_MANIFEST_FILE_NAME, "renderer: helm-values"),
+            ("values.yaml", ""),
+        ]);
+
+        let manifest = TemplateManifest::take_from(&mut files).unwrap();
+        assert_eq!(manifest.renderer, RendererKind::HelmValues);
+        assert_eq!(files.len(), 1);
+
+        let mut files = make_files(&[(TEMPLATE_MANIFEST_FILE_NAME, "strict: true")]);
+        let manifest = TemplateManifest::take_from(&mut files).unwrap();
+        assert_eq!(manifest.renderer, RendererKind::Handlebars);
+        assert!(manifest.strict);
+
+        let mut files = make_files(&[("deployment.yaml", "")]);
+        let manifest = TemplateManifest::take_from(&mut files).unwrap();
+        assert_eq!(manifest.renderer, RendererKind::Handlebars);
+
+        let mut files = make_files(&[(TEMPLATE_MANIFEST_FILE_NAME, "renderer: jsonnet")]);
+        assert!(TemplateManifest::take_from(&mut files).is_err());
+    }
+
+    #[test]
+    fn test_take_host_patches() {
+        let mut files = make_files(&[
+            ("deployment.yaml", ""),
+            ("host-patches/ingress.yaml", "host: {{host.id}}"),
+            ("host-patches/regions/endpoint.yaml", ""),
+            ("host-patches-readme.md", ""),
+        ]);
+
+        let host_patches = take_host_patches(&mut files);
+
+        assert_eq!(
+            host_patches.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("ingress.yaml"),
+                Path::new("regions/endpoint.yaml")
+            ]
+        );
+        assert_eq!(read(&host_patches, "ingress.yaml"), "host: {{host.id}}");
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("deployment.yaml"),
+                Path::new("host-patches-readme.md")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let values = make_values();
+
+        let files = make_files(&[("deployment.yaml", "team: {{team}}\ntag: {{image.tag}}")]);
+        let rendered = HandlebarsRenderer::default()
+            .render(&files, &values)
+            .unwrap();
+        assert_eq!(
+            read(&rendered, "deployment.yaml"),
+            "team: fabriq\ntag: 1.2.0"
+        );
+
+        let files = make_files(&[("deployment.yaml", "region: {{region}}")]);
+        let rendered = HandlebarsRenderer::default()
+            .render(&files, &values)
+            .unwrap();
+        assert_eq!(read(&rendered, "deployment.yaml"), "region: ");
+
+        let err = HandlebarsRenderer { strict: true }
+            .render(&files, &values)
+            .unwrap_err();
+        assert!(err.to_string().contains("deployment.yaml"));
+        assert!(err.to_string().contains("\"region\""));
+
+        let files = make_files(&[(
+            "deployment.yaml",
+            "team: {{ team | upper }}\n{% if image %}tag: {{ image.tag }}{% endif %}",
+        )]);
+        let rendered = TeraRenderer {}.render(&files, &values).unwrap();
+        assert_eq!(
+            read(&rendered, "deployment.yaml"),
+            "team: FABRIQ\ntag: 1.2.0"
+        );
+
+        let files = make_files(&[("deployment.yaml", "team: {{ missing }}")]);
+        let err = TeraRenderer {}.render(&files, &values).unwrap_err();
+        assert!(err.to_string().contains("deployment.yaml"));
+        assert!(err.to_string().contains("missing"));
+
+        let files = make_files(&[("kustomization.yaml", "name: {{ .Release.Name }}")]);
+        let rendered = RawRenderer {}.render(&files, &values).unwrap();
+        assert_eq!(rendered, files);
+    }
+
+    #[test]
+    fn test_render_helm_values() {
+        let files = make_files(&[
+            ("Chart.yaml", "name: service"),
+            (
+                "templates/deployment.yaml",
+                "image: {{ .Values.image.tag }}",
+            ),
+            (
+                "values.yaml",
+                "replicas: 1\nimage:\n  repository: service\n  tag: latest\n",
+            ),
+        ]);
+
+        let rendered = HelmValuesRenderer {}
+            .render(&files, &make_values())
+            .unwrap();
+
+        assert_eq!(
+            rendered[Path::new("templates/deployment.yaml")],
+            files[Path::new("templates/deployment.yaml")]
+        );
+
+        let chart_values: Json =
+            serde_yaml::from_slice(&rendered[Path::new("values.yaml")]).unwrap();
+        assert_eq!(
+            chart_values,
+            json!({
+                "replicas": 1,
+                "team": "fabriq",
+                "image": { "repository": "service", "tag": "1.2.0" },
+            })
+        );
+    }
+}
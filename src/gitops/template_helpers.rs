@@ -0,0 +1,225 @@
+use handlebars::{Context, Handlebars, Helper, HelperDef, RenderContext, RenderError, ScopedJson};
+use serde_json::Value as Json;
+use sha2::{Digest, Sha256};
+
+// A helper that computes a value from its params, so that it can also be used in subexpressions,
+// eg. `{{indent 4 (toYaml labels)}}`.
+struct ValueHelper(fn(&Helper, &Handlebars) -> Result<Json, RenderError>);
+
+impl HelperDef for ValueHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'reg, 'rc>, RenderError> {
+        Ok(ScopedJson::Derived((self.0)(h, r)?))
+    }
+}
+
+pub fn register_helpers(handlebars: &mut Handlebars) {
+    let helpers: [(&str, ValueHelper); 9] = [
+        ("default", ValueHelper(default)),
+        ("required", ValueHelper(required)),
+        ("b64enc", ValueHelper(b64enc)),
+        ("toYaml", ValueHelper(to_yaml)),
+        ("toJson", ValueHelper(to_json)),
+        ("indent", ValueHelper(indent)),
+        ("lower", ValueHelper(lower)),
+        ("upper", ValueHelper(upper)),
+        ("sha256", ValueHelper(sha256)),
+    ];
+
+    for (name, helper) in helpers {
+        handlebars.register_helper(name, Box::new(helper));
+    }
+}
+
+// In strict mode, a param naming a missing variable fails the render like a missing
+// `{{variable}}` does.
+fn get_param<'a>(h: &'a Helper, r: &Handlebars, index: usize) -> Result<&'a Json, RenderError> {
+    let param = h
+        .param(index)
+        .ok_or_else(|| RenderError::new(format!("{} expects {} params", h.name(), index + 1)))?;
+
+    if r.strict_mode() && param.is_value_missing() {
+        return Err(RenderError::strict_error(param.relative_path()));
+    }
+
+    Ok(param.value())
+}
+
+fn get_string_param(h: &Helper, r: &Handlebars, index: usize) -> Result<String, RenderError> {
+    Ok(match get_param(h, r, index)? {
+        Json::String(value) => value.clone(),
+        Json::Null => String::new(),
+        value => value.to_string(),
+    })
+}
+
+fn is_empty(value: &Json) -> bool {
+    match value {
+        Json::Null => true,
+        Json::String(value) => value.is_empty(),
+        Json::Array(values) => values.is_empty(),
+        Json::Object(values) => values.is_empty(),
+        _ => false,
+    }
+}
+
+// {{default "fallback" value}} renders value, or fallback if value is missing or empty.
+fn default(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    let fallback = get_param(h, r, 0)?;
+
+    match h.param(1) {
+        Some(param) if !is_empty(param.value()) => Ok(param.value().clone()),
+        _ => Ok(fallback.clone()),
+    }
+}
+
+// {{required "message" value}} renders value, or fails the render with message if value is
+// missing or empty.
+fn required(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    let message = get_string_param(h, r, 0)?;
+
+    match h.param(1) {
+        Some(param) if !is_empty(param.value()) => Ok(param.value().clone()),
+        Some(param) => Err(RenderError::new(match param.relative_path() {
+            Some(path) => format!("required value {path:?} is missing: {message}"),
+            None => format!("required value is missing: {message}"),
+        })),
+        None => Err(RenderError::new(format!(
+            "required value is missing: {message}"
+        ))),
+    }
+}
+
+#[allow(deprecated)]
+fn b64enc(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    Ok(Json::String(base64::encode(get_string_param(h, r, 0)?)))
+}
+
+// Serializes without serde_yaml's trailing newline, so the value can be placed inline or piped
+// into indent.
+fn to_yaml(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    let yaml = serde_yaml::to_string(get_param(h, r, 0)?)
+        .map_err(|err| RenderError::new(format!("toYaml: {err}")))?;
+
+    Ok(Json::String(yaml.trim_end().to_string()))
+}
+
+fn to_json(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    Ok(Json::String(get_param(h, r, 0)?.to_string()))
+}
+
+// {{indent 4 value}} prefixes every line of value with 4 spaces.
+fn indent(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    let spaces = get_param(h, r, 0)?
+        .as_u64()
+        .ok_or_else(|| RenderError::new("indent expects a number of spaces as its first param"))?;
+    let prefix = " ".repeat(spaces as usize);
+
+    let indented = get_string_param(h, r, 1)?
+        .lines()
+        .map(|line| format!("{prefix}{line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(Json::String(indented))
+}
+
+fn lower(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    Ok(Json::String(get_string_param(h, r, 0)?.to_lowercase()))
+}
+
+fn upper(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    Ok(Json::String(get_string_param(h, r, 0)?.to_uppercase()))
+}
+
+fn sha256(h: &Helper, r: &Handlebars) -> Result<Json, RenderError> {
+    let digest = Sha256::digest(get_string_param(h, r, 0)?.as_bytes());
+
+    Ok(Json::String(format!("{digest:x}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn render(template: &str, values: &Json, strict: bool) -> Result<String, RenderError> {
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(strict);
+        register_helpers(&mut handlebars);
+
+        handlebars.render_template(template, values)
+    }
+
+    #[test]
+    fn test_helpers() {
+        let values = json!({
+            "team": "Fabriq",
+            "empty": "",
+            "labels": { "region": "eastus2", "tier": "web" },
+        });
+
+        let cases = [
+            (r#"{{default "main" branch}}"#, "main"),
+            (r#"{{default "main" empty}}"#, "main"),
+            (r#"{{default "main" team}}"#, "Fabriq"),
+            (r#"{{required "team is required" team}}"#, "Fabriq"),
+            ("{{b64enc team}}", "RmFicmlx"),
+            ("{{lower team}} {{upper team}}", "fabriq FABRIQ"),
+            (
+                "{{{toJson labels}}}",
+                r#"{"region":"eastus2","tier":"web"}"#,
+            ),
+            ("{{toYaml labels}}", "region: eastus2\ntier: web"),
+            (
+                "labels:\n{{indent 2 (toYaml labels)}}",
+                "labels:\n  region: eastus2\n  tier: web",
+            ),
+            (
+                "{{sha256 team}}",
+                "7dddf86df4d3dfb9073245b186b79acb4aee73f2a4f4bf399caa825247507d8c",
+            ),
+            (r#"{{upper (default "none" missing)}}"#, "NONE"),
+        ];
+
+        for (template, expected) in cases {
+            assert_eq!(
+                render(template, &values, false).unwrap(),
+                expected,
+                "{template}"
+            );
+            assert_eq!(
+                render(template, &values, true).unwrap(),
+                expected,
+                "{template}"
+            );
+        }
+
+        let err = render(r#"{{required "set a region" region}}"#, &values, false).unwrap_err();
+        assert!(err.to_string().contains("region"));
+        assert!(err.to_string().contains("set a region"));
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let values = json!({ "team": "fabriq" });
+
+        assert_eq!(
+            render("{{team}}{{missing}}", &values, false).unwrap(),
+            "fabriq"
+        );
+        assert_eq!(render("{{upper missing}}", &values, false).unwrap(), "");
+
+        let err = render("{{team}}{{missing}}", &values, true).unwrap_err();
+        assert!(err.to_string().contains("\"missing\""));
+
+        let err = render("{{upper missing}}", &values, true).unwrap_err();
+        assert!(err.to_string().contains("\"missing\""));
+    }
+}
@@ -0,0 +1,488 @@
+use fabriq_core::{
+    cloud_event::decode_model,
+    git::{ClonedGitRepo, GitSignature},
+    Event, ModelType,
+};
+use std::{
+    env,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use crate::pull_request::{DeliveryPolicy, PullRequest};
+
+const DEFAULT_AUTHOR_NAME: &str = "fabriq";
+const DEFAULT_AUTHOR_EMAIL: &str = "fabriq@localhost";
+const DEFAULT_COMMITTER_EMAIL_DOMAIN: &str = "users.noreply.github.com";
+
+// How the changes made for a batch of events are split into commits.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CommitMode {
+    // One commit for the whole batch.
+    #[default]
+    Batch,
+
+    // One commit for each run of events with the same operation id, eg. all of the assignment
+    // changes caused by one host label change.
+    Operation,
+}
+
+impl FromStr for CommitMode {
+    type Err = anyhow::Error;
+
+    fn from_str(commit_mode: &str) -> anyhow::Result<Self> {
+        match commit_mode {
+            "batch" => Ok(CommitMode::Batch),
+            "operation" => Ok(CommitMode::Operation),
+            _ => Err(anyhow::anyhow!(
+                "unknown commit mode '{commit_mode}', expected batch or operation"
+            )),
+        }
+    }
+}
+
+// Commits are authored by fabriq itself, and committed by the actor whose api call caused them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitIdentity {
+    pub author: GitSignature,
+
+    // makes committer emails for actors that aren't email addresses themselves, eg. GitHub logins.
+    pub committer_email_domain: String,
+}
+
+impl Default for CommitIdentity {
+    fn default() -> Self {
+        CommitIdentity {
+            author: GitSignature {
+                name: DEFAULT_AUTHOR_NAME.to_string(),
+                email: DEFAULT_AUTHOR_EMAIL.to_string(),
+            },
+            committer_email_domain: DEFAULT_COMMITTER_EMAIL_DOMAIN.to_string(),
+        }
+    }
+}
+
+impl CommitIdentity {
+    pub fn from_env() -> Self {
+        let default = CommitIdentity::default();
+
+        CommitIdentity {
+            author: GitSignature {
+                name: env::var("GITOPS_COMMIT_AUTHOR_NAME").unwrap_or(default.author.name),
+                email: env::var("GITOPS_COMMIT_AUTHOR_EMAIL").unwrap_or(default.author.email),
+            },
+            committer_email_domain: env::var("GITOPS_COMMITTER_EMAIL_DOMAIN")
+                .unwrap_or(default.committer_email_domain),
+        }
+    }
+
+    // A commit only has one committer, so changes made by several actors, or none, are committed
+    // by the author.
+    fn make_committer(&self, actors: &[String]) -> GitSignature {
+        match actors {
+            [actor] if actor.contains('@') => GitSignature {
+                name: actor.clone(),
+                email: actor.clone(),
+            },
+            [actor] => GitSignature {
+                name: actor.clone(),
+                email: format!("{actor}@{}", self.committer_email_domain),
+            },
+            _ => self.author.clone(),
+        }
+    }
+}
+
+// What the next commit will hold: a description of each change, the targets they were made for,
+// and the actor, operation and model of each event that made them.
+#[derive(Debug, Default)]
+struct PendingCommit {
+    changes: Vec<String>,
+    event_changed: bool,
+    target_ids: Vec<String>,
+    unknown_target: bool,
+
+    actors: Vec<String>,
+    models: Vec<String>,
+    operation_ids: Vec<String>,
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+// A clone of the gitops repo that a batch of events is rendered into, along with what has changed
+// since the last commit for its message and committer.
+#[derive(Debug)]
+pub struct WorkingCopy {
+    pub repo: Arc<dyn ClonedGitRepo>,
+    identity: CommitIdentity,
+    pending: Mutex<PendingCommit>,
+}
+
+impl WorkingCopy {
+    pub fn new(repo: Arc<dyn ClonedGitRepo>, identity: CommitIdentity) -> Self {
+        WorkingCopy {
+            repo,
+            identity,
+            pending: Mutex::new(PendingCommit::default()),
+        }
+    }
+
+    // Events in a batch often touch the same model more than once, so each change is only
+    // described once.
+    pub fn record(&self, change: String) {
+        let mut pending = self.pending.lock().unwrap();
+
+        push_unique(&mut pending.changes, change);
+        pending.event_changed = true;
+    }
+
+    pub fn record_target(&self, target_id: &str) {
+        push_unique(
+            &mut self.pending.lock().unwrap().target_ids,
+            target_id.to_string(),
+        );
+    }
+
+    // For changes whose target can no longer be looked up, eg. those removing the files of a
+    // deleted host or workload, which could be for any target.
+    pub fn record_unknown_target(&self) {
+        self.pending.lock().unwrap().unknown_target = true;
+    }
+
+    pub fn requires_pull_request(&self, policy: &DeliveryPolicy) -> bool {
+        let pending = self.pending.lock().unwrap();
+
+        (pending.unknown_target && policy.has_pull_requests())
+            || policy.requires_pull_request(&pending.target_ids)
+    }
+
+    // Attributes the changes recorded while processing event to it. Events that changed nothing
+    // aren't mentioned in the commit.
+    pub fn record_event(&self, event: &Event) -> anyhow::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+
+        if !pending.event_changed {
+            return Ok(());
+        }
+
+        pending.event_changed = false;
+
+        if !event.actor.is_empty() {
+            push_unique(&mut pending.actors, event.actor.clone());
+        }
+
+        if let Some(operation_id) = &event.operation_id {
+            push_unique(&mut pending.operation_ids, operation_id.id.clone());
+        }
+
+        let serialized_model = match &event.serialized_current_model {
+            Some(serialized_current_model) => Some(serialized_current_model),
+            None => event.serialized_previous_model.as_ref(),
+        };
+
+        if let Some(serialized_model) = serialized_model {
+            let model_type = ModelType::from(event.model_type);
+            let (model_id, _) = decode_model(model_type, serialized_model)?;
+
+            push_unique(
+                &mut pending.models,
+                format!("{} {model_id}", model_type.as_str_name().to_lowercase()),
+            );
+        }
+
+        Ok(())
+    }
+
+    // The trailers trace the commit back to the api calls that caused it.
+    fn make_commit_message(pending: &PendingCommit) -> String {
+        let summary = match pending.changes.as_slice() {
+            [change] => change.clone(),
+            changes => {
+                let change_list = changes
+                    .iter()
+                    .map(|change| format!("- {change}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                format!("{} changes\n\n{change_list}", changes.len())
+            }
+        };
+
+        let trailers = pending
+            .operation_ids
+            .iter()
+            .map(|operation_id| format!("Fabriq-Operation-Id: {operation_id}"))
+            .chain(
+                pending
+                    .models
+                    .iter()
+                    .map(|model| format!("Fabriq-Model: {model}")),
+            )
+            .collect::<Vec<_>>();
+
+        if trailers.is_empty() {
+            summary
+        } else {
+            format!("{summary}\n\n{}", trailers.join("\n"))
+        }
+    }
+
+    // Rendering often reproduces what is already committed, eg. when a deployment is updated
+    // without changing anything it renders, so recorded changes are dropped if the repo has no
+    // staged changes to commit.
+    fn take_pending(&self) -> anyhow::Result<Option<PendingCommit>> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+
+        if pending.changes.is_empty() || !self.repo.has_changes()? {
+            return Ok(None);
+        }
+
+        Ok(Some(pending))
+    }
+
+    fn commit_pending(&self, pending: &PendingCommit) -> anyhow::Result<String> {
+        let message = Self::make_commit_message(pending);
+
+        self.repo.commit(
+            &self.identity.author,
+            &self.identity.make_committer(&pending.actors),
+            &message,
+        )?;
+
+        Ok(message)
+    }
+
+    // Commits the changes recorded since the last commit, if there are any. Returns whether it
+    // committed.
+    pub fn commit(&self) -> anyhow::Result<bool> {
+        let pending = match self.take_pending()? {
+            Some(pending) => pending,
+            None => return Ok(false),
+        };
+
+        self.commit_pending(&pending)?;
+
+        Ok(true)
+    }
+
+    // Commits the changes recorded since the last commit onto a branch of their own instead, to
+    // be pushed and proposed as a pull request. They must all have been made by one operation,
+    // which the branch is named after. An operation's events can arrive over several batches, eg.
+    // a deployment and then the assignments the reconciler makes for it, so the changes are
+    // appended to the branch if an earlier batch has already pushed it.
+    pub fn propose(&self) -> anyhow::Result<Option<PullRequest>> {
+        let pending = match self.take_pending()? {
+            Some(pending) => pending,
+            None => return Ok(None),
+        };
+
+        let branch = match pending.operation_ids.as_slice() {
+            [operation_id] => PullRequest::make_branch(operation_id),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "changes from {} operations can't be proposed together",
+                    pending.operation_ids.len()
+                ))
+            }
+        };
+
+        let message = self.commit_pending(&pending)?;
+        self.repo.append_last_commit(&branch)?;
+
+        Ok(Some(Self::make_pull_request(branch, &message)))
+    }
+
+    // Like propose, but onto the given branch, for changes that no operation made. They replace
+    // whatever was proposed on it before.
+    pub fn propose_on(&self, branch: &str) -> anyhow::Result<Option<PullRequest>> {
+        let pending = match self.take_pending()? {
+            Some(pending) => pending,
+            None => return Ok(None),
+        };
+
+        let message = self.commit_pending(&pending)?;
+        self.repo.move_last_commit(branch)?;
+
+        Ok(Some(Self::make_pull_request(branch.to_string(), &message)))
+    }
+
+    fn make_pull_request(branch: String, message: &str) -> PullRequest {
+        let (title, body) = message.split_once("\n\n").unwrap_or((message, ""));
+
+        PullRequest {
+            branch,
+            title: title.to_string(),
+            body: body.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{
+        create_event,
+        git::{GitRepo, MemoryGitRepo},
+        test::get_assignment_fixture,
+        EventType, OperationId,
+    };
+
+    use super::*;
+
+    fn make_event(assignment_id: &str, operation_id: &OperationId, actor: &str) -> Event {
+        Event {
+            actor: actor.to_string(),
+            ..create_event(
+                &None,
+                &Some(get_assignment_fixture(Some(assignment_id))),
+                EventType::Created,
+                ModelType::Assignment,
+                operation_id,
+            )
+        }
+    }
+
+    // Records change along with a file for it, so that there is something to commit.
+    fn render(working_copy: &WorkingCopy, change: &str) -> anyhow::Result<()> {
+        working_copy
+            .repo
+            .write_file(&format!("{change}.yaml"), change.as_bytes())?;
+        working_copy.record(change.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit() -> anyhow::Result<()> {
+        let gitops_repo = MemoryGitRepo::new()?;
+        let working_copy = WorkingCopy::new(gitops_repo.clone_repo()?, CommitIdentity::default());
+
+        assert!(!working_copy.commit()?);
+
+        render(&working_copy, "Updated deployment a")?;
+        assert!(working_copy.commit()?);
+
+        render(&working_copy, "Updated assignment b")?;
+        render(&working_copy, "Updated deployment a")?;
+        render(&working_copy, "Updated assignment b")?;
+        assert!(working_copy.commit()?);
+
+        assert_eq!(
+            gitops_repo.get_commit_messages(),
+            vec![
+                "Updated deployment a".to_string(),
+                "2 changes\n\n- Updated assignment b\n- Updated deployment a".to_string()
+            ]
+        );
+
+        // rendering the same contents again leaves nothing to commit.
+        render(&working_copy, "Updated deployment a")?;
+        assert!(!working_copy.commit()?);
+        assert_eq!(gitops_repo.get_commit_messages().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_attribution() -> anyhow::Result<()> {
+        let gitops_repo = MemoryGitRepo::new()?;
+        let identity = CommitIdentity::default();
+        let working_copy = WorkingCopy::new(gitops_repo.clone_repo()?, identity.clone());
+
+        let operation_id = OperationId::create();
+
+        render(&working_copy, "Updated assignment a")?;
+        working_copy.record_event(&make_event("a", &operation_id, "octocat"))?;
+
+        // changed nothing, so neither its actor nor its model are mentioned.
+        working_copy.record_event(&make_event("b", &OperationId::create(), "hubot"))?;
+
+        assert!(working_copy.commit()?);
+
+        render(&working_copy, "Updated assignment c")?;
+        working_copy.record_event(&make_event("c", &operation_id, "octocat"))?;
+        render(&working_copy, "Updated assignment d")?;
+        working_copy.record_event(&make_event("d", &operation_id, "hubot@example.com"))?;
+
+        assert!(working_copy.commit()?);
+
+        let commits = gitops_repo.get_commits();
+        assert_eq!(commits.len(), 2);
+
+        assert_eq!(commits[0].author, identity.author);
+        assert_eq!(
+            commits[0].committer,
+            GitSignature {
+                name: "octocat".to_string(),
+                email: "octocat@users.noreply.github.com".to_string(),
+            }
+        );
+        assert_eq!(
+            commits[0].message,
+            format!(
+                "Updated assignment a\n\nFabriq-Operation-Id: {}\nFabriq-Model: assignment a",
+                operation_id.id
+            )
+        );
+
+        // made by two actors, so committed by the author.
+        assert_eq!(commits[1].committer, identity.author);
+        assert!(commits[1].message.ends_with(&format!(
+            "\n\nFabriq-Operation-Id: {}\nFabriq-Model: assignment c\nFabriq-Model: assignment d",
+            operation_id.id
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_propose() -> anyhow::Result<()> {
+        let gitops_repo = MemoryGitRepo::new()?;
+        let working_copy = WorkingCopy::new(gitops_repo.clone_repo()?, CommitIdentity::default());
+        let policy = DeliveryPolicy {
+            pull_request_targets: vec!["production".to_string()],
+        };
+
+        assert!(working_copy.propose()?.is_none());
+
+        let operation_id = OperationId::create();
+
+        render(&working_copy, "Updated assignment a")?;
+        working_copy.record_target("staging");
+        working_copy.record_event(&make_event("a", &operation_id, "octocat"))?;
+        assert!(!working_copy.requires_pull_request(&policy));
+
+        working_copy.record_target("production");
+        assert!(working_copy.requires_pull_request(&policy));
+
+        let pull_request = working_copy.propose()?.unwrap();
+        let branch = format!("fabriq/{}", operation_id.id);
+
+        assert_eq!(pull_request.branch, branch);
+        assert_eq!(pull_request.title, "Updated assignment a");
+        assert!(pull_request
+            .body
+            .starts_with(&format!("Fabriq-Operation-Id: {}", operation_id.id)));
+
+        assert!(gitops_repo.get_commits().is_empty());
+        assert_eq!(
+            gitops_repo.get_branch_commit(&branch).unwrap().message,
+            format!("{}\n\n{}", pull_request.title, pull_request.body)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_commit_mode() {
+        assert_eq!("batch".parse::<CommitMode>().unwrap(), CommitMode::Batch);
+        assert_eq!(
+            "operation".parse::<CommitMode>().unwrap(),
+            CommitMode::Operation
+        );
+        assert!("event".parse::<CommitMode>().is_err());
+    }
+}
@@ -1,6 +1,30 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
 use tonic::{Request, Status};
+use tower::{Layer, Service};
 
-pub async fn get_pat_from_headers<T>(req: &Request<T>) -> Result<String, Status> {
+use crate::auth::{AuthProvider, Identity, RbacAuthorizer};
+use crate::services::with_actor;
+
+const BEARER_PREFIX: &str = "Bearer ";
+
+// The gRPC method path (eg. /fabriq.host.Host/Upsert) of a request. Interceptors only see the
+// request metadata, so this is recorded into the request extensions by an outer layer.
+#[derive(Clone, Debug)]
+pub struct GrpcMethod(pub String);
+
+pub fn record_grpc_method<Body>(mut req: hyper::Request<Body>) -> hyper::Request<Body> {
+    let grpc_method = GrpcMethod(req.uri().path().to_string());
+    req.extensions_mut().insert(grpc_method);
+
+    req
+}
+
+pub async fn get_token_from_headers<T>(req: &Request<T>) -> Result<String, Status> {
     let headers = req.metadata().clone().into_headers();
 
     let auth_header = match headers.get("authorization") {
@@ -13,8 +37,8 @@ pub async fn get_pat_from_headers<T>(req: &Request<T>) -> Result<String, Status>
         }
     };
 
-    let pat = match auth_header.to_str() {
-        Ok(pat) => pat,
+    let token = match auth_header.to_str() {
+        Ok(token) => token,
         Err(_) => {
             return Err(Status::new(
                 tonic::Code::InvalidArgument,
@@ -23,12 +47,175 @@ pub async fn get_pat_from_headers<T>(req: &Request<T>) -> Result<String, Status>
         }
     };
 
-    Ok(pat.to_string())
+    // PATs have historically been sent bare, bearer tokens (eg. OIDC JWTs) with the standard prefix.
+    let token = token.strip_prefix(BEARER_PREFIX).unwrap_or(token);
+
+    Ok(token.to_string())
 }
 
-#[tracing::instrument(name = "authenticate")]
-pub async fn authenticate(req: Request<()>) -> Result<Request<()>, Status> {
-    let _pat = get_pat_from_headers(&req).await?;
+#[tracing::instrument(name = "authenticate", skip_all)]
+pub async fn authenticate(
+    auth_provider: Arc<dyn AuthProvider>,
+    authorizer: Arc<RbacAuthorizer>,
+    mut req: Request<()>,
+) -> Result<Request<()>, Status> {
+    let token = get_token_from_headers(&req).await?;
+
+    let identity = match auth_provider.authenticate(&token).await? {
+        Some(identity) => identity,
+        None => {
+            return Err(Status::new(
+                tonic::Code::Unauthenticated,
+                "token was not accepted by auth provider",
+            ))
+        }
+    };
+
+    tracing::info!(
+        "authenticated {} with {} provider",
+        identity.subject,
+        identity.provider
+    );
+
+    // requests without a recorded method fall through to the most restrictive role.
+    let method_path = req
+        .extensions()
+        .get::<GrpcMethod>()
+        .map(|grpc_method| grpc_method.0.clone())
+        .unwrap_or_default();
+
+    let team_scope = authorizer
+        .authorize(&token, &identity, &method_path)
+        .await?;
+
+    req.extensions_mut().insert(identity);
+    if let Some(team_scope) = team_scope {
+        req.extensions_mut().insert(team_scope);
+    }
 
     Ok(req)
 }
+
+// Runs each authenticated request with its identity's subject as the actor, so that the audit
+// entries the services record for it are attributed to the caller. Must be layered inside the
+// authenticate interceptor, which attaches the identity.
+#[derive(Clone, Debug)]
+pub struct ActorLayer;
+
+impl<S> Layer<S> for ActorLayer {
+    type Service = ActorService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ActorService { inner }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ActorService<S> {
+    inner: S,
+}
+
+impl<S, Body> Service<hyper::Request<Body>> for ActorService<S>
+where
+    S: Service<hyper::Request<Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: hyper::Request<Body>) -> Self::Future {
+        let actor = req
+            .extensions()
+            .get::<Identity>()
+            .map(|identity| identity.subject.clone());
+
+        let future = self.inner.call(req);
+
+        match actor {
+            Some(actor) => Box::pin(with_actor(actor, future)),
+            None => Box::pin(future),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::EventStream;
+    use fabriq_memory_stream::MemoryEventStream;
+    use tonic::metadata::MetadataValue;
+
+    use super::*;
+    use crate::{
+        auth::{Identity, MockAuthProvider, RoleBindingCache},
+        models::RoleBinding,
+        persistence::memory::MemoryPersistence,
+        services::{current_actor, RoleBindingService},
+    };
+
+    fn make_request(method_path: &str) -> anyhow::Result<Request<()>> {
+        let http_request = hyper::Request::builder().uri(method_path).body(())?;
+        let mut request = Request::from_http(record_grpc_method(http_request));
+
+        let token: MetadataValue<_> = "Bearer a.b.c".parse()?;
+        request.metadata_mut().insert("authorization", token);
+
+        Ok(request)
+    }
+
+    #[tokio::test]
+    async fn test_authenticate() -> anyhow::Result<()> {
+        let auth_provider = Arc::new(MockAuthProvider::new(&["fabriq-cloud/fabriq"]));
+        let event_stream = Arc::new(MemoryEventStream::new()?) as Arc<dyn EventStream>;
+
+        let authorizer = Arc::new(RbacAuthorizer {
+            role_binding_service: Arc::new(RoleBindingService {
+                persistence: Box::<MemoryPersistence<RoleBinding>>::default(),
+                event_stream,
+            }),
+            auth_provider: auth_provider.clone(),
+            default_role: Some(RbacAuthorizer::DEFAULT_ROLE),
+            role_binding_cache: RoleBindingCache::default(),
+        });
+
+        let result =
+            authenticate(auth_provider.clone(), authorizer.clone(), Request::new(())).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::Unauthenticated);
+
+        let request = make_request("/fabriq.workload.Workload/Upsert")?;
+        assert_eq!(get_token_from_headers(&request).await?, "a.b.c");
+
+        let request = authenticate(auth_provider.clone(), authorizer.clone(), request).await?;
+        let identity = request.extensions().get::<Identity>().unwrap();
+
+        assert_eq!(identity.subject, "test-user");
+
+        let request = make_request("/fabriq.host.Host/Upsert")?;
+        let result = authenticate(auth_provider, authorizer, request).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_actor_layer() -> anyhow::Result<()> {
+        let mut service = ActorLayer.layer(tower::service_fn(|_: hyper::Request<()>| async {
+            Ok::<_, Status>(current_actor())
+        }));
+
+        let mut request = hyper::Request::new(());
+        request.extensions_mut().insert(Identity {
+            provider: "mock".to_string(),
+            subject: "octocat".to_string(),
+            teams: None,
+        });
+
+        assert_eq!(service.call(request).await?, "octocat");
+        assert_eq!(service.call(hyper::Request::new(())).await?, "system");
+
+        Ok(())
+    }
+}
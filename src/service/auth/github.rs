@@ -0,0 +1,389 @@
+use async_trait::async_trait;
+use fabriq_core::WorkloadMessage;
+use octocrab::{models::User, Octocrab};
+use std::{fmt::Debug, sync::Arc};
+use tonic::Status;
+
+use super::identity_cache::{IdentityCache, IdentityCacheConfig};
+use super::{AuthProvider, Identity};
+
+#[async_trait]
+pub trait GitHubClient: Debug + Send + Sync {
+    async fn get_login(&self, pat: &str) -> Result<String, Status>;
+    async fn get_team_member_logins(
+        &self,
+        pat: &str,
+        org: &str,
+        team: &str,
+    ) -> Result<Vec<String>, Status>;
+}
+
+#[derive(Debug, Default)]
+pub struct OctocrabGitHubClient {}
+
+#[async_trait]
+impl GitHubClient for OctocrabGitHubClient {
+    async fn get_login(&self, pat: &str) -> Result<String, Status> {
+        let octocrab = build_octocrab_client(pat).await?;
+
+        Ok(get_user(&octocrab).await?.login)
+    }
+
+    async fn get_team_member_logins(
+        &self,
+        pat: &str,
+        org: &str,
+        team: &str,
+    ) -> Result<Vec<String>, Status> {
+        let octocrab = build_octocrab_client(pat).await?;
+        let team_members = get_team_members(&octocrab, org, team).await?;
+
+        Ok(team_members
+            .into_iter()
+            .map(|team_member| team_member.login)
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+pub struct GitHubAuthProvider {
+    client: Arc<dyn GitHubClient>,
+
+    logins: IdentityCache<Option<String>>,
+    memberships: IdentityCache<bool>,
+}
+
+impl Default for GitHubAuthProvider {
+    fn default() -> Self {
+        GitHubAuthProvider::new(
+            Arc::new(OctocrabGitHubClient::default()),
+            IdentityCacheConfig::default(),
+        )
+    }
+}
+
+impl GitHubAuthProvider {
+    pub const NAME: &str = "github";
+    const LOGIN_SCOPE: &str = "login";
+
+    pub fn new(client: Arc<dyn GitHubClient>, cache_config: IdentityCacheConfig) -> Self {
+        GitHubAuthProvider {
+            client,
+
+            logins: IdentityCache::new(cache_config.clone()),
+            memberships: IdentityCache::new(cache_config),
+        }
+    }
+
+    async fn get_login(&self, pat: &str) -> Result<Option<String>, Status> {
+        let key = IdentityCache::<Option<String>>::make_key(pat, Self::LOGIN_SCOPE);
+
+        if let Some(login) = self.logins.get(&key) {
+            return Ok(login);
+        }
+
+        // only a rejected token is cached as having no login: other failures are returned so that
+        // a github outage doesn't lock valid users out for the negative ttl.
+        let login = match self.client.get_login(pat).await {
+            Ok(login) => Some(login),
+            Err(err) if err.code() == tonic::Code::Unauthenticated => {
+                tracing::warn!("failed to resolve login for token: {}", err);
+                None
+            }
+            Err(err) => return Err(err),
+        };
+
+        self.logins.insert(&key, login.clone(), login.is_none());
+
+        Ok(login)
+    }
+}
+
+// GitHub is the provider of last resort: any token that reaches it is treated as a PAT.
+#[async_trait]
+impl AuthProvider for GitHubAuthProvider {
+    fn name(&self) -> &str {
+        GitHubAuthProvider::NAME
+    }
+
+    async fn authenticate(&self, pat: &str) -> Result<Option<Identity>, Status> {
+        match self.get_login(pat).await? {
+            Some(login) => Ok(Some(Identity {
+                provider: GitHubAuthProvider::NAME.to_string(),
+                subject: login,
+                teams: None,
+            })),
+            None => Err(Status::new(
+                tonic::Code::Unauthenticated,
+                "failed to resolve github user for token",
+            )),
+        }
+    }
+
+    async fn is_team_member(
+        &self,
+        pat: &str,
+        identity: &Identity,
+        team_id: &str,
+    ) -> Result<bool, Status> {
+        let (org, team) = match WorkloadMessage::split_team_id(team_id) {
+            Ok(split) => split,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("team id {team_id} is invalid: {err}"),
+                ))
+            }
+        };
+
+        let key = IdentityCache::<bool>::make_key(pat, team_id);
+
+        if let Some(is_member) = self.memberships.get(&key) {
+            return Ok(is_member);
+        }
+
+        let is_member = match self.client.get_team_member_logins(pat, &org, &team).await {
+            Ok(team_member_logins) => team_member_logins.contains(&identity.subject),
+            Err(err) if err.code() == tonic::Code::NotFound => false,
+            Err(err) => return Err(err),
+        };
+
+        self.memberships.insert(&key, is_member, !is_member);
+
+        Ok(is_member)
+    }
+}
+
+pub async fn build_octocrab_client(pat: &str) -> Result<Octocrab, Status> {
+    let octocrab = octocrab::OctocrabBuilder::new()
+        .personal_token(pat.to_string())
+        .build()
+        .map_err(|_| Status::new(tonic::Code::Internal, "failed to create octocrab instance"))?;
+
+    Ok(octocrab)
+}
+
+pub async fn get_user(octocrab: &Octocrab) -> Result<User, Status> {
+    get_github(octocrab, "/user", "user for PAT").await
+}
+
+pub async fn get_team_members(
+    octocrab: &Octocrab,
+    org: &str,
+    team: &str,
+) -> Result<Vec<User>, Status> {
+    let route = format!("/orgs/{org}/teams/{team}/members");
+
+    get_github(octocrab, &route, "members for team").await
+}
+
+// Only 401 and 404 are answers about the token or team: they map to Unauthenticated and NotFound.
+// Anything else (a transport error, a 5xx, rate limiting) is Unavailable, so callers can tell a
+// failed lookup from a negative one.
+async fn get_github<R: serde::de::DeserializeOwned>(
+    octocrab: &Octocrab,
+    route: &str,
+    description: &str,
+) -> Result<R, Status> {
+    let unavailable = |err: &dyn std::fmt::Display| {
+        Status::new(
+            tonic::Code::Unavailable,
+            format!("failed to get {description} from github: {err}"),
+        )
+    };
+
+    let url = octocrab
+        .absolute_url(route)
+        .map_err(|err| Status::new(tonic::Code::Internal, err.to_string()))?;
+
+    let response = octocrab
+        ._get(url, None::<&()>)
+        .await
+        .map_err(|err| unavailable(&err))?;
+
+    match response.status() {
+        status if status.is_success() => response.json().await.map_err(|err| unavailable(&err)),
+        reqwest::StatusCode::UNAUTHORIZED => Err(Status::new(
+            tonic::Code::Unauthenticated,
+            format!("github rejected the token getting {description}"),
+        )),
+        reqwest::StatusCode::NOT_FOUND => Err(Status::new(
+            tonic::Code::NotFound,
+            format!("github has no {description}"),
+        )),
+        status => Err(unavailable(&status)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Default)]
+    struct FakeGitHubClient {
+        login_calls: AtomicUsize,
+        team_calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl GitHubClient for FakeGitHubClient {
+        async fn get_login(&self, pat: &str) -> Result<String, Status> {
+            self.login_calls.fetch_add(1, Ordering::SeqCst);
+
+            match pat {
+                "valid-pat" => Ok("timfpark".to_string()),
+                "invalid-pat" => Err(Status::new(tonic::Code::Unauthenticated, "bad credentials")),
+                _ => Err(Status::new(tonic::Code::Unavailable, "github is down")),
+            }
+        }
+
+        async fn get_team_member_logins(
+            &self,
+            _pat: &str,
+            org: &str,
+            team: &str,
+        ) -> Result<Vec<String>, Status> {
+            self.team_calls.fetch_add(1, Ordering::SeqCst);
+
+            match (org, team) {
+                ("fabriq-cloud", "fabriq") => Ok(vec!["timfpark".to_string()]),
+                ("fabriq-cloud", "outage") => {
+                    Err(Status::new(tonic::Code::Unavailable, "github is down"))
+                }
+                _ => Ok(vec![]),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_team_membership() -> anyhow::Result<()> {
+        let client = Arc::new(FakeGitHubClient::default());
+        let auth_provider = GitHubAuthProvider::new(
+            Arc::clone(&client) as Arc<dyn GitHubClient>,
+            IdentityCacheConfig::default(),
+        );
+
+        for _ in 0..3 {
+            let identity = auth_provider.authenticate("valid-pat").await?.unwrap();
+            assert_eq!(identity.subject, "timfpark");
+
+            assert!(
+                auth_provider
+                    .is_team_member("valid-pat", &identity, "fabriq-cloud/fabriq")
+                    .await?
+            );
+            assert!(
+                !auth_provider
+                    .is_team_member("valid-pat", &identity, "another-cloud/team")
+                    .await?
+            );
+        }
+
+        assert_eq!(client.login_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.team_calls.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cached_invalid_token() -> anyhow::Result<()> {
+        let client = Arc::new(FakeGitHubClient::default());
+        let auth_provider = GitHubAuthProvider::new(
+            Arc::clone(&client) as Arc<dyn GitHubClient>,
+            IdentityCacheConfig::default(),
+        );
+
+        for _ in 0..3 {
+            let result = auth_provider.authenticate("invalid-pat").await;
+
+            assert_eq!(result.unwrap_err().code(), tonic::Code::Unauthenticated);
+        }
+
+        assert_eq!(client.login_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.team_calls.load(Ordering::SeqCst), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_github_is_not_cached() -> anyhow::Result<()> {
+        let client = Arc::new(FakeGitHubClient::default());
+        let auth_provider = GitHubAuthProvider::new(
+            Arc::clone(&client) as Arc<dyn GitHubClient>,
+            IdentityCacheConfig::default(),
+        );
+
+        for _ in 0..3 {
+            let result = auth_provider.authenticate("flaky-pat").await;
+            assert_eq!(result.unwrap_err().code(), tonic::Code::Unavailable);
+        }
+
+        let identity = auth_provider.authenticate("valid-pat").await?.unwrap();
+        for _ in 0..3 {
+            let result = auth_provider
+                .is_team_member("valid-pat", &identity, "fabriq-cloud/outage")
+                .await;
+            assert_eq!(result.unwrap_err().code(), tonic::Code::Unavailable);
+        }
+
+        assert_eq!(client.login_calls.load(Ordering::SeqCst), 4);
+        assert_eq!(client.team_calls.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_user() -> anyhow::Result<()> {
+        let pat = std::env::var("FABRIQ_GITHUB_TOKEN").expect("FABRIQ_GITHUB_TOKEN must be set");
+
+        let octocrab = build_octocrab_client(&pat).await?;
+        get_user(&octocrab).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_team_members() -> anyhow::Result<()> {
+        let pat = std::env::var("FABRIQ_GITHUB_TOKEN").expect("FABRIQ_GITHUB_TOKEN must be set");
+
+        let octocrab = build_octocrab_client(&pat).await?;
+        let members = get_team_members(&octocrab, "fabriq-cloud", "fabriq").await?;
+
+        assert!(!members.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_is_team_member() -> anyhow::Result<()> {
+        let pat = std::env::var("FABRIQ_GITHUB_TOKEN").expect("FABRIQ_GITHUB_TOKEN must be set");
+
+        let auth_provider = GitHubAuthProvider::default();
+        let identity = auth_provider.authenticate(&pat).await?.unwrap();
+
+        let is_member = auth_provider
+            .is_team_member(&pat, &identity, "fabriq-cloud/fabriq")
+            .await?;
+
+        assert!(is_member);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_is_not_team_member() -> anyhow::Result<()> {
+        let pat = std::env::var("FABRIQ_GITHUB_TOKEN").expect("FABRIQ_GITHUB_TOKEN must be set");
+
+        let auth_provider = GitHubAuthProvider::default();
+        let identity = auth_provider.authenticate(&pat).await?.unwrap();
+
+        let is_member = auth_provider
+            .is_team_member(&pat, &identity, "another-cloud/team")
+            .await?;
+
+        assert!(!is_member);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    env,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_TTL_SECONDS: u64 = 300;
+const DEFAULT_NEGATIVE_TTL_SECONDS: u64 = 30;
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+#[derive(Clone, Debug)]
+pub struct IdentityCacheConfig {
+    pub ttl: Duration,
+    pub negative_ttl: Duration,
+    pub max_entries: usize,
+}
+
+impl Default for IdentityCacheConfig {
+    fn default() -> Self {
+        IdentityCacheConfig {
+            ttl: Duration::from_secs(DEFAULT_TTL_SECONDS),
+            negative_ttl: Duration::from_secs(DEFAULT_NEGATIVE_TTL_SECONDS),
+            max_entries: DEFAULT_MAX_ENTRIES,
+        }
+    }
+}
+
+impl IdentityCacheConfig {
+    pub fn from_env() -> Self {
+        let default = IdentityCacheConfig::default();
+
+        let ttl = env::var("IDENTITY_CACHE_TTL_SECONDS")
+            .ok()
+            .and_then(|ttl| ttl.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default.ttl);
+
+        let negative_ttl = env::var("IDENTITY_CACHE_NEGATIVE_TTL_SECONDS")
+            .ok()
+            .and_then(|ttl| ttl.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default.negative_ttl);
+
+        let max_entries = env::var("IDENTITY_CACHE_MAX_ENTRIES")
+            .ok()
+            .and_then(|max_entries| max_entries.parse().ok())
+            .unwrap_or(default.max_entries);
+
+        IdentityCacheConfig {
+            ttl,
+            negative_ttl,
+            max_entries,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry<Value> {
+    value: Value,
+    expires_at: Instant,
+}
+
+// Caches identity lookups so that we don't hit the identity provider on every request. Entries are
+// keyed by a hash of the token so tokens themselves are never held in memory longer than a request.
+#[derive(Debug)]
+pub struct IdentityCache<Value> {
+    config: IdentityCacheConfig,
+    entries: Mutex<HashMap<String, CacheEntry<Value>>>,
+}
+
+impl<Value: Clone> IdentityCache<Value> {
+    pub fn new(config: IdentityCacheConfig) -> Self {
+        IdentityCache {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn make_key(token: &str, scope: &str) -> String {
+        let token_hash = Sha256::digest(token.as_bytes());
+
+        format!("{token_hash:x}:{scope}")
+    }
+
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    // negative results (invalid tokens, non-membership) are cached for a shorter period so that
+    // fixing them upstream (eg. adding someone to a team) takes effect quickly.
+    pub fn insert(&self, key: &str, value: Value, negative: bool) {
+        if self.config.max_entries == 0 {
+            return;
+        }
+
+        let ttl = if negative {
+            self.config.negative_ttl
+        } else {
+            self.config.ttl
+        };
+
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+
+        if !entries.contains_key(key) && entries.len() >= self.config.max_entries {
+            entries.retain(|_, entry| entry.expires_at > now);
+        }
+
+        if !entries.contains_key(key) && entries.len() >= self.config.max_entries {
+            let oldest_key = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires_at)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest_key) = oldest_key {
+                entries.remove(&oldest_key);
+            }
+        }
+
+        entries.insert(
+            key.to_string(),
+            CacheEntry {
+                value,
+                expires_at: now + ttl,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_and_negative_ttl() {
+        let cache = IdentityCache::<bool>::new(IdentityCacheConfig {
+            ttl: Duration::from_secs(60),
+            negative_ttl: Duration::ZERO,
+            max_entries: 10,
+        });
+
+        let positive_key = IdentityCache::<bool>::make_key("pat", "fabriq-cloud/fabriq");
+        let negative_key = IdentityCache::<bool>::make_key("pat", "another-cloud/team");
+
+        assert!(!positive_key.contains("pat:"));
+
+        cache.insert(&positive_key, true, false);
+        cache.insert(&negative_key, false, true);
+
+        assert_eq!(cache.get(&positive_key), Some(true));
+        assert_eq!(cache.get(&negative_key), None);
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_size_bound() {
+        let cache = IdentityCache::<String>::new(IdentityCacheConfig {
+            ttl: Duration::from_secs(60),
+            negative_ttl: Duration::from_secs(60),
+            max_entries: 2,
+        });
+
+        cache.insert("a", "a".to_string(), false);
+        cache.insert("b", "b".to_string(), false);
+        cache.insert("c", "c".to_string(), false);
+
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("c"), Some("c".to_string()));
+    }
+}
@@ -0,0 +1,332 @@
+use async_trait::async_trait;
+use fabriq_core::WorkloadMessage;
+use std::{env, fmt::Debug, sync::Arc};
+use tonic::{Request, Status};
+
+mod github;
+mod identity_cache;
+mod oidc;
+mod rbac;
+mod static_token;
+
+pub use github::{GitHubAuthProvider, OctocrabGitHubClient};
+pub use identity_cache::IdentityCacheConfig;
+pub use oidc::{OidcAuthProvider, OidcConfig};
+pub use rbac::{
+    bootstrap_platform_admins_from_env, RbacAuthorizer, Role, RoleBindingCache, TeamScope,
+};
+pub use static_token::StaticTokenAuthProvider;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Identity {
+    pub provider: String,
+    pub subject: String,
+
+    // teams asserted by the credential itself, None if membership needs to be looked up by the provider.
+    pub teams: Option<Vec<String>>,
+}
+
+#[async_trait]
+pub trait AuthProvider: Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    // Returns Ok(None) if the token is not one this provider understands, so that the next
+    // provider in the chain can try it.
+    async fn authenticate(&self, token: &str) -> Result<Option<Identity>, Status>;
+
+    async fn is_team_member(
+        &self,
+        _token: &str,
+        identity: &Identity,
+        team_id: &str,
+    ) -> Result<bool, Status> {
+        Ok(identity
+            .teams
+            .as_ref()
+            .map(|teams| teams.iter().any(|team| team == team_id))
+            .unwrap_or(false))
+    }
+}
+
+#[derive(Debug)]
+pub struct ChainedAuthProvider {
+    pub providers: Vec<Arc<dyn AuthProvider>>,
+}
+
+impl ChainedAuthProvider {
+    pub const NAME: &str = "chained";
+}
+
+#[async_trait]
+impl AuthProvider for ChainedAuthProvider {
+    fn name(&self) -> &str {
+        ChainedAuthProvider::NAME
+    }
+
+    async fn authenticate(&self, token: &str) -> Result<Option<Identity>, Status> {
+        for provider in self.providers.iter() {
+            if let Some(identity) = provider.authenticate(token).await? {
+                return Ok(Some(identity));
+            }
+        }
+
+        Err(Status::new(
+            tonic::Code::Unauthenticated,
+            "token was not accepted by any auth provider",
+        ))
+    }
+
+    async fn is_team_member(
+        &self,
+        token: &str,
+        identity: &Identity,
+        team_id: &str,
+    ) -> Result<bool, Status> {
+        match self
+            .providers
+            .iter()
+            .find(|provider| provider.name() == identity.provider)
+        {
+            Some(provider) => provider.is_team_member(token, identity, team_id).await,
+            None => Ok(false),
+        }
+    }
+}
+
+pub async fn build_auth_provider_from_env() -> anyhow::Result<ChainedAuthProvider> {
+    let mut providers: Vec<Arc<dyn AuthProvider>> = Vec::new();
+
+    if let Ok(static_tokens_file) = env::var("AUTH_STATIC_TOKENS_FILE") {
+        tracing::info!("static token auth enabled from {}", static_tokens_file);
+        providers.push(Arc::new(StaticTokenAuthProvider::from_file(
+            &static_tokens_file,
+        )?));
+    }
+
+    if let Ok(jwks) = env::var("AUTH_OIDC_JWKS") {
+        tracing::info!("oidc auth enabled with jwks from {}", jwks);
+        let config = OidcConfig {
+            jwks,
+            issuer: env::var("AUTH_OIDC_ISSUER").ok(),
+            audience: env::var("AUTH_OIDC_AUDIENCE").ok(),
+            teams_claim: env::var("AUTH_OIDC_TEAMS_CLAIM")
+                .unwrap_or_else(|_| OidcConfig::DEFAULT_TEAMS_CLAIM.to_string()),
+        };
+        providers.push(Arc::new(OidcAuthProvider::new(config).await?));
+    }
+
+    let github_enabled = env::var("AUTH_GITHUB_ENABLED").unwrap_or_else(|_| "true".to_string());
+    if github_enabled != "false" {
+        providers.push(Arc::new(GitHubAuthProvider::new(
+            Arc::new(OctocrabGitHubClient::default()),
+            IdentityCacheConfig::from_env(),
+        )));
+    }
+
+    if providers.is_empty() {
+        return Err(anyhow::anyhow!("no auth providers are enabled"));
+    }
+
+    Ok(ChainedAuthProvider { providers })
+}
+
+// The acl interceptor attaches the authenticated identity to the request, but fall back to
+// authenticating here so that services can also be called directly.
+pub async fn get_identity<T>(
+    request: &Request<T>,
+    auth_provider: &dyn AuthProvider,
+) -> Result<Identity, Status> {
+    if let Some(identity) = request.extensions().get::<Identity>() {
+        return Ok(identity.clone());
+    }
+
+    let token = crate::acl::get_token_from_headers(request).await?;
+
+    match auth_provider.authenticate(&token).await? {
+        Some(identity) => Ok(identity),
+        None => Err(Status::new(
+            tonic::Code::Unauthenticated,
+            "token was not accepted by auth provider",
+        )),
+    }
+}
+
+pub async fn authorize_team_member<T>(
+    request: &Request<T>,
+    auth_provider: &dyn AuthProvider,
+    team_id: &str,
+) -> Result<(), Status> {
+    let token = crate::acl::get_token_from_headers(request).await?;
+    let identity = get_identity(request, auth_provider).await?;
+
+    if let Err(err) = WorkloadMessage::split_team_id(team_id) {
+        return Err(Status::new(
+            tonic::Code::InvalidArgument,
+            format!("team id {team_id} is invalid: {err}"),
+        ));
+    }
+
+    if !auth_provider
+        .is_team_member(&token, &identity, team_id)
+        .await?
+    {
+        return Err(Status::new(
+            tonic::Code::PermissionDenied,
+            format!("{} is not a member of team {team_id}", identity.subject),
+        ));
+    }
+
+    if let Some(TeamScope(team_ids)) = request.extensions().get::<TeamScope>() {
+        if !team_ids
+            .iter()
+            .any(|scoped_team_id| scoped_team_id == team_id)
+        {
+            return Err(Status::new(
+                tonic::Code::PermissionDenied,
+                format!("{} is not a team admin of team {team_id}", identity.subject),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// For requests that only some of the roles allowed to call their method may make, eg. changing a
+// host's configs.
+pub async fn authorize_platform_admin<T>(
+    request: &Request<T>,
+    auth_provider: &dyn AuthProvider,
+    authorizer: &RbacAuthorizer,
+    required_for: &str,
+) -> Result<(), Status> {
+    let token = crate::acl::get_token_from_headers(request).await?;
+    let identity = get_identity(request, auth_provider).await?;
+
+    authorizer
+        .authorize_role(&token, &identity, Role::PlatformAdmin, required_for)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockAuthProvider {
+    pub team_ids: Vec<String>,
+}
+
+#[cfg(test)]
+impl MockAuthProvider {
+    pub const NAME: &str = "mock";
+
+    pub fn new(team_ids: &[&str]) -> Self {
+        MockAuthProvider {
+            team_ids: team_ids.iter().map(|team_id| team_id.to_string()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl AuthProvider for MockAuthProvider {
+    fn name(&self) -> &str {
+        MockAuthProvider::NAME
+    }
+
+    async fn authenticate(&self, _token: &str) -> Result<Option<Identity>, Status> {
+        Ok(Some(Identity {
+            provider: MockAuthProvider::NAME.to_string(),
+            subject: "test-user".to_string(),
+            teams: Some(self.team_ids.clone()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::metadata::MetadataValue;
+
+    #[tokio::test]
+    async fn test_authorize_team_member() -> anyhow::Result<()> {
+        let auth_provider = MockAuthProvider::new(&["fabriq-cloud/fabriq"]);
+
+        let mut request = Request::new(());
+        let result = authorize_team_member(&request, &auth_provider, "fabriq-cloud/fabriq").await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::Unauthenticated);
+
+        let token: MetadataValue<_> = "test-pat".parse()?;
+        request.metadata_mut().insert("authorization", token);
+
+        authorize_team_member(&request, &auth_provider, "fabriq-cloud/fabriq").await?;
+
+        let result = authorize_team_member(&request, &auth_provider, "another/team").await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        let result = authorize_team_member(&request, &auth_provider, "not-a-team").await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        // team scoped team-admins can only change their teams' models, even those of other teams
+        // they are members of.
+        let auth_provider = MockAuthProvider::new(&["fabriq-cloud/fabriq", "another/team"]);
+        request
+            .extensions_mut()
+            .insert(TeamScope(vec!["fabriq-cloud/fabriq".to_string()]));
+
+        authorize_team_member(&request, &auth_provider, "fabriq-cloud/fabriq").await?;
+
+        let result = authorize_team_member(&request, &auth_provider, "another/team").await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chained_auth_provider() -> anyhow::Result<()> {
+        let static_tokens = StaticTokenAuthProvider::from_json(
+            r#"{
+                "tokens": [{
+                    "sha256": "784c8e01994654a577f492116789bb8d9153c8774836fc8cb6bfa2cc773ae549",
+                    "subject": "gitops",
+                    "teams": ["fabriq-cloud/fabriq"]
+                }]
+            }"#,
+        )?;
+
+        let chained_auth_provider = ChainedAuthProvider {
+            providers: vec![
+                Arc::new(static_tokens),
+                Arc::new(MockAuthProvider::new(&["another-cloud/team"])),
+            ],
+        };
+
+        let identity = chained_auth_provider
+            .authenticate("service-token")
+            .await?
+            .unwrap();
+        assert_eq!(identity.provider, StaticTokenAuthProvider::NAME);
+        assert_eq!(identity.subject, "gitops");
+        assert!(
+            chained_auth_provider
+                .is_team_member("service-token", &identity, "fabriq-cloud/fabriq")
+                .await?
+        );
+
+        let identity = chained_auth_provider
+            .authenticate("some-other-token")
+            .await?
+            .unwrap();
+        assert_eq!(identity.provider, MockAuthProvider::NAME);
+        assert!(
+            !chained_auth_provider
+                .is_team_member("some-other-token", &identity, "fabriq-cloud/fabriq")
+                .await?
+        );
+
+        let empty_auth_provider = ChainedAuthProvider { providers: vec![] };
+        let result = empty_auth_provider.authenticate("service-token").await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::Unauthenticated);
+
+        Ok(())
+    }
+}
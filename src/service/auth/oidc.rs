@@ -0,0 +1,287 @@
+use async_trait::async_trait;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tonic::Status;
+
+use super::{AuthProvider, Identity};
+
+// don't let a stream of tokens with unknown key ids turn into a stream of JWKS fetches.
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+pub struct OidcConfig {
+    // path to a JWKS file or an http(s) url to fetch it from.
+    pub jwks: String,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    pub teams_claim: String,
+}
+
+impl OidcConfig {
+    pub const DEFAULT_TEAMS_CLAIM: &str = "groups";
+}
+
+#[derive(Debug)]
+struct CachedJwks {
+    jwk_set: JwkSet,
+    fetched_at: Instant,
+}
+
+#[derive(Debug)]
+pub struct OidcAuthProvider {
+    config: OidcConfig,
+    jwks: RwLock<CachedJwks>,
+}
+
+impl OidcAuthProvider {
+    pub const NAME: &str = "oidc";
+
+    pub async fn new(config: OidcConfig) -> anyhow::Result<Self> {
+        let jwk_set = OidcAuthProvider::load_jwks(&config.jwks).await?;
+
+        Ok(OidcAuthProvider::from_jwks(config, jwk_set))
+    }
+
+    pub fn from_jwks(config: OidcConfig, jwk_set: JwkSet) -> Self {
+        OidcAuthProvider {
+            config,
+            jwks: RwLock::new(CachedJwks {
+                jwk_set,
+                fetched_at: Instant::now(),
+            }),
+        }
+    }
+
+    fn is_remote(jwks: &str) -> bool {
+        jwks.starts_with("https://") || jwks.starts_with("http://")
+    }
+
+    async fn load_jwks(jwks: &str) -> anyhow::Result<JwkSet> {
+        let jwks_json = if OidcAuthProvider::is_remote(jwks) {
+            reqwest::get(jwks).await?.error_for_status()?.text().await?
+        } else {
+            tokio::fs::read_to_string(jwks).await?
+        };
+
+        Ok(serde_json::from_str(&jwks_json)?)
+    }
+
+    async fn find_decoding_key(
+        &self,
+        kid: &Option<String>,
+    ) -> Result<(DecodingKey, Option<Algorithm>), Status> {
+        if let Some(key) = self.find_cached_key(kid).await? {
+            return Ok(key);
+        }
+
+        // signing keys rotate, so refetch remote key sets when we see a key id we don't know.
+        if OidcAuthProvider::is_remote(&self.config.jwks) {
+            let mut jwks = self.jwks.write().await;
+
+            if jwks.fetched_at.elapsed() > JWKS_REFRESH_INTERVAL {
+                match OidcAuthProvider::load_jwks(&self.config.jwks).await {
+                    Ok(jwk_set) => {
+                        jwks.jwk_set = jwk_set;
+                        jwks.fetched_at = Instant::now();
+                    }
+                    Err(err) => tracing::warn!("failed to refresh jwks: {}", err),
+                }
+            }
+        }
+
+        match self.find_cached_key(kid).await? {
+            Some(key) => Ok(key),
+            None => Err(Status::new(
+                tonic::Code::Unauthenticated,
+                format!("no signing key found for key id {kid:?}"),
+            )),
+        }
+    }
+
+    async fn find_cached_key(
+        &self,
+        kid: &Option<String>,
+    ) -> Result<Option<(DecodingKey, Option<Algorithm>)>, Status> {
+        let jwks = self.jwks.read().await;
+
+        let jwk = match kid {
+            Some(kid) => jwks.jwk_set.find(kid),
+            // tokens without a key id are only accepted if the key set is unambiguous.
+            None if jwks.jwk_set.keys.len() == 1 => jwks.jwk_set.keys.first(),
+            None => None,
+        };
+
+        match jwk {
+            Some(jwk) => match DecodingKey::from_jwk(jwk) {
+                Ok(decoding_key) => Ok(Some((decoding_key, jwk.common.algorithm))),
+                Err(err) => Err(Status::new(
+                    tonic::Code::Internal,
+                    format!("jwks contains an invalid key: {err}"),
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for OidcAuthProvider {
+    fn name(&self) -> &str {
+        OidcAuthProvider::NAME
+    }
+
+    async fn authenticate(&self, token: &str) -> Result<Option<Identity>, Status> {
+        if token.split('.').count() != 3 {
+            return Ok(None);
+        }
+
+        let header = match decode_header(token) {
+            Ok(header) => header,
+            Err(_) => return Ok(None),
+        };
+
+        let (decoding_key, key_algorithm) = self.find_decoding_key(&header.kid).await?;
+
+        // only accept the algorithm the key was published for, and never a symmetric one, so
+        // a token can't choose how it is verified.
+        let algorithm = key_algorithm.unwrap_or(header.alg);
+        if matches!(
+            algorithm,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(Status::new(
+                tonic::Code::Unauthenticated,
+                "symmetric token algorithms are not supported",
+            ));
+        }
+
+        let mut validation = Validation::new(algorithm);
+
+        if let Some(issuer) = &self.config.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+
+        if let Some(audience) = &self.config.audience {
+            validation.set_audience(&[audience]);
+        }
+
+        let claims = match decode::<serde_json::Value>(token, &decoding_key, &validation) {
+            Ok(token_data) => token_data.claims,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::Unauthenticated,
+                    format!("token validation failed: {err}"),
+                ))
+            }
+        };
+
+        let subject = match claims["sub"].as_str() {
+            Some(subject) => subject.to_string(),
+            None => {
+                return Err(Status::new(
+                    tonic::Code::Unauthenticated,
+                    "token is missing a sub claim",
+                ))
+            }
+        };
+
+        let teams = match claims[&self.config.teams_claim].as_array() {
+            Some(teams) => teams
+                .iter()
+                .filter_map(|team| team.as_str().map(|team| team.to_string()))
+                .collect(),
+            None => vec![],
+        };
+
+        Ok(Some(Identity {
+            provider: OidcAuthProvider::NAME.to_string(),
+            subject,
+            teams: Some(teams),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use openssl::rsa::Rsa;
+    use serde_json::json;
+
+    const ISSUER: &str = "https://kubernetes.default.svc";
+    const AUDIENCE: &str = "fabriq";
+
+    fn base64_url_encode(bytes: &[u8]) -> String {
+        base64::encode(bytes)
+            .replace('+', "-")
+            .replace('/', "_")
+            .trim_end_matches('=')
+            .to_string()
+    }
+
+    fn create_provider_and_key() -> anyhow::Result<(OidcAuthProvider, EncodingKey)> {
+        let rsa = Rsa::generate(2048)?;
+
+        let encoding_key = EncodingKey::from_rsa_pem(&rsa.private_key_to_pem()?)?;
+
+        let jwks = json!({
+            "keys": [{
+                "kty": "RSA",
+                "kid": "test-key",
+                "alg": "RS256",
+                "n": base64_url_encode(&rsa.n().to_vec()),
+                "e": base64_url_encode(&rsa.e().to_vec()),
+            }]
+        });
+
+        let config = OidcConfig {
+            jwks: "unused".to_string(),
+            issuer: Some(ISSUER.to_string()),
+            audience: Some(AUDIENCE.to_string()),
+            teams_claim: OidcConfig::DEFAULT_TEAMS_CLAIM.to_string(),
+        };
+
+        let provider = OidcAuthProvider::from_jwks(config, serde_json::from_value(jwks)?);
+
+        Ok((provider, encoding_key))
+    }
+
+    fn create_token(encoding_key: &EncodingKey, audience: &str) -> anyhow::Result<String> {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("test-key".to_string());
+
+        let claims = json!({
+            "sub": "system:serviceaccount:fabriq:gitops",
+            "iss": ISSUER,
+            "aud": audience,
+            "exp": jsonwebtoken::get_current_timestamp() + 600,
+            "groups": ["fabriq-cloud/fabriq"],
+        });
+
+        Ok(encode(&header, &claims, encoding_key)?)
+    }
+
+    #[tokio::test]
+    async fn test_oidc_authenticate() -> anyhow::Result<()> {
+        let (provider, encoding_key) = create_provider_and_key()?;
+
+        let token = create_token(&encoding_key, AUDIENCE)?;
+        let identity = provider.authenticate(&token).await?.unwrap();
+
+        assert_eq!(identity.subject, "system:serviceaccount:fabriq:gitops");
+        assert!(
+            provider
+                .is_team_member(&token, &identity, "fabriq-cloud/fabriq")
+                .await?
+        );
+
+        let token = create_token(&encoding_key, "another-audience")?;
+        let result = provider.authenticate(&token).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::Unauthenticated);
+
+        assert!(provider.authenticate("ghp_notajwt").await?.is_none());
+
+        Ok(())
+    }
+}
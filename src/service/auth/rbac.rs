@@ -0,0 +1,503 @@
+use fabriq_core::{OperationId, RoleBindingMessage};
+use std::{
+    env, fmt,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+use tonic::Status;
+
+use super::{AuthProvider, Identity};
+use crate::{models::RoleBinding, services::RoleBindingService};
+
+// Roles are ordered so that a higher role grants everything a lower one does.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Role {
+    Viewer,
+    TeamAdmin,
+    PlatformAdmin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => RoleBindingMessage::VIEWER_ROLE,
+            Role::TeamAdmin => RoleBindingMessage::TEAM_ADMIN_ROLE,
+            Role::PlatformAdmin => RoleBindingMessage::PLATFORM_ADMIN_ROLE,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        match role {
+            RoleBindingMessage::VIEWER_ROLE => Ok(Role::Viewer),
+            RoleBindingMessage::TEAM_ADMIN_ROLE => Ok(Role::TeamAdmin),
+            RoleBindingMessage::PLATFORM_ADMIN_ROLE => Ok(Role::PlatformAdmin),
+            _ => Err(anyhow::anyhow!(
+                "unknown role {role}, expected one of {}",
+                RoleBindingMessage::ROLES.join(", ")
+            )),
+        }
+    }
+}
+
+// Services whose models are owned by a team. Mutations additionally require membership of the
+// owning team, which is checked by the service itself.
+const TEAM_SERVICES: &[&str] = &[
+    "fabriq.assignment.Assignment",
+    "fabriq.config.Config",
+    "fabriq.deployment.Deployment",
+    "fabriq.workload.Workload",
+];
+
+// Services whose models are shared by the whole fleet.
+const PLATFORM_SERVICES: &[&str] = &[
+    "fabriq.host.Host",
+    "fabriq.target.Target",
+    "fabriq.template.Template",
+];
+
+const READ_METHOD_PREFIXES: &[&str] = &["Get", "List", "Query"];
+
+// gRPC method paths are of the form /<package>.<service>/<method>. Anything we don't recognize,
+// including the rbac service itself, requires platform-admin.
+pub fn required_role(method_path: &str) -> Role {
+    let (service, method) = match method_path.trim_start_matches('/').split_once('/') {
+        Some(service_and_method) => service_and_method,
+        None => return Role::PlatformAdmin,
+    };
+
+    let is_read = READ_METHOD_PREFIXES
+        .iter()
+        .any(|prefix| method.starts_with(prefix));
+
+    if TEAM_SERVICES.contains(&service) {
+        if is_read {
+            Role::Viewer
+        } else {
+            Role::TeamAdmin
+        }
+    } else if PLATFORM_SERVICES.contains(&service) {
+        if is_read {
+            Role::Viewer
+        } else {
+            Role::PlatformAdmin
+        }
+    } else {
+        Role::PlatformAdmin
+    }
+}
+
+// The roles an identity holds. A team-admin binding for a team only makes its members admins of
+// that team's models, so those teams are kept apart from the role that applies to every team.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Grant {
+    pub role: Option<Role>,
+    pub team_admin_team_ids: Vec<String>,
+}
+
+// The teams whose models a request may change, attached to requests that were only authorized by
+// team scoped team-admin bindings and checked by authorize_team_member.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TeamScope(pub Vec<String>);
+
+// Role bindings as last listed, so that authorizing a request doesn't list them every time. A zero
+// ttl lists them for every request. Writes aren't seen until the ttl passes, as the other api
+// processes couldn't be told of them anyway, so a revoked binding applies for up to the ttl.
+#[derive(Debug, Default)]
+pub struct RoleBindingCache {
+    pub ttl: Duration,
+    role_bindings: RwLock<Option<(Instant, Arc<Vec<RoleBinding>>)>>,
+}
+
+impl RoleBindingCache {
+    const DEFAULT_TTL_SECONDS: u64 = 5;
+
+    pub fn new(ttl: Duration) -> Self {
+        RoleBindingCache {
+            ttl,
+            role_bindings: RwLock::new(None),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let ttl = env::var("RBAC_ROLE_BINDING_CACHE_TTL_SECONDS")
+            .ok()
+            .and_then(|ttl| ttl.parse().ok())
+            .unwrap_or(RoleBindingCache::DEFAULT_TTL_SECONDS);
+
+        RoleBindingCache::new(Duration::from_secs(ttl))
+    }
+
+    fn get(&self) -> Option<Arc<Vec<RoleBinding>>> {
+        let role_bindings = self.role_bindings.read().ok()?;
+
+        match role_bindings.as_ref() {
+            Some((listed_at, role_bindings)) if listed_at.elapsed() < self.ttl => {
+                Some(Arc::clone(role_bindings))
+            }
+            _ => None,
+        }
+    }
+
+    fn insert(&self, role_bindings: Arc<Vec<RoleBinding>>) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        if let Ok(mut cached) = self.role_bindings.write() {
+            *cached = Some((Instant::now(), role_bindings));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RbacAuthorizer {
+    pub role_binding_service: Arc<RoleBindingService>,
+    pub auth_provider: Arc<dyn AuthProvider>,
+
+    // role granted to every authenticated identity, None if every identity needs a binding.
+    pub default_role: Option<Role>,
+
+    pub role_binding_cache: RoleBindingCache,
+}
+
+impl RbacAuthorizer {
+    // team-admin preserves the behavior from before role bindings existed for team owned models.
+    pub const DEFAULT_ROLE: Role = Role::TeamAdmin;
+
+    pub fn default_role_from_env() -> anyhow::Result<Option<Role>> {
+        match env::var("RBAC_DEFAULT_ROLE") {
+            Ok(role) if role == "none" => Ok(None),
+            Ok(role) => Ok(Some(role.parse()?)),
+            Err(_) => Ok(Some(RbacAuthorizer::DEFAULT_ROLE)),
+        }
+    }
+
+    async fn list_role_bindings(&self) -> Result<Arc<Vec<RoleBinding>>, Status> {
+        if let Some(role_bindings) = self.role_binding_cache.get() {
+            return Ok(role_bindings);
+        }
+
+        let role_bindings = match self.role_binding_service.list().await {
+            Ok(role_bindings) => Arc::new(role_bindings),
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::Internal,
+                    format!("listing role bindings failed with {err}"),
+                ))
+            }
+        };
+
+        self.role_binding_cache.insert(Arc::clone(&role_bindings));
+
+        Ok(role_bindings)
+    }
+
+    pub async fn get_grant(&self, token: &str, identity: &Identity) -> Result<Grant, Status> {
+        let role_bindings = self.list_role_bindings().await?;
+
+        let mut grant = Grant {
+            role: self.default_role,
+            team_admin_team_ids: Vec::new(),
+        };
+
+        for role_binding in role_bindings.iter() {
+            let bound_role: Role = match role_binding.role.parse() {
+                Ok(bound_role) => bound_role,
+                Err(err) => {
+                    tracing::warn!("skipping role binding {}: {}", role_binding.id, err);
+                    continue;
+                }
+            };
+
+            // skip the (potentially remote) membership check if it can't raise the role.
+            if grant.role >= Some(bound_role) {
+                continue;
+            }
+
+            if !role_binding.subject.is_empty() {
+                if role_binding.subject == identity.subject {
+                    grant.role = Some(bound_role);
+                }
+            } else if self
+                .auth_provider
+                .is_team_member(token, identity, &role_binding.team_id)
+                .await?
+            {
+                if bound_role == Role::TeamAdmin {
+                    grant.team_admin_team_ids.push(role_binding.team_id.clone());
+                } else {
+                    grant.role = Some(bound_role);
+                }
+            }
+        }
+
+        Ok(grant)
+    }
+
+    // Returns the teams the request is limited to when it was only authorized by team scoped
+    // team-admin bindings.
+    pub async fn authorize(
+        &self,
+        token: &str,
+        identity: &Identity,
+        method_path: &str,
+    ) -> Result<Option<TeamScope>, Status> {
+        self.authorize_role(token, identity, required_role(method_path), method_path)
+            .await
+    }
+
+    // Like authorize, for checks that depend on the request's contents rather than its method.
+    // required_for names what the role is required for in the error.
+    pub async fn authorize_role(
+        &self,
+        token: &str,
+        identity: &Identity,
+        required_role: Role,
+        required_for: &str,
+    ) -> Result<Option<TeamScope>, Status> {
+        if self.default_role >= Some(required_role) {
+            return Ok(None);
+        }
+
+        let grant = self.get_grant(token, identity).await?;
+
+        if grant.role >= Some(required_role) {
+            return Ok(None);
+        }
+
+        if required_role == Role::TeamAdmin && !grant.team_admin_team_ids.is_empty() {
+            return Ok(Some(TeamScope(grant.team_admin_team_ids)));
+        }
+
+        Err(Status::new(
+            tonic::Code::PermissionDenied,
+            format!(
+                "{} does not have the {required_role} role required for {required_for}",
+                identity.subject
+            ),
+        ))
+    }
+}
+
+// Grants platform-admin to the teams and subjects in RBAC_PLATFORM_ADMIN_TEAMS and
+// RBAC_PLATFORM_ADMIN_SUBJECTS so that a fresh installation can manage its own role bindings.
+pub async fn bootstrap_platform_admins_from_env(
+    role_binding_service: &RoleBindingService,
+) -> anyhow::Result<()> {
+    let role = Role::PlatformAdmin.as_str();
+    let mut role_bindings = Vec::new();
+
+    if let Ok(team_ids) = env::var("RBAC_PLATFORM_ADMIN_TEAMS") {
+        for team_id in team_ids.split(',').filter(|team_id| !team_id.is_empty()) {
+            role_bindings.push(RoleBinding {
+                id: RoleBindingMessage::make_team_id(role, team_id),
+                role: role.to_string(),
+                team_id: team_id.to_string(),
+                subject: "".to_string(),
+                resource_version: 0,
+            });
+        }
+    }
+
+    if let Ok(subjects) = env::var("RBAC_PLATFORM_ADMIN_SUBJECTS") {
+        for subject in subjects.split(',').filter(|subject| !subject.is_empty()) {
+            role_bindings.push(RoleBinding {
+                id: RoleBindingMessage::make_subject_id(role, subject),
+                role: role.to_string(),
+                team_id: "".to_string(),
+                subject: subject.to_string(),
+                resource_version: 0,
+            });
+        }
+    }
+
+    let operation_id = OperationId::create();
+
+    for role_binding in role_bindings {
+        if role_binding_service
+            .get_by_id(&role_binding.id)
+            .await?
+            .is_none()
+        {
+            tracing::info!("bootstrapping role binding {}", role_binding.id);
+            role_binding_service
+                .upsert(&role_binding, Some(operation_id.clone()))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{test::get_role_binding_fixture, EventStream};
+    use fabriq_memory_stream::MemoryEventStream;
+
+    use super::*;
+    use crate::{auth::MockAuthProvider, persistence::memory::MemoryPersistence};
+
+    #[test]
+    fn test_required_role() {
+        assert_eq!(required_role("/fabriq.host.Host/List"), Role::Viewer);
+        assert_eq!(
+            required_role("/fabriq.host.Host/Upsert"),
+            Role::PlatformAdmin
+        );
+        assert_eq!(
+            required_role("/fabriq.template.Template/Delete"),
+            Role::PlatformAdmin
+        );
+        assert_eq!(
+            required_role("/fabriq.deployment.Deployment/GetById"),
+            Role::Viewer
+        );
+        assert_eq!(
+            required_role("/fabriq.workload.Workload/Upsert"),
+            Role::TeamAdmin
+        );
+        assert_eq!(required_role("/fabriq.rbac.Rbac/List"), Role::PlatformAdmin);
+        assert_eq!(
+            required_role("/fabriq.audit.Audit/Query"),
+            Role::PlatformAdmin
+        );
+        assert_eq!(
+            required_role("/fabriq.admin.Admin/Export"),
+            Role::PlatformAdmin
+        );
+        assert_eq!(required_role(""), Role::PlatformAdmin);
+    }
+
+    #[tokio::test]
+    async fn test_authorize() -> anyhow::Result<()> {
+        let event_stream = Arc::new(MemoryEventStream::new()?) as Arc<dyn EventStream>;
+
+        let role_binding_service = Arc::new(RoleBindingService {
+            persistence: Box::<MemoryPersistence<RoleBinding>>::default(),
+            event_stream,
+        });
+
+        let authorizer = RbacAuthorizer {
+            role_binding_service: Arc::clone(&role_binding_service),
+            auth_provider: Arc::new(MockAuthProvider::new(&["fabriq-cloud/fabriq"])),
+            default_role: None,
+            role_binding_cache: RoleBindingCache::default(),
+        };
+
+        let identity = authorizer
+            .auth_provider
+            .authenticate("test-pat")
+            .await?
+            .unwrap();
+
+        let result = authorizer
+            .authorize("test-pat", &identity, "/fabriq.host.Host/List")
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        let viewer_binding: RoleBinding = get_role_binding_fixture(None).into();
+        role_binding_service.upsert(&viewer_binding, None).await?;
+
+        authorizer
+            .authorize("test-pat", &identity, "/fabriq.host.Host/List")
+            .await?;
+
+        let result = authorizer
+            .authorize("test-pat", &identity, "/fabriq.host.Host/Delete")
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        let admin_binding = RoleBinding {
+            id: RoleBindingMessage::make_subject_id(
+                RoleBindingMessage::PLATFORM_ADMIN_ROLE,
+                "test-user",
+            ),
+            role: RoleBindingMessage::PLATFORM_ADMIN_ROLE.to_string(),
+            team_id: "".to_string(),
+            subject: "test-user".to_string(),
+            resource_version: 0,
+        };
+        role_binding_service.upsert(&admin_binding, None).await?;
+
+        authorizer
+            .authorize("test-pat", &identity, "/fabriq.host.Host/Delete")
+            .await?;
+
+        assert_eq!(
+            authorizer.get_grant("test-pat", &identity).await?.role,
+            Some(Role::PlatformAdmin)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_team_admin_binding_is_team_scoped() -> anyhow::Result<()> {
+        let event_stream = Arc::new(MemoryEventStream::new()?) as Arc<dyn EventStream>;
+
+        let role_binding_service = Arc::new(RoleBindingService {
+            persistence: Box::<MemoryPersistence<RoleBinding>>::default(),
+            event_stream,
+        });
+
+        let authorizer = RbacAuthorizer {
+            role_binding_service: Arc::clone(&role_binding_service),
+            auth_provider: Arc::new(MockAuthProvider::new(&[
+                "fabriq-cloud/fabriq",
+                "another/team",
+            ])),
+            default_role: Some(Role::Viewer),
+            role_binding_cache: RoleBindingCache::new(Duration::from_secs(60)),
+        };
+
+        let identity = authorizer
+            .auth_provider
+            .authenticate("test-pat")
+            .await?
+            .unwrap();
+
+        let team_admin_binding: RoleBinding =
+            get_role_binding_fixture(Some(RoleBindingMessage::TEAM_ADMIN_ROLE)).into();
+        role_binding_service
+            .upsert(&team_admin_binding, None)
+            .await?;
+
+        let team_scope = authorizer
+            .authorize("test-pat", &identity, "/fabriq.workload.Workload/Upsert")
+            .await?;
+        assert_eq!(
+            team_scope,
+            Some(TeamScope(vec![team_admin_binding.team_id.clone()]))
+        );
+
+        let result = authorizer
+            .authorize("test-pat", &identity, "/fabriq.host.Host/Upsert")
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        // role bindings are cached, so a revoked binding applies until the cache ttl passes.
+        role_binding_service
+            .delete(&team_admin_binding.id, None)
+            .await?;
+        assert_eq!(
+            authorizer
+                .get_grant("test-pat", &identity)
+                .await?
+                .team_admin_team_ids,
+            vec![team_admin_binding.team_id]
+        );
+
+        Ok(())
+    }
+}
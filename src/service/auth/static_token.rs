@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, fs};
+use tonic::Status;
+
+use super::{AuthProvider, Identity};
+
+#[derive(Clone, Debug, Deserialize)]
+struct StaticToken {
+    // hex encoded sha256 of the token so that the file doesn't contain usable credentials.
+    sha256: String,
+    subject: String,
+    #[serde(default)]
+    teams: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaticTokenFile {
+    tokens: Vec<StaticToken>,
+}
+
+// Authenticates service accounts against a file of token hashes, eg:
+// { "tokens": [{ "sha256": "<sha256 of token>", "subject": "gitops", "teams": ["org/team"] }] }
+#[derive(Debug)]
+pub struct StaticTokenAuthProvider {
+    tokens: HashMap<String, StaticToken>,
+}
+
+impl StaticTokenAuthProvider {
+    pub const NAME: &str = "static";
+
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        let json = fs::read_to_string(path)?;
+
+        StaticTokenAuthProvider::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let token_file: StaticTokenFile = serde_json::from_str(json)?;
+
+        let tokens = token_file
+            .tokens
+            .into_iter()
+            .map(|token| (token.sha256.to_lowercase(), token))
+            .collect();
+
+        Ok(StaticTokenAuthProvider { tokens })
+    }
+}
+
+#[async_trait]
+impl AuthProvider for StaticTokenAuthProvider {
+    fn name(&self) -> &str {
+        StaticTokenAuthProvider::NAME
+    }
+
+    async fn authenticate(&self, token: &str) -> Result<Option<Identity>, Status> {
+        let token_hash = format!("{:x}", Sha256::digest(token.as_bytes()));
+
+        Ok(self.tokens.get(&token_hash).map(|static_token| Identity {
+            provider: StaticTokenAuthProvider::NAME.to_string(),
+            subject: static_token.subject.clone(),
+            teams: Some(static_token.teams.clone()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_static_token_authenticate() -> anyhow::Result<()> {
+        let auth_provider = StaticTokenAuthProvider::from_json(
+            r#"{
+                "tokens": [{
+                    "sha256": "784C8E01994654A577F492116789BB8D9153C8774836FC8CB6BFA2CC773AE549",
+                    "subject": "gitops",
+                    "teams": ["fabriq-cloud/fabriq"]
+                }]
+            }"#,
+        )?;
+
+        let identity = auth_provider.authenticate("service-token").await?.unwrap();
+
+        assert_eq!(identity.subject, "gitops");
+        assert_eq!(
+            identity.teams,
+            Some(vec!["fabriq-cloud/fabriq".to_string()])
+        );
+
+        assert!(auth_provider.authenticate("unknown-token").await?.is_none());
+
+        Ok(())
+    }
+}
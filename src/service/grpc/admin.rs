@@ -0,0 +1,72 @@
+use fabriq_core::{AdminTrait, ExportRequest, ExportResponse, ImportRequest, ImportResponse};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::services::AdminService;
+
+#[derive(Debug)]
+pub struct GrpcAdminService {
+    service: Arc<AdminService>,
+}
+
+impl GrpcAdminService {
+    pub fn new(service: Arc<AdminService>) -> Self {
+        GrpcAdminService { service }
+    }
+}
+
+#[tonic::async_trait]
+impl AdminTrait for GrpcAdminService {
+    #[tracing::instrument(name = "grpc::admin::export", skip_all)]
+    async fn export(
+        &self,
+        _request: Request<ExportRequest>,
+    ) -> Result<Response<ExportResponse>, Status> {
+        let bundle = match self.service.export().await {
+            Ok(bundle) => bundle,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::Internal,
+                    format!("exporting bundle failed with {}", err),
+                ))
+            }
+        };
+
+        Ok(Response::new(ExportResponse {
+            bundle: Some(bundle),
+        }))
+    }
+
+    #[tracing::instrument(name = "grpc::admin::import", skip_all)]
+    async fn import(
+        &self,
+        request: Request<ImportRequest>,
+    ) -> Result<Response<ImportResponse>, Status> {
+        let bundle = match request.into_inner().bundle {
+            Some(bundle) => bundle,
+            None => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    "bundle is required",
+                ))
+            }
+        };
+
+        let (operation_id, summary) = match self.service.import(bundle).await {
+            Ok(imported) => imported,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::FailedPrecondition,
+                    format!("importing bundle failed with {}", err),
+                ))
+            }
+        };
+
+        Ok(Response::new(ImportResponse {
+            operation_id: Some(operation_id),
+            created: summary.created as i32,
+            updated: summary.updated as i32,
+            unchanged: summary.unchanged as i32,
+        }))
+    }
+}
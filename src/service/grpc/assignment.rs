@@ -1,22 +1,44 @@
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
+use super::is_resource_version_conflict;
+use crate::auth::{authorize_team_member, AuthProvider};
 use crate::models::Assignment;
+use crate::persistence::ListOptions;
 use crate::services::AssignmentService;
 
 use fabriq_core::{
     common::AssignmentIdRequest, AssignmentMessage, AssignmentTrait, DeploymentIdRequest,
-    ListAssignmentsRequest, ListAssignmentsResponse, OperationId,
+    DeploymentMessage, ListAssignmentsRequest, ListAssignmentsResponse, OperationId,
 };
 
 #[derive(Debug)]
 pub struct GrpcAssignmentService {
     service: Arc<AssignmentService>,
+    auth_provider: Arc<dyn AuthProvider>,
 }
 
 impl GrpcAssignmentService {
-    pub fn new(service: Arc<AssignmentService>) -> Self {
-        GrpcAssignmentService { service }
+    pub fn new(service: Arc<AssignmentService>, auth_provider: Arc<dyn AuthProvider>) -> Self {
+        GrpcAssignmentService {
+            service,
+            auth_provider,
+        }
+    }
+
+    // assignments are authorized against the team that owns the assigned deployment.
+    async fn authorize<T>(&self, request: &Request<T>, deployment_id: &str) -> Result<(), Status> {
+        let (team_id, _, _) = match DeploymentMessage::split_id(deployment_id) {
+            Ok(split) => split,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("deployment id {deployment_id} is invalid: {err}"),
+                ))
+            }
+        };
+
+        authorize_team_member(request, &*self.auth_provider, &team_id).await
     }
 }
 
@@ -27,10 +49,47 @@ impl AssignmentTrait for GrpcAssignmentService {
         &self,
         request: Request<AssignmentMessage>,
     ) -> Result<Response<OperationId>, Status> {
+        let assignment = request.get_ref();
+
+        if AssignmentMessage::make_id(&assignment.deployment_id, &assignment.host_id)
+            != assignment.id
+        {
+            return Err(Status::new(
+                tonic::Code::InvalidArgument,
+                format!(
+                    "assignment id {} does not match deployment {} and host {}",
+                    assignment.id, assignment.deployment_id, assignment.host_id
+                ),
+            ));
+        }
+
+        self.authorize(&request, &assignment.deployment_id).await?;
+
+        // the assignment being replaced must be the caller's too.
+        match self.service.get_by_id(&assignment.id).await {
+            Ok(Some(existing_assignment)) => {
+                self.authorize(&request, &existing_assignment.deployment_id)
+                    .await?
+            }
+            Ok(None) => {}
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::Internal,
+                    format!(
+                        "fetching assignment with id {} failed with {err}",
+                        assignment.id
+                    ),
+                ))
+            }
+        }
+
         let new_assignment: Assignment = request.into_inner().into();
 
         let operation_id = match self.service.upsert(&new_assignment, &None).await {
             Ok(operation_id) => operation_id,
+            Err(err) if is_resource_version_conflict(&err) => {
+                return Err(Status::new(tonic::Code::Aborted, err.to_string()))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::AlreadyExists,
@@ -50,11 +109,26 @@ impl AssignmentTrait for GrpcAssignmentService {
         // TODO: check that no workloads are currently still using assignment
         // Query workload service for workloads by assignment_id, error if any exist
 
-        let operation_id = match self
-            .service
-            .delete(&request.into_inner().assignment_id, &None)
-            .await
-        {
+        let assignment_id = &request.get_ref().assignment_id;
+        let assignment = match self.service.get_by_id(assignment_id).await {
+            Ok(Some(assignment)) => assignment,
+            Ok(None) => {
+                return Err(Status::new(
+                    tonic::Code::NotFound,
+                    format!("assignment with id {assignment_id} not found"),
+                ))
+            }
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::Internal,
+                    format!("fetching assignment with id {assignment_id} failed with {err}"),
+                ))
+            }
+        };
+
+        self.authorize(&request, &assignment.deployment_id).await?;
+
+        let operation_id = match self.service.delete(&assignment.id, &None).await {
             Ok(operation_id) => operation_id,
             Err(err) => {
                 return Err(Status::new(
@@ -95,17 +169,41 @@ impl AssignmentTrait for GrpcAssignmentService {
             .into_iter()
             .map(|assignment| assignment.into())
             .collect();
-        let response = ListAssignmentsResponse { assignments };
+        let response = ListAssignmentsResponse {
+            assignments,
+            next_page_token: "".to_string(),
+        };
         Ok(Response::new(response))
     }
 
     #[tracing::instrument(name = "grpc::assignment::list", skip_all)]
     async fn list(
         &self,
-        _request: Request<ListAssignmentsRequest>,
+        request: Request<ListAssignmentsRequest>,
     ) -> Result<Response<ListAssignmentsResponse>, Status> {
-        let assignments = match self.service.list().await {
-            Ok(assignments) => assignments,
+        let request = request.into_inner();
+
+        let options = match ListOptions::parse::<Assignment>(
+            request.page_size,
+            &request.page_token,
+            &request.order_by,
+            &[
+                ("deployment_id", request.deployment_id.as_str()),
+                ("host_id", request.host_id.as_str()),
+            ],
+            &[],
+        ) {
+            Ok(options) => options,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("list options are invalid: {err}"),
+                ))
+            }
+        };
+
+        let page = match self.service.list_page(&options).await {
+            Ok(page) => page,
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
@@ -114,17 +212,13 @@ impl AssignmentTrait for GrpcAssignmentService {
             }
         };
 
-        let assignment_messages = assignments
-            .iter()
-            .map(|assignment| AssignmentMessage {
-                id: assignment.id.clone(),
-                host_id: assignment.host_id.clone(),
-                deployment_id: assignment.deployment_id.clone(),
-            })
-            .collect();
-
         let response = ListAssignmentsResponse {
-            assignments: assignment_messages,
+            assignments: page
+                .models
+                .into_iter()
+                .map(AssignmentMessage::from)
+                .collect(),
+            next_page_token: page.next_page_token,
         };
 
         Ok(Response::new(response))
@@ -134,14 +228,15 @@ impl AssignmentTrait for GrpcAssignmentService {
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
-    use tonic::Request;
+    use tonic::{metadata::MetadataValue, Request};
 
     use fabriq_core::{
-        common::AssignmentIdRequest, test::get_assignment_fixture, AssignmentTrait, EventStream,
-        ListAssignmentsRequest,
+        common::AssignmentIdRequest, test::get_assignment_fixture, AssignmentMessage,
+        AssignmentTrait, EventStream, ListAssignmentsRequest,
     };
     use fabriq_memory_stream::MemoryEventStream;
 
+    use crate::auth::MockAuthProvider;
     use crate::grpc::GrpcAssignmentService;
     use crate::persistence::memory::AssignmentMemoryPersistence;
     use crate::services::AssignmentService;
@@ -156,10 +251,17 @@ mod tests {
             event_stream,
         });
 
-        let assignment_grpc_service = GrpcAssignmentService::new(Arc::clone(&assignment_service));
+        let auth_provider = Arc::new(MockAuthProvider::new(&["fabriq-cloud/fabriq"]));
+        let assignment_grpc_service =
+            GrpcAssignmentService::new(Arc::clone(&assignment_service), auth_provider);
 
         let assignment = get_assignment_fixture(None);
-        let request = Request::new(assignment.clone());
+        let token: MetadataValue<_> = "test-pat".parse()?;
+
+        let mut request = Request::new(assignment.clone());
+        request
+            .metadata_mut()
+            .insert("authorization", token.clone());
 
         let response = assignment_grpc_service
             .upsert(request)
@@ -169,16 +271,29 @@ mod tests {
 
         assert_eq!(response.id.len(), 36);
 
-        let request = Request::new(ListAssignmentsRequest {});
+        // another team can't take over the assignment by naming its own deployment.
+        let mut request = Request::new(AssignmentMessage {
+            deployment_id: "another-cloud/team/workload/deployment".to_string(),
+            ..assignment.clone()
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", token.clone());
+
+        let result = assignment_grpc_service.upsert(request).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        let request = Request::new(ListAssignmentsRequest::default());
         let _ = assignment_grpc_service
             .list(request)
             .await
             .unwrap()
             .into_inner();
 
-        let request = Request::new(AssignmentIdRequest {
+        let mut request = Request::new(AssignmentIdRequest {
             assignment_id: assignment.id.clone(),
         });
+        request.metadata_mut().insert("authorization", token);
 
         let response = assignment_grpc_service
             .delete(request)
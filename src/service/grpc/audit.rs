@@ -0,0 +1,145 @@
+use fabriq_core::{AuditEntryMessage, AuditTrait, QueryAuditRequest, QueryAuditResponse};
+use prost_types::Timestamp;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::{sync::Arc, time::SystemTime};
+use tonic::{Request, Response, Status};
+
+use crate::persistence::{AuditQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::services::AuditService;
+
+#[derive(Debug)]
+pub struct GrpcAuditService {
+    service: Arc<AuditService>,
+}
+
+impl GrpcAuditService {
+    pub fn new(service: Arc<AuditService>) -> Self {
+        GrpcAuditService { service }
+    }
+
+    fn make_query(request: QueryAuditRequest) -> anyhow::Result<AuditQuery> {
+        let limit = match request.limit {
+            0 => DEFAULT_PAGE_SIZE,
+            limit if limit > 0 && limit as usize <= MAX_PAGE_SIZE => limit as usize,
+            limit => {
+                return Err(anyhow::anyhow!(
+                    "limit {limit} must be between 1 and {MAX_PAGE_SIZE}"
+                ))
+            }
+        };
+
+        let non_empty = |value: String| (!value.is_empty()).then_some(value);
+
+        Ok(AuditQuery {
+            model_type: non_empty(request.model_type.to_lowercase()),
+            model_id: non_empty(request.model_id),
+            actor: non_empty(request.actor),
+            since: request.since.map(to_date_time).transpose()?,
+            until: request.until.map(to_date_time).transpose()?,
+            limit,
+        })
+    }
+}
+
+fn to_date_time(timestamp: Timestamp) -> anyhow::Result<DateTime<Utc>> {
+    Ok(SystemTime::try_from(timestamp)?.into())
+}
+
+#[tonic::async_trait]
+impl AuditTrait for GrpcAuditService {
+    #[tracing::instrument(name = "grpc::audit::query", skip_all)]
+    async fn query(
+        &self,
+        request: Request<QueryAuditRequest>,
+    ) -> Result<Response<QueryAuditResponse>, Status> {
+        let query = match GrpcAuditService::make_query(request.into_inner()) {
+            Ok(query) => query,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("audit query is invalid: {err}"),
+                ))
+            }
+        };
+
+        let audit_entries = match self.service.query(&query).await {
+            Ok(audit_entries) => audit_entries,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::Internal,
+                    format!("querying audit entries failed with {}", err),
+                ))
+            }
+        };
+
+        let response = QueryAuditResponse {
+            entries: audit_entries
+                .into_iter()
+                .map(AuditEntryMessage::from)
+                .collect(),
+        };
+
+        Ok(Response::new(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{test::get_target_fixture, EventStream};
+    use fabriq_memory_stream::MemoryEventStream;
+
+    use super::*;
+    use crate::models::Target;
+    use crate::persistence::memory::{AuditMemoryPersistence, MemoryPersistence};
+    use crate::services::{with_actor, AuditingEventStream, TargetService};
+
+    #[tokio::test]
+    async fn test_query_audit() -> anyhow::Result<()> {
+        let audit_service = Arc::new(AuditService {
+            persistence: Box::<AuditMemoryPersistence>::default(),
+        });
+
+        let target_service = TargetService {
+            persistence: Box::<MemoryPersistence<Target>>::default(),
+            event_stream: Arc::new(AuditingEventStream {
+                event_stream: Arc::new(MemoryEventStream::new()?) as Arc<dyn EventStream>,
+                audit_service: Arc::clone(&audit_service),
+            }),
+        };
+
+        let target: Target = get_target_fixture(None).into();
+        with_actor("octocat".to_string(), target_service.upsert(&target, &None)).await?;
+
+        let audit_grpc_service = GrpcAuditService::new(audit_service);
+
+        let request = Request::new(QueryAuditRequest {
+            model_type: "Target".to_string(),
+            actor: "octocat".to_string(),
+            since: Some(SystemTime::UNIX_EPOCH.into()),
+            ..QueryAuditRequest::default()
+        });
+        let response = audit_grpc_service.query(request).await?.into_inner();
+
+        assert_eq!(response.entries.len(), 1);
+        assert_eq!(response.entries[0].model_id, target.id);
+        assert!(response.entries[0].current_model.is_some());
+
+        let request = Request::new(QueryAuditRequest {
+            until: Some(SystemTime::UNIX_EPOCH.into()),
+            ..QueryAuditRequest::default()
+        });
+        let response = audit_grpc_service.query(request).await?.into_inner();
+
+        assert!(response.entries.is_empty());
+
+        let request = Request::new(QueryAuditRequest {
+            limit: -1,
+            ..QueryAuditRequest::default()
+        });
+        let result = audit_grpc_service.query(request).await;
+
+        assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        Ok(())
+    }
+}
@@ -5,15 +5,20 @@ use fabriq_core::{
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
-use super::auth::*;
+use super::is_resource_version_conflict;
+use crate::auth::{authorize_platform_admin, authorize_team_member, AuthProvider, RbacAuthorizer};
 use crate::models::{Config, Deployment, Workload};
-use crate::services::{ConfigService, DeploymentService, WorkloadService};
+use crate::services::{ConfigService, DeploymentService, HostService, WorkloadService};
 
 #[derive(Debug)]
 pub struct GrpcConfigService {
     pub config_service: Arc<ConfigService>,
     pub deployment_service: Arc<DeploymentService>,
+    pub host_service: Arc<HostService>,
     pub workload_service: Arc<WorkloadService>,
+
+    pub auth_provider: Arc<dyn AuthProvider>,
+    pub authorizer: Arc<RbacAuthorizer>,
 }
 
 impl GrpcConfigService {
@@ -64,7 +69,7 @@ impl GrpcConfigService {
         Ok(workload_result)
     }
 
-    async fn check_auth(&self, pat: &str, config: &Config) -> Result<(), Status> {
+    async fn check_auth<T>(&self, request: &Request<T>, config: &Config) -> Result<(), Status> {
         let (owning_model, owning_model_id) = match config.split_owning_model() {
             Ok((owning_model, owning_model_id)) => (owning_model, owning_model_id),
             Err(err) => {
@@ -99,7 +104,7 @@ impl GrpcConfigService {
                 }
             };
 
-            is_team_member(pat, &workload.team_id).await?;
+            authorize_team_member(request, &*self.auth_provider, &workload.team_id).await?;
         } else if owning_model == ConfigMessage::WORKLOAD_OWNER {
             let workload = match self.wrapped_get_workload_by_id(&owning_model_id).await {
                 Ok(workload) => workload,
@@ -111,7 +116,33 @@ impl GrpcConfigService {
                 }
             };
 
-            is_team_member(pat, &workload.team_id).await?;
+            authorize_team_member(request, &*self.auth_provider, &workload.team_id).await?;
+        } else if owning_model == ConfigMessage::HOST_OWNER {
+            match self.host_service.get_by_id(&owning_model_id).await {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    return Err(Status::new(
+                        tonic::Code::NotFound,
+                        format!("host with id {} not found", owning_model_id),
+                    ))
+                }
+                Err(err) => {
+                    return Err(Status::new(
+                        tonic::Code::Internal,
+                        format!("get_host_by_id failed: {}", err),
+                    ))
+                }
+            };
+
+            // hosts are shared by the fleet rather than owned by a team, so their configs need
+            // the same role as the hosts themselves.
+            authorize_platform_admin(
+                request,
+                &*self.auth_provider,
+                &self.authorizer,
+                "host configs",
+            )
+            .await?;
         } else if owning_model == ConfigMessage::TEMPLATE_OWNER {
             // what to do here?  check if member of special platform team?
             // should templates be owned by a team so changes can be authed?
@@ -133,13 +164,15 @@ impl ConfigTrait for GrpcConfigService {
         &self,
         request: Request<ConfigMessage>,
     ) -> Result<Response<OperationId>, Status> {
-        let pat = crate::acl::get_pat_from_headers(&request).await?;
-        let config: Config = request.into_inner().into();
+        let config: Config = request.get_ref().clone().into();
 
-        self.check_auth(&pat, &config).await?;
+        self.check_auth(&request, &config).await?;
 
         let operation_id = match self.config_service.upsert(&config, &None).await {
             Ok(operation_id) => operation_id,
+            Err(err) if is_resource_version_conflict(&err) => {
+                return Err(Status::new(tonic::Code::Aborted, err.to_string()))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::InvalidArgument,
@@ -156,8 +189,7 @@ impl ConfigTrait for GrpcConfigService {
         &self,
         request: Request<ConfigIdRequest>,
     ) -> Result<Response<OperationId>, Status> {
-        let pat = crate::acl::get_pat_from_headers(&request).await?;
-        let config_id = request.into_inner().config_id;
+        let config_id = request.get_ref().config_id.clone();
 
         let config = match self.config_service.get_by_id(&config_id).await {
             Ok(config) => match config {
@@ -177,7 +209,7 @@ impl ConfigTrait for GrpcConfigService {
             }
         };
 
-        self.check_auth(&pat, &config).await?;
+        self.check_auth(&request, &config).await?;
 
         // TODO: check that no workloads are currently still using config
         // Query workload service for workloads by config_id, error if any exist
@@ -216,6 +248,8 @@ impl ConfigTrait for GrpcConfigService {
                 (Some(deployment), Some(workload), template_id)
             }
 
+            ConfigMessage::HOST_OWNER => (None, None, String::new()),
+
             ConfigMessage::TEMPLATE_OWNER => (None, None, query.model_id.clone()),
 
             ConfigMessage::WORKLOAD_OWNER => {
@@ -261,30 +295,32 @@ impl ConfigTrait for GrpcConfigService {
 mod tests {
     use fabriq_core::{
         test::{
-            get_deployment_fixture, get_string_config_fixture, get_target_fixture,
-            get_template_fixture, get_workload_fixture,
+            get_deployment_fixture, get_host_fixture, get_string_config_fixture,
+            get_target_fixture, get_template_fixture, get_workload_fixture,
         },
-        ConfigIdRequest, ConfigTrait, EventStream, QueryConfigRequest,
+        ConfigIdRequest, ConfigMessage, ConfigTrait, ConfigValueType, EventStream,
+        QueryConfigRequest, RoleBindingMessage,
     };
     use fabriq_memory_stream::MemoryEventStream;
-    use std::{env, sync::Arc};
+    use std::sync::Arc;
     use tonic::{metadata::MetadataValue, Request};
 
     use super::GrpcConfigService;
+    use crate::auth::{MockAuthProvider, RbacAuthorizer, RoleBindingCache};
 
     use crate::services::{
         ConfigService, DeploymentService, TargetService, TemplateService, WorkloadService,
     };
     use crate::{
-        models::{Deployment, Target, Template, Workload},
+        models::{Config, Deployment, Host, RoleBinding, Target, Template, Workload},
         persistence::memory::{
             AssignmentMemoryPersistence, ConfigMemoryPersistence, DeploymentMemoryPersistence,
-            MemoryPersistence, WorkloadMemoryPersistence,
+            HostMemoryPersistence, MemoryPersistence, WorkloadMemoryPersistence,
         },
-        services::AssignmentService,
+        services::{AssignmentService, HostService, RoleBindingService},
     };
 
-    async fn create_config_grpc_service() -> GrpcConfigService {
+    async fn create_config_grpc_service(team_ids: &[&str]) -> GrpcConfigService {
         let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
 
         let template_persistence = MemoryPersistence::<Template>::default();
@@ -348,22 +384,101 @@ mod tests {
             .await
             .unwrap();
 
+        let host_service = Arc::new(HostService {
+            persistence: Box::<HostMemoryPersistence>::default(),
+            event_stream: Arc::clone(&event_stream),
+        });
+
+        let auth_provider = Arc::new(MockAuthProvider::new(team_ids));
+
+        let authorizer = Arc::new(RbacAuthorizer {
+            role_binding_service: Arc::new(RoleBindingService {
+                persistence: Box::<MemoryPersistence<RoleBinding>>::default(),
+                event_stream: Arc::clone(&event_stream),
+            }),
+            auth_provider: auth_provider.clone(),
+            default_role: Some(RbacAuthorizer::DEFAULT_ROLE),
+            role_binding_cache: RoleBindingCache::default(),
+        });
+
         GrpcConfigService {
             config_service: Arc::clone(&config_service),
             deployment_service: Arc::clone(&deployment_service),
+            host_service,
             workload_service: Arc::clone(&workload_service),
+
+            auth_provider,
+            authorizer,
         }
     }
+
+    // Binds platform-admin to the mock auth provider's test-user.
+    async fn bind_platform_admin(config_grpc_service: &GrpcConfigService) -> anyhow::Result<()> {
+        let admin_binding = RoleBinding {
+            id: RoleBindingMessage::make_subject_id(
+                RoleBindingMessage::PLATFORM_ADMIN_ROLE,
+                "test-user",
+            ),
+            role: RoleBindingMessage::PLATFORM_ADMIN_ROLE.to_string(),
+            team_id: "".to_string(),
+            subject: "test-user".to_string(),
+            resource_version: 0,
+        };
+
+        config_grpc_service
+            .authorizer
+            .role_binding_service
+            .upsert(&admin_binding, None)
+            .await?;
+
+        Ok(())
+    }
+
+    fn make_host_config(host_id: &str) -> anyhow::Result<ConfigMessage> {
+        let owning_model = ConfigMessage::make_owning_model("host", host_id)?;
+
+        Ok(ConfigMessage {
+            id: ConfigMessage::make_id(&owning_model, "clusterName"),
+            owning_model,
+            key: "clusterName".to_string(),
+            value: "eastus2-1".to_string(),
+            value_type: ConfigValueType::StringType as i32,
+            ..Default::default()
+        })
+    }
+
     #[tokio::test]
     async fn test_check_auth() -> anyhow::Result<()> {
-        let access_token =
-            env::var("FABRIQ_GITHUB_TOKEN").expect("FABRIQ_GITHUB_TOKEN must be set");
         let config = get_string_config_fixture().into();
 
-        let config_grpc_service = create_config_grpc_service().await;
+        let mut request = Request::new(());
+        let token: MetadataValue<_> = "test-pat".parse()?;
+        request.metadata_mut().insert("authorization", token);
+
+        let config_grpc_service = create_config_grpc_service(&["fabriq-cloud/fabriq"]).await;
+        config_grpc_service.check_auth(&request, &config).await?;
 
+        let config_grpc_service = create_config_grpc_service(&["another-cloud/team"]).await;
+        let result = config_grpc_service.check_auth(&request, &config).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        let host_config: Config = make_host_config("host-fixture")?.into();
+
+        let result = config_grpc_service.check_auth(&request, &host_config).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::NotFound);
+
+        let host: Host = get_host_fixture(None).into();
         config_grpc_service
-            .check_auth(&access_token, &config)
+            .host_service
+            .upsert(&host, &None)
+            .await?;
+
+        let result = config_grpc_service.check_auth(&request, &host_config).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        bind_platform_admin(&config_grpc_service).await?;
+        config_grpc_service
+            .check_auth(&request, &host_config)
             .await?;
 
         Ok(())
@@ -372,15 +487,13 @@ mod tests {
     #[tokio::test]
     async fn test_create_list_config() -> anyhow::Result<()> {
         let config = get_string_config_fixture();
-        let config_grpc_service = create_config_grpc_service().await;
+        let config_grpc_service = create_config_grpc_service(&["fabriq-cloud/fabriq"]).await;
 
         let deployment: Deployment = get_deployment_fixture(None).into();
 
         let mut request = Request::new(config.clone());
 
-        let access_token =
-            env::var("FABRIQ_GITHUB_TOKEN").expect("FABRIQ_GITHUB_TOKEN must be set");
-        let token: MetadataValue<_> = access_token.parse()?;
+        let token: MetadataValue<_> = "test-pat".parse()?;
 
         request
             .metadata_mut()
@@ -407,6 +520,35 @@ mod tests {
 
         assert_eq!(response.configs.len(), 1);
 
+        let host: Host = get_host_fixture(None).into();
+        config_grpc_service
+            .host_service
+            .upsert(&host, &None)
+            .await?;
+        bind_platform_admin(&config_grpc_service).await?;
+
+        let host_config = make_host_config(&host.id)?;
+
+        let mut request = Request::new(host_config.clone());
+        request
+            .metadata_mut()
+            .insert("authorization", token.clone());
+        config_grpc_service.upsert(request).await?;
+
+        let request = Request::new(QueryConfigRequest {
+            model_name: "host".to_string(),
+            model_id: "host-fixture".to_string(),
+        });
+
+        let response = config_grpc_service
+            .query(request)
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.configs.len(), 1);
+        assert_eq!(response.configs[0].id, host_config.id);
+
         let mut request = Request::new(ConfigIdRequest {
             config_id: config.id.clone(),
         });
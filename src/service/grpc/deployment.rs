@@ -1,21 +1,49 @@
 use fabriq_core::{
-    common::TemplateIdRequest, DeploymentIdRequest, DeploymentMessage, DeploymentTrait,
-    ListDeploymentsRequest, ListDeploymentsResponse, OperationId, WorkloadIdRequest,
+    common::TemplateIdRequest, DeleteDeploymentRequest, DeploymentIdRequest, DeploymentMessage,
+    DeploymentTrait, ListDeploymentsRequest, ListDeploymentsResponse, ModelType, OperationId,
+    WorkloadIdRequest,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
+use super::{is_dependents_exist, is_resource_version_conflict};
+use crate::auth::{authorize_team_member, AuthProvider};
 use crate::models::Deployment;
-use crate::services::DeploymentService;
+use crate::persistence::ListOptions;
+use crate::services::{DeletionService, DeploymentService, ModelReference};
 
 #[derive(Debug)]
 pub struct GrpcDeploymentService {
     service: Arc<DeploymentService>,
+    deletion_service: Arc<DeletionService>,
+    auth_provider: Arc<dyn AuthProvider>,
 }
 
 impl GrpcDeploymentService {
-    pub fn new(service: Arc<DeploymentService>) -> Self {
-        GrpcDeploymentService { service }
+    pub fn new(
+        service: Arc<DeploymentService>,
+        deletion_service: Arc<DeletionService>,
+        auth_provider: Arc<dyn AuthProvider>,
+    ) -> Self {
+        GrpcDeploymentService {
+            service,
+            deletion_service,
+            auth_provider,
+        }
+    }
+
+    async fn authorize<T>(&self, request: &Request<T>, deployment_id: &str) -> Result<(), Status> {
+        let (team_id, _, _) = match DeploymentMessage::split_id(deployment_id) {
+            Ok(split) => split,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("deployment id {deployment_id} is invalid: {err}"),
+                ))
+            }
+        };
+
+        authorize_team_member(request, &*self.auth_provider, &team_id).await
     }
 }
 
@@ -26,10 +54,27 @@ impl DeploymentTrait for GrpcDeploymentService {
         &self,
         request: Request<DeploymentMessage>,
     ) -> Result<Response<OperationId>, Status> {
+        self.authorize(&request, &request.get_ref().id).await?;
+
         let new_deployment: Deployment = request.into_inner().into();
 
+        if DeploymentMessage::make_id(&new_deployment.workload_id, &new_deployment.name)
+            != new_deployment.id
+        {
+            return Err(Status::new(
+                tonic::Code::InvalidArgument,
+                format!(
+                    "deployment id {} does not match workload {} and name {}",
+                    new_deployment.id, new_deployment.workload_id, new_deployment.name
+                ),
+            ));
+        }
+
         let operation_id = match self.service.upsert(&new_deployment, &None).await {
             Ok(operation_id) => operation_id,
+            Err(err) if is_resource_version_conflict(&err) => {
+                return Err(Status::new(tonic::Code::Aborted, err.to_string()))
+            }
             Err(err) => {
                 let message = format!(
                     "delete deployment with id {} returned error {err}",
@@ -47,20 +92,34 @@ impl DeploymentTrait for GrpcDeploymentService {
     #[tracing::instrument(name = "grpc::deployment::delete", skip_all)]
     async fn delete(
         &self,
-        request: Request<DeploymentIdRequest>,
+        request: Request<DeleteDeploymentRequest>,
     ) -> Result<Response<OperationId>, Status> {
-        // TODO: check that no workloads are currently still using deployment
-        // Query workload service for workloads by deployment_id, error if any exist
+        self.authorize(&request, &request.get_ref().deployment_id)
+            .await?;
 
-        let deployment_id = request.into_inner().deployment_id;
-        let operation_id = match self.service.delete(&deployment_id, &None).await {
+        let request = request.into_inner();
+        let deployment = ModelReference::new(ModelType::Deployment, &request.deployment_id);
+
+        let operation_id = match self
+            .deletion_service
+            .delete(&deployment, request.mode(), None)
+            .await
+        {
             Ok(operation_id) => operation_id,
+            Err(err) if is_dependents_exist(&err) => {
+                return Err(Status::new(
+                    tonic::Code::FailedPrecondition,
+                    format!("{err}, delete them first or delete with cascade"),
+                ))
+            }
             Err(err) => {
-                let message =
-                    format!("delete deployment with id {deployment_id} returned error {err}");
-
-                tracing::error!(message);
-                return Err(Status::new(tonic::Code::NotFound, message));
+                return Err(Status::new(
+                    tonic::Code::NotFound,
+                    format!(
+                        "delete deployment with id {} returned error {}",
+                        request.deployment_id, err
+                    ),
+                ))
             }
         };
 
@@ -126,6 +185,7 @@ impl DeploymentTrait for GrpcDeploymentService {
 
         let response = ListDeploymentsResponse {
             deployments: deployment_messages,
+            next_page_token: "".to_string(),
         };
 
         Ok(Response::new(response))
@@ -157,6 +217,7 @@ impl DeploymentTrait for GrpcDeploymentService {
 
         let response = ListDeploymentsResponse {
             deployments: deployment_messages,
+            next_page_token: "".to_string(),
         };
 
         Ok(Response::new(response))
@@ -165,10 +226,32 @@ impl DeploymentTrait for GrpcDeploymentService {
     #[tracing::instrument(name = "grpc::deployment::list", skip_all)]
     async fn list(
         &self,
-        _request: Request<ListDeploymentsRequest>,
+        request: Request<ListDeploymentsRequest>,
     ) -> Result<Response<ListDeploymentsResponse>, Status> {
-        let deployments = match self.service.list().await {
-            Ok(deployments) => deployments,
+        let request = request.into_inner();
+
+        let options = match ListOptions::parse::<Deployment>(
+            request.page_size,
+            &request.page_token,
+            &request.order_by,
+            &[
+                ("workload_id", request.workload_id.as_str()),
+                ("target_id", request.target_id.as_str()),
+                ("template_id", request.template_id.as_str()),
+            ],
+            &request.labels,
+        ) {
+            Ok(options) => options,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("list options are invalid: {err}"),
+                ))
+            }
+        };
+
+        let page = match self.service.list_page(&options).await {
+            Ok(page) => page,
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
@@ -177,13 +260,13 @@ impl DeploymentTrait for GrpcDeploymentService {
             }
         };
 
-        let deployment_messages = deployments
-            .iter()
-            .map(|deployment| deployment.clone().into())
-            .collect();
-
         let response = ListDeploymentsResponse {
-            deployments: deployment_messages,
+            deployments: page
+                .models
+                .into_iter()
+                .map(DeploymentMessage::from)
+                .collect(),
+            next_page_token: page.next_page_token,
         };
 
         Ok(Response::new(response))
@@ -194,66 +277,43 @@ impl DeploymentTrait for GrpcDeploymentService {
 mod tests {
     use fabriq_core::{
         common::TemplateIdRequest,
-        test::{get_deployment_fixture, get_target_fixture},
-        DeploymentIdRequest, DeploymentTrait, EventStream, ListDeploymentsRequest,
-        WorkloadIdRequest,
+        test::{get_assignment_fixture, get_deployment_fixture, get_target_fixture},
+        DeleteDeploymentRequest, DeleteMode, DeploymentIdRequest, DeploymentTrait,
+        ListDeploymentsRequest, WorkloadIdRequest,
     };
-    use fabriq_memory_stream::MemoryEventStream;
     use std::sync::Arc;
-    use tonic::Request;
+    use tonic::{metadata::MetadataValue, Request};
 
     use super::GrpcDeploymentService;
+    use crate::auth::MockAuthProvider;
 
-    use crate::services::{DeploymentService, TargetService};
-    use crate::{models::Target, services::ConfigService};
-    use crate::{
-        persistence::memory::{
-            AssignmentMemoryPersistence, ConfigMemoryPersistence, DeploymentMemoryPersistence,
-            MemoryPersistence,
-        },
-        services::AssignmentService,
-    };
+    use crate::models::{Assignment, Deployment, Target};
+    use crate::services::make_deletion_service;
 
     #[tokio::test]
     async fn test_create_list_deployment() -> anyhow::Result<()> {
-        let deployment_persistence = Box::<DeploymentMemoryPersistence>::default();
-        let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
-
-        let target_persistence = MemoryPersistence::<Target>::default();
-        let target_service = Arc::new(TargetService {
-            persistence: Box::new(target_persistence),
-            event_stream: Arc::clone(&event_stream),
-        });
+        let deletion_service = Arc::new(make_deletion_service());
 
         let target: Target = get_target_fixture(None).into();
-        target_service.upsert(&target, &None).await.unwrap();
-
-        let assignment_persistence = Box::<AssignmentMemoryPersistence>::default();
-        let assignment_service = Arc::new(AssignmentService {
-            persistence: assignment_persistence,
-            event_stream: Arc::clone(&event_stream),
-        });
-
-        let config_persistence = Box::<ConfigMemoryPersistence>::default();
-        let config_service = Arc::new(ConfigService {
-            persistence: config_persistence,
-            event_stream: Arc::clone(&event_stream),
-        });
-
-        let deployment_service = Arc::new(DeploymentService {
-            persistence: deployment_persistence,
-            event_stream: Arc::clone(&event_stream),
+        deletion_service
+            .target_service
+            .upsert(&target, &None)
+            .await
+            .unwrap();
 
-            assignment_service,
-            config_service,
-            target_service,
-        });
+        let deployment_service = Arc::clone(&deletion_service.deployment_service);
 
-        let deployment_grpc_service = GrpcDeploymentService::new(Arc::clone(&deployment_service));
+        let auth_provider = Arc::new(MockAuthProvider::new(&["fabriq-cloud/fabriq"]));
+        let deployment_grpc_service =
+            GrpcDeploymentService::new(deployment_service, deletion_service, auth_provider);
 
         let deployment = get_deployment_fixture(None);
+        let token: MetadataValue<_> = "test-pat".parse()?;
 
-        let request = Request::new(deployment.clone());
+        let mut request = Request::new(deployment.clone());
+        request
+            .metadata_mut()
+            .insert("authorization", token.clone());
 
         let response = deployment_grpc_service
             .upsert(request)
@@ -299,7 +359,7 @@ mod tests {
 
         assert_eq!(response.deployments.len(), 1);
 
-        let request = Request::new(ListDeploymentsRequest {});
+        let request = Request::new(ListDeploymentsRequest::default());
         let response = deployment_grpc_service
             .list(request)
             .await
@@ -308,9 +368,12 @@ mod tests {
 
         assert_eq!(response.deployments.len(), 1);
 
-        let request = Request::new(DeploymentIdRequest {
+        let mut request = Request::new(DeleteDeploymentRequest {
             deployment_id: deployment.id.to_string(),
+            mode: 0,
         });
+        request.metadata_mut().insert("authorization", token);
+
         let response = deployment_grpc_service
             .delete(request)
             .await
@@ -321,4 +384,51 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_delete_deployment_with_assignments() -> anyhow::Result<()> {
+        let deletion_service = Arc::new(make_deletion_service());
+
+        let target: Target = get_target_fixture(None).into();
+        deletion_service
+            .target_service
+            .upsert(&target, &None)
+            .await?;
+
+        let deployment: Deployment = get_deployment_fixture(None).into();
+        deletion_service
+            .deployment_service
+            .upsert(&deployment, &None)
+            .await?;
+
+        let assignment: Assignment = get_assignment_fixture(None).into();
+        deletion_service
+            .assignment_service
+            .upsert(&assignment, &None)
+            .await?;
+
+        let deployment_service = Arc::clone(&deletion_service.deployment_service);
+        let assignment_service = Arc::clone(&deletion_service.assignment_service);
+
+        let auth_provider = Arc::new(MockAuthProvider::new(&["fabriq-cloud/fabriq"]));
+        let deployment_grpc_service =
+            GrpcDeploymentService::new(deployment_service, deletion_service, auth_provider);
+
+        // the reconciler's assignments are part of the deployment, so don't need a cascade.
+        let mut request = Request::new(DeleteDeploymentRequest {
+            deployment_id: deployment.id.clone(),
+            mode: DeleteMode::Refuse as i32,
+        });
+        let token: MetadataValue<_> = "test-pat".parse()?;
+        request.metadata_mut().insert("authorization", token);
+
+        deployment_grpc_service.delete(request).await?;
+
+        assert!(assignment_service
+            .get_by_id(&assignment.id)
+            .await?
+            .is_none());
+
+        Ok(())
+    }
 }
@@ -1,20 +1,27 @@
 use fabriq_core::{
-    DeleteHostRequest, HostMessage, HostTrait, ListHostsRequest, ListHostsResponse, OperationId,
+    common::HostIdRequest, DeleteHostRequest, HostMessage, HostTrait, ListHostsRequest,
+    ListHostsResponse, ModelType, OperationId,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
+use super::{is_dependents_exist, is_resource_version_conflict};
 use crate::models::Host;
-use crate::services::HostService;
+use crate::persistence::ListOptions;
+use crate::services::{DeletionService, HostService, ModelReference};
 
 #[derive(Debug)]
 pub struct GrpcHostService {
     service: Arc<HostService>,
+    deletion_service: Arc<DeletionService>,
 }
 
 impl GrpcHostService {
-    pub fn new(service: Arc<HostService>) -> Self {
-        GrpcHostService { service }
+    pub fn new(service: Arc<HostService>, deletion_service: Arc<DeletionService>) -> Self {
+        GrpcHostService {
+            service,
+            deletion_service,
+        }
     }
 }
 
@@ -26,6 +33,9 @@ impl HostTrait for GrpcHostService {
 
         let operation_id = match self.service.upsert(&new_host, &None).await {
             Ok(operation_id) => operation_id,
+            Err(err) if is_resource_version_conflict(&err) => {
+                return Err(Status::new(tonic::Code::Aborted, err.to_string()))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::AlreadyExists,
@@ -42,11 +52,21 @@ impl HostTrait for GrpcHostService {
         &self,
         request: Request<DeleteHostRequest>,
     ) -> Result<Response<OperationId>, Status> {
-        // TODO: check that no workloads are currently still using host
-        // Query workload service for workloads by host_id, error if any exist
+        let request = request.into_inner();
+        let host = ModelReference::new(ModelType::Host, &request.id);
 
-        let operation_id = match self.service.delete(&request.into_inner().id, None).await {
+        let operation_id = match self
+            .deletion_service
+            .delete(&host, request.mode(), None)
+            .await
+        {
             Ok(operation_id) => operation_id,
+            Err(err) if is_dependents_exist(&err) => {
+                return Err(Status::new(
+                    tonic::Code::FailedPrecondition,
+                    format!("{err}, delete them first or delete with cascade"),
+                ))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::NotFound,
@@ -58,13 +78,61 @@ impl HostTrait for GrpcHostService {
         Ok(Response::new(operation_id))
     }
 
+    #[tracing::instrument(name = "grpc::host::get_by_id", skip_all)]
+    async fn get_by_id(
+        &self,
+        request: Request<HostIdRequest>,
+    ) -> Result<Response<HostMessage>, Status> {
+        let host_id = request.into_inner().host_id;
+        let host = match self.service.get_by_id(&host_id).await {
+            Ok(host) => host,
+            Err(err) => {
+                tracing::error!("get host with id {}: failed: {}", host_id, err);
+                return Err(Status::new(
+                    tonic::Code::Internal,
+                    format!("get host with id {}: failed", &host_id),
+                ));
+            }
+        };
+
+        let host = match host {
+            Some(host) => host,
+            None => {
+                return Err(Status::new(
+                    tonic::Code::NotFound,
+                    format!("get host with id {}: not found", &host_id),
+                ))
+            }
+        };
+
+        Ok(Response::new(host.into()))
+    }
+
     #[tracing::instrument(name = "grpc::host::list", skip_all)]
     async fn list(
         &self,
-        _request: Request<ListHostsRequest>,
+        request: Request<ListHostsRequest>,
     ) -> Result<Response<ListHostsResponse>, Status> {
-        let hosts = match self.service.list().await {
-            Ok(hosts) => hosts,
+        let request = request.into_inner();
+
+        let options = match ListOptions::parse::<Host>(
+            request.page_size,
+            &request.page_token,
+            &request.order_by,
+            &[],
+            &request.labels,
+        ) {
+            Ok(options) => options,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("list options are invalid: {err}"),
+                ))
+            }
+        };
+
+        let page = match self.service.list_page(&options).await {
+            Ok(page) => page,
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
@@ -73,16 +141,9 @@ impl HostTrait for GrpcHostService {
             }
         };
 
-        let host_messages = hosts
-            .iter()
-            .map(|host| HostMessage {
-                id: host.id.clone(),
-                labels: host.labels.clone(),
-            })
-            .collect();
-
         let response = ListHostsResponse {
-            hosts: host_messages,
+            hosts: page.models.into_iter().map(HostMessage::from).collect(),
+            next_page_token: page.next_page_token,
         };
 
         Ok(Response::new(response))
@@ -92,28 +153,22 @@ impl HostTrait for GrpcHostService {
 #[cfg(test)]
 mod tests {
     use fabriq_core::{
-        test::get_host_fixture, DeleteHostRequest, EventStream, HostTrait, ListHostsRequest,
+        common::HostIdRequest, test::get_host_fixture, DeleteHostRequest, HostTrait,
+        ListHostsRequest,
     };
-    use fabriq_memory_stream::MemoryEventStream;
     use std::sync::Arc;
     use tonic::Request;
 
     use super::GrpcHostService;
 
-    use crate::persistence::memory::HostMemoryPersistence;
-    use crate::services::HostService;
+    use crate::services::make_deletion_service;
 
     #[tokio::test]
     async fn test_create_list_host() -> anyhow::Result<()> {
-        let host_persistence = Box::<HostMemoryPersistence>::default();
-        let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
-
-        let host_service = Arc::new(HostService {
-            persistence: host_persistence,
-            event_stream,
-        });
+        let deletion_service = Arc::new(make_deletion_service());
+        let host_service = Arc::clone(&deletion_service.host_service);
 
-        let host_grpc_service = GrpcHostService::new(Arc::clone(&host_service));
+        let host_grpc_service = GrpcHostService::new(host_service, deletion_service);
 
         let host = get_host_fixture(None);
 
@@ -127,13 +182,42 @@ mod tests {
 
         assert_eq!(response.id.len(), 36);
 
-        let request = Request::new(ListHostsRequest {});
+        let request = Request::new(ListHostsRequest::default());
         let response = host_grpc_service.list(request).await.unwrap().into_inner();
 
         assert_eq!(response.hosts.len(), 1);
+        assert!(response.next_page_token.is_empty());
+
+        let request = Request::new(HostIdRequest {
+            host_id: host.id.clone(),
+        });
+        let response = host_grpc_service
+            .get_by_id(request)
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.labels, host.labels);
+
+        let request = Request::new(ListHostsRequest {
+            labels: vec!["region:westus".to_string()],
+            ..ListHostsRequest::default()
+        });
+        let response = host_grpc_service.list(request).await.unwrap().into_inner();
+
+        assert!(response.hosts.is_empty());
+
+        let request = Request::new(ListHostsRequest {
+            order_by: "region".to_string(),
+            ..ListHostsRequest::default()
+        });
+        let result = host_grpc_service.list(request).await;
+
+        assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
 
         let request = Request::new(DeleteHostRequest {
             id: host.id.to_string(),
+            mode: 0,
         });
         let response = host_grpc_service
             .delete(request)
@@ -143,6 +227,13 @@ mod tests {
 
         assert_eq!(response.id.len(), 36);
 
+        let request = Request::new(HostIdRequest {
+            host_id: host.id.clone(),
+        });
+        let result = host_grpc_service.get_by_id(request).await;
+
+        assert_eq!(result.unwrap_err().code(), tonic::Code::NotFound);
+
         Ok(())
     }
 }
@@ -1,16 +1,34 @@
+use crate::persistence::ResourceVersionConflict;
+use crate::services::DependentsExist;
+
+mod admin;
 mod assignment;
-mod auth;
+mod audit;
 mod config;
 mod deployment;
 mod host;
+mod rbac;
 mod target;
 mod template;
 mod workload;
 
+pub use admin::GrpcAdminService;
 pub use assignment::GrpcAssignmentService;
+pub use audit::GrpcAuditService;
 pub use config::GrpcConfigService;
 pub use deployment::GrpcDeploymentService;
 pub use host::GrpcHostService;
+pub use rbac::GrpcRbacService;
 pub use target::GrpcTargetService;
 pub use template::GrpcTemplateService;
 pub use workload::GrpcWorkloadService;
+
+// Upserts with a stale resource version surface as Aborted so clients know to re-read and retry.
+fn is_resource_version_conflict(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<ResourceVersionConflict>().is_some()
+}
+
+// Refused deletes surface as FailedPrecondition, listing the models that depend on the model.
+fn is_dependents_exist(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<DependentsExist>().is_some()
+}
@@ -0,0 +1,241 @@
+use fabriq_core::{
+    ListRoleBindingsRequest, ListRoleBindingsResponse, OperationId, RbacTrait,
+    RoleBindingIdRequest, RoleBindingMessage, WorkloadMessage,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use super::is_resource_version_conflict;
+use crate::auth::Role;
+use crate::models::RoleBinding;
+use crate::persistence::ListOptions;
+use crate::services::RoleBindingService;
+
+#[derive(Debug)]
+pub struct GrpcRbacService {
+    service: Arc<RoleBindingService>,
+}
+
+impl GrpcRbacService {
+    pub fn new(service: Arc<RoleBindingService>) -> Self {
+        GrpcRbacService { service }
+    }
+
+    fn validate(role_binding: &RoleBindingMessage) -> anyhow::Result<()> {
+        if role_binding.id.is_empty() {
+            return Err(anyhow::anyhow!("role binding id is required"));
+        }
+
+        role_binding.role.parse::<Role>()?;
+
+        if role_binding.team_id.is_empty() == role_binding.subject.is_empty() {
+            return Err(anyhow::anyhow!(
+                "role binding must have exactly one of team_id or subject"
+            ));
+        }
+
+        let expected_id = if !role_binding.team_id.is_empty() {
+            WorkloadMessage::split_team_id(&role_binding.team_id)?;
+            RoleBindingMessage::make_team_id(&role_binding.role, &role_binding.team_id)
+        } else {
+            RoleBindingMessage::make_subject_id(&role_binding.role, &role_binding.subject)
+        };
+
+        if role_binding.id != expected_id {
+            return Err(anyhow::anyhow!(
+                "role binding id {} does not match its role and team or subject, expected {expected_id}",
+                role_binding.id
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl RbacTrait for GrpcRbacService {
+    #[tracing::instrument(name = "grpc::rbac::upsert", skip_all)]
+    async fn upsert(
+        &self,
+        request: Request<RoleBindingMessage>,
+    ) -> Result<Response<OperationId>, Status> {
+        let role_binding_message = request.into_inner();
+
+        if let Err(err) = GrpcRbacService::validate(&role_binding_message) {
+            return Err(Status::new(
+                tonic::Code::InvalidArgument,
+                format!("role binding is invalid: {err}"),
+            ));
+        }
+
+        let role_binding: RoleBinding = role_binding_message.into();
+
+        let operation_id = match self.service.upsert(&role_binding, None).await {
+            Ok(operation_id) => operation_id,
+            Err(err) if is_resource_version_conflict(&err) => {
+                return Err(Status::new(tonic::Code::Aborted, err.to_string()))
+            }
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::Internal,
+                    format!("upserting role binding failed with {}", err),
+                ))
+            }
+        };
+
+        Ok(Response::new(operation_id))
+    }
+
+    #[tracing::instrument(name = "grpc::rbac::delete", skip_all)]
+    async fn delete(
+        &self,
+        request: Request<RoleBindingIdRequest>,
+    ) -> Result<Response<OperationId>, Status> {
+        let role_binding_id = request.into_inner().role_binding_id;
+
+        let operation_id = match self.service.delete(&role_binding_id, None).await {
+            Ok(operation_id) => operation_id,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::NotFound,
+                    format!("role binding with id {} not found", err),
+                ))
+            }
+        };
+
+        Ok(Response::new(operation_id))
+    }
+
+    #[tracing::instrument(name = "grpc::rbac::list", skip_all)]
+    async fn list(
+        &self,
+        request: Request<ListRoleBindingsRequest>,
+    ) -> Result<Response<ListRoleBindingsResponse>, Status> {
+        let request = request.into_inner();
+
+        let options = match ListOptions::parse::<RoleBinding>(
+            request.page_size,
+            &request.page_token,
+            &request.order_by,
+            &[
+                ("role", request.role.as_str()),
+                ("team_id", request.team_id.as_str()),
+                ("subject", request.subject.as_str()),
+            ],
+            &[],
+        ) {
+            Ok(options) => options,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("list options are invalid: {err}"),
+                ))
+            }
+        };
+
+        let page = match self.service.list_page(&options).await {
+            Ok(page) => page,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::Internal,
+                    format!("listing role bindings failed with {}", err),
+                ))
+            }
+        };
+
+        let response = ListRoleBindingsResponse {
+            role_bindings: page
+                .models
+                .into_iter()
+                .map(RoleBindingMessage::from)
+                .collect(),
+            next_page_token: page.next_page_token,
+        };
+
+        Ok(Response::new(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{
+        test::get_role_binding_fixture, EventStream, ListRoleBindingsRequest, RbacTrait,
+        RoleBindingIdRequest, RoleBindingMessage,
+    };
+    use fabriq_memory_stream::MemoryEventStream;
+    use std::sync::Arc;
+    use tonic::Request;
+
+    use super::GrpcRbacService;
+
+    use crate::models::RoleBinding;
+    use crate::persistence::memory::MemoryPersistence;
+    use crate::services::RoleBindingService;
+
+    #[tokio::test]
+    async fn test_create_list_delete_role_binding() -> anyhow::Result<()> {
+        let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
+
+        let role_binding_service = Arc::new(RoleBindingService {
+            persistence: Box::<MemoryPersistence<RoleBinding>>::default(),
+            event_stream,
+        });
+
+        let rbac_grpc_service = GrpcRbacService::new(Arc::clone(&role_binding_service));
+
+        let role_binding = get_role_binding_fixture(None);
+
+        let mut invalid_role_binding = role_binding.clone();
+        invalid_role_binding.role = "superuser".to_string();
+        let result = rbac_grpc_service
+            .upsert(Request::new(invalid_role_binding))
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        let mut invalid_role_binding = role_binding.clone();
+        invalid_role_binding.subject = "test-user".to_string();
+        let result = rbac_grpc_service
+            .upsert(Request::new(invalid_role_binding))
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        // a binding's id must be the one derived from its role and team, so that it can't shadow
+        // another binding.
+        let mut invalid_role_binding = role_binding.clone();
+        invalid_role_binding.role = RoleBindingMessage::PLATFORM_ADMIN_ROLE.to_string();
+        let result = rbac_grpc_service
+            .upsert(Request::new(invalid_role_binding))
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+
+        let response = rbac_grpc_service
+            .upsert(Request::new(role_binding.clone()))
+            .await?
+            .into_inner();
+        assert_eq!(response.id.len(), 36);
+
+        let response = rbac_grpc_service
+            .list(Request::new(ListRoleBindingsRequest::default()))
+            .await?
+            .into_inner();
+        assert_eq!(response.role_bindings.len(), 1);
+        assert_eq!(response.role_bindings[0].resource_version, 1);
+
+        let mut stale_role_binding = role_binding.clone();
+        stale_role_binding.resource_version = 5;
+        let result = rbac_grpc_service
+            .upsert(Request::new(stale_role_binding))
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::Aborted);
+
+        let response = rbac_grpc_service
+            .delete(Request::new(RoleBindingIdRequest {
+                role_binding_id: role_binding.id,
+            }))
+            .await?
+            .into_inner();
+        assert_eq!(response.id.len(), 36);
+
+        Ok(())
+    }
+}
@@ -1,20 +1,26 @@
 use fabriq_core::{
-    ListTargetsRequest, ListTargetsResponse, OperationId, TargetIdRequest, TargetMessage,
-    TargetTrait,
+    DeleteTargetRequest, ListTargetsRequest, ListTargetsResponse, ModelType, OperationId,
+    TargetIdRequest, TargetMessage, TargetTrait,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
+use super::{is_dependents_exist, is_resource_version_conflict};
 use crate::models::Target;
-use crate::services::TargetService;
+use crate::persistence::ListOptions;
+use crate::services::{DeletionService, ModelReference, TargetService};
 
 #[derive(Debug)]
 pub struct GrpcTargetService {
     service: Arc<TargetService>,
+    deletion_service: Arc<DeletionService>,
 }
 impl GrpcTargetService {
-    pub fn new(service: Arc<TargetService>) -> Self {
-        GrpcTargetService { service }
+    pub fn new(service: Arc<TargetService>, deletion_service: Arc<DeletionService>) -> Self {
+        GrpcTargetService {
+            service,
+            deletion_service,
+        }
     }
 }
 
@@ -29,6 +35,9 @@ impl TargetTrait for GrpcTargetService {
 
         let operation_id = match self.service.upsert(&new_target, &None).await {
             Ok(operation_id) => operation_id,
+            Err(err) if is_resource_version_conflict(&err) => {
+                return Err(Status::new(tonic::Code::Aborted, err.to_string()))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::AlreadyExists,
@@ -43,17 +52,23 @@ impl TargetTrait for GrpcTargetService {
     #[tracing::instrument(name = "grpc::target::delete", skip_all)]
     async fn delete(
         &self,
-        request: Request<TargetIdRequest>,
+        request: Request<DeleteTargetRequest>,
     ) -> Result<Response<OperationId>, Status> {
-        // TODO: check that no workloads are currently still using target
-        // Query workload service for workloads by target_id, error if any exist
+        let request = request.into_inner();
+        let target = ModelReference::new(ModelType::Target, &request.target_id);
 
         let operation_id = match self
-            .service
-            .delete(&request.into_inner().target_id, None)
+            .deletion_service
+            .delete(&target, request.mode(), None)
             .await
         {
             Ok(operation_id) => operation_id,
+            Err(err) if is_dependents_exist(&err) => {
+                return Err(Status::new(
+                    tonic::Code::FailedPrecondition,
+                    format!("{err}, delete them first or delete with cascade"),
+                ))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::NotFound,
@@ -100,10 +115,28 @@ impl TargetTrait for GrpcTargetService {
     #[tracing::instrument(name = "grpc::target::list", skip_all)]
     async fn list(
         &self,
-        _request: Request<ListTargetsRequest>,
+        request: Request<ListTargetsRequest>,
     ) -> Result<Response<ListTargetsResponse>, Status> {
-        let targets = match self.service.list().await {
-            Ok(targets) => targets,
+        let request = request.into_inner();
+
+        let options = match ListOptions::parse::<Target>(
+            request.page_size,
+            &request.page_token,
+            &request.order_by,
+            &[],
+            &request.labels,
+        ) {
+            Ok(options) => options,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("list options are invalid: {err}"),
+                ))
+            }
+        };
+
+        let page = match self.service.list_page(&options).await {
+            Ok(page) => page,
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
@@ -112,16 +145,9 @@ impl TargetTrait for GrpcTargetService {
             }
         };
 
-        let target_messages = targets
-            .iter()
-            .map(|target| TargetMessage {
-                id: target.id.clone(),
-                labels: target.labels.clone(),
-            })
-            .collect();
-
         let response = ListTargetsResponse {
-            targets: target_messages,
+            targets: page.models.into_iter().map(TargetMessage::from).collect(),
+            next_page_token: page.next_page_token,
         };
 
         Ok(Response::new(response))
@@ -131,29 +157,22 @@ impl TargetTrait for GrpcTargetService {
 #[cfg(test)]
 mod tests {
     use fabriq_core::{
-        test::get_target_fixture, EventStream, ListTargetsRequest, TargetIdRequest, TargetTrait,
+        test::get_target_fixture, DeleteTargetRequest, ListTargetsRequest, TargetIdRequest,
+        TargetTrait,
     };
-    use fabriq_memory_stream::MemoryEventStream;
     use std::sync::Arc;
     use tonic::Request;
 
     use super::GrpcTargetService;
 
-    use crate::models::Target;
-    use crate::persistence::memory::MemoryPersistence;
-    use crate::services::TargetService;
+    use crate::services::make_deletion_service;
 
     #[tokio::test]
     async fn test_create_list_target() -> anyhow::Result<()> {
-        let target_persistence = Box::<MemoryPersistence<Target>>::default();
-        let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
+        let deletion_service = Arc::new(make_deletion_service());
+        let target_service = Arc::clone(&deletion_service.target_service);
 
-        let target_service = Arc::new(TargetService {
-            persistence: target_persistence,
-            event_stream,
-        });
-
-        let target_grpc_service = GrpcTargetService::new(Arc::clone(&target_service));
+        let target_grpc_service = GrpcTargetService::new(target_service, deletion_service);
 
         let target = get_target_fixture(None);
 
@@ -167,7 +186,7 @@ mod tests {
 
         assert_eq!(response.id.len(), 36);
 
-        let request = Request::new(ListTargetsRequest {});
+        let request = Request::new(ListTargetsRequest::default());
         let list_response = target_grpc_service
             .list(request)
             .await
@@ -187,8 +206,9 @@ mod tests {
 
         assert_eq!(get_response.id, target.id);
 
-        let request = Request::new(TargetIdRequest {
+        let request = Request::new(DeleteTargetRequest {
             target_id: target.id.to_string(),
+            mode: 0,
         });
         let response = target_grpc_service
             .delete(request)
@@ -1,20 +1,26 @@
 use fabriq_core::{
-    common::TemplateIdRequest, ListTemplatesRequest, ListTemplatesResponse, OperationId,
-    TemplateMessage, TemplateTrait,
+    common::TemplateIdRequest, DeleteTemplateRequest, ListTemplatesRequest, ListTemplatesResponse,
+    ModelType, OperationId, TemplateMessage, TemplateTrait,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
+use super::{is_dependents_exist, is_resource_version_conflict};
 use crate::models::Template;
-use crate::services::TemplateService;
+use crate::persistence::ListOptions;
+use crate::services::{DeletionService, ModelReference, TemplateService};
 
 #[derive(Clone, Debug)]
 pub struct GrpcTemplateService {
     service: Arc<TemplateService>,
+    deletion_service: Arc<DeletionService>,
 }
 impl GrpcTemplateService {
-    pub fn new(service: Arc<TemplateService>) -> Self {
-        GrpcTemplateService { service }
+    pub fn new(service: Arc<TemplateService>, deletion_service: Arc<DeletionService>) -> Self {
+        GrpcTemplateService {
+            service,
+            deletion_service,
+        }
     }
 }
 
@@ -29,6 +35,9 @@ impl TemplateTrait for GrpcTemplateService {
 
         let operation_id = match self.service.upsert(&new_template, None).await {
             Ok(operation_id) => operation_id,
+            Err(err) if is_resource_version_conflict(&err) => {
+                return Err(Status::new(tonic::Code::Aborted, err.to_string()))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
@@ -43,21 +52,27 @@ impl TemplateTrait for GrpcTemplateService {
     #[tracing::instrument(name = "grpc::target::delete", skip_all)]
     async fn delete(
         &self,
-        request: Request<TemplateIdRequest>,
+        request: Request<DeleteTemplateRequest>,
     ) -> Result<Response<OperationId>, Status> {
-        // TODO: Check that no workloads are currently still using template
-        // Query workload service for workloads by template_id
+        let request = request.into_inner();
+        let template = ModelReference::new(ModelType::Template, &request.template_id);
 
         let operation_id = match self
-            .service
-            .delete(&request.into_inner().template_id, None)
+            .deletion_service
+            .delete(&template, request.mode(), None)
             .await
         {
             Ok(operation_id) => operation_id,
+            Err(err) if is_dependents_exist(&err) => {
+                return Err(Status::new(
+                    tonic::Code::FailedPrecondition,
+                    format!("{err}, delete them first or delete with cascade"),
+                ))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
-                    format!("deleting workspace failed with {}", err),
+                    format!("deleting template failed with {}", err),
                 ))
             }
         };
@@ -100,10 +115,28 @@ impl TemplateTrait for GrpcTemplateService {
     #[tracing::instrument(name = "grpc::target::list", skip_all)]
     async fn list(
         &self,
-        _request: Request<ListTemplatesRequest>,
+        request: Request<ListTemplatesRequest>,
     ) -> Result<Response<ListTemplatesResponse>, Status> {
-        let templates = match self.service.list().await {
-            Ok(templates) => templates,
+        let request = request.into_inner();
+
+        let options = match ListOptions::parse::<Template>(
+            request.page_size,
+            &request.page_token,
+            &request.order_by,
+            &[("repository", request.repository.as_str())],
+            &request.labels,
+        ) {
+            Ok(options) => options,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("list options are invalid: {err}"),
+                ))
+            }
+        };
+
+        let page = match self.service.list_page(&options).await {
+            Ok(page) => page,
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
@@ -112,18 +145,9 @@ impl TemplateTrait for GrpcTemplateService {
             }
         };
 
-        let template_messages = templates
-            .iter()
-            .map(|template| TemplateMessage {
-                id: template.id.clone(),
-                repository: template.repository.clone(),
-                git_ref: template.git_ref.clone(),
-                path: template.path.clone(),
-            })
-            .collect();
-
         let response = ListTemplatesResponse {
-            templates: template_messages,
+            templates: page.models.into_iter().map(TemplateMessage::from).collect(),
+            next_page_token: page.next_page_token,
         };
 
         Ok(Response::new(response))
@@ -133,30 +157,25 @@ impl TemplateTrait for GrpcTemplateService {
 #[cfg(test)]
 mod tests {
     use fabriq_core::{
-        common::TemplateIdRequest, test::get_template_fixture, EventStream, ListTemplatesRequest,
-        TemplateTrait,
+        common::TemplateIdRequest,
+        test::{get_template_fixture, get_workload_fixture},
+        DeleteMode, DeleteTemplateRequest, ListTemplatesRequest, TemplateTrait,
     };
-    use fabriq_memory_stream::MemoryEventStream;
     use std::sync::Arc;
     use tonic::Request;
 
     use super::GrpcTemplateService;
 
-    use crate::models::Template;
-    use crate::persistence::memory::MemoryPersistence;
-    use crate::services::TemplateService;
+    use crate::models::Workload;
+    use crate::services::make_deletion_service;
 
     #[tokio::test]
     async fn test_create_list_template() -> anyhow::Result<()> {
-        let template_persistence = Box::<MemoryPersistence<Template>>::default();
-        let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
-
-        let template_service = Arc::new(TemplateService {
-            persistence: template_persistence,
-            event_stream,
-        });
+        let deletion_service = Arc::new(make_deletion_service());
+        let template_service = Arc::clone(&deletion_service.template_service);
+        let workload_service = Arc::clone(&deletion_service.workload_service);
 
-        let template_grpc_service = GrpcTemplateService::new(Arc::clone(&template_service));
+        let template_grpc_service = GrpcTemplateService::new(template_service, deletion_service);
 
         let template = get_template_fixture(None);
 
@@ -170,7 +189,7 @@ mod tests {
 
         assert_eq!(create_response.id.len(), 36);
 
-        let request = Request::new(ListTemplatesRequest {});
+        let request = Request::new(ListTemplatesRequest::default());
 
         let list_response = template_grpc_service
             .list(request)
@@ -188,8 +207,24 @@ mod tests {
 
         assert_eq!(get_by_id_response.into_inner().id, template.id);
 
-        let request = Request::new(TemplateIdRequest {
+        let workload: Workload = get_workload_fixture(None).into();
+        workload_service.upsert(&workload, None).await?;
+
+        let request = Request::new(DeleteTemplateRequest {
+            template_id: template.id.clone(),
+            mode: DeleteMode::Refuse as i32,
+        });
+
+        let delete_result = template_grpc_service.delete(request).await;
+
+        assert_eq!(
+            delete_result.unwrap_err().code(),
+            tonic::Code::FailedPrecondition
+        );
+
+        let request = Request::new(DeleteTemplateRequest {
             template_id: template.id,
+            mode: DeleteMode::Cascade as i32,
         });
 
         let delete_response = template_grpc_service
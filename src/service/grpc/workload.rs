@@ -1,20 +1,47 @@
 use fabriq_core::{
-    common::TemplateIdRequest, ListWorkloadsRequest, ListWorkloadsResponse, OperationId,
-    WorkloadIdRequest, WorkloadMessage, WorkloadTrait,
+    common::TemplateIdRequest, DeleteWorkloadRequest, ListWorkloadsRequest, ListWorkloadsResponse,
+    ModelType, OperationId, WorkloadIdRequest, WorkloadMessage, WorkloadTrait,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
+use super::{is_dependents_exist, is_resource_version_conflict};
+use crate::auth::{authorize_team_member, AuthProvider};
 use crate::models::Workload;
-use crate::services::WorkloadService;
+use crate::persistence::ListOptions;
+use crate::services::{DeletionService, ModelReference, WorkloadService};
 
 #[derive(Debug)]
 pub struct GrpcWorkloadService {
     service: Arc<WorkloadService>,
+    deletion_service: Arc<DeletionService>,
+    auth_provider: Arc<dyn AuthProvider>,
 }
 impl GrpcWorkloadService {
-    pub fn new(service: Arc<WorkloadService>) -> Self {
-        GrpcWorkloadService { service }
+    pub fn new(
+        service: Arc<WorkloadService>,
+        deletion_service: Arc<DeletionService>,
+        auth_provider: Arc<dyn AuthProvider>,
+    ) -> Self {
+        GrpcWorkloadService {
+            service,
+            deletion_service,
+            auth_provider,
+        }
+    }
+
+    async fn authorize<T>(&self, request: &Request<T>, workload_id: &str) -> Result<(), Status> {
+        let (team_id, _) = match WorkloadMessage::split_id(workload_id) {
+            Ok(split) => split,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("workload id {workload_id} is invalid: {err}"),
+                ))
+            }
+        };
+
+        authorize_team_member(request, &*self.auth_provider, &team_id).await
     }
 }
 
@@ -25,10 +52,25 @@ impl WorkloadTrait for GrpcWorkloadService {
         &self,
         request: Request<WorkloadMessage>,
     ) -> Result<Response<OperationId>, Status> {
+        self.authorize(&request, &request.get_ref().id).await?;
+
         let new_workload: Workload = request.into_inner().into();
 
+        if WorkloadMessage::make_id(&new_workload.team_id, &new_workload.name) != new_workload.id {
+            return Err(Status::new(
+                tonic::Code::InvalidArgument,
+                format!(
+                    "workload id {} does not match team {} and name {}",
+                    new_workload.id, new_workload.team_id, new_workload.name
+                ),
+            ));
+        }
+
         let operation_id = match self.service.upsert(&new_workload, None).await {
             Ok(operation_id) => operation_id,
+            Err(err) if is_resource_version_conflict(&err) => {
+                return Err(Status::new(tonic::Code::Aborted, err.to_string()))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::InvalidArgument,
@@ -43,21 +85,30 @@ impl WorkloadTrait for GrpcWorkloadService {
     #[tracing::instrument(name = "grpc::workload::delete", skip_all)]
     async fn delete(
         &self,
-        request: Request<WorkloadIdRequest>,
+        request: Request<DeleteWorkloadRequest>,
     ) -> Result<Response<OperationId>, Status> {
-        // TODO: Check that no workloads are currently still using workload
-        // Query workload service for workloads by workload_id
+        self.authorize(&request, &request.get_ref().workload_id)
+            .await?;
+
+        let request = request.into_inner();
+        let workload = ModelReference::new(ModelType::Workload, &request.workload_id);
 
         let operation_id = match self
-            .service
-            .delete(&request.into_inner().workload_id, None)
+            .deletion_service
+            .delete(&workload, request.mode(), None)
             .await
         {
             Ok(operation_id) => operation_id,
+            Err(err) if is_dependents_exist(&err) => {
+                return Err(Status::new(
+                    tonic::Code::FailedPrecondition,
+                    format!("{err}, delete them first or delete with cascade"),
+                ))
+            }
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
-                    format!("deleting workspace failed with {}", err),
+                    format!("deleting workload failed with {}", err),
                 ))
             }
         };
@@ -124,6 +175,7 @@ impl WorkloadTrait for GrpcWorkloadService {
 
         let response = ListWorkloadsResponse {
             workloads: workload_messages,
+            next_page_token: "".to_string(),
         };
 
         Ok(Response::new(response))
@@ -132,10 +184,31 @@ impl WorkloadTrait for GrpcWorkloadService {
     #[tracing::instrument(name = "grpc::workload::list", skip_all)]
     async fn list(
         &self,
-        _request: Request<ListWorkloadsRequest>,
+        request: Request<ListWorkloadsRequest>,
     ) -> Result<Response<ListWorkloadsResponse>, Status> {
-        let workloads = match self.service.list().await {
-            Ok(workloads) => workloads,
+        let request = request.into_inner();
+
+        let options = match ListOptions::parse::<Workload>(
+            request.page_size,
+            &request.page_token,
+            &request.order_by,
+            &[
+                ("team_id", request.team_id.as_str()),
+                ("template_id", request.template_id.as_str()),
+            ],
+            &request.labels,
+        ) {
+            Ok(options) => options,
+            Err(err) => {
+                return Err(Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("list options are invalid: {err}"),
+                ))
+            }
+        };
+
+        let page = match self.service.list_page(&options).await {
+            Ok(page) => page,
             Err(err) => {
                 return Err(Status::new(
                     tonic::Code::Internal,
@@ -144,13 +217,9 @@ impl WorkloadTrait for GrpcWorkloadService {
             }
         };
 
-        let workload_messages = workloads
-            .iter()
-            .map(|workload| workload.clone().into())
-            .collect();
-
         let response = ListWorkloadsResponse {
-            workloads: workload_messages,
+            workloads: page.models.into_iter().map(WorkloadMessage::from).collect(),
+            next_page_token: page.next_page_token,
         };
 
         Ok(Response::new(response))
@@ -162,43 +231,46 @@ mod tests {
     use fabriq_core::{
         common::TemplateIdRequest,
         test::{get_template_fixture, get_workload_fixture},
-        EventStream, ListWorkloadsRequest, WorkloadIdRequest, WorkloadTrait,
+        DeleteWorkloadRequest, ListWorkloadsRequest, WorkloadIdRequest, WorkloadTrait,
     };
-    use fabriq_memory_stream::MemoryEventStream;
     use std::sync::Arc;
-    use tonic::Request;
+    use tonic::{metadata::MetadataValue, Request};
 
     use super::GrpcWorkloadService;
+    use crate::auth::MockAuthProvider;
 
     use crate::models::Template;
-    use crate::persistence::memory::{MemoryPersistence, WorkloadMemoryPersistence};
-    use crate::services::{TemplateService, WorkloadService};
+    use crate::services::make_deletion_service;
 
     #[tokio::test]
     async fn test_create_list_workload() -> anyhow::Result<()> {
-        let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
-
-        let template_persistence = MemoryPersistence::<Template>::default();
-        let template_service = Arc::new(TemplateService {
-            persistence: Box::new(template_persistence),
-            event_stream: Arc::clone(&event_stream),
-        });
+        let deletion_service = Arc::new(make_deletion_service());
 
         let template: Template = get_template_fixture(Some("template-fixture")).into();
-        template_service.upsert(&template, None).await.unwrap();
-
-        let workload_persistence = Box::<WorkloadMemoryPersistence>::default();
-        let workload_service = Arc::new(WorkloadService {
-            persistence: workload_persistence,
-            event_stream,
+        deletion_service
+            .template_service
+            .upsert(&template, None)
+            .await
+            .unwrap();
 
-            template_service,
-        });
+        let workload_service = Arc::clone(&deletion_service.workload_service);
 
-        let workload_grpc_service = GrpcWorkloadService::new(Arc::clone(&workload_service));
+        let auth_provider = Arc::new(MockAuthProvider::new(&["fabriq-cloud/fabriq"]));
+        let workload_grpc_service =
+            GrpcWorkloadService::new(workload_service, deletion_service, auth_provider);
         let workload = get_workload_fixture(None);
+        let token: MetadataValue<_> = "test-pat".parse()?;
+
+        let mut request = Request::new(workload.clone());
+
+        let result = workload_grpc_service
+            .upsert(Request::new(workload.clone()))
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::Unauthenticated);
 
-        let request = Request::new(workload.clone());
+        request
+            .metadata_mut()
+            .insert("authorization", token.clone());
 
         let create_response = workload_grpc_service
             .upsert(request)
@@ -220,7 +292,7 @@ mod tests {
 
         assert_eq!(response.workloads.len(), 1);
 
-        let request = Request::new(ListWorkloadsRequest {});
+        let request = Request::new(ListWorkloadsRequest::default());
 
         let list_response = workload_grpc_service
             .list(request)
@@ -242,9 +314,11 @@ mod tests {
 
         assert_eq!(get_by_id_response.id, workload.id);
 
-        let request = Request::new(WorkloadIdRequest {
+        let mut request = Request::new(DeleteWorkloadRequest {
             workload_id: workload.id,
+            mode: 0,
         });
+        request.metadata_mut().insert("authorization", token);
 
         let delete_response = workload_grpc_service
             .delete(request)
@@ -256,4 +330,23 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_upsert_workload_outside_team_denied() -> anyhow::Result<()> {
+        let deletion_service = Arc::new(make_deletion_service());
+        let workload_service = Arc::clone(&deletion_service.workload_service);
+
+        let auth_provider = Arc::new(MockAuthProvider::new(&["another-cloud/team"]));
+        let workload_grpc_service =
+            GrpcWorkloadService::new(workload_service, deletion_service, auth_provider);
+
+        let mut request = Request::new(get_workload_fixture(None));
+        let token: MetadataValue<_> = "test-pat".parse()?;
+        request.metadata_mut().insert("authorization", token);
+
+        let result = workload_grpc_service.upsert(request).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+        Ok(())
+    }
 }
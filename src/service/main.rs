@@ -1,5 +1,9 @@
 use opentelemetry::{
-    sdk::{trace as sdktrace, Resource},
+    sdk::{
+        export::metrics::aggregation::cumulative_temporality_selector,
+        metrics::{controllers::BasicController, selectors},
+        trace as sdktrace, Resource,
+    },
     KeyValue,
 };
 use opentelemetry_otlp::WithExportConfig;
@@ -8,19 +12,22 @@ use std::{env, sync::Arc};
 use tokio::time::Duration;
 use tonic::transport::Server;
 use tonic_async_interceptor::async_interceptor;
-use tower::ServiceBuilder;
+use tower::{util::MapRequestLayer, ServiceBuilder};
 use tower_http::trace::TraceLayer;
 use tracing::Level;
 use tracing_subscriber::prelude::*;
 use url::Url;
 
 use fabriq_core::{
-    AssignmentServer, ConfigServer, DeploymentServer, EventStream, HostServer, TargetServer,
-    TemplateServer, WorkloadServer,
+    AdminServer, AssignmentServer, AuditServer, ConfigServer, DeploymentServer, EventStream,
+    HostServer, RbacServer, TargetServer, TemplateServer, WorkloadServer,
 };
+use fabriq_memory_stream::MemoryEventStream;
 use fabriq_postgresql_stream::PostgresqlEventStream;
+use fabriq_sqlite_stream::SqliteEventStream;
 
 mod acl;
+mod auth;
 mod grpc;
 mod http;
 mod hybrid;
@@ -29,6 +36,7 @@ mod persistence;
 mod reconcilation;
 mod services;
 
+use auth::{AuthProvider, RbacAuthorizer, RoleBindingCache};
 use hybrid::HybridMakeService;
 
 pub fn hybrid_service<MakeWeb, Grpc>(
@@ -39,24 +47,27 @@ pub fn hybrid_service<MakeWeb, Grpc>(
 }
 
 use grpc::{
-    GrpcAssignmentService, GrpcConfigService, GrpcDeploymentService, GrpcHostService,
-    GrpcTargetService, GrpcTemplateService, GrpcWorkloadService,
+    GrpcAdminService, GrpcAssignmentService, GrpcAuditService, GrpcConfigService,
+    GrpcDeploymentService, GrpcHostService, GrpcRbacService, GrpcTargetService,
+    GrpcTemplateService, GrpcWorkloadService,
 };
 
-use persistence::relational::{
-    AssignmentRelationalPersistence, ConfigRelationalPersistence, DeploymentRelationalPersistence,
-    HostRelationalPersistence, TargetRelationalPersistence, TemplateRelationalPersistence,
-    WorkloadRelationalPersistence,
+use persistence::{
+    cache::{InvalidatingEventStream, PersistenceCacheConfig, PersistenceCaches},
+    memory::{MemoryModels, MemorySnapshotter},
+    relational, sqlite, Persistences,
 };
 
 use reconcilation::Reconciler;
 
 use services::{
-    AssignmentService, ConfigService, DeploymentService, HostService, TargetService,
-    TemplateService, WorkloadService,
+    with_actor, AdminService, AssignmentService, AuditService, AuditingEventStream, ConfigService,
+    DeletionService, DeploymentService, EventExport, ExportingEventStream, HostService,
+    RoleBindingService, TargetService, TemplateService, WorkloadService,
 };
 
 const DEFAULT_RECONCILER_CONSUMER_ID: &str = "reconciler";
+const DEFAULT_MEMORY_SNAPSHOT_INTERVAL_SECS: u64 = 60;
 
 async fn reconcile(
     reconciler: Reconciler,
@@ -67,7 +78,13 @@ async fn reconcile(
         let events = event_stream.receive(consumer_id).await?;
 
         for event in events.iter() {
-            reconciler.process(event).await?;
+            // the changes the reconciler cascades from an event are made on behalf of its actor.
+            if event.actor.is_empty() {
+                reconciler.process(event).await?;
+            } else {
+                with_actor(event.actor.clone(), reconciler.process(event)).await?;
+            }
+
             event_stream.delete(event, consumer_id).await?;
         }
 
@@ -77,37 +94,62 @@ async fn reconcile(
     }
 }
 
-fn init_tracer() -> anyhow::Result<sdktrace::Tracer> {
-    let opentelemetry_endpoint = env::var("OTEL_ENDPOINT").expect("OTEL_ENDPOINT expected");
-    let opentelemetry_endpoint =
-        Url::parse(&opentelemetry_endpoint).expect("OTEL_ENDPOINT is not a valid url");
-
+fn init_resource() -> Resource {
     let service_name = env::var("SERVICE_NAME").unwrap_or_else(|_| "fabriq-api".to_string());
     let service_version = env::var("SERVICE_VERSION").unwrap_or_else(|_| "unknown".to_string());
 
+    Resource::new(vec![
+        KeyValue::new(
+            opentelemetry_semantic_conventions::resource::SERVICE_NAME,
+            service_name,
+        ),
+        KeyValue::new(
+            opentelemetry_semantic_conventions::resource::SERVICE_VERSION,
+            service_version,
+        ),
+    ])
+}
+
+fn init_opentelemetry_endpoint() -> Url {
+    let opentelemetry_endpoint = env::var("OTEL_ENDPOINT").expect("OTEL_ENDPOINT expected");
+
+    Url::parse(&opentelemetry_endpoint).expect("OTEL_ENDPOINT is not a valid url")
+}
+
+fn init_tracer() -> anyhow::Result<sdktrace::Tracer> {
     let tracer = opentelemetry_otlp::new_pipeline()
         .tracing()
         .with_exporter(
             opentelemetry_otlp::new_exporter()
                 .tonic()
-                .with_endpoint(opentelemetry_endpoint),
+                .with_endpoint(init_opentelemetry_endpoint()),
         )
-        .with_trace_config(sdktrace::config().with_resource(Resource::new(vec![
-            KeyValue::new(
-                opentelemetry_semantic_conventions::resource::SERVICE_NAME,
-                service_name,
-            ),
-            KeyValue::new(
-                opentelemetry_semantic_conventions::resource::SERVICE_VERSION,
-                service_version,
-            ),
-        ])))
+        .with_trace_config(sdktrace::config().with_resource(init_resource()))
         .install_batch(opentelemetry::runtime::Tokio)
         .expect("init tracer failed");
 
     Ok(tracer)
 }
 
+// Installs the global meter provider, which exports eg. the persistence cache hit and miss counts.
+fn init_meter() -> anyhow::Result<BasicController> {
+    let controller = opentelemetry_otlp::new_pipeline()
+        .metrics(
+            selectors::simple::inexpensive(),
+            cumulative_temporality_selector(),
+            opentelemetry::runtime::Tokio,
+        )
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(init_opentelemetry_endpoint()),
+        )
+        .with_resource(init_resource())
+        .build()?;
+
+    Ok(controller)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
@@ -115,6 +157,7 @@ async fn main() -> anyhow::Result<()> {
     let http_services = http::http_router().into_make_service();
 
     let tracer = init_tracer().expect("failed to instantiate opentelemetry tracing");
+    let _meter = init_meter().expect("failed to instantiate opentelemetry metrics");
 
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::from_default_env())
@@ -129,53 +172,122 @@ async fn main() -> anyhow::Result<()> {
         .map(|s| s.to_string())
         .collect();
 
+    // sqlite: urls (eg. sqlite://fabriq.db) run everything on a local database file, memory: urls
+    // keep everything in the api process (snapshotted to a file if one follows, eg.
+    // memory:fabriq.json), anything else is a PostgreSQL url.
     let database_url = dotenvy::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let db = Arc::new(
-        PgPoolOptions::new()
-            .max_connections(20)
-            .connect(&database_url)
-            .await
-            .expect("failed to connect to DATABASE_URL"),
-    );
+    let mut memory_snapshotter = None;
+    let (persistences, event_stream): (Persistences, Arc<dyn EventStream>) =
+        if let Some(snapshot_path) = database_url.strip_prefix("memory:") {
+            let models = MemoryModels::default();
+
+            if !snapshot_path.is_empty() {
+                let snapshotter = MemorySnapshotter {
+                    models: models.clone(),
+                    path: snapshot_path.into(),
+                };
+
+                snapshotter.load().await?;
+                memory_snapshotter = Some(snapshotter);
+            }
+
+            (
+                models.make_persistences(),
+                Arc::new(MemoryEventStream::new()?),
+            )
+        } else if database_url.starts_with("sqlite:") {
+            let db = Arc::new(
+                fabriq_sqlite_stream::connect(&database_url)
+                    .await
+                    .expect("failed to open DATABASE_URL"),
+            );
+
+            sqlx::migrate!("./migrations/sqlite").run(&*db).await?;
+
+            let event_stream = SqliteEventStream {
+                db: Arc::clone(&db),
+                subscribers,
+            };
+
+            (sqlite::make_persistences(&db), Arc::new(event_stream))
+        } else {
+            let db = Arc::new(
+                PgPoolOptions::new()
+                    .max_connections(20)
+                    .connect(&database_url)
+                    .await
+                    .expect("failed to connect to DATABASE_URL"),
+            );
+
+            sqlx::migrate!().run(&*db).await?;
+
+            let event_stream = PostgresqlEventStream {
+                db: Arc::clone(&db),
+                subscribers,
+            };
+
+            (relational::make_persistences(&db), Arc::new(event_stream))
+        };
+
+    // PERSISTENCE_CACHE=true caches models in the api process. Writes made by other processes
+    // sharing the database are seen if this process's reconciler receives their events, and
+    // otherwise once the cached models expire.
+    let (persistences, event_stream) = if dotenvy::var("PERSISTENCE_CACHE")
+        .is_ok_and(|persistence_cache| persistence_cache == "true")
+    {
+        let caches = Arc::new(PersistenceCaches::new(PersistenceCacheConfig::from_env()));
+
+        let event_stream: Arc<dyn EventStream> = Arc::new(InvalidatingEventStream {
+            event_stream,
+            caches: Arc::clone(&caches),
+        });
+
+        (caches.wrap(persistences), event_stream)
+    } else {
+        (persistences, event_stream)
+    };
 
-    sqlx::migrate!().run(&*db).await?;
+    let endpoint = env::var("ENDPOINT").unwrap_or_else(|_| "0.0.0.0:8080".to_owned());
 
-    let postgresql_event_stream = PostgresqlEventStream {
-        db: Arc::clone(&db),
-        subscribers,
+    // EVENT_EXPORT_WEBHOOK_URL and EVENT_EXPORT_FILE export events as CloudEvents, from
+    // EVENT_EXPORT_SOURCE (default //<ENDPOINT>).
+    let exports = EventExport::from_env()?;
+    let event_stream: Arc<dyn EventStream> = if exports.is_empty() {
+        event_stream
+    } else {
+        Arc::new(ExportingEventStream::new(
+            event_stream,
+            dotenvy::var("EVENT_EXPORT_SOURCE").unwrap_or_else(|_| format!("//{endpoint}")),
+            exports,
+        ))
     };
 
-    let event_stream: Arc<dyn EventStream> = Arc::new(postgresql_event_stream);
+    let audit_service = Arc::new(AuditService {
+        persistence: persistences.audit,
+    });
 
-    let assignment_persistence = Box::new(AssignmentRelationalPersistence {
-        db: Arc::clone(&db),
+    let event_stream: Arc<dyn EventStream> = Arc::new(AuditingEventStream {
+        event_stream,
+        audit_service: Arc::clone(&audit_service),
     });
+
     let assignment_service = Arc::new(AssignmentService {
-        persistence: assignment_persistence,
+        persistence: persistences.assignment,
         event_stream: Arc::clone(&event_stream),
     });
 
-    let target_persistence = Box::new(TargetRelationalPersistence {
-        db: Arc::clone(&db),
-    });
     let target_service = Arc::new(TargetService {
-        persistence: target_persistence,
+        persistence: persistences.target,
         event_stream: Arc::clone(&event_stream),
     });
 
-    let config_persistence = Box::new(ConfigRelationalPersistence {
-        db: Arc::clone(&db),
-    });
     let config_service = Arc::new(ConfigService {
-        persistence: config_persistence,
+        persistence: persistences.config,
         event_stream: Arc::clone(&event_stream),
     });
 
-    let deployment_persistence = Box::new(DeploymentRelationalPersistence {
-        db: Arc::clone(&db),
-    });
     let deployment_service = Arc::new(DeploymentService {
-        persistence: deployment_persistence,
+        persistence: persistences.deployment,
         event_stream: Arc::clone(&event_stream),
 
         assignment_service: Arc::clone(&assignment_service),
@@ -183,57 +295,111 @@ async fn main() -> anyhow::Result<()> {
         target_service: Arc::clone(&target_service),
     });
 
-    let host_persistence = Box::new(HostRelationalPersistence {
-        db: Arc::clone(&db),
-    });
     let host_service = Arc::new(HostService {
-        persistence: host_persistence,
+        persistence: persistences.host,
         event_stream: Arc::clone(&event_stream),
     });
 
-    let template_persistence = Box::new(TemplateRelationalPersistence {
-        db: Arc::clone(&db),
+    let role_binding_service = Arc::new(RoleBindingService {
+        persistence: persistences.role_binding,
+        event_stream: Arc::clone(&event_stream),
     });
+
+    auth::bootstrap_platform_admins_from_env(&role_binding_service).await?;
+
     let template_service = Arc::new(TemplateService {
-        persistence: template_persistence,
+        persistence: persistences.template,
         event_stream: Arc::clone(&event_stream),
     });
 
-    let workload_persistence = Box::new(WorkloadRelationalPersistence {
-        db: Arc::clone(&db),
-    });
     let workload_service = Arc::new(WorkloadService {
-        persistence: workload_persistence,
+        persistence: persistences.workload,
         event_stream: Arc::clone(&event_stream),
 
         template_service: Arc::clone(&template_service),
     });
 
-    let endpoint = env::var("ENDPOINT").unwrap_or_else(|_| "0.0.0.0:8080".to_owned());
+    let admin_service = Arc::new(AdminService {
+        config_service: Arc::clone(&config_service),
+        deployment_service: Arc::clone(&deployment_service),
+        host_service: Arc::clone(&host_service),
+        target_service: Arc::clone(&target_service),
+        template_service: Arc::clone(&template_service),
+        workload_service: Arc::clone(&workload_service),
+    });
+
+    let deletion_service = Arc::new(DeletionService {
+        assignment_service: Arc::clone(&assignment_service),
+        config_service: Arc::clone(&config_service),
+        deployment_service: Arc::clone(&deployment_service),
+        host_service: Arc::clone(&host_service),
+        target_service: Arc::clone(&target_service),
+        template_service: Arc::clone(&template_service),
+        workload_service: Arc::clone(&workload_service),
+    });
+
     let addr = endpoint.parse()?;
 
-    let assignment_grpc_service =
-        AssignmentServer::new(GrpcAssignmentService::new(Arc::clone(&assignment_service)));
+    let auth_provider: Arc<dyn AuthProvider> =
+        Arc::new(auth::build_auth_provider_from_env().await?);
+
+    let admin_grpc_service = AdminServer::new(GrpcAdminService::new(admin_service));
+
+    let audit_grpc_service = AuditServer::new(GrpcAuditService::new(audit_service));
+
+    let assignment_grpc_service = AssignmentServer::new(GrpcAssignmentService::new(
+        Arc::clone(&assignment_service),
+        Arc::clone(&auth_provider),
+    ));
+
+    let rbac_authorizer = Arc::new(RbacAuthorizer {
+        role_binding_service: Arc::clone(&role_binding_service),
+        auth_provider: Arc::clone(&auth_provider),
+        default_role: RbacAuthorizer::default_role_from_env()?,
+        role_binding_cache: RoleBindingCache::from_env(),
+    });
 
     let config_grpc_service = ConfigServer::new(GrpcConfigService {
         config_service: Arc::clone(&config_service),
         deployment_service: Arc::clone(&deployment_service),
+        host_service: Arc::clone(&host_service),
         workload_service: Arc::clone(&workload_service),
+
+        auth_provider: Arc::clone(&auth_provider),
+        authorizer: Arc::clone(&rbac_authorizer),
     });
 
-    let deployment_grpc_service =
-        DeploymentServer::new(GrpcDeploymentService::new(Arc::clone(&deployment_service)));
+    let deployment_grpc_service = DeploymentServer::new(GrpcDeploymentService::new(
+        Arc::clone(&deployment_service),
+        Arc::clone(&deletion_service),
+        Arc::clone(&auth_provider),
+    ));
 
-    let host_grpc_service = HostServer::new(GrpcHostService::new(Arc::clone(&host_service)));
+    let host_grpc_service = HostServer::new(GrpcHostService::new(
+        Arc::clone(&host_service),
+        Arc::clone(&deletion_service),
+    ));
 
-    let target_grpc_service =
-        TargetServer::new(GrpcTargetService::new(Arc::clone(&target_service)));
+    let rbac_grpc_service =
+        RbacServer::new(GrpcRbacService::new(Arc::clone(&role_binding_service)));
 
-    let template_grpc_service =
-        TemplateServer::new(GrpcTemplateService::new(Arc::clone(&template_service)));
+    let target_grpc_service = TargetServer::new(GrpcTargetService::new(
+        Arc::clone(&target_service),
+        Arc::clone(&deletion_service),
+    ));
 
-    let workload_grpc_service =
-        WorkloadServer::new(GrpcWorkloadService::new(Arc::clone(&workload_service)));
+    let template_grpc_service = TemplateServer::new(GrpcTemplateService::new(
+        Arc::clone(&template_service),
+        Arc::clone(&deletion_service),
+    ));
+
+    let workload_grpc_service = WorkloadServer::new(GrpcWorkloadService::new(
+        Arc::clone(&workload_service),
+        deletion_service,
+        Arc::clone(&auth_provider),
+    ));
+
+    let acl_auth_provider = auth_provider;
 
     tracing::info!("grpc services listening on {}", addr);
 
@@ -245,11 +411,22 @@ async fn main() -> anyhow::Result<()> {
 
     let grpc_services = Server::builder()
         .layer(tracing_layer)
-        .layer(async_interceptor(acl::authenticate))
+        .layer(MapRequestLayer::new(acl::record_grpc_method))
+        .layer(async_interceptor(move |req| {
+            acl::authenticate(
+                Arc::clone(&acl_auth_provider),
+                Arc::clone(&rbac_authorizer),
+                req,
+            )
+        }))
+        .layer(acl::ActorLayer)
+        .add_service(admin_grpc_service)
         .add_service(assignment_grpc_service)
+        .add_service(audit_grpc_service)
         .add_service(config_grpc_service)
         .add_service(deployment_grpc_service)
         .add_service(host_grpc_service)
+        .add_service(rbac_grpc_service)
         .add_service(workload_grpc_service)
         .add_service(target_grpc_service)
         .add_service(template_grpc_service)
@@ -269,14 +446,55 @@ async fn main() -> anyhow::Result<()> {
 
     let reconciler_future = reconcile(reconciler, event_stream, DEFAULT_RECONCILER_CONSUMER_ID);
 
+    let snapshot_interval = Duration::from_secs(
+        dotenvy::var("MEMORY_SNAPSHOT_INTERVAL_SECS")
+            .map(|secs| secs.parse())
+            .unwrap_or(Ok(DEFAULT_MEMORY_SNAPSHOT_INTERVAL_SECS))?,
+    );
+
+    let snapshot_future = async {
+        match &memory_snapshotter {
+            Some(snapshotter) => snapshotter.run(snapshot_interval).await,
+            None => std::future::pending().await,
+        }
+    };
+
     tokio::select! {
         r = api_future => {
             tracing::error!("api future failed: {:?}", r);
         },
         r = reconciler_future => {
             tracing::error!("reconciler future failed: {:?}", r);
+        },
+        r = snapshot_future => {
+            tracing::error!("snapshot future failed: {:?}", r);
+        },
+        r = shutdown_signal() => {
+            tracing::info!("shutting down: {:?}", r);
         }
     };
 
+    if let Some(snapshotter) = memory_snapshotter {
+        snapshotter.save().await?;
+    }
+
+    Ok(())
+}
+
+async fn shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+
+        tokio::select! {
+            r = tokio::signal::ctrl_c() => r?,
+            _ = terminate.recv() => {}
+        };
+    }
+
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+
     Ok(())
 }
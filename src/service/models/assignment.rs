@@ -1,19 +1,63 @@
-use fabriq_core::AssignmentMessage;
+use fabriq_core::{AssignmentMessage, ModelMetadata, ModelType};
+use sqlx::types::chrono::{DateTime, Utc};
 
+use super::{changed_metadata, to_date_time, to_timestamp};
 use crate::persistence::Persistable;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct Assignment {
     pub id: String,
 
     pub deployment_id: String,
     pub host_id: String,
+    pub resource_version: i64,
+
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
 }
 
 impl Persistable<Assignment> for Assignment {
+    const LIST_FIELDS: &'static [&'static str] = &["id", "deployment_id", "host_id"];
+    const MODEL_TYPE: ModelType = ModelType::Assignment;
+
     fn get_id(&self) -> String {
         self.id.clone()
     }
+
+    fn get_resource_version(&self) -> i64 {
+        self.resource_version
+    }
+
+    fn set_resource_version(&mut self, resource_version: i64) {
+        self.resource_version = resource_version;
+    }
+
+    fn set_changed(&mut self, actor: &str, previous: Option<&Assignment>) {
+        (
+            self.created_at,
+            self.created_by,
+            self.updated_at,
+            self.updated_by,
+        ) = changed_metadata(
+            actor,
+            previous.map(|previous| (previous.created_at, previous.created_by.as_str())),
+        );
+    }
+
+    fn get_field(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.clone()),
+            "deployment_id" => Some(self.deployment_id.clone()),
+            "host_id" => Some(self.host_id.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(AssignmentMessage::from(self.clone()))?)
+    }
 }
 
 impl From<Assignment> for AssignmentMessage {
@@ -22,16 +66,31 @@ impl From<Assignment> for AssignmentMessage {
             id: assignment.id,
             deployment_id: assignment.deployment_id,
             host_id: assignment.host_id,
+            resource_version: assignment.resource_version,
+            metadata: Some(ModelMetadata {
+                created_at: to_timestamp(assignment.created_at),
+                created_by: assignment.created_by,
+                updated_at: to_timestamp(assignment.updated_at),
+                updated_by: assignment.updated_by,
+            }),
         }
     }
 }
 
 impl From<AssignmentMessage> for Assignment {
     fn from(assignment: AssignmentMessage) -> Self {
+        let metadata = assignment.metadata.unwrap_or_default();
+
         Self {
             id: assignment.id,
             deployment_id: assignment.deployment_id,
             host_id: assignment.host_id,
+            resource_version: assignment.resource_version,
+
+            created_at: to_date_time(&metadata.created_at),
+            created_by: metadata.created_by,
+            updated_at: to_date_time(&metadata.updated_at),
+            updated_by: metadata.updated_by,
         }
     }
 }
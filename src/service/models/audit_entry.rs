@@ -0,0 +1,113 @@
+use fabriq_core::{cloud_event::decode_model, AuditEntryMessage, Event, EventType, ModelType};
+use sqlx::types::chrono::{DateTime, Utc};
+use std::time::SystemTime;
+
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub id: String,
+    pub actor: String,
+    pub recorded_at: DateTime<Utc>,
+    pub operation_id: String,
+
+    pub event_type: String,
+    pub model_type: String,
+    pub model_id: String,
+
+    pub previous_model: Option<serde_json::Value>,
+    pub current_model: Option<serde_json::Value>,
+}
+
+impl AuditEntry {
+    pub fn from_event(event: &Event, actor: &str) -> anyhow::Result<Self> {
+        let model_type = ModelType::from(event.model_type);
+        let event_type = EventType::from(event.event_type);
+
+        let previous_model = match &event.serialized_previous_model {
+            Some(serialized_model) => Some(decode_model(model_type, serialized_model)?),
+            None => None,
+        };
+
+        let current_model = match &event.serialized_current_model {
+            Some(serialized_model) => Some(decode_model(model_type, serialized_model)?),
+            None => None,
+        };
+
+        let model_id = match (&current_model, &previous_model) {
+            (Some((model_id, _)), _) | (None, Some((model_id, _))) => model_id.clone(),
+            (None, None) => {
+                return Err(anyhow::anyhow!(
+                    "event {} has neither a previous nor a current model",
+                    event.id
+                ))
+            }
+        };
+
+        let recorded_at = match &event.timestamp {
+            Some(timestamp) => SystemTime::try_from(timestamp.clone())?.into(),
+            None => Utc::now(),
+        };
+
+        Ok(AuditEntry {
+            id: event.id.clone(),
+            actor: actor.to_string(),
+            recorded_at,
+            operation_id: event
+                .operation_id
+                .as_ref()
+                .map(|operation_id| operation_id.id.clone())
+                .unwrap_or_default(),
+
+            event_type: event_type.as_str_name().to_lowercase(),
+            model_type: model_type.as_str_name().to_lowercase(),
+            model_id,
+
+            previous_model: previous_model.map(|(_, json)| json),
+            current_model: current_model.map(|(_, json)| json),
+        })
+    }
+}
+
+impl From<AuditEntry> for AuditEntryMessage {
+    fn from(audit_entry: AuditEntry) -> Self {
+        Self {
+            id: audit_entry.id,
+            actor: audit_entry.actor,
+            timestamp: Some(SystemTime::from(audit_entry.recorded_at).into()),
+            operation_id: audit_entry.operation_id,
+
+            event_type: audit_entry.event_type,
+            model_type: audit_entry.model_type,
+            model_id: audit_entry.model_id,
+
+            previous_model: audit_entry.previous_model.map(|json| json.to_string()),
+            current_model: audit_entry.current_model.map(|json| json.to_string()),
+        }
+    }
+}
+
+impl TryFrom<AuditEntryMessage> for AuditEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(audit_entry_message: AuditEntryMessage) -> anyhow::Result<Self> {
+        let parse_model = |model: Option<String>| -> anyhow::Result<Option<serde_json::Value>> {
+            Ok(match model {
+                Some(model) => Some(serde_json::from_str(&model)?),
+                None => None,
+            })
+        };
+
+        Ok(Self {
+            id: audit_entry_message.id,
+            actor: audit_entry_message.actor,
+            recorded_at: super::to_date_time(&audit_entry_message.timestamp),
+            operation_id: audit_entry_message.operation_id,
+
+            event_type: audit_entry_message.event_type,
+            model_type: audit_entry_message.model_type,
+            model_id: audit_entry_message.model_id,
+
+            previous_model: parse_model(audit_entry_message.previous_model)?,
+            current_model: parse_model(audit_entry_message.current_model)?,
+        })
+    }
+}
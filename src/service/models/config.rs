@@ -1,8 +1,10 @@
-use fabriq_core::ConfigMessage;
+use fabriq_core::{ConfigMessage, ModelMetadata, ModelType};
+use sqlx::types::chrono::{DateTime, Utc};
 
+use super::{changed_metadata, to_date_time, to_timestamp};
 use crate::persistence::Persistable;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, sqlx::FromRow)]
 pub struct Config {
     pub id: String,
 
@@ -12,12 +14,54 @@ pub struct Config {
     pub value: String,
 
     pub value_type: i32,
+    pub resource_version: i64,
+
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
 }
 
 impl Persistable<Config> for Config {
+    const LIST_FIELDS: &'static [&'static str] = &["id", "owning_model", "key"];
+    const MODEL_TYPE: ModelType = ModelType::Config;
+
     fn get_id(&self) -> String {
         self.id.clone()
     }
+
+    fn get_resource_version(&self) -> i64 {
+        self.resource_version
+    }
+
+    fn set_resource_version(&mut self, resource_version: i64) {
+        self.resource_version = resource_version;
+    }
+
+    fn set_changed(&mut self, actor: &str, previous: Option<&Config>) {
+        (
+            self.created_at,
+            self.created_by,
+            self.updated_at,
+            self.updated_by,
+        ) = changed_metadata(
+            actor,
+            previous.map(|previous| (previous.created_at, previous.created_by.as_str())),
+        );
+    }
+
+    fn get_field(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.clone()),
+            "owning_model" => Some(self.owning_model.clone()),
+            "key" => Some(self.key.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(ConfigMessage::from(self.clone()))?)
+    }
 }
 
 impl From<Config> for ConfigMessage {
@@ -30,12 +74,21 @@ impl From<Config> for ConfigMessage {
             value: config.value,
 
             value_type: config.value_type,
+            resource_version: config.resource_version,
+            metadata: Some(ModelMetadata {
+                created_at: to_timestamp(config.created_at),
+                created_by: config.created_by,
+                updated_at: to_timestamp(config.updated_at),
+                updated_by: config.updated_by,
+            }),
         }
     }
 }
 
 impl From<ConfigMessage> for Config {
     fn from(config: ConfigMessage) -> Self {
+        let metadata = config.metadata.unwrap_or_default();
+
         Self {
             id: config.id,
             owning_model: config.owning_model,
@@ -44,6 +97,12 @@ impl From<ConfigMessage> for Config {
             value: config.value,
 
             value_type: config.value_type,
+            resource_version: config.resource_version,
+
+            created_at: to_date_time(&metadata.created_at),
+            created_by: metadata.created_by,
+            updated_at: to_date_time(&metadata.updated_at),
+            updated_by: metadata.updated_by,
         }
     }
 }
@@ -0,0 +1,58 @@
+use fabriq_core::DeletedModelMessage;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::time::SystemTime;
+
+use crate::persistence::Persistable;
+
+// A model as it was when it was deleted. Deletes keep it in the same transaction as they remove
+// the model, so who deleted it and what it looked like survive even if its audit entry doesn't.
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+pub struct DeletedModel {
+    pub model_type: String,
+    pub model_id: String,
+    pub deleted_at: DateTime<Utc>,
+    pub deleted_by: String,
+
+    pub model: serde_json::Value,
+}
+
+impl DeletedModel {
+    pub fn new<Model: Persistable<Model>>(model: &Model, actor: &str) -> anyhow::Result<Self> {
+        Ok(DeletedModel {
+            model_type: Model::MODEL_TYPE.as_str_name().to_lowercase(),
+            model_id: model.get_id(),
+            deleted_at: Utc::now(),
+            deleted_by: actor.to_string(),
+
+            model: model.to_json()?,
+        })
+    }
+}
+
+impl From<DeletedModel> for DeletedModelMessage {
+    fn from(deleted_model: DeletedModel) -> Self {
+        Self {
+            model_type: deleted_model.model_type,
+            model_id: deleted_model.model_id,
+            deleted_at: Some(SystemTime::from(deleted_model.deleted_at).into()),
+            deleted_by: deleted_model.deleted_by,
+
+            model: deleted_model.model.to_string(),
+        }
+    }
+}
+
+impl TryFrom<DeletedModelMessage> for DeletedModel {
+    type Error = anyhow::Error;
+
+    fn try_from(deleted_model_message: DeletedModelMessage) -> anyhow::Result<Self> {
+        Ok(Self {
+            model_type: deleted_model_message.model_type,
+            model_id: deleted_model_message.model_id,
+            deleted_at: super::to_date_time(&deleted_model_message.deleted_at),
+            deleted_by: deleted_model_message.deleted_by,
+
+            model: serde_json::from_str(&deleted_model_message.model)?,
+        })
+    }
+}
@@ -1,8 +1,13 @@
-use fabriq_core::DeploymentMessage;
+use fabriq_core::{DeploymentMessage, ModelMetadata, ModelType};
+use sqlx::types::{
+    chrono::{DateTime, Utc},
+    Json,
+};
 
+use super::{changed_metadata, to_date_time, to_timestamp, Annotations};
 use crate::persistence::Persistable;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct Deployment {
     pub id: String,
     pub name: String,
@@ -10,12 +15,64 @@ pub struct Deployment {
     pub target_id: String,
     pub template_id: Option<String>,
     pub host_count: i32,
+    pub labels: Vec<String>,
+    pub annotations: Annotations,
+    pub resource_version: i64,
+
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
 }
 
 impl Persistable<Deployment> for Deployment {
+    const LIST_FIELDS: &'static [&'static str] =
+        &["id", "name", "workload_id", "target_id", "template_id"];
+    const LABELLED: bool = true;
+    const MODEL_TYPE: ModelType = ModelType::Deployment;
+
     fn get_id(&self) -> String {
         self.id.clone()
     }
+
+    fn get_resource_version(&self) -> i64 {
+        self.resource_version
+    }
+
+    fn set_resource_version(&mut self, resource_version: i64) {
+        self.resource_version = resource_version;
+    }
+
+    fn set_changed(&mut self, actor: &str, previous: Option<&Deployment>) {
+        (
+            self.created_at,
+            self.created_by,
+            self.updated_at,
+            self.updated_by,
+        ) = changed_metadata(
+            actor,
+            previous.map(|previous| (previous.created_at, previous.created_by.as_str())),
+        );
+    }
+
+    fn get_field(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.clone()),
+            "name" => Some(self.name.clone()),
+            "workload_id" => Some(self.workload_id.clone()),
+            "target_id" => Some(self.target_id.clone()),
+            "template_id" => self.template_id.clone(),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(DeploymentMessage::from(self.clone()))?)
+    }
+
+    fn get_labels(&self) -> &[String] {
+        &self.labels
+    }
 }
 
 impl From<Deployment> for DeploymentMessage {
@@ -27,12 +84,23 @@ impl From<Deployment> for DeploymentMessage {
             target_id: deployment.target_id,
             template_id: deployment.template_id,
             host_count: deployment.host_count,
+            resource_version: deployment.resource_version,
+            labels: deployment.labels,
+            annotations: deployment.annotations.0.into_iter().collect(),
+            metadata: Some(ModelMetadata {
+                created_at: to_timestamp(deployment.created_at),
+                created_by: deployment.created_by,
+                updated_at: to_timestamp(deployment.updated_at),
+                updated_by: deployment.updated_by,
+            }),
         }
     }
 }
 
 impl From<DeploymentMessage> for Deployment {
     fn from(deployment: DeploymentMessage) -> Self {
+        let metadata = deployment.metadata.unwrap_or_default();
+
         Self {
             id: deployment.id,
             name: deployment.name,
@@ -40,6 +108,14 @@ impl From<DeploymentMessage> for Deployment {
             target_id: deployment.target_id,
             template_id: deployment.template_id,
             host_count: deployment.host_count,
+            labels: deployment.labels,
+            annotations: Json(deployment.annotations.into_iter().collect()),
+            resource_version: deployment.resource_version,
+
+            created_at: to_date_time(&metadata.created_at),
+            created_by: metadata.created_by,
+            updated_at: to_date_time(&metadata.updated_at),
+            updated_by: metadata.updated_by,
         }
     }
 }
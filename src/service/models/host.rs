@@ -1,17 +1,64 @@
-use fabriq_core::HostMessage;
+use fabriq_core::{HostMessage, ModelMetadata, ModelType};
+use sqlx::types::chrono::{DateTime, Utc};
 
+use super::{changed_metadata, to_date_time, to_timestamp};
 use crate::persistence::Persistable;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct Host {
     pub id: String,
     pub labels: Vec<String>,
+    pub resource_version: i64,
+
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
 }
 
 impl Persistable<Host> for Host {
+    const LIST_FIELDS: &'static [&'static str] = &["id"];
+    const LABELLED: bool = true;
+    const MODEL_TYPE: ModelType = ModelType::Host;
+
     fn get_id(&self) -> String {
         self.id.clone()
     }
+
+    fn get_resource_version(&self) -> i64 {
+        self.resource_version
+    }
+
+    fn set_resource_version(&mut self, resource_version: i64) {
+        self.resource_version = resource_version;
+    }
+
+    fn set_changed(&mut self, actor: &str, previous: Option<&Host>) {
+        (
+            self.created_at,
+            self.created_by,
+            self.updated_at,
+            self.updated_by,
+        ) = changed_metadata(
+            actor,
+            previous.map(|previous| (previous.created_at, previous.created_by.as_str())),
+        );
+    }
+
+    fn get_field(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(HostMessage::from(self.clone()))?)
+    }
+
+    fn get_labels(&self) -> &[String] {
+        &self.labels
+    }
 }
 
 impl From<Host> for HostMessage {
@@ -19,15 +66,30 @@ impl From<Host> for HostMessage {
         Self {
             id: host.id,
             labels: host.labels,
+            resource_version: host.resource_version,
+            metadata: Some(ModelMetadata {
+                created_at: to_timestamp(host.created_at),
+                created_by: host.created_by,
+                updated_at: to_timestamp(host.updated_at),
+                updated_by: host.updated_by,
+            }),
         }
     }
 }
 
 impl From<HostMessage> for Host {
     fn from(host_message: HostMessage) -> Self {
+        let metadata = host_message.metadata.unwrap_or_default();
+
         Self {
             id: host_message.id,
             labels: host_message.labels,
+            resource_version: host_message.resource_version,
+
+            created_at: to_date_time(&metadata.created_at),
+            created_by: metadata.created_by,
+            updated_at: to_date_time(&metadata.updated_at),
+            updated_by: metadata.updated_by,
         }
     }
 }
@@ -1,15 +1,62 @@
+use prost_types::Timestamp;
+use sqlx::types::{
+    chrono::{DateTime, Utc},
+    Json,
+};
+use std::{collections::BTreeMap, time::SystemTime};
+
 mod assignment;
+mod audit_entry;
 mod config;
+mod deleted_model;
 mod deployment;
 mod host;
+mod role_binding;
 mod target;
 mod template;
 mod workload;
 
 pub use assignment::Assignment;
+pub use audit_entry::AuditEntry;
 pub use config::Config;
+pub use deleted_model::DeletedModel;
 pub use deployment::Deployment;
 pub use host::Host;
+pub use role_binding::RoleBinding;
 pub use target::Target;
 pub use template::Template;
 pub use workload::Workload;
+
+// Free-form key/value annotations, stored as JSON. Ordered so that models with the same
+// annotations render and compare identically.
+pub type Annotations = Json<BTreeMap<String, String>>;
+
+// Models keep their metadata flat so that rows map directly onto them, and nest it as a
+// ModelMetadata in their messages.
+fn to_timestamp(date_time: DateTime<Utc>) -> Option<Timestamp> {
+    Some(SystemTime::from(date_time).into())
+}
+
+fn to_date_time(timestamp: &Option<Timestamp>) -> DateTime<Utc> {
+    timestamp
+        .clone()
+        .and_then(|timestamp| SystemTime::try_from(timestamp).ok())
+        .map(DateTime::from)
+        .unwrap_or_default()
+}
+
+// The created and updated metadata of a model changed by actor now, carrying over when and by
+// whom it was created from the currently stored model, if there is one.
+fn changed_metadata(
+    actor: &str,
+    previous_created: Option<(DateTime<Utc>, &str)>,
+) -> (DateTime<Utc>, String, DateTime<Utc>, String) {
+    let now = Utc::now();
+
+    let (created_at, created_by) = match previous_created {
+        Some((created_at, created_by)) => (created_at, created_by.to_string()),
+        None => (now, actor.to_string()),
+    };
+
+    (created_at, created_by, now, actor.to_string())
+}
@@ -0,0 +1,69 @@
+use fabriq_core::{ModelType, RoleBindingMessage};
+
+use crate::persistence::Persistable;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct RoleBinding {
+    pub id: String,
+    pub role: String,
+    pub team_id: String,
+    pub subject: String,
+    pub resource_version: i64,
+}
+
+impl Persistable<RoleBinding> for RoleBinding {
+    const LIST_FIELDS: &'static [&'static str] = &["id", "role", "team_id", "subject"];
+    const MODEL_TYPE: ModelType = ModelType::RoleBinding;
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn get_resource_version(&self) -> i64 {
+        self.resource_version
+    }
+
+    fn set_resource_version(&mut self, resource_version: i64) {
+        self.resource_version = resource_version;
+    }
+
+    fn get_field(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.clone()),
+            "role" => Some(self.role.clone()),
+            "team_id" => Some(self.team_id.clone()),
+            "subject" => Some(self.subject.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(RoleBindingMessage::from(
+            self.clone(),
+        ))?)
+    }
+}
+
+impl From<RoleBinding> for RoleBindingMessage {
+    fn from(role_binding: RoleBinding) -> Self {
+        Self {
+            id: role_binding.id,
+            role: role_binding.role,
+            team_id: role_binding.team_id,
+            subject: role_binding.subject,
+            resource_version: role_binding.resource_version,
+        }
+    }
+}
+
+impl From<RoleBindingMessage> for RoleBinding {
+    fn from(role_binding_message: RoleBindingMessage) -> Self {
+        Self {
+            id: role_binding_message.id,
+            role: role_binding_message.role,
+            team_id: role_binding_message.team_id,
+            subject: role_binding_message.subject,
+            resource_version: role_binding_message.resource_version,
+        }
+    }
+}
@@ -1,18 +1,65 @@
-use fabriq_core::TargetMessage;
+use fabriq_core::{ModelMetadata, ModelType, TargetMessage};
+use sqlx::types::chrono::{DateTime, Utc};
 
+use super::{changed_metadata, to_date_time, to_timestamp};
 use crate::persistence::Persistable;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct Target {
     pub id: String,
 
     pub labels: Vec<String>,
+    pub resource_version: i64,
+
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
 }
 
 impl Persistable<Target> for Target {
+    const LIST_FIELDS: &'static [&'static str] = &["id"];
+    const LABELLED: bool = true;
+    const MODEL_TYPE: ModelType = ModelType::Target;
+
     fn get_id(&self) -> String {
         self.id.clone()
     }
+
+    fn get_resource_version(&self) -> i64 {
+        self.resource_version
+    }
+
+    fn set_resource_version(&mut self, resource_version: i64) {
+        self.resource_version = resource_version;
+    }
+
+    fn set_changed(&mut self, actor: &str, previous: Option<&Target>) {
+        (
+            self.created_at,
+            self.created_by,
+            self.updated_at,
+            self.updated_by,
+        ) = changed_metadata(
+            actor,
+            previous.map(|previous| (previous.created_at, previous.created_by.as_str())),
+        );
+    }
+
+    fn get_field(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(TargetMessage::from(self.clone()))?)
+    }
+
+    fn get_labels(&self) -> &[String] {
+        &self.labels
+    }
 }
 
 impl From<Target> for TargetMessage {
@@ -20,15 +67,30 @@ impl From<Target> for TargetMessage {
         Self {
             id: target.id,
             labels: target.labels,
+            resource_version: target.resource_version,
+            metadata: Some(ModelMetadata {
+                created_at: to_timestamp(target.created_at),
+                created_by: target.created_by,
+                updated_at: to_timestamp(target.updated_at),
+                updated_by: target.updated_by,
+            }),
         }
     }
 }
 
 impl From<TargetMessage> for Target {
     fn from(target: TargetMessage) -> Self {
+        let metadata = target.metadata.unwrap_or_default();
+
         Self {
             id: target.id,
             labels: target.labels,
+            resource_version: target.resource_version,
+
+            created_at: to_date_time(&metadata.created_at),
+            created_by: metadata.created_by,
+            updated_at: to_date_time(&metadata.updated_at),
+            updated_by: metadata.updated_by,
         }
     }
 }
@@ -1,19 +1,74 @@
-use fabriq_core::TemplateMessage;
+use fabriq_core::{ModelMetadata, ModelType, TemplateMessage};
+use sqlx::types::{
+    chrono::{DateTime, Utc},
+    Json,
+};
 
+use super::{changed_metadata, to_date_time, to_timestamp, Annotations};
 use crate::persistence::Persistable;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct Template {
     pub id: String, // external-service
     pub repository: String,
     pub git_ref: String,
     pub path: String,
+    pub labels: Vec<String>,
+    pub annotations: Annotations,
+    pub resource_version: i64,
+
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
 }
 
 impl Persistable<Template> for Template {
+    const LIST_FIELDS: &'static [&'static str] = &["id", "repository", "git_ref", "path"];
+    const LABELLED: bool = true;
+    const MODEL_TYPE: ModelType = ModelType::Template;
+
     fn get_id(&self) -> String {
         self.id.clone()
     }
+
+    fn get_resource_version(&self) -> i64 {
+        self.resource_version
+    }
+
+    fn set_resource_version(&mut self, resource_version: i64) {
+        self.resource_version = resource_version;
+    }
+
+    fn set_changed(&mut self, actor: &str, previous: Option<&Template>) {
+        (
+            self.created_at,
+            self.created_by,
+            self.updated_at,
+            self.updated_by,
+        ) = changed_metadata(
+            actor,
+            previous.map(|previous| (previous.created_at, previous.created_by.as_str())),
+        );
+    }
+
+    fn get_field(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.clone()),
+            "repository" => Some(self.repository.clone()),
+            "git_ref" => Some(self.git_ref.clone()),
+            "path" => Some(self.path.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(TemplateMessage::from(self.clone()))?)
+    }
+
+    fn get_labels(&self) -> &[String] {
+        &self.labels
+    }
 }
 
 impl From<Template> for TemplateMessage {
@@ -23,17 +78,36 @@ impl From<Template> for TemplateMessage {
             repository: template.repository,
             git_ref: template.git_ref,
             path: template.path,
+            resource_version: template.resource_version,
+            labels: template.labels,
+            annotations: template.annotations.0.into_iter().collect(),
+            metadata: Some(ModelMetadata {
+                created_at: to_timestamp(template.created_at),
+                created_by: template.created_by,
+                updated_at: to_timestamp(template.updated_at),
+                updated_by: template.updated_by,
+            }),
         }
     }
 }
 
 impl From<TemplateMessage> for Template {
     fn from(template: TemplateMessage) -> Self {
+        let metadata = template.metadata.unwrap_or_default();
+
         Self {
             id: template.id,
             repository: template.repository,
             git_ref: template.git_ref,
             path: template.path,
+            labels: template.labels,
+            annotations: Json(template.annotations.into_iter().collect()),
+            resource_version: template.resource_version,
+
+            created_at: to_date_time(&metadata.created_at),
+            created_by: metadata.created_by,
+            updated_at: to_date_time(&metadata.updated_at),
+            updated_by: metadata.updated_by,
         }
     }
 }
@@ -1,18 +1,74 @@
+use fabriq_core::{ModelMetadata, ModelType, WorkloadMessage};
+use sqlx::types::{
+    chrono::{DateTime, Utc},
+    Json,
+};
+
+use super::{changed_metadata, to_date_time, to_timestamp, Annotations};
 use crate::persistence::Persistable;
-use fabriq_core::WorkloadMessage;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct Workload {
     pub id: String,
     pub name: String,
     pub team_id: String,
     pub template_id: String,
+    pub labels: Vec<String>,
+    pub annotations: Annotations,
+    pub resource_version: i64,
+
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
 }
 
 impl Persistable<Workload> for Workload {
+    const LIST_FIELDS: &'static [&'static str] = &["id", "name", "team_id", "template_id"];
+    const LABELLED: bool = true;
+    const MODEL_TYPE: ModelType = ModelType::Workload;
+
     fn get_id(&self) -> String {
         self.id.clone()
     }
+
+    fn get_resource_version(&self) -> i64 {
+        self.resource_version
+    }
+
+    fn set_resource_version(&mut self, resource_version: i64) {
+        self.resource_version = resource_version;
+    }
+
+    fn set_changed(&mut self, actor: &str, previous: Option<&Workload>) {
+        (
+            self.created_at,
+            self.created_by,
+            self.updated_at,
+            self.updated_by,
+        ) = changed_metadata(
+            actor,
+            previous.map(|previous| (previous.created_at, previous.created_by.as_str())),
+        );
+    }
+
+    fn get_field(&self, field: &str) -> Option<String> {
+        match field {
+            "id" => Some(self.id.clone()),
+            "name" => Some(self.name.clone()),
+            "team_id" => Some(self.team_id.clone()),
+            "template_id" => Some(self.template_id.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(WorkloadMessage::from(self.clone()))?)
+    }
+
+    fn get_labels(&self) -> &[String] {
+        &self.labels
+    }
 }
 
 impl From<Workload> for WorkloadMessage {
@@ -22,17 +78,36 @@ impl From<Workload> for WorkloadMessage {
             name: workload.name,
             team_id: workload.team_id,
             template_id: workload.template_id,
+            resource_version: workload.resource_version,
+            labels: workload.labels,
+            annotations: workload.annotations.0.into_iter().collect(),
+            metadata: Some(ModelMetadata {
+                created_at: to_timestamp(workload.created_at),
+                created_by: workload.created_by,
+                updated_at: to_timestamp(workload.updated_at),
+                updated_by: workload.updated_by,
+            }),
         }
     }
 }
 
 impl From<WorkloadMessage> for Workload {
     fn from(workload: WorkloadMessage) -> Self {
+        let metadata = workload.metadata.unwrap_or_default();
+
         Self {
             id: workload.id,
             name: workload.name,
             team_id: workload.team_id,
             template_id: workload.template_id,
+            labels: workload.labels,
+            annotations: Json(workload.annotations.into_iter().collect()),
+            resource_version: workload.resource_version,
+
+            created_at: to_date_time(&metadata.created_at),
+            created_by: metadata.created_by,
+            updated_at: to_date_time(&metadata.updated_at),
+            updated_by: metadata.updated_by,
         }
     }
 }
@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use fabriq_core::{Event, EventStream};
+use std::sync::Arc;
+
+use super::PersistenceCaches;
+
+// Cached persistences see the writes made through them, but not those made by other api
+// processes sharing the database. Invalidating on the events this process receives catches some
+// of them, but every api process receives as the same reconciler consumer, so each event only
+// reaches one of them: the others only see the write once their cached models expire.
+#[derive(Debug)]
+pub struct InvalidatingEventStream {
+    pub event_stream: Arc<dyn EventStream>,
+    pub caches: Arc<PersistenceCaches>,
+}
+
+#[async_trait]
+impl EventStream for InvalidatingEventStream {
+    async fn delete(&self, event: &Event, consumer_id: &str) -> anyhow::Result<u64> {
+        self.event_stream.delete(event, consumer_id).await
+    }
+
+    async fn receive(&self, consumer_id: &str) -> anyhow::Result<Vec<Event>> {
+        let events = self.event_stream.receive(consumer_id).await?;
+
+        for event in &events {
+            self.caches.invalidate(event)?;
+        }
+
+        Ok(events)
+    }
+
+    async fn send(&self, event: &Event) -> anyhow::Result<()> {
+        self.event_stream.send(event).await
+    }
+
+    async fn send_many(&self, events: &[Event]) -> anyhow::Result<()> {
+        self.event_stream.send_many(events).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{
+        create_event, test::get_host_fixture, EventType, HostMessage, ModelType, OperationId,
+    };
+    use fabriq_memory_stream::MemoryEventStream;
+
+    use super::*;
+    use crate::models::Host;
+    use crate::persistence::{
+        cache::{CachedPersistence, PersistenceCacheConfig},
+        memory::HostMemoryPersistence,
+        Persistence,
+    };
+
+    #[tokio::test]
+    async fn test_invalidate_on_receive() -> anyhow::Result<()> {
+        let caches = Arc::new(PersistenceCaches::new(PersistenceCacheConfig::default()));
+
+        // Stands in for the database shared with another api process.
+        let shared_persistence = HostMemoryPersistence::default();
+        let persistence = CachedPersistence {
+            persistence: Box::new(HostMemoryPersistence {
+                models: Arc::clone(&shared_persistence.models),
+                deleted_models: Arc::clone(&shared_persistence.deleted_models),
+            }),
+            cache: Arc::clone(&caches.host),
+        };
+
+        let event_stream = InvalidatingEventStream {
+            event_stream: Arc::new(MemoryEventStream::new()?),
+            caches: Arc::clone(&caches),
+        };
+
+        let mut host: Host = get_host_fixture(None).into();
+        persistence.upsert(&host).await?;
+        persistence.get_by_id(&host.id).await?;
+
+        host.labels.push("region:westus".to_string());
+        shared_persistence.upsert(&host).await?;
+
+        let cached_host = persistence.get_by_id(&host.id).await?.unwrap();
+        assert_ne!(cached_host.labels, host.labels);
+
+        let event = create_event(
+            &Some(HostMessage::from(cached_host)),
+            &Some(HostMessage::from(host.clone())),
+            EventType::Updated,
+            ModelType::Host,
+            &OperationId::create(),
+        );
+        event_stream.send(&event).await?;
+        event_stream.receive("reconciler").await?;
+
+        let fetched_host = persistence.get_by_id(&host.id).await?.unwrap();
+        assert_eq!(fetched_host.labels, host.labels);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,326 @@
+use fabriq_core::{cloud_event::decode_model, Event, ModelType};
+use opentelemetry::{global, metrics::Counter, Context, KeyValue};
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use crate::models::{Assignment, Config, Deployment, Host, Target, Template, Workload};
+
+use super::Persistences;
+
+mod event_stream;
+mod persistence;
+
+pub use event_stream::InvalidatingEventStream;
+pub use persistence::CachedPersistence;
+
+const DEFAULT_TTL_SECONDS: u64 = 60;
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+#[derive(Clone, Debug)]
+pub struct PersistenceCacheConfig {
+    pub ttl: Duration,
+
+    // per model type, for models and query results each.
+    pub max_entries: usize,
+}
+
+impl Default for PersistenceCacheConfig {
+    fn default() -> Self {
+        PersistenceCacheConfig {
+            ttl: Duration::from_secs(DEFAULT_TTL_SECONDS),
+            max_entries: DEFAULT_MAX_ENTRIES,
+        }
+    }
+}
+
+impl PersistenceCacheConfig {
+    pub fn from_env() -> Self {
+        let default = PersistenceCacheConfig::default();
+
+        let ttl = env::var("PERSISTENCE_CACHE_TTL_SECONDS")
+            .ok()
+            .and_then(|ttl| ttl.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default.ttl);
+
+        let max_entries = env::var("PERSISTENCE_CACHE_MAX_ENTRIES")
+            .ok()
+            .and_then(|max_entries| max_entries.parse().ok())
+            .unwrap_or(default.max_entries);
+
+        PersistenceCacheConfig { ttl, max_entries }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> anyhow::Result<MutexGuard<'_, T>> {
+    match mutex.lock() {
+        Ok(locked) => Ok(locked),
+        Err(_) => Err(anyhow::anyhow!("failed to acquire lock")),
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry<Value> {
+    value: Value,
+    expires_at: Instant,
+}
+
+fn get_entry<Value: Clone>(
+    entries: &mut HashMap<String, CacheEntry<Value>>,
+    key: &str,
+) -> Option<Value> {
+    match entries.get(key) {
+        Some(entry) if entry.expires_at > Instant::now() => Some(entry.value.clone()),
+        Some(_) => {
+            entries.remove(key);
+            None
+        }
+        None => None,
+    }
+}
+
+// Evicts expired entries, and failing that the oldest, to make room for key.
+fn insert_entry<Value>(
+    entries: &mut HashMap<String, CacheEntry<Value>>,
+    key: &str,
+    value: Value,
+    config: &PersistenceCacheConfig,
+) {
+    if config.max_entries == 0 {
+        return;
+    }
+
+    let now = Instant::now();
+
+    if !entries.contains_key(key) && entries.len() >= config.max_entries {
+        entries.retain(|_, entry| entry.expires_at > now);
+    }
+
+    if !entries.contains_key(key) && entries.len() >= config.max_entries {
+        let oldest_key = entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.expires_at)
+            .map(|(key, _)| key.clone());
+
+        if let Some(oldest_key) = oldest_key {
+            entries.remove(&oldest_key);
+        }
+    }
+
+    entries.insert(
+        key.to_string(),
+        CacheEntry {
+            value,
+            expires_at: now + config.ttl,
+        },
+    );
+}
+
+#[derive(Debug)]
+struct Entries<Model> {
+    models: HashMap<String, CacheEntry<Model>>,
+    queries: HashMap<String, CacheEntry<Vec<Model>>>,
+
+    // Bumped on every invalidation, so that a read that raced with a write doesn't cache what it
+    // fetched from before the write.
+    generation: u64,
+}
+
+// Models of one type by id, and the results of queries for them by query key. Writing a model
+// drops it along with every query result, since any query might now include or exclude it.
+// Entries also expire after the configured ttl, which bounds how stale a model written by another
+// api process can be if its event is received elsewhere.
+#[derive(Debug)]
+pub struct ModelCache<Model> {
+    model_type: &'static str,
+    config: PersistenceCacheConfig,
+    entries: Mutex<Entries<Model>>,
+
+    hit_counter: Counter<u64>,
+    miss_counter: Counter<u64>,
+}
+
+impl<Model: Clone> ModelCache<Model> {
+    pub fn new(model_type: &'static str, config: PersistenceCacheConfig) -> Self {
+        let meter = global::meter("fabriq-api");
+
+        ModelCache {
+            model_type,
+            config,
+            entries: Mutex::new(Entries {
+                models: HashMap::new(),
+                queries: HashMap::new(),
+                generation: 0,
+            }),
+
+            hit_counter: meter
+                .u64_counter("persistence.cache.hits")
+                .with_description("Reads served from the persistence cache")
+                .init(),
+            miss_counter: meter
+                .u64_counter("persistence.cache.misses")
+                .with_description("Reads that went through to the database")
+                .init(),
+        }
+    }
+
+    fn record<T>(&self, cached: Option<T>) -> Option<T> {
+        let counter = match cached {
+            Some(_) => &self.hit_counter,
+            None => &self.miss_counter,
+        };
+
+        counter.add(
+            &Context::current(),
+            1,
+            &[KeyValue::new("model_type", self.model_type)],
+        );
+
+        cached
+    }
+
+    // Returns the cached model if there is one, else the generation to pass to insert_model once
+    // it has been fetched.
+    pub fn get_model(&self, id: &str) -> anyhow::Result<Result<Model, u64>> {
+        let mut entries = lock(&self.entries)?;
+        let cached = self.record(get_entry(&mut entries.models, id));
+
+        Ok(cached.ok_or(entries.generation))
+    }
+
+    pub fn get_query(&self, key: &str) -> anyhow::Result<Result<Vec<Model>, u64>> {
+        let mut entries = lock(&self.entries)?;
+        let cached = self.record(get_entry(&mut entries.queries, key));
+
+        Ok(cached.ok_or(entries.generation))
+    }
+
+    // Caches model unless something has been invalidated since generation.
+    pub fn insert_model(&self, id: &str, model: &Model, generation: u64) -> anyhow::Result<()> {
+        let mut entries = lock(&self.entries)?;
+
+        if entries.generation == generation {
+            insert_entry(&mut entries.models, id, model.clone(), &self.config);
+        }
+
+        Ok(())
+    }
+
+    pub fn insert_query(&self, key: &str, models: &[Model], generation: u64) -> anyhow::Result<()> {
+        let mut entries = lock(&self.entries)?;
+
+        if entries.generation == generation {
+            insert_entry(&mut entries.queries, key, models.to_vec(), &self.config);
+        }
+
+        Ok(())
+    }
+
+    pub fn invalidate(&self, id: &str) -> anyhow::Result<()> {
+        let mut entries = lock(&self.entries)?;
+
+        entries.generation += 1;
+        entries.models.remove(id);
+        entries.queries.clear();
+
+        Ok(())
+    }
+}
+
+// The caches of every model type but audit entries, which are only ever appended and queried, and
+// role bindings, which the rbac authorizer already caches for its own ttl (see RoleBindingCache).
+#[derive(Debug)]
+pub struct PersistenceCaches {
+    pub assignment: Arc<ModelCache<Assignment>>,
+    pub config: Arc<ModelCache<Config>>,
+    pub deployment: Arc<ModelCache<Deployment>>,
+    pub host: Arc<ModelCache<Host>>,
+    pub target: Arc<ModelCache<Target>>,
+    pub template: Arc<ModelCache<Template>>,
+    pub workload: Arc<ModelCache<Workload>>,
+}
+
+impl PersistenceCaches {
+    pub fn new(config: PersistenceCacheConfig) -> Self {
+        PersistenceCaches {
+            assignment: Arc::new(ModelCache::new("assignment", config.clone())),
+            config: Arc::new(ModelCache::new("config", config.clone())),
+            deployment: Arc::new(ModelCache::new("deployment", config.clone())),
+            host: Arc::new(ModelCache::new("host", config.clone())),
+            target: Arc::new(ModelCache::new("target", config.clone())),
+            template: Arc::new(ModelCache::new("template", config.clone())),
+            workload: Arc::new(ModelCache::new("workload", config)),
+        }
+    }
+
+    // Wraps persistences with these caches.
+    pub fn wrap(&self, persistences: Persistences) -> Persistences {
+        Persistences {
+            assignment: Box::new(CachedPersistence {
+                persistence: persistences.assignment,
+                cache: Arc::clone(&self.assignment),
+            }),
+            audit: persistences.audit,
+            config: Box::new(CachedPersistence {
+                persistence: persistences.config,
+                cache: Arc::clone(&self.config),
+            }),
+            deployment: Box::new(CachedPersistence {
+                persistence: persistences.deployment,
+                cache: Arc::clone(&self.deployment),
+            }),
+            host: Box::new(CachedPersistence {
+                persistence: persistences.host,
+                cache: Arc::clone(&self.host),
+            }),
+            role_binding: persistences.role_binding,
+            target: Box::new(CachedPersistence {
+                persistence: persistences.target,
+                cache: Arc::clone(&self.target),
+            }),
+            template: Box::new(CachedPersistence {
+                persistence: persistences.template,
+                cache: Arc::clone(&self.template),
+            }),
+            workload: Box::new(CachedPersistence {
+                persistence: persistences.workload,
+                cache: Arc::clone(&self.workload),
+            }),
+        }
+    }
+
+    // Drops the models event changed.
+    pub fn invalidate(&self, event: &Event) -> anyhow::Result<()> {
+        let model_type = ModelType::from(event.model_type);
+
+        if matches!(model_type, ModelType::RoleBinding | ModelType::Workspace) {
+            return Ok(());
+        }
+
+        let serialized_models = [
+            &event.serialized_previous_model,
+            &event.serialized_current_model,
+        ];
+
+        for serialized_model in serialized_models.into_iter().flatten() {
+            let (model_id, _) = decode_model(model_type, serialized_model)?;
+
+            match model_type {
+                ModelType::Assignment => self.assignment.invalidate(&model_id)?,
+                ModelType::Config => self.config.invalidate(&model_id)?,
+                ModelType::Deployment => self.deployment.invalidate(&model_id)?,
+                ModelType::Host => self.host.invalidate(&model_id)?,
+                ModelType::Target => self.target.invalidate(&model_id)?,
+                ModelType::Template => self.template.invalidate(&model_id)?,
+                ModelType::Workload => self.workload.invalidate(&model_id)?,
+                ModelType::RoleBinding | ModelType::Workspace => {}
+            }
+        }
+
+        Ok(())
+    }
+}
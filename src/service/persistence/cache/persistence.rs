@@ -0,0 +1,279 @@
+use async_trait::async_trait;
+use std::{fmt::Debug, future::Future, sync::Arc};
+
+use crate::models::{Assignment, Config, Deployment, Host, Target, Workload};
+use crate::persistence::{
+    AssignmentPersistence, ConfigPersistence, DeploymentPersistence, HostPersistence, ListOptions,
+    ListPage, Persistable, Persistence, WorkloadPersistence,
+};
+
+use super::ModelCache;
+
+// Serves get_by_id and the model type's queries from cache, reading through to persistence on a
+// miss. Lists are always read from persistence, as they are paged and rarely repeated.
+#[derive(Debug)]
+pub struct CachedPersistence<P: ?Sized, Model> {
+    pub persistence: Box<P>,
+    pub cache: Arc<ModelCache<Model>>,
+}
+
+impl<P: ?Sized, Model: Clone> CachedPersistence<P, Model> {
+    async fn query(
+        &self,
+        key: String,
+        fetch: impl Future<Output = anyhow::Result<Vec<Model>>>,
+    ) -> anyhow::Result<Vec<Model>> {
+        let generation = match self.cache.get_query(&key)? {
+            Ok(models) => return Ok(models),
+            Err(generation) => generation,
+        };
+
+        let models = fetch.await?;
+        self.cache.insert_query(&key, &models, generation)?;
+
+        Ok(models)
+    }
+}
+
+#[async_trait]
+impl<P, Model> Persistence<Model> for CachedPersistence<P, Model>
+where
+    P: Persistence<Model> + ?Sized,
+    Model: Persistable<Model> + 'static,
+{
+    async fn upsert(&self, model: &Model) -> anyhow::Result<u64> {
+        let upserted = self.persistence.upsert(model).await;
+        self.cache.invalidate(&model.get_id())?;
+
+        upserted
+    }
+
+    async fn delete(&self, model_id: &str) -> anyhow::Result<u64> {
+        let deleted = self.persistence.delete(model_id).await;
+        self.cache.invalidate(model_id)?;
+
+        deleted
+    }
+
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Model>> {
+        let generation = match self.cache.get_model(id)? {
+            Ok(model) => return Ok(Some(model)),
+            Err(generation) => generation,
+        };
+
+        // Missing models aren't cached, so that one created by another api process is found as
+        // soon as it exists.
+        let model = self.persistence.get_by_id(id).await?;
+        if let Some(model) = &model {
+            self.cache.insert_model(id, model, generation)?;
+        }
+
+        Ok(model)
+    }
+
+    async fn list(&self) -> anyhow::Result<Vec<Model>> {
+        self.persistence.list().await
+    }
+
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Model>> {
+        self.persistence.list_page(options).await
+    }
+}
+
+#[async_trait]
+impl AssignmentPersistence for CachedPersistence<dyn AssignmentPersistence, Assignment> {
+    async fn get_by_deployment_id(&self, id: &str) -> anyhow::Result<Vec<Assignment>> {
+        self.query(
+            format!("deployment_id:{id}"),
+            self.persistence.get_by_deployment_id(id),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl ConfigPersistence for CachedPersistence<dyn ConfigPersistence, Config> {
+    async fn get_by_deployment_id(&self, deployment_id: &str) -> anyhow::Result<Vec<Config>> {
+        self.query(
+            format!("deployment_id:{deployment_id}"),
+            self.persistence.get_by_deployment_id(deployment_id),
+        )
+        .await
+    }
+
+    async fn get_by_host_id(&self, host_id: &str) -> anyhow::Result<Vec<Config>> {
+        self.query(
+            format!("host_id:{host_id}"),
+            self.persistence.get_by_host_id(host_id),
+        )
+        .await
+    }
+
+    async fn get_by_template_id(&self, template_id: &str) -> anyhow::Result<Vec<Config>> {
+        self.query(
+            format!("template_id:{template_id}"),
+            self.persistence.get_by_template_id(template_id),
+        )
+        .await
+    }
+
+    async fn get_by_workload_id(&self, workload_id: &str) -> anyhow::Result<Vec<Config>> {
+        self.query(
+            format!("workload_id:{workload_id}"),
+            self.persistence.get_by_workload_id(workload_id),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl DeploymentPersistence for CachedPersistence<dyn DeploymentPersistence, Deployment> {
+    async fn get_by_target_id(&self, target_id: &str) -> anyhow::Result<Vec<Deployment>> {
+        self.query(
+            format!("target_id:{target_id}"),
+            self.persistence.get_by_target_id(target_id),
+        )
+        .await
+    }
+
+    async fn get_by_template_id(&self, id: &str) -> anyhow::Result<Vec<Deployment>> {
+        self.query(
+            format!("template_id:{id}"),
+            self.persistence.get_by_template_id(id),
+        )
+        .await
+    }
+
+    async fn get_by_workload_id(&self, workload_id: &str) -> anyhow::Result<Vec<Deployment>> {
+        self.query(
+            format!("workload_id:{workload_id}"),
+            self.persistence.get_by_workload_id(workload_id),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl HostPersistence for CachedPersistence<dyn HostPersistence, Host> {
+    // Which hosts match depends only on the target's labels, so targets with the same labels
+    // share a result.
+    async fn get_matching_target(&self, target: &Target) -> anyhow::Result<Vec<Host>> {
+        let mut labels = target.labels.clone();
+        labels.sort();
+
+        self.query(
+            format!("matching_labels:{labels:?}"),
+            self.persistence.get_matching_target(target),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl WorkloadPersistence for CachedPersistence<dyn WorkloadPersistence, Workload> {
+    async fn get_by_template_id(&self, id: &str) -> anyhow::Result<Vec<Workload>> {
+        self.query(
+            format!("template_id:{id}"),
+            self.persistence.get_by_template_id(id),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::{get_host_fixture, get_target_fixture};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::persistence::{cache::PersistenceCacheConfig, memory::HostMemoryPersistence};
+
+    // Returns a cached persistence and, standing in for another api process sharing the database,
+    // an uncached one backed by the same models.
+    fn make_host_persistences(
+        config: PersistenceCacheConfig,
+    ) -> (
+        CachedPersistence<dyn HostPersistence, Host>,
+        HostMemoryPersistence,
+    ) {
+        let shared_persistence = HostMemoryPersistence::default();
+
+        let cached_persistence = CachedPersistence {
+            persistence: Box::new(HostMemoryPersistence {
+                models: Arc::clone(&shared_persistence.models),
+                deleted_models: Arc::clone(&shared_persistence.deleted_models),
+            }) as Box<dyn HostPersistence>,
+            cache: Arc::new(ModelCache::new("host", config)),
+        };
+
+        (cached_persistence, shared_persistence)
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id() -> anyhow::Result<()> {
+        let (persistence, shared_persistence) =
+            make_host_persistences(PersistenceCacheConfig::default());
+
+        let mut host: Host = get_host_fixture(None).into();
+        persistence.upsert(&host).await?;
+        persistence.get_by_id(&host.id).await?;
+
+        // Written behind the cache's back, so only seen once the cached host is invalidated.
+        shared_persistence.delete(&host.id).await?;
+        assert!(persistence.get_by_id(&host.id).await?.is_some());
+
+        host.labels.push("region:westus".to_string());
+        persistence.upsert(&host).await?;
+
+        let fetched_host = persistence.get_by_id(&host.id).await?.unwrap();
+        assert_eq!(fetched_host.labels, host.labels);
+
+        persistence.delete(&host.id).await?;
+        assert!(persistence.get_by_id(&host.id).await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_matching_target() -> anyhow::Result<()> {
+        let (persistence, shared_persistence) =
+            make_host_persistences(PersistenceCacheConfig::default());
+        let target: Target = get_target_fixture(None).into();
+
+        persistence
+            .upsert(&get_host_fixture(Some("cached-host-a")).into())
+            .await?;
+        assert_eq!(persistence.get_matching_target(&target).await?.len(), 1);
+
+        shared_persistence
+            .upsert(&get_host_fixture(Some("cached-host-b")).into())
+            .await?;
+        assert_eq!(persistence.get_matching_target(&target).await?.len(), 1);
+
+        persistence
+            .upsert(&get_host_fixture(Some("cached-host-c")).into())
+            .await?;
+        assert_eq!(persistence.get_matching_target(&target).await?.len(), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ttl() -> anyhow::Result<()> {
+        let (persistence, shared_persistence) = make_host_persistences(PersistenceCacheConfig {
+            ttl: Duration::from_millis(10),
+            ..PersistenceCacheConfig::default()
+        });
+
+        let host: Host = get_host_fixture(None).into();
+        persistence.upsert(&host).await?;
+        persistence.get_by_id(&host.id).await?;
+
+        shared_persistence.delete(&host.id).await?;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert!(persistence.get_by_id(&host.id).await?.is_none());
+
+        Ok(())
+    }
+}
@@ -5,13 +5,18 @@ use std::{
 };
 
 use crate::{
-    models::Assignment,
-    persistence::{AssignmentPersistence, Persistable, Persistence},
+    models::{Assignment, DeletedModel},
+    persistence::{
+        memory::{keep_deleted, list_page},
+        next_resource_version, AssignmentPersistence, ListOptions, ListPage, Persistable,
+        Persistence,
+    },
 };
 
 #[derive(Debug)]
 pub struct AssignmentMemoryPersistence {
-    models: Arc<Mutex<HashMap<String, Assignment>>>,
+    pub models: Arc<Mutex<HashMap<String, Assignment>>>,
+    pub deleted_models: Arc<Mutex<Vec<DeletedModel>>>,
 }
 
 #[async_trait]
@@ -19,7 +24,18 @@ impl Persistence<Assignment> for AssignmentMemoryPersistence {
     async fn upsert(&self, assignment: &Assignment) -> anyhow::Result<u64> {
         let mut locked_assignments = self.get_models_locked()?;
 
-        locked_assignments.insert(assignment.get_id(), assignment.clone());
+        let current_resource_version = locked_assignments
+            .get(&assignment.get_id())
+            .map(|current| current.get_resource_version());
+
+        let mut assignment = assignment.clone();
+        assignment.set_resource_version(next_resource_version(
+            &assignment.get_id(),
+            assignment.get_resource_version(),
+            current_resource_version,
+        )?);
+
+        locked_assignments.insert(assignment.get_id(), assignment);
 
         Ok(1)
     }
@@ -27,7 +43,9 @@ impl Persistence<Assignment> for AssignmentMemoryPersistence {
     async fn delete(&self, assignment_id: &str) -> anyhow::Result<u64> {
         let mut locked_assignments = self.get_models_locked()?;
 
-        locked_assignments.remove_entry(&assignment_id.to_string());
+        if let Some(assignment) = locked_assignments.remove(assignment_id) {
+            keep_deleted(&self.deleted_models, &assignment)?;
+        }
 
         Ok(1)
     }
@@ -48,6 +66,10 @@ impl Persistence<Assignment> for AssignmentMemoryPersistence {
 
         Ok(assignments)
     }
+
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Assignment>> {
+        list_page(self.list().await?, options)
+    }
 }
 
 #[async_trait]
@@ -79,6 +101,7 @@ impl Default for AssignmentMemoryPersistence {
     fn default() -> Self {
         Self {
             models: Arc::new(Mutex::new(HashMap::new())),
+            deleted_models: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{
+    models::AuditEntry,
+    persistence::{AuditPersistence, AuditQuery},
+};
+
+#[derive(Debug, Default)]
+pub struct AuditMemoryPersistence {
+    pub audit_entries: Arc<Mutex<Vec<AuditEntry>>>,
+}
+
+#[async_trait]
+impl AuditPersistence for AuditMemoryPersistence {
+    async fn insert(&self, audit_entry: &AuditEntry) -> anyhow::Result<u64> {
+        let mut locked_audit_entries = self.get_audit_entries_locked()?;
+
+        locked_audit_entries.push(audit_entry.clone());
+
+        Ok(1)
+    }
+
+    async fn query(&self, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>> {
+        let locked_audit_entries = self.get_audit_entries_locked()?;
+
+        let matches = |value: &String, filter: &Option<String>| match filter {
+            Some(filter) => value == filter,
+            None => true,
+        };
+
+        let mut audit_entries: Vec<AuditEntry> = locked_audit_entries
+            .iter()
+            .filter(|audit_entry| {
+                matches(&audit_entry.model_type, &query.model_type)
+                    && matches(&audit_entry.model_id, &query.model_id)
+                    && matches(&audit_entry.actor, &query.actor)
+                    && query
+                        .since
+                        .is_none_or(|since| audit_entry.recorded_at >= since)
+                    && query
+                        .until
+                        .is_none_or(|until| audit_entry.recorded_at < until)
+            })
+            .cloned()
+            .collect();
+
+        audit_entries.sort_by(|a, b| {
+            b.recorded_at
+                .cmp(&a.recorded_at)
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        audit_entries.truncate(query.limit);
+
+        Ok(audit_entries)
+    }
+}
+
+impl AuditMemoryPersistence {
+    fn get_audit_entries_locked(&self) -> anyhow::Result<MutexGuard<'_, Vec<AuditEntry>>> {
+        match self.audit_entries.lock() {
+            Ok(locked_audit_entries) => Ok(locked_audit_entries),
+            Err(_) => Err(anyhow::anyhow!("failed to acquire lock")),
+        }
+    }
+}
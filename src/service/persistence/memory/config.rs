@@ -5,13 +5,17 @@ use std::{
 };
 
 use crate::{
-    models::Config,
-    persistence::{ConfigPersistence, Persistable, Persistence},
+    models::{Config, DeletedModel},
+    persistence::{
+        memory::{keep_deleted, list_page},
+        next_resource_version, ConfigPersistence, ListOptions, ListPage, Persistable, Persistence,
+    },
 };
 
 #[derive(Debug)]
 pub struct ConfigMemoryPersistence {
-    models: Arc<Mutex<HashMap<String, Config>>>,
+    pub models: Arc<Mutex<HashMap<String, Config>>>,
+    pub deleted_models: Arc<Mutex<Vec<DeletedModel>>>,
 }
 
 #[async_trait]
@@ -19,7 +23,18 @@ impl Persistence<Config> for ConfigMemoryPersistence {
     async fn upsert(&self, config: &Config) -> anyhow::Result<u64> {
         let mut locked_configs = self.get_models_locked()?;
 
-        locked_configs.insert(config.get_id(), config.clone());
+        let current_resource_version = locked_configs
+            .get(&config.get_id())
+            .map(|current| current.get_resource_version());
+
+        let mut config = config.clone();
+        config.set_resource_version(next_resource_version(
+            &config.get_id(),
+            config.get_resource_version(),
+            current_resource_version,
+        )?);
+
+        locked_configs.insert(config.get_id(), config);
 
         Ok(1)
     }
@@ -27,7 +42,9 @@ impl Persistence<Config> for ConfigMemoryPersistence {
     async fn delete(&self, config_id: &str) -> anyhow::Result<u64> {
         let mut locked_configs = self.get_models_locked()?;
 
-        locked_configs.remove_entry(&config_id.to_string());
+        if let Some(config) = locked_configs.remove(config_id) {
+            keep_deleted(&self.deleted_models, &config)?;
+        }
 
         Ok(1)
     }
@@ -48,6 +65,10 @@ impl Persistence<Config> for ConfigMemoryPersistence {
 
         Ok(configs)
     }
+
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Config>> {
+        list_page(self.list().await?, options)
+    }
 }
 
 #[async_trait]
@@ -66,13 +87,27 @@ impl ConfigPersistence for ConfigMemoryPersistence {
         Ok(configs_for_deployment)
     }
 
+    async fn get_by_host_id(&self, host_id: &str) -> anyhow::Result<Vec<Config>> {
+        let locked_configs = self.get_models_locked()?;
+
+        let mut configs_for_host = Vec::new();
+        for config in (*locked_configs).values() {
+            let (model_type, model_id) = config.split_owning_model()?;
+            if model_type == "host" && model_id == host_id {
+                configs_for_host.push(config.clone());
+            }
+        }
+
+        Ok(configs_for_host)
+    }
+
     async fn get_by_template_id(&self, template_id: &str) -> anyhow::Result<Vec<Config>> {
         let locked_configs = self.get_models_locked()?;
 
         let mut configs_for_template = Vec::new();
         for config in (*locked_configs).values() {
             let (model_type, model_id) = config.split_owning_model()?;
-            if model_type == "deployment" && model_id == template_id {
+            if model_type == "template" && model_id == template_id {
                 configs_for_template.push(config.clone());
             }
         }
@@ -108,6 +143,7 @@ impl Default for ConfigMemoryPersistence {
     fn default() -> Self {
         Self {
             models: Arc::new(Mutex::new(HashMap::new())),
+            deleted_models: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -5,13 +5,18 @@ use std::{
 };
 
 use crate::{
-    models::Deployment,
-    persistence::{DeploymentPersistence, Persistable, Persistence},
+    models::{DeletedModel, Deployment},
+    persistence::{
+        memory::{keep_deleted, list_page},
+        next_resource_version, DeploymentPersistence, ListOptions, ListPage, Persistable,
+        Persistence,
+    },
 };
 
 #[derive(Debug)]
 pub struct DeploymentMemoryPersistence {
-    models: Arc<Mutex<HashMap<String, Deployment>>>,
+    pub models: Arc<Mutex<HashMap<String, Deployment>>>,
+    pub deleted_models: Arc<Mutex<Vec<DeletedModel>>>,
 }
 
 #[async_trait]
@@ -19,7 +24,18 @@ impl Persistence<Deployment> for DeploymentMemoryPersistence {
     async fn upsert(&self, deployment: &Deployment) -> anyhow::Result<u64> {
         let mut locked_deployments = self.get_models_locked()?;
 
-        locked_deployments.insert(deployment.get_id(), deployment.clone());
+        let current_resource_version = locked_deployments
+            .get(&deployment.get_id())
+            .map(|current| current.get_resource_version());
+
+        let mut deployment = deployment.clone();
+        deployment.set_resource_version(next_resource_version(
+            &deployment.get_id(),
+            deployment.get_resource_version(),
+            current_resource_version,
+        )?);
+
+        locked_deployments.insert(deployment.get_id(), deployment);
 
         Ok(1)
     }
@@ -27,7 +43,9 @@ impl Persistence<Deployment> for DeploymentMemoryPersistence {
     async fn delete(&self, deployment_id: &str) -> anyhow::Result<u64> {
         let mut locked_deployments = self.get_models_locked()?;
 
-        locked_deployments.remove_entry(&deployment_id.to_string());
+        if let Some(deployment) = locked_deployments.remove(deployment_id) {
+            keep_deleted(&self.deleted_models, &deployment)?;
+        }
 
         Ok(1)
     }
@@ -48,6 +66,10 @@ impl Persistence<Deployment> for DeploymentMemoryPersistence {
 
         Ok(deployments)
     }
+
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Deployment>> {
+        list_page(self.list().await?, options)
+    }
 }
 
 #[async_trait]
@@ -105,6 +127,7 @@ impl Default for DeploymentMemoryPersistence {
     fn default() -> Self {
         Self {
             models: Arc::new(Mutex::new(HashMap::new())),
+            deleted_models: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
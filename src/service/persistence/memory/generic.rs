@@ -5,14 +5,21 @@ use std::{
     sync::{Arc, Mutex, MutexGuard},
 };
 
-use crate::persistence::{Persistable, Persistence};
+use crate::{
+    models::DeletedModel,
+    persistence::{
+        memory::{keep_deleted, list_page},
+        next_resource_version, ListOptions, ListPage, Persistable, Persistence,
+    },
+};
 
 #[derive(Debug)]
 pub struct MemoryPersistence<Model>
 where
     Model: Persistable<Model>,
 {
-    models: Arc<Mutex<HashMap<String, Model>>>,
+    pub models: Arc<Mutex<HashMap<String, Model>>>,
+    pub deleted_models: Arc<Mutex<Vec<DeletedModel>>>,
 }
 
 #[async_trait]
@@ -23,7 +30,18 @@ where
     async fn upsert(&self, model: &Model) -> anyhow::Result<u64> {
         let mut locked_models = self.get_models_locked()?;
 
-        locked_models.insert(model.get_id(), model.clone());
+        let current_resource_version = locked_models
+            .get(&model.get_id())
+            .map(|current| current.get_resource_version());
+
+        let mut model = model.clone();
+        model.set_resource_version(next_resource_version(
+            &model.get_id(),
+            model.get_resource_version(),
+            current_resource_version,
+        )?);
+
+        locked_models.insert(model.get_id(), model);
 
         Ok(1)
     }
@@ -31,7 +49,9 @@ where
     async fn delete(&self, model_id: &str) -> anyhow::Result<u64> {
         let mut locked_models = self.get_models_locked()?;
 
-        locked_models.remove_entry(&model_id.to_string());
+        if let Some(model) = locked_models.remove(model_id) {
+            keep_deleted(&self.deleted_models, &model)?;
+        }
 
         Ok(1)
     }
@@ -44,6 +64,10 @@ where
         Ok(models)
     }
 
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Model>> {
+        list_page(self.list().await?, options)
+    }
+
     async fn get_by_id(&self, model_id: &str) -> anyhow::Result<Option<Model>> {
         let locked_models = self.get_models_locked()?;
 
@@ -61,6 +85,7 @@ where
     fn default() -> Self {
         Self {
             models: Arc::new(Mutex::new(HashMap::new())),
+            deleted_models: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -83,7 +108,8 @@ mod tests {
 
     use super::*;
 
-    use crate::models::Host;
+    use crate::models::{Host, Workload};
+    use crate::persistence::{ListOptions, ResourceVersionConflict};
 
     #[tokio::test]
     async fn test_create_get_delete() {
@@ -98,8 +124,93 @@ mod tests {
 
         assert_eq!(fetched_host.id, host.id);
         assert_eq!(fetched_host.labels.len(), 2);
+        assert_eq!(fetched_host.resource_version, 1);
+
+        host_persistence.upsert(&fetched_host).await.unwrap();
+
+        let err = host_persistence.upsert(&fetched_host).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ResourceVersionConflict>(),
+            Some(&ResourceVersionConflict {
+                model_id: host.id.clone(),
+                resource_version: 1,
+            })
+        );
+
+        // a resource version of 0 always writes.
+        host_persistence.upsert(&host).await.unwrap();
+        let fetched_host = host_persistence.get_by_id(&host.id).await.unwrap().unwrap();
+        assert_eq!(fetched_host.resource_version, 3);
 
         let deleted_hosts = host_persistence.delete(&host.id).await.unwrap();
         assert_eq!(deleted_hosts, 1);
+
+        // the deleted host is kept as it was when it was deleted.
+        let deleted_models = host_persistence.deleted_models.lock().unwrap().clone();
+        assert_eq!(deleted_models.len(), 1);
+        assert_eq!(deleted_models[0].model_type, "host");
+        assert_eq!(deleted_models[0].model_id, host.id);
+        assert_eq!(deleted_models[0].deleted_by, "system");
+        assert_eq!(deleted_models[0].model["resource_version"], 3);
+
+        // a non-zero resource version doesn't recreate a model deleted since it was read.
+        let err = host_persistence.upsert(&fetched_host).await.unwrap_err();
+        assert!(err.downcast_ref::<ResourceVersionConflict>().is_some());
+        assert!(host_persistence
+            .get_by_id(&host.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_page() {
+        let workload_persistence = MemoryPersistence::<Workload>::default();
+
+        for (name, team_id) in [("a", "team-1"), ("b", "team-2"), ("c", "team-1")] {
+            let workload = Workload {
+                id: name.to_string(),
+                name: name.to_string(),
+                team_id: team_id.to_string(),
+                template_id: "template".to_string(),
+                ..Default::default()
+            };
+
+            workload_persistence.upsert(&workload).await.unwrap();
+        }
+
+        let options = ListOptions {
+            filters: vec![("team_id".to_string(), "team-1".to_string())],
+            page_size: 1,
+            ..ListOptions::default()
+        };
+
+        let page = workload_persistence.list_page(&options).await.unwrap();
+        assert_eq!(page.models.len(), 1);
+        assert_eq!(page.models[0].id, "a");
+
+        let options = ListOptions::parse::<Workload>(
+            1,
+            &page.next_page_token,
+            "",
+            &[("team_id", "team-1")],
+            &[],
+        )
+        .unwrap();
+
+        let page = workload_persistence.list_page(&options).await.unwrap();
+        assert_eq!(page.models.len(), 1);
+        assert_eq!(page.models[0].id, "c");
+        assert!(page.next_page_token.is_empty());
+
+        let options = ListOptions {
+            order_by: Some("name".to_string()),
+            descending: true,
+            ..ListOptions::default()
+        };
+
+        let page = workload_persistence.list_page(&options).await.unwrap();
+        let ids: Vec<&str> = page.models.iter().map(|model| model.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "b", "a"]);
     }
 }
@@ -5,13 +5,17 @@ use std::{
 };
 
 use crate::{
-    models::{Host, Target},
-    persistence::{HostPersistence, Persistable, Persistence},
+    models::{DeletedModel, Host, Target},
+    persistence::{
+        memory::{keep_deleted, list_page},
+        next_resource_version, HostPersistence, ListOptions, ListPage, Persistable, Persistence,
+    },
 };
 
 #[derive(Debug)]
 pub struct HostMemoryPersistence {
-    models: Arc<Mutex<HashMap<String, Host>>>,
+    pub models: Arc<Mutex<HashMap<String, Host>>>,
+    pub deleted_models: Arc<Mutex<Vec<DeletedModel>>>,
 }
 
 #[async_trait]
@@ -19,7 +23,18 @@ impl Persistence<Host> for HostMemoryPersistence {
     async fn upsert(&self, host: &Host) -> anyhow::Result<u64> {
         let mut locked_hosts = self.get_models_locked()?;
 
-        locked_hosts.insert(host.get_id(), host.clone());
+        let current_resource_version = locked_hosts
+            .get(&host.get_id())
+            .map(|current| current.get_resource_version());
+
+        let mut host = host.clone();
+        host.set_resource_version(next_resource_version(
+            &host.get_id(),
+            host.get_resource_version(),
+            current_resource_version,
+        )?);
+
+        locked_hosts.insert(host.get_id(), host);
 
         Ok(1)
     }
@@ -27,7 +42,9 @@ impl Persistence<Host> for HostMemoryPersistence {
     async fn delete(&self, host_id: &str) -> anyhow::Result<u64> {
         let mut locked_hosts = self.get_models_locked()?;
 
-        locked_hosts.remove_entry(&host_id.to_string());
+        if let Some(host) = locked_hosts.remove(host_id) {
+            keep_deleted(&self.deleted_models, &host)?;
+        }
 
         Ok(1)
     }
@@ -48,6 +65,10 @@ impl Persistence<Host> for HostMemoryPersistence {
 
         Ok(hosts)
     }
+
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Host>> {
+        list_page(self.list().await?, options)
+    }
 }
 
 #[async_trait]
@@ -77,6 +98,7 @@ impl Default for HostMemoryPersistence {
     fn default() -> Self {
         Self {
             models: Arc::new(Mutex::new(HashMap::new())),
+            deleted_models: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -1,13 +1,79 @@
 mod assignment;
+mod audit;
 mod config;
 mod deployment;
 mod generic;
 mod host;
+mod snapshot;
 mod workload;
 
 pub use assignment::AssignmentMemoryPersistence;
+pub use audit::AuditMemoryPersistence;
 pub use config::ConfigMemoryPersistence;
 pub use deployment::DeploymentMemoryPersistence;
 pub use generic::MemoryPersistence;
 pub use host::HostMemoryPersistence;
+pub use snapshot::{MemoryModels, MemorySnapshotter};
 pub use workload::WorkloadMemoryPersistence;
+
+use std::sync::Mutex;
+
+use super::{ListOptions, ListPage, Persistable};
+use crate::{models::DeletedModel, services::current_actor};
+
+// Keeps model, which has just been deleted, in deleted_models, as the relational persistences keep
+// deleted models in their deleted_models table.
+pub fn keep_deleted<Model: Persistable<Model>>(
+    deleted_models: &Mutex<Vec<DeletedModel>>,
+    model: &Model,
+) -> anyhow::Result<()> {
+    let deleted_model = DeletedModel::new(model, &current_actor())?;
+
+    match deleted_models.lock() {
+        Ok(mut locked_deleted_models) => locked_deleted_models.push(deleted_model),
+        Err(_) => return Err(anyhow::anyhow!("failed to acquire lock")),
+    }
+
+    Ok(())
+}
+
+// Filters, orders and pages models in the same way as relational persistence does in SQL. Fields
+// are compared as bytes, which both databases are also made to do, so eg. uppercase sorts before
+// lowercase.
+pub fn list_page<Model: Persistable<Model>>(
+    models: Vec<Model>,
+    options: &ListOptions,
+) -> anyhow::Result<ListPage<Model>> {
+    options.validate::<Model>()?;
+
+    let mut models: Vec<Model> = models
+        .into_iter()
+        .filter(|model| {
+            options
+                .filters
+                .iter()
+                .all(|(field, value)| model.get_field(field).as_ref() == Some(value))
+        })
+        .filter(|model| {
+            options
+                .labels
+                .iter()
+                .all(|label| model.get_labels().contains(label))
+        })
+        .collect();
+
+    let order_by = options.get_order_by();
+    models.sort_by_cached_key(|model| (model.get_field(order_by), model.get_id()));
+
+    if options.descending {
+        models.reverse();
+    }
+
+    let models = models
+        .into_iter()
+        .skip(options.offset)
+        .take(options.get_page_size() + 1)
+        .collect();
+
+    Ok(ListPage::new(models, options))
+}
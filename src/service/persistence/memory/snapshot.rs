@@ -0,0 +1,372 @@
+use fabriq_core::Snapshot;
+use prost::Message;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use crate::{
+    models::{
+        Assignment, AuditEntry, Config, DeletedModel, Deployment, Host, RoleBinding, Target,
+        Template, Workload,
+    },
+    persistence::{Persistable, Persistences},
+};
+
+use super::{
+    AssignmentMemoryPersistence, AuditMemoryPersistence, ConfigMemoryPersistence,
+    DeploymentMemoryPersistence, HostMemoryPersistence, MemoryPersistence,
+    WorkloadMemoryPersistence,
+};
+
+type Models<Model> = Arc<Mutex<HashMap<String, Model>>>;
+
+// The models held by every memory persistence. The persistences made from it share its maps, so
+// it sees every write made through them.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryModels {
+    pub assignments: Models<Assignment>,
+    pub audit_entries: Arc<Mutex<Vec<AuditEntry>>>,
+    pub configs: Models<Config>,
+    pub deleted_models: Arc<Mutex<Vec<DeletedModel>>>,
+    pub deployments: Models<Deployment>,
+    pub hosts: Models<Host>,
+    pub role_bindings: Models<RoleBinding>,
+    pub targets: Models<Target>,
+    pub templates: Models<Template>,
+    pub workloads: Models<Workload>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> anyhow::Result<MutexGuard<'_, T>> {
+    match mutex.lock() {
+        Ok(locked) => Ok(locked),
+        Err(_) => Err(anyhow::anyhow!("failed to acquire lock")),
+    }
+}
+
+// Sorted by id so that snapshots of the same models are identical.
+fn to_messages<Model, ModelMessage>(models: &Models<Model>) -> anyhow::Result<Vec<ModelMessage>>
+where
+    Model: Persistable<Model> + Into<ModelMessage>,
+{
+    let mut models: Vec<Model> = lock(models)?.values().cloned().collect();
+    models.sort_by_key(|model| model.get_id());
+
+    Ok(models.into_iter().map(Model::into).collect())
+}
+
+// Restored models keep the resource version they were snapshotted with.
+fn restore_models<Model, ModelMessage>(
+    models: &Models<Model>,
+    messages: Vec<ModelMessage>,
+) -> anyhow::Result<()>
+where
+    Model: Persistable<Model> + From<ModelMessage>,
+{
+    let mut locked_models = lock(models)?;
+
+    locked_models.clear();
+    for message in messages {
+        let model = Model::from(message);
+        locked_models.insert(model.get_id(), model);
+    }
+
+    Ok(())
+}
+
+impl MemoryModels {
+    pub fn make_persistences(&self) -> Persistences {
+        Persistences {
+            assignment: Box::new(AssignmentMemoryPersistence {
+                models: Arc::clone(&self.assignments),
+                deleted_models: Arc::clone(&self.deleted_models),
+            }),
+            audit: Box::new(AuditMemoryPersistence {
+                audit_entries: Arc::clone(&self.audit_entries),
+            }),
+            config: Box::new(ConfigMemoryPersistence {
+                models: Arc::clone(&self.configs),
+                deleted_models: Arc::clone(&self.deleted_models),
+            }),
+            deployment: Box::new(DeploymentMemoryPersistence {
+                models: Arc::clone(&self.deployments),
+                deleted_models: Arc::clone(&self.deleted_models),
+            }),
+            host: Box::new(HostMemoryPersistence {
+                models: Arc::clone(&self.hosts),
+                deleted_models: Arc::clone(&self.deleted_models),
+            }),
+            role_binding: Box::new(MemoryPersistence {
+                models: Arc::clone(&self.role_bindings),
+                deleted_models: Arc::clone(&self.deleted_models),
+            }),
+            target: Box::new(MemoryPersistence {
+                models: Arc::clone(&self.targets),
+                deleted_models: Arc::clone(&self.deleted_models),
+            }),
+            template: Box::new(MemoryPersistence {
+                models: Arc::clone(&self.templates),
+                deleted_models: Arc::clone(&self.deleted_models),
+            }),
+            workload: Box::new(WorkloadMemoryPersistence {
+                models: Arc::clone(&self.workloads),
+                deleted_models: Arc::clone(&self.deleted_models),
+            }),
+        }
+    }
+
+    pub fn to_snapshot(&self) -> anyhow::Result<Snapshot> {
+        Ok(Snapshot {
+            assignments: to_messages(&self.assignments)?,
+            audit_entries: lock(&self.audit_entries)?
+                .iter()
+                .cloned()
+                .map(AuditEntry::into)
+                .collect(),
+            configs: to_messages(&self.configs)?,
+            deleted_models: lock(&self.deleted_models)?
+                .iter()
+                .cloned()
+                .map(DeletedModel::into)
+                .collect(),
+            deployments: to_messages(&self.deployments)?,
+            hosts: to_messages(&self.hosts)?,
+            role_bindings: to_messages(&self.role_bindings)?,
+            targets: to_messages(&self.targets)?,
+            templates: to_messages(&self.templates)?,
+            workloads: to_messages(&self.workloads)?,
+        })
+    }
+
+    // Replaces every model with those in snapshot.
+    pub fn restore(&self, snapshot: Snapshot) -> anyhow::Result<()> {
+        *lock(&self.audit_entries)? = snapshot
+            .audit_entries
+            .into_iter()
+            .map(AuditEntry::try_from)
+            .collect::<anyhow::Result<_>>()?;
+
+        *lock(&self.deleted_models)? = snapshot
+            .deleted_models
+            .into_iter()
+            .map(DeletedModel::try_from)
+            .collect::<anyhow::Result<_>>()?;
+
+        restore_models(&self.assignments, snapshot.assignments)?;
+        restore_models(&self.configs, snapshot.configs)?;
+        restore_models(&self.deployments, snapshot.deployments)?;
+        restore_models(&self.hosts, snapshot.hosts)?;
+        restore_models(&self.role_bindings, snapshot.role_bindings)?;
+        restore_models(&self.targets, snapshot.targets)?;
+        restore_models(&self.templates, snapshot.templates)?;
+        restore_models(&self.workloads, snapshot.workloads)?;
+
+        Ok(())
+    }
+}
+
+// Saves models to path, and loads them back from it. Paths ending in .json are written as JSON,
+// anything else as protobuf.
+#[derive(Debug)]
+pub struct MemorySnapshotter {
+    pub models: MemoryModels,
+    pub path: PathBuf,
+}
+
+impl MemorySnapshotter {
+    fn is_json(&self) -> bool {
+        self.path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+    }
+
+    // Restores the models from the snapshot at path, if there is one.
+    pub async fn load(&self) -> anyhow::Result<()> {
+        let bytes = match tokio::fs::read(&self.path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                tracing::info!("no snapshot at {}, starting empty", self.path.display());
+
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let snapshot = if self.is_json() {
+            serde_json::from_slice(&bytes)?
+        } else {
+            Snapshot::decode(bytes.as_slice())?
+        };
+
+        self.models.restore(snapshot)?;
+
+        tracing::info!("restored snapshot from {}", self.path.display());
+
+        Ok(())
+    }
+
+    // Writes to a temporary file first so that a crash mid-write leaves the previous snapshot.
+    pub async fn save(&self) -> anyhow::Result<()> {
+        let snapshot = self.models.to_snapshot()?;
+
+        let bytes = if self.is_json() {
+            serde_json::to_vec_pretty(&snapshot)?
+        } else {
+            snapshot.encode_to_vec()
+        };
+
+        let mut temporary_path = self.path.clone().into_os_string();
+        temporary_path.push(".tmp");
+
+        tokio::fs::write(&temporary_path, bytes).await?;
+        tokio::fs::rename(&temporary_path, &self.path).await?;
+
+        tracing::debug!("saved snapshot to {}", self.path.display());
+
+        Ok(())
+    }
+
+    // Saves every interval until the api shuts down. A failed save (eg. a full disk) is logged
+    // rather than stopping the api, and the next interval tries again.
+    pub async fn run(&self, interval: Duration) -> anyhow::Result<()> {
+        loop {
+            tokio::time::sleep(interval).await;
+
+            if let Err(err) = self.save().await {
+                tracing::error!(
+                    "failed to save snapshot to {}: {}",
+                    self.path.display(),
+                    err
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{
+        create_event,
+        test::{get_deployment_fixture, get_host_fixture, get_workload_fixture},
+        EventType, ModelType, OperationId,
+    };
+
+    use super::*;
+
+    async fn assert_round_trip(file_name: &str) -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("{}-{file_name}", uuid::Uuid::new_v4()));
+
+        let models = MemoryModels::default();
+        let persistences = models.make_persistences();
+
+        let host: Host = get_host_fixture(None).into();
+        persistences.host.upsert(&host).await?;
+
+        let deleted_host: Host = get_host_fixture(Some("deleted-host")).into();
+        persistences.host.upsert(&deleted_host).await?;
+        persistences.host.delete(&deleted_host.id).await?;
+        persistences
+            .workload
+            .upsert(&get_workload_fixture(None).into())
+            .await?;
+        persistences
+            .deployment
+            .upsert(&get_deployment_fixture(None).into())
+            .await?;
+
+        let event = create_event(
+            &Some(get_host_fixture(None)),
+            &None,
+            EventType::Created,
+            ModelType::Host,
+            &OperationId::create(),
+        );
+        persistences
+            .audit
+            .insert(&AuditEntry::from_event(&event, "octocat")?)
+            .await?;
+
+        MemorySnapshotter {
+            models: models.clone(),
+            path: path.clone(),
+        }
+        .save()
+        .await?;
+
+        let restored_models = MemoryModels::default();
+        MemorySnapshotter {
+            models: restored_models.clone(),
+            path: path.clone(),
+        }
+        .load()
+        .await?;
+
+        tokio::fs::remove_file(&path).await?;
+
+        assert_eq!(restored_models.to_snapshot()?, models.to_snapshot()?);
+
+        let restored_host = restored_models
+            .make_persistences()
+            .host
+            .get_by_id(&host.id)
+            .await?
+            .unwrap();
+        assert_eq!(restored_host.resource_version, 1);
+
+        let restored_deleted_models = lock(&restored_models.deleted_models)?.clone();
+        assert_eq!(restored_deleted_models.len(), 1);
+        assert_eq!(restored_deleted_models[0].model_id, deleted_host.id);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_round_trip() -> anyhow::Result<()> {
+        assert_round_trip("snapshot.json").await
+    }
+
+    #[tokio::test]
+    async fn test_protobuf_round_trip() -> anyhow::Result<()> {
+        assert_round_trip("snapshot.pb").await
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_snapshot() -> anyhow::Result<()> {
+        let models = MemoryModels::default();
+
+        MemorySnapshotter {
+            models: models.clone(),
+            path: std::env::temp_dir().join("fabriq-missing-snapshot.json"),
+        }
+        .load()
+        .await?;
+
+        assert!(models.to_snapshot()?.hosts.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_continues_after_failed_save() -> anyhow::Result<()> {
+        let snapshotter = MemorySnapshotter {
+            models: MemoryModels::default(),
+            path: std::env::temp_dir()
+                .join(uuid::Uuid::new_v4().to_string())
+                .join("snapshot.json"),
+        };
+
+        assert!(snapshotter.save().await.is_err());
+
+        // every save fails, as the directory doesn't exist, but run keeps going.
+        let result = tokio::time::timeout(
+            Duration::from_millis(50),
+            snapshotter.run(Duration::from_millis(5)),
+        )
+        .await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
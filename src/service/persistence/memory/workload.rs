@@ -5,13 +5,18 @@ use std::{
 };
 
 use crate::{
-    models::Workload,
-    persistence::{Persistable, Persistence, WorkloadPersistence},
+    models::{DeletedModel, Workload},
+    persistence::{
+        memory::{keep_deleted, list_page},
+        next_resource_version, ListOptions, ListPage, Persistable, Persistence,
+        WorkloadPersistence,
+    },
 };
 
 #[derive(Debug)]
 pub struct WorkloadMemoryPersistence {
-    models: Arc<Mutex<HashMap<String, Workload>>>,
+    pub models: Arc<Mutex<HashMap<String, Workload>>>,
+    pub deleted_models: Arc<Mutex<Vec<DeletedModel>>>,
 }
 
 #[async_trait]
@@ -19,7 +24,18 @@ impl Persistence<Workload> for WorkloadMemoryPersistence {
     async fn upsert(&self, workload: &Workload) -> anyhow::Result<u64> {
         let mut locked_workloads = self.get_models_locked()?;
 
-        locked_workloads.insert(workload.get_id(), workload.clone());
+        let current_resource_version = locked_workloads
+            .get(&workload.get_id())
+            .map(|current| current.get_resource_version());
+
+        let mut workload = workload.clone();
+        workload.set_resource_version(next_resource_version(
+            &workload.get_id(),
+            workload.get_resource_version(),
+            current_resource_version,
+        )?);
+
+        locked_workloads.insert(workload.get_id(), workload);
 
         Ok(1)
     }
@@ -27,7 +43,9 @@ impl Persistence<Workload> for WorkloadMemoryPersistence {
     async fn delete(&self, workload_id: &str) -> anyhow::Result<u64> {
         let mut locked_workloads = self.get_models_locked()?;
 
-        locked_workloads.remove_entry(&workload_id.to_string());
+        if let Some(workload) = locked_workloads.remove(workload_id) {
+            keep_deleted(&self.deleted_models, &workload)?;
+        }
 
         Ok(1)
     }
@@ -48,6 +66,10 @@ impl Persistence<Workload> for WorkloadMemoryPersistence {
 
         Ok(workloads)
     }
+
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Workload>> {
+        list_page(self.list().await?, options)
+    }
 }
 
 #[async_trait]
@@ -79,6 +101,7 @@ impl Default for WorkloadMemoryPersistence {
     fn default() -> Self {
         Self {
             models: Arc::new(Mutex::new(HashMap::new())),
+            deleted_models: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
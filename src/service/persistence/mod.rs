@@ -1,10 +1,16 @@
 use async_trait::async_trait;
-use std::fmt::Debug;
+use fabriq_core::ModelType;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::fmt::{self, Debug};
 
-use crate::models::{Assignment, Config, Deployment, Host, Target, Workload};
+use crate::models::{
+    Assignment, AuditEntry, Config, Deployment, Host, RoleBinding, Target, Template, Workload,
+};
 
+pub mod cache;
 pub mod memory;
 pub mod relational;
+pub mod sqlite;
 
 #[async_trait]
 pub trait Persistence<Model>: Debug + Send + Sync {
@@ -12,10 +18,253 @@ pub trait Persistence<Model>: Debug + Send + Sync {
     async fn delete(&self, model_id: &str) -> anyhow::Result<u64>;
     async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Model>>;
     async fn list(&self) -> anyhow::Result<Vec<Model>>;
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Model>>;
 }
 
 pub trait Persistable<Model>: Clone + Debug + Send + Sync {
+    // Fields that list_page can filter and order by. Only string fields are listed so that
+    // ordering is the same in every persistence.
+    const LIST_FIELDS: &'static [&'static str];
+
+    // Whether the model has labels that list_page can filter on.
+    const LABELLED: bool = false;
+
+    // The type that the model is kept as when it's deleted.
+    const MODEL_TYPE: ModelType;
+
     fn get_id(&self) -> String;
+    fn get_resource_version(&self) -> i64;
+    fn set_resource_version(&mut self, resource_version: i64);
+    fn get_field(&self, field: &str) -> Option<String>;
+
+    // The JSON form of the model's message, as kept when it's deleted and in audit entries.
+    fn to_json(&self) -> anyhow::Result<serde_json::Value>;
+
+    // Records actor as changing the model now, carrying over when and by whom previous, the
+    // currently stored model if any, was created. Models without change metadata ignore this.
+    fn set_changed(&mut self, _actor: &str, _previous: Option<&Model>) {}
+
+    fn get_labels(&self) -> &[String] {
+        &[]
+    }
+}
+
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+const PAGE_TOKEN_PREFIX: &str = "offset:";
+
+// Filtering, ordering and paging for list_page. Models are always ordered by id after order_by so
+// that pages are stable.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListOptions {
+    // (field, value) pairs that a model must all match.
+    pub filters: Vec<(String, String)>,
+
+    // labels that a model must all have.
+    pub labels: Vec<String>,
+
+    pub order_by: Option<String>,
+    pub descending: bool,
+
+    // 0 uses DEFAULT_PAGE_SIZE.
+    pub page_size: usize,
+    pub offset: usize,
+}
+
+impl ListOptions {
+    // Builds list options from the fields shared by the List RPCs. order_by is a field name
+    // optionally followed by asc or desc, and filters with an empty value are ignored.
+    pub fn parse<Model: Persistable<Model>>(
+        page_size: i32,
+        page_token: &str,
+        order_by: &str,
+        filters: &[(&str, &str)],
+        labels: &[String],
+    ) -> anyhow::Result<Self> {
+        let page_size = match usize::try_from(page_size) {
+            Ok(page_size) => page_size,
+            Err(_) => return Err(anyhow::anyhow!("page size {page_size} is negative")),
+        };
+
+        let offset = if page_token.is_empty() {
+            0
+        } else {
+            decode_page_token(page_token)?
+        };
+
+        let mut order_by_parts = order_by.split_whitespace();
+        let order_field = order_by_parts.next().map(|field| field.to_string());
+        let descending = match order_by_parts
+            .next()
+            .map(|direction| direction.to_lowercase())
+        {
+            None => false,
+            Some(direction) if direction == "asc" => false,
+            Some(direction) if direction == "desc" => true,
+            Some(direction) => {
+                return Err(anyhow::anyhow!(
+                    "order direction {direction} must be asc or desc"
+                ))
+            }
+        };
+
+        if order_by_parts.next().is_some() {
+            return Err(anyhow::anyhow!(
+                "order by '{order_by}' must be a field optionally followed by asc or desc"
+            ));
+        }
+
+        let options = ListOptions {
+            filters: filters
+                .iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(field, value)| (field.to_string(), value.to_string()))
+                .collect(),
+            labels: labels.to_vec(),
+            order_by: order_field,
+            descending,
+            page_size,
+            offset,
+        };
+
+        options.validate::<Model>()?;
+
+        Ok(options)
+    }
+
+    // Relational persistence interpolates field names into SQL, so this must be checked before
+    // every query.
+    pub fn validate<Model: Persistable<Model>>(&self) -> anyhow::Result<()> {
+        let fields = self
+            .filters
+            .iter()
+            .map(|(field, _)| field)
+            .chain(self.order_by.iter());
+
+        for field in fields {
+            if !Model::LIST_FIELDS.contains(&field.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "unknown field {field}, expected one of {}",
+                    Model::LIST_FIELDS.join(", ")
+                ));
+            }
+        }
+
+        if !self.labels.is_empty() && !Model::LABELLED {
+            return Err(anyhow::anyhow!("model does not have labels to filter on"));
+        }
+
+        if self.page_size > MAX_PAGE_SIZE {
+            return Err(anyhow::anyhow!(
+                "page size {} exceeds the maximum of {MAX_PAGE_SIZE}",
+                self.page_size
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn get_page_size(&self) -> usize {
+        if self.page_size == 0 {
+            DEFAULT_PAGE_SIZE
+        } else {
+            self.page_size
+        }
+    }
+
+    pub fn get_order_by(&self) -> &str {
+        self.order_by.as_deref().unwrap_or("id")
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ListPage<Model> {
+    pub models: Vec<Model>,
+
+    // empty on the last page.
+    pub next_page_token: String,
+}
+
+impl<Model> ListPage<Model> {
+    // Persistences fetch one model more than the page size so that they can tell whether there
+    // is a next page.
+    pub fn new(mut models: Vec<Model>, options: &ListOptions) -> Self {
+        let page_size = options.get_page_size();
+
+        let next_page_token = if models.len() > page_size {
+            models.truncate(page_size);
+            encode_page_token(options.offset + page_size)
+        } else {
+            "".to_string()
+        };
+
+        ListPage {
+            models,
+            next_page_token,
+        }
+    }
+}
+
+fn encode_page_token(offset: usize) -> String {
+    base64::encode(format!("{PAGE_TOKEN_PREFIX}{offset}"))
+}
+
+fn decode_page_token(page_token: &str) -> anyhow::Result<usize> {
+    let invalid_page_token = || anyhow::anyhow!("invalid page token {page_token}");
+
+    let decoded = base64::decode(page_token).map_err(|_| invalid_page_token())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid_page_token())?;
+
+    decoded
+        .strip_prefix(PAGE_TOKEN_PREFIX)
+        .and_then(|offset| offset.parse().ok())
+        .ok_or_else(invalid_page_token)
+}
+
+// Returned by upsert when the model carries a resource version that no longer matches the stored
+// model, ie. someone else has modified it since it was read. A resource version of 0 skips the
+// check and always writes.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ResourceVersionConflict {
+    pub model_id: String,
+    pub resource_version: i64,
+}
+
+impl fmt::Display for ResourceVersionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} has been modified since resource version {}",
+            self.model_id, self.resource_version
+        )
+    }
+}
+
+impl std::error::Error for ResourceVersionConflict {}
+
+// Computes the resource version to store for an upsert given the currently stored version, if
+// any. A non-zero resource_version for a model that isn't stored means it was deleted since it
+// was read, so is a conflict rather than recreating it. Relational persistence does the
+// equivalent in SQL.
+pub fn next_resource_version(
+    model_id: &str,
+    resource_version: i64,
+    current_resource_version: Option<i64>,
+) -> anyhow::Result<i64> {
+    match current_resource_version {
+        None if resource_version == 0 => Ok(1),
+        Some(current_resource_version)
+            if resource_version == 0 || resource_version == current_resource_version =>
+        {
+            Ok(current_resource_version + 1)
+        }
+        _ => Err(ResourceVersionConflict {
+            model_id: model_id.to_string(),
+            resource_version,
+        }
+        .into()),
+    }
 }
 
 #[async_trait]
@@ -23,9 +272,27 @@ pub trait AssignmentPersistence: Debug + Send + Sync + Persistence<Assignment> {
     async fn get_by_deployment_id(&self, id: &str) -> anyhow::Result<Vec<Assignment>>;
 }
 
+// Audit entries matching every set filter, newest first. since is inclusive and until exclusive.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuditQuery {
+    pub model_type: Option<String>,
+    pub model_id: Option<String>,
+    pub actor: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: usize,
+}
+
+#[async_trait]
+pub trait AuditPersistence: Debug + Send + Sync {
+    async fn insert(&self, audit_entry: &AuditEntry) -> anyhow::Result<u64>;
+    async fn query(&self, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>>;
+}
+
 #[async_trait]
 pub trait ConfigPersistence: Debug + Send + Sync + Persistence<Config> {
     async fn get_by_deployment_id(&self, deployment_id: &str) -> anyhow::Result<Vec<Config>>;
+    async fn get_by_host_id(&self, host_id: &str) -> anyhow::Result<Vec<Config>>;
     async fn get_by_template_id(&self, template_id: &str) -> anyhow::Result<Vec<Config>>;
     async fn get_by_workload_id(&self, workload_id: &str) -> anyhow::Result<Vec<Config>>;
 }
@@ -51,3 +318,72 @@ pub trait TargetPersistence: Debug + Send + Sync + Persistence<Target> {
 pub trait WorkloadPersistence: Send + Sync + Persistence<Workload> {
     async fn get_by_template_id(&self, id: &str) -> anyhow::Result<Vec<Workload>>;
 }
+
+// The persistence of every model, all backed by the same database.
+#[derive(Debug)]
+pub struct Persistences {
+    pub assignment: Box<dyn AssignmentPersistence>,
+    pub audit: Box<dyn AuditPersistence>,
+    pub config: Box<dyn ConfigPersistence>,
+    pub deployment: Box<dyn DeploymentPersistence>,
+    pub host: Box<dyn HostPersistence>,
+    pub role_binding: Box<dyn Persistence<RoleBinding>>,
+    pub target: Box<dyn Persistence<Target>>,
+    pub template: Box<dyn Persistence<Template>>,
+    pub workload: Box<dyn WorkloadPersistence>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Config, Host, Workload};
+
+    #[test]
+    fn test_parse_list_options() {
+        let options = ListOptions::parse::<Workload>(
+            10,
+            "",
+            "name desc",
+            &[("team_id", "fabriq-cloud/fabriq"), ("template_id", "")],
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            options,
+            ListOptions {
+                filters: vec![("team_id".to_string(), "fabriq-cloud/fabriq".to_string())],
+                labels: vec![],
+                order_by: Some("name".to_string()),
+                descending: true,
+                page_size: 10,
+                offset: 0,
+            }
+        );
+
+        assert!(ListOptions::parse::<Workload>(0, "", "name; DROP TABLE", &[], &[]).is_err());
+        assert!(ListOptions::parse::<Workload>(0, "", "labels", &[], &[]).is_err());
+        assert!(ListOptions::parse::<Config>(0, "", "", &[], &["a:b".to_string()]).is_err());
+        assert!(ListOptions::parse::<Workload>(0, "", "", &[], &["a:b".to_string()]).is_ok());
+        assert!(ListOptions::parse::<Host>(0, "", "", &[], &["a:b".to_string()]).is_ok());
+        assert!(ListOptions::parse::<Host>(0, "not-a-token", "", &[], &[]).is_err());
+        assert!(ListOptions::parse::<Host>(-1, "", "", &[], &[]).is_err());
+        assert!(ListOptions::parse::<Host>(5000, "", "", &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_list_page() {
+        let options = ListOptions {
+            page_size: 2,
+            offset: 4,
+            ..ListOptions::default()
+        };
+
+        let page = ListPage::new(vec![1, 2, 3], &options);
+        assert_eq!(page.models, vec![1, 2]);
+        assert_eq!(decode_page_token(&page.next_page_token).unwrap(), 6);
+
+        let page = ListPage::new(vec![1, 2], &options);
+        assert!(page.next_page_token.is_empty());
+    }
+}
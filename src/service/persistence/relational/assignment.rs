@@ -3,7 +3,10 @@ use sqlx::PgPool;
 use std::sync::Arc;
 
 use crate::models::Assignment;
-use crate::persistence::{AssignmentPersistence, Persistence};
+use crate::persistence::{
+    relational::{keep_deleted, list_page},
+    AssignmentPersistence, ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
 
 #[derive(Debug)]
 pub struct AssignmentRelationalPersistence {
@@ -17,37 +20,64 @@ impl Persistence<Assignment> for AssignmentRelationalPersistence {
         let result = sqlx::query!(
             r#"
             INSERT INTO assignments
-               (id, deployment_id, host_id)
-            VALUES
-               ($1, $2, $3)
+               (id, deployment_id, host_id, resource_version,
+                created_at, created_by, updated_at, updated_by)
+            SELECT
+               $1, $2, $3, 1, $5, $6, $7, $8
+            WHERE
+               $4::BIGINT = 0 OR EXISTS (SELECT 1 FROM assignments WHERE id = $1 FOR UPDATE)
             ON CONFLICT (id) DO UPDATE SET
                deployment_id = $2,
-               host_id = $3
+               host_id = $3,
+               updated_at = $7,
+               updated_by = $8,
+               resource_version = assignments.resource_version + 1
+            WHERE
+               $4::BIGINT = 0 OR assignments.resource_version = $4
             "#,
             assignment.id,
             assignment.deployment_id,
-            assignment.host_id
+            assignment.host_id,
+            assignment.resource_version,
+            assignment.created_at,
+            assignment.created_by,
+            assignment.updated_at,
+            assignment.updated_by
         )
         .execute(&*self.db)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: assignment.id.clone(),
+                resource_version: assignment.resource_version,
+            }
+            .into());
+        }
+
         Ok(result.rows_affected())
     }
 
     #[tracing::instrument(name = "relational::assignment::delete", skip_all)]
     async fn delete(&self, id: &str) -> anyhow::Result<u64> {
-        let result = sqlx::query!(
+        let mut transaction = self.db.begin().await?;
+
+        let assignment = sqlx::query_as!(
+            Assignment,
             // language=PostgreSQL
             r#"
                 DELETE FROM assignments WHERE id = $1
+                RETURNING *
             "#,
             id
         )
-        .bind(id)
-        .execute(&*self.db)
+        .fetch_optional(&mut transaction)
         .await?;
 
-        Ok(result.rows_affected())
+        let deleted_count = keep_deleted(&mut transaction, assignment.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
     }
 
     #[tracing::instrument(name = "relational::assignment::get_by_id", skip_all)]
@@ -77,6 +107,11 @@ impl Persistence<Assignment> for AssignmentRelationalPersistence {
 
         Ok(models)
     }
+
+    #[tracing::instrument(name = "relational::assignment::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Assignment>> {
+        list_page(&self.db, "assignments", options).await
+    }
 }
 
 #[async_trait]
@@ -100,6 +135,7 @@ impl AssignmentPersistence for AssignmentRelationalPersistence {
         Ok(models)
     }
 }
+
 #[cfg(test)]
 mod tests {
     use fabriq_core::test::get_assignment_fixture;
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use crate::{
+    models::AuditEntry,
+    persistence::{AuditPersistence, AuditQuery},
+};
+
+#[derive(Debug)]
+pub struct AuditRelationalPersistence {
+    pub db: Arc<PgPool>,
+}
+
+#[async_trait]
+impl AuditPersistence for AuditRelationalPersistence {
+    #[tracing::instrument(name = "relational::audit::insert", skip_all)]
+    async fn insert(&self, audit_entry: &AuditEntry) -> anyhow::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO audit_entries
+               (id, actor, recorded_at, operation_id, event_type, model_type, model_id,
+                previous_model, current_model)
+            VALUES
+               ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+            audit_entry.id,
+            audit_entry.actor,
+            audit_entry.recorded_at,
+            audit_entry.operation_id,
+            audit_entry.event_type,
+            audit_entry.model_type,
+            audit_entry.model_id,
+            audit_entry.previous_model,
+            audit_entry.current_model
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "relational::audit::query", skip_all)]
+    async fn query(&self, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>> {
+        let rows = sqlx::query_as!(
+            AuditEntry,
+            r#"
+                SELECT * FROM audit_entries
+                WHERE
+                    ($1::TEXT IS NULL OR model_type = $1) AND
+                    ($2::TEXT IS NULL OR model_id = $2) AND
+                    ($3::TEXT IS NULL OR actor = $3) AND
+                    ($4::TIMESTAMPTZ IS NULL OR recorded_at >= $4) AND
+                    ($5::TIMESTAMPTZ IS NULL OR recorded_at < $5)
+                ORDER BY recorded_at DESC, id DESC
+                LIMIT $6
+            "#,
+            query.model_type,
+            query.model_id,
+            query.actor,
+            query.since,
+            query.until,
+            query.limit as i64
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{create_event, test::get_host_fixture, EventType, ModelType, OperationId};
+    use sqlx::types::chrono::{DateTime, Utc};
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::persistence::relational::tests::ensure_fixtures;
+
+    #[tokio::test]
+    async fn test_insert_query() -> anyhow::Result<()> {
+        dotenvy::from_filename(".env.test").ok();
+        let db = ensure_fixtures().await;
+
+        let audit_persistence = AuditRelationalPersistence { db };
+
+        let host = get_host_fixture(Some("audited-host"));
+        let event = create_event(
+            &Some(host.clone()),
+            &None,
+            EventType::Deleted,
+            ModelType::Host,
+            &OperationId::create(),
+        );
+        let audit_entry = AuditEntry::from_event(&event, "octocat")?;
+
+        // the database stores microseconds, so compare against a time safely before the entry.
+        let before_entry = DateTime::<Utc>::from(
+            SystemTime::from(audit_entry.recorded_at) - Duration::from_secs(1),
+        );
+
+        let inserted_count = audit_persistence.insert(&audit_entry).await?;
+        assert_eq!(inserted_count, 1);
+
+        let audit_entries = audit_persistence
+            .query(&AuditQuery {
+                model_type: Some("host".to_string()),
+                model_id: Some(host.id.clone()),
+                actor: Some("octocat".to_string()),
+                since: Some(before_entry),
+                limit: 10,
+                ..AuditQuery::default()
+            })
+            .await?;
+
+        assert!(audit_entries
+            .iter()
+            .any(|fetched_entry| fetched_entry.id == audit_entry.id
+                && fetched_entry.previous_model == audit_entry.previous_model));
+
+        let audit_entries = audit_persistence
+            .query(&AuditQuery {
+                model_id: Some(host.id),
+                until: Some(before_entry),
+                limit: 10,
+                ..AuditQuery::default()
+            })
+            .await?;
+
+        assert!(audit_entries
+            .iter()
+            .all(|fetched_entry| fetched_entry.id != audit_entry.id));
+
+        Ok(())
+    }
+}
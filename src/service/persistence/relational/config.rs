@@ -4,7 +4,10 @@ use sqlx::PgPool;
 use std::sync::Arc;
 
 use crate::models::Config;
-use crate::persistence::{ConfigPersistence, Persistence};
+use crate::persistence::{
+    relational::{keep_deleted, list_page},
+    ConfigPersistence, ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
 
 #[derive(Debug)]
 pub struct ConfigRelationalPersistence {
@@ -18,41 +21,68 @@ impl Persistence<Config> for ConfigRelationalPersistence {
         let result = sqlx::query!(
             r#"
             INSERT INTO configs
-               (id, owning_model, key, value, value_type)
-            VALUES
-               ($1, $2, $3, $4, $5)
+               (id, owning_model, key, value, value_type, resource_version,
+                created_at, created_by, updated_at, updated_by)
+            SELECT
+               $1, $2, $3, $4, $5, 1, $7, $8, $9, $10
+            WHERE
+               $6::BIGINT = 0 OR EXISTS (SELECT 1 FROM configs WHERE id = $1 FOR UPDATE)
             ON CONFLICT (id) DO UPDATE SET
                owning_model = $2,
                key = $3,
                value = $4,
-               value_type = $5
+               value_type = $5,
+               updated_at = $9,
+               updated_by = $10,
+               resource_version = configs.resource_version + 1
+            WHERE
+               $6::BIGINT = 0 OR configs.resource_version = $6
             "#,
             config.id,
             config.owning_model,
             config.key,
             config.value,
-            config.value_type
+            config.value_type,
+            config.resource_version,
+            config.created_at,
+            config.created_by,
+            config.updated_at,
+            config.updated_by
         )
         .execute(&*self.db)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: config.id.clone(),
+                resource_version: config.resource_version,
+            }
+            .into());
+        }
+
         Ok(result.rows_affected())
     }
 
     #[tracing::instrument(name = "relational::config::delete", skip_all)]
     async fn delete(&self, id: &str) -> anyhow::Result<u64> {
-        let result = sqlx::query!(
+        let mut transaction = self.db.begin().await?;
+
+        let config = sqlx::query_as!(
+            Config,
             // language=PostgreSQL
             r#"
                 DELETE FROM configs WHERE id = $1
+                RETURNING *
             "#,
             id
         )
-        .bind(id)
-        .execute(&*self.db)
+        .fetch_optional(&mut transaction)
         .await?;
 
-        Ok(result.rows_affected())
+        let deleted_count = keep_deleted(&mut transaction, config.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
     }
 
     #[tracing::instrument(name = "relational::config::list", skip_all)]
@@ -71,6 +101,11 @@ impl Persistence<Config> for ConfigRelationalPersistence {
         Ok(models)
     }
 
+    #[tracing::instrument(name = "relational::config::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Config>> {
+        list_page(&self.db, "configs", options).await
+    }
+
     #[tracing::instrument(name = "relational::config::get_by_id", skip_all)]
     async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Config>> {
         let supply = sqlx::query_as!(Config, "SELECT * FROM configs WHERE id = $1", id)
@@ -110,6 +145,13 @@ impl ConfigPersistence for ConfigRelationalPersistence {
         self.get_owning_model(&query_owning_model).await
     }
 
+    #[tracing::instrument(name = "relational::config::get_by_host_id", skip_all)]
+    async fn get_by_host_id(&self, query_host_id: &str) -> anyhow::Result<Vec<Config>> {
+        let query_owning_model = ConfigMessage::make_owning_model("host", query_host_id)?;
+
+        self.get_owning_model(&query_owning_model).await
+    }
+
     #[tracing::instrument(name = "relational::config::get_by_template_id", skip_all)]
     async fn get_by_template_id(&self, query_template_id: &str) -> anyhow::Result<Vec<Config>> {
         let query_owning_model = ConfigMessage::make_owning_model("template", query_template_id)?;
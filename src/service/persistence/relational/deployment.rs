@@ -3,7 +3,10 @@ use sqlx::PgPool;
 use std::sync::Arc;
 
 use crate::models::Deployment;
-use crate::persistence::{DeploymentPersistence, Persistence};
+use crate::persistence::{
+    relational::{keep_deleted, list_page},
+    DeploymentPersistence, ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
 
 #[derive(Debug)]
 pub struct DeploymentRelationalPersistence {
@@ -17,43 +20,76 @@ impl Persistence<Deployment> for DeploymentRelationalPersistence {
         let result = sqlx::query!(
             r#"
             INSERT INTO deployments
-               (id, name, workload_id, target_id, template_id, host_count)
-            VALUES
-               ($1, $2, $3, $4, $5, $6)
+               (id, name, workload_id, target_id, template_id, host_count, resource_version,
+                created_at, created_by, updated_at, updated_by, labels, annotations)
+            SELECT
+               $1, $2, $3, $4, $5, $6, 1, $8, $9, $10, $11, $12, $13
+            WHERE
+               $7::BIGINT = 0 OR EXISTS (SELECT 1 FROM deployments WHERE id = $1 FOR UPDATE)
             ON CONFLICT (id) DO UPDATE SET
                name = $2,
                workload_id = $3,
                target_id = $4,
                template_id = $5,
-               host_count = $6
+               host_count = $6,
+               labels = $12,
+               annotations = $13,
+               updated_at = $10,
+               updated_by = $11,
+               resource_version = deployments.resource_version + 1
+            WHERE
+               $7::BIGINT = 0 OR deployments.resource_version = $7
             "#,
             deployment.id,
             deployment.name,
             deployment.workload_id,
             deployment.target_id,
             deployment.template_id,
-            deployment.host_count
+            deployment.host_count,
+            deployment.resource_version,
+            deployment.created_at,
+            deployment.created_by,
+            deployment.updated_at,
+            deployment.updated_by,
+            &deployment.labels,
+            &deployment.annotations as _
         )
         .execute(&*self.db)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: deployment.id.clone(),
+                resource_version: deployment.resource_version,
+            }
+            .into());
+        }
+
         Ok(result.rows_affected())
     }
 
     #[tracing::instrument(name = "relational::deployment::delete", skip_all)]
     async fn delete(&self, id: &str) -> anyhow::Result<u64> {
-        let result = sqlx::query!(
+        let mut transaction = self.db.begin().await?;
+
+        let deployment = sqlx::query_as!(
+            Deployment,
             // language=PostgreSQL
             r#"
                 DELETE FROM deployments WHERE id = $1
+                RETURNING id, name, workload_id, target_id, template_id, host_count, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
             "#,
             id
         )
-        .bind(id)
-        .execute(&*self.db)
+        .fetch_optional(&mut transaction)
         .await?;
 
-        Ok(result.rows_affected())
+        let deleted_count = keep_deleted(&mut transaction, deployment.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
     }
 
     #[tracing::instrument(name = "relational::deployment::list", skip_all)]
@@ -61,7 +97,10 @@ impl Persistence<Deployment> for DeploymentRelationalPersistence {
         let rows = sqlx::query_as!(
             Deployment,
             r#"
-                SELECT * FROM deployments
+                SELECT id, name, workload_id, target_id, template_id, host_count, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM deployments
             "#,
         )
         .fetch_all(&*self.db)
@@ -75,11 +114,25 @@ impl Persistence<Deployment> for DeploymentRelationalPersistence {
         Ok(models)
     }
 
+    #[tracing::instrument(name = "relational::deployment::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Deployment>> {
+        list_page(&self.db, "deployments", options).await
+    }
+
     #[tracing::instrument(name = "relational::deployment::get_by_id", skip_all)]
     async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Deployment>> {
-        let supply = sqlx::query_as!(Deployment, "SELECT * FROM deployments WHERE id = $1", id)
-            .fetch_optional(&*self.db)
-            .await?;
+        let supply = sqlx::query_as!(
+            Deployment,
+            r#"
+                SELECT id, name, workload_id, target_id, template_id, host_count, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM deployments WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&*self.db)
+        .await?;
 
         Ok(supply)
     }
@@ -92,7 +145,10 @@ impl DeploymentPersistence for DeploymentRelationalPersistence {
         let rows = sqlx::query_as!(
             Deployment,
             r#"
-                SELECT * FROM deployments WHERE target_id = $1
+                SELECT id, name, workload_id, target_id, template_id, host_count, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM deployments WHERE target_id = $1
             "#,
             target_id
         )
@@ -112,7 +168,10 @@ impl DeploymentPersistence for DeploymentRelationalPersistence {
         let rows = sqlx::query_as!(
             Deployment,
             r#"
-                SELECT * FROM deployments WHERE template_id = $1
+                SELECT id, name, workload_id, target_id, template_id, host_count, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM deployments WHERE template_id = $1
             "#,
             template_id
         )
@@ -132,7 +191,10 @@ impl DeploymentPersistence for DeploymentRelationalPersistence {
         let rows = sqlx::query_as!(
             Deployment,
             r#"
-                SELECT * FROM deployments WHERE workload_id = $1
+                SELECT id, name, workload_id, target_id, template_id, host_count, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM deployments WHERE workload_id = $1
             "#,
             workload_id
         )
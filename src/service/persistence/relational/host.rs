@@ -5,7 +5,10 @@ use sqlx::PgPool;
 
 use crate::{
     models::{Host, Target},
-    persistence::{HostPersistence, Persistence},
+    persistence::{
+        relational::{keep_deleted, list_page},
+        HostPersistence, ListOptions, ListPage, Persistence, ResourceVersionConflict,
+    },
 };
 
 #[derive(Debug)]
@@ -20,35 +23,62 @@ impl Persistence<Host> for HostRelationalPersistence {
         let result = sqlx::query!(
             r#"
             INSERT INTO hosts
-               (id, labels)
-            VALUES
-               ($1, $2)
+               (id, labels, resource_version,
+                created_at, created_by, updated_at, updated_by)
+            SELECT
+               $1, $2, 1, $4, $5, $6, $7
+            WHERE
+               $3::BIGINT = 0 OR EXISTS (SELECT 1 FROM hosts WHERE id = $1 FOR UPDATE)
             ON CONFLICT (id) DO UPDATE SET
-               labels = $2
+               labels = $2,
+               updated_at = $6,
+               updated_by = $7,
+               resource_version = hosts.resource_version + 1
+            WHERE
+               $3::BIGINT = 0 OR hosts.resource_version = $3
             "#,
             host.id,
-            &host.labels
+            &host.labels,
+            host.resource_version,
+            host.created_at,
+            host.created_by,
+            host.updated_at,
+            host.updated_by
         )
         .execute(&*self.db)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: host.id.clone(),
+                resource_version: host.resource_version,
+            }
+            .into());
+        }
+
         Ok(result.rows_affected())
     }
 
     #[tracing::instrument(name = "relational::host::delete", skip_all)]
     async fn delete(&self, id: &str) -> anyhow::Result<u64> {
-        let result = sqlx::query!(
+        let mut transaction = self.db.begin().await?;
+
+        let host = sqlx::query_as!(
+            Host,
             // language=PostgreSQL
             r#"
                 DELETE FROM hosts WHERE id = $1
+                RETURNING *
             "#,
             id
         )
-        .bind(id)
-        .execute(&*self.db)
+        .fetch_optional(&mut transaction)
         .await?;
 
-        Ok(result.rows_affected())
+        let deleted_count = keep_deleted(&mut transaction, host.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
     }
 
     #[tracing::instrument(name = "relational::host::list", skip_all)]
@@ -67,6 +97,11 @@ impl Persistence<Host> for HostRelationalPersistence {
         Ok(models)
     }
 
+    #[tracing::instrument(name = "relational::host::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Host>> {
+        list_page(&self.db, "hosts", options).await
+    }
+
     #[tracing::instrument(name = "relational::host::get_by_id", skip_all)]
     async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Host>> {
         let supply = sqlx::query_as!(Host, "SELECT * FROM hosts WHERE id = $1", id)
@@ -103,7 +138,9 @@ mod tests {
     use fabriq_core::test::{get_host_fixture, get_target_fixture};
 
     use super::*;
-    use crate::persistence::relational::tests::ensure_fixtures;
+    use crate::{
+        models::DeletedModel, persistence::relational::tests::ensure_fixtures, services::with_actor,
+    };
 
     #[tokio::test]
     async fn test_create_delete() {
@@ -121,8 +158,25 @@ mod tests {
         let fetched_host = host_persistence.get_by_id(&host.id).await.unwrap().unwrap();
         assert_eq!(fetched_host.id, host.id);
 
-        let deleted_hosts = host_persistence.delete(&host.id).await.unwrap();
+        let deleted_hosts = with_actor("octocat".to_string(), host_persistence.delete(&host.id))
+            .await
+            .unwrap();
         assert_eq!(deleted_hosts, 1);
+
+        let deleted_host = sqlx::query_as::<_, DeletedModel>(
+            r#"
+                SELECT * FROM deleted_models
+                WHERE model_type = 'host' AND model_id = $1
+                ORDER BY deleted_at DESC
+                LIMIT 1
+            "#,
+        )
+        .bind(&host.id)
+        .fetch_one(&*host_persistence.db)
+        .await
+        .unwrap();
+        assert_eq!(deleted_host.deleted_by, "octocat");
+        assert_eq!(deleted_host.model["id"], host.id);
     }
 
     #[tokio::test]
@@ -156,6 +210,7 @@ mod tests {
         let non_matching_target = Target {
             id: "target-hawaii".to_owned(),
             labels: vec!["region:hawaii5".to_string()],
+            ..Default::default()
         };
 
         let non_matching_hosts = host_persistence
@@ -165,4 +220,52 @@ mod tests {
 
         assert!(non_matching_hosts.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_list_page() {
+        dotenvy::from_filename(".env.test").ok();
+        let db = ensure_fixtures().await;
+
+        let host_persistence = HostRelationalPersistence { db };
+
+        let mut host_ids = Vec::new();
+        // ids are ordered by bytes, so uppercase sorts before lowercase whatever the database locale.
+        for name in [
+            "host-list-page-a",
+            "host-list-page-B",
+            "host-list-page-b",
+            "host-list-page-c",
+        ] {
+            let mut host: Host = get_host_fixture(Some(name)).into();
+            host.labels.push("list-page:test".to_string());
+            host_persistence.upsert(&host).await.unwrap();
+            host_ids.push(host.id);
+        }
+
+        let options = ListOptions {
+            labels: vec!["list-page:test".to_string()],
+            descending: true,
+            page_size: 2,
+            ..ListOptions::default()
+        };
+
+        let page = host_persistence.list_page(&options).await.unwrap();
+        let page_ids: Vec<&str> = page.models.iter().map(|host| host.id.as_str()).collect();
+        assert_eq!(page_ids, vec!["host-list-page-c", "host-list-page-b"]);
+        assert!(!page.next_page_token.is_empty());
+
+        let options = ListOptions {
+            offset: 2,
+            ..options
+        };
+
+        let page = host_persistence.list_page(&options).await.unwrap();
+        let page_ids: Vec<&str> = page.models.iter().map(|host| host.id.as_str()).collect();
+        assert_eq!(page_ids, vec!["host-list-page-a", "host-list-page-B"]);
+        assert!(page.next_page_token.is_empty());
+
+        for host_id in host_ids {
+            host_persistence.delete(&host_id).await.unwrap();
+        }
+    }
 }
@@ -1,19 +1,120 @@
 mod assignment;
+mod audit;
 mod config;
 mod deployment;
 mod host;
+mod role_binding;
 mod target;
 mod template;
 mod workload;
 
 pub use assignment::AssignmentRelationalPersistence;
+pub use audit::AuditRelationalPersistence;
 pub use config::ConfigRelationalPersistence;
 pub use deployment::DeploymentRelationalPersistence;
 pub use host::HostRelationalPersistence;
+pub use role_binding::RoleBindingRelationalPersistence;
 pub use target::TargetRelationalPersistence;
 pub use template::TemplateRelationalPersistence;
 pub use workload::WorkloadRelationalPersistence;
 
+use sqlx::{postgres::PgRow, FromRow, PgPool, Postgres, QueryBuilder, Transaction};
+use std::sync::Arc;
+
+use super::{ListOptions, ListPage, Persistable, Persistences};
+use crate::{models::DeletedModel, services::current_actor};
+
+pub fn make_persistences(db: &Arc<PgPool>) -> Persistences {
+    Persistences {
+        assignment: Box::new(AssignmentRelationalPersistence { db: Arc::clone(db) }),
+        audit: Box::new(AuditRelationalPersistence { db: Arc::clone(db) }),
+        config: Box::new(ConfigRelationalPersistence { db: Arc::clone(db) }),
+        deployment: Box::new(DeploymentRelationalPersistence { db: Arc::clone(db) }),
+        host: Box::new(HostRelationalPersistence { db: Arc::clone(db) }),
+        role_binding: Box::new(RoleBindingRelationalPersistence { db: Arc::clone(db) }),
+        target: Box::new(TargetRelationalPersistence { db: Arc::clone(db) }),
+        template: Box::new(TemplateRelationalPersistence { db: Arc::clone(db) }),
+        workload: Box::new(WorkloadRelationalPersistence { db: Arc::clone(db) }),
+    }
+}
+
+// Fetches a page of models from table. Field names are checked against the model's list fields
+// before they are interpolated, values are always bound.
+pub async fn list_page<Model>(
+    db: &PgPool,
+    table: &str,
+    options: &ListOptions,
+) -> anyhow::Result<ListPage<Model>>
+where
+    Model: Persistable<Model> + for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    options.validate::<Model>()?;
+
+    let mut query = QueryBuilder::<Postgres>::new(format!("SELECT * FROM {table} WHERE TRUE"));
+
+    for (field, value) in &options.filters {
+        query
+            .push(format!(" AND {field} = "))
+            .push_bind(value.clone());
+    }
+
+    if !options.labels.is_empty() {
+        query
+            .push(" AND labels @> ")
+            .push_bind(options.labels.clone());
+    }
+
+    let direction = if options.descending { "DESC" } else { "ASC" };
+
+    // the C collation orders by bytes, as sqlite and memory persistence do, rather than by the
+    // database's locale.
+    query
+        .push(format!(
+            " ORDER BY {} COLLATE \"C\" {direction}, id COLLATE \"C\" {direction}",
+            options.get_order_by()
+        ))
+        .push(" LIMIT ")
+        .push_bind((options.get_page_size() + 1) as i64)
+        .push(" OFFSET ")
+        .push_bind(options.offset as i64);
+
+    let models = query.build_query_as::<Model>().fetch_all(db).await?;
+
+    Ok(ListPage::new(models, options))
+}
+
+// Keeps the model that a delete in transaction returned, if it deleted one, in deleted_models so
+// that it's only ever deleted along with being kept. Returns how many models were deleted.
+pub async fn keep_deleted<Model: Persistable<Model>>(
+    transaction: &mut Transaction<'_, Postgres>,
+    model: Option<&Model>,
+) -> anyhow::Result<u64> {
+    let model = match model {
+        Some(model) => model,
+        None => return Ok(0),
+    };
+
+    let deleted_model = DeletedModel::new(model, &current_actor())?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO deleted_models
+           (model_type, model_id, deleted_at, deleted_by, model)
+        VALUES
+           ($1, $2, $3, $4, $5)
+        "#,
+        deleted_model.model_type,
+        deleted_model.model_id,
+        deleted_model.deleted_at,
+        deleted_model.deleted_by,
+        deleted_model.model
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    Ok(1)
+}
+
 #[cfg(test)]
 pub mod tests {
     use lazy_static::lazy_static;
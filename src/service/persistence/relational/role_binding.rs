@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use crate::{
+    models::RoleBinding,
+    persistence::{
+        relational::{keep_deleted, list_page},
+        ListOptions, ListPage, Persistence, ResourceVersionConflict,
+    },
+};
+
+#[derive(Debug)]
+pub struct RoleBindingRelationalPersistence {
+    pub db: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Persistence<RoleBinding> for RoleBindingRelationalPersistence {
+    #[tracing::instrument(name = "relational::role_binding::create", skip_all)]
+    async fn upsert(&self, role_binding: &RoleBinding) -> anyhow::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO role_bindings
+               (id, role, team_id, subject, resource_version)
+            SELECT
+               $1, $2, $3, $4, 1
+            WHERE
+               $5::BIGINT = 0 OR EXISTS (SELECT 1 FROM role_bindings WHERE id = $1 FOR UPDATE)
+            ON CONFLICT (id) DO UPDATE SET
+               role = $2,
+               team_id = $3,
+               subject = $4,
+               resource_version = role_bindings.resource_version + 1
+            WHERE
+               $5::BIGINT = 0 OR role_bindings.resource_version = $5
+            "#,
+            role_binding.id,
+            role_binding.role,
+            role_binding.team_id,
+            role_binding.subject,
+            role_binding.resource_version
+        )
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: role_binding.id.clone(),
+                resource_version: role_binding.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "relational::role_binding::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let role_binding = sqlx::query_as!(
+            RoleBinding,
+            // language=PostgreSQL
+            r#"
+                DELETE FROM role_bindings WHERE id = $1
+                RETURNING *
+            "#,
+            id
+        )
+        .fetch_optional(&mut transaction)
+        .await?;
+
+        let deleted_count = keep_deleted(&mut transaction, role_binding.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "relational::role_binding::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<RoleBinding>> {
+        let rows = sqlx::query_as!(
+            RoleBinding,
+            r#"
+                SELECT * FROM role_bindings
+            "#,
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows)
+    }
+
+    #[tracing::instrument(name = "relational::role_binding::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<RoleBinding>> {
+        list_page(&self.db, "role_bindings", options).await
+    }
+
+    #[tracing::instrument(name = "relational::role_binding::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<RoleBinding>> {
+        let role_binding =
+            sqlx::query_as!(RoleBinding, "SELECT * FROM role_bindings WHERE id = $1", id)
+                .fetch_optional(&*self.db)
+                .await?;
+
+        Ok(role_binding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{test::get_role_binding_fixture, RoleBindingMessage};
+
+    use super::*;
+    use crate::persistence::relational::tests::ensure_fixtures;
+
+    #[tokio::test]
+    async fn test_create_get_delete() {
+        dotenvy::from_filename(".env.test").ok();
+        let db = ensure_fixtures().await;
+
+        let role_binding_persistence = RoleBindingRelationalPersistence { db };
+        let role_binding: RoleBinding =
+            get_role_binding_fixture(Some(RoleBindingMessage::TEAM_ADMIN_ROLE)).into();
+
+        role_binding_persistence
+            .delete(&role_binding.id)
+            .await
+            .unwrap();
+
+        let created_count = role_binding_persistence
+            .upsert(&role_binding)
+            .await
+            .unwrap();
+        assert_eq!(created_count, 1);
+
+        let fetched_role_binding = role_binding_persistence
+            .get_by_id(&role_binding.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched_role_binding.role, role_binding.role);
+        assert_eq!(fetched_role_binding.team_id, role_binding.team_id);
+        assert_eq!(fetched_role_binding.resource_version, 1);
+
+        role_binding_persistence
+            .upsert(&fetched_role_binding)
+            .await
+            .unwrap();
+
+        // fetched_role_binding is now one version behind the stored role binding.
+        let err = role_binding_persistence
+            .upsert(&fetched_role_binding)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<ResourceVersionConflict>().is_some());
+
+        let deleted_count = role_binding_persistence
+            .delete(&role_binding.id)
+            .await
+            .unwrap();
+        assert_eq!(deleted_count, 1);
+
+        // a non-zero resource version doesn't recreate a model deleted since it was read.
+        let err = role_binding_persistence
+            .upsert(&fetched_role_binding)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<ResourceVersionConflict>().is_some());
+        assert!(role_binding_persistence
+            .get_by_id(&role_binding.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
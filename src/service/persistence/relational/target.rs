@@ -4,7 +4,10 @@ use async_trait::async_trait;
 use sqlx::PgPool;
 
 use crate::models::{Host, Target};
-use crate::persistence::{Persistence, TargetPersistence};
+use crate::persistence::{
+    relational::{keep_deleted, list_page},
+    ListOptions, ListPage, Persistence, ResourceVersionConflict, TargetPersistence,
+};
 
 #[derive(Debug)]
 pub struct TargetRelationalPersistence {
@@ -18,35 +21,62 @@ impl Persistence<Target> for TargetRelationalPersistence {
         let result = sqlx::query!(
             r#"
             INSERT INTO targets
-               (id, labels)
-            VALUES
-               ($1, $2)
+               (id, labels, resource_version,
+                created_at, created_by, updated_at, updated_by)
+            SELECT
+               $1, $2, 1, $4, $5, $6, $7
+            WHERE
+               $3::BIGINT = 0 OR EXISTS (SELECT 1 FROM targets WHERE id = $1 FOR UPDATE)
             ON CONFLICT (id) DO UPDATE SET
-               labels = $2
+               labels = $2,
+               updated_at = $6,
+               updated_by = $7,
+               resource_version = targets.resource_version + 1
+            WHERE
+               $3::BIGINT = 0 OR targets.resource_version = $3
             "#,
             target.id,
             &target.labels,
+            target.resource_version,
+            target.created_at,
+            target.created_by,
+            target.updated_at,
+            target.updated_by
         )
         .execute(&*self.db)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: target.id.clone(),
+                resource_version: target.resource_version,
+            }
+            .into());
+        }
+
         Ok(result.rows_affected())
     }
 
     #[tracing::instrument(name = "relational::target::delete", skip_all)]
     async fn delete(&self, id: &str) -> anyhow::Result<u64> {
-        let result = sqlx::query!(
+        let mut transaction = self.db.begin().await?;
+
+        let target = sqlx::query_as!(
+            Target,
             // language=PostgreSQL
             r#"
                 DELETE FROM targets WHERE id = $1
+                RETURNING *
             "#,
             id
         )
-        .bind(id)
-        .execute(&*self.db)
+        .fetch_optional(&mut transaction)
         .await?;
 
-        Ok(result.rows_affected())
+        let deleted_count = keep_deleted(&mut transaction, target.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
     }
 
     #[tracing::instrument(name = "relational::target::list", skip_all)]
@@ -65,6 +95,11 @@ impl Persistence<Target> for TargetRelationalPersistence {
         Ok(models)
     }
 
+    #[tracing::instrument(name = "relational::target::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Target>> {
+        list_page(&self.db, "targets", options).await
+    }
+
     #[tracing::instrument(name = "relational::target::get_by_id", skip_all)]
     async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Target>> {
         let supply = sqlx::query_as!(Target, "SELECT * FROM targets WHERE id = $1", id)
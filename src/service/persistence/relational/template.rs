@@ -3,7 +3,13 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use sqlx::PgPool;
 
-use crate::{models::Template, persistence::Persistence};
+use crate::{
+    models::Template,
+    persistence::{
+        relational::{keep_deleted, list_page},
+        ListOptions, ListPage, Persistence, ResourceVersionConflict,
+    },
+};
 
 #[derive(Debug)]
 pub struct TemplateRelationalPersistence {
@@ -17,39 +23,72 @@ impl Persistence<Template> for TemplateRelationalPersistence {
         let result = sqlx::query!(
             r#"
             INSERT INTO templates
-               (id, repository, git_ref, path)
-            VALUES
-               ($1, $2, $3, $4)
+               (id, repository, git_ref, path, resource_version,
+                created_at, created_by, updated_at, updated_by, labels, annotations)
+            SELECT
+               $1, $2, $3, $4, 1, $6, $7, $8, $9, $10, $11
+            WHERE
+               $5::BIGINT = 0 OR EXISTS (SELECT 1 FROM templates WHERE id = $1 FOR UPDATE)
             ON CONFLICT (id) DO UPDATE SET
                repository = $2,
                git_ref = $3,
-               path = $4
+               path = $4,
+               labels = $10,
+               annotations = $11,
+               updated_at = $8,
+               updated_by = $9,
+               resource_version = templates.resource_version + 1
+            WHERE
+               $5::BIGINT = 0 OR templates.resource_version = $5
             "#,
             template.id,
             template.repository,
             template.git_ref,
-            template.path
+            template.path,
+            template.resource_version,
+            template.created_at,
+            template.created_by,
+            template.updated_at,
+            template.updated_by,
+            &template.labels,
+            &template.annotations as _
         )
         .execute(&*self.db)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: template.id.clone(),
+                resource_version: template.resource_version,
+            }
+            .into());
+        }
+
         Ok(result.rows_affected())
     }
 
     #[tracing::instrument(name = "relational::template::delete", skip_all)]
     async fn delete(&self, id: &str) -> anyhow::Result<u64> {
-        let result = sqlx::query!(
+        let mut transaction = self.db.begin().await?;
+
+        let template = sqlx::query_as!(
+            Template,
             // language=PostgreSQL
             r#"
                 DELETE FROM templates WHERE id = $1
+                RETURNING id, repository, git_ref, path, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
             "#,
             id
         )
-        .bind(id)
-        .execute(&*self.db)
+        .fetch_optional(&mut transaction)
         .await?;
 
-        Ok(result.rows_affected())
+        let deleted_count = keep_deleted(&mut transaction, template.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
     }
 
     #[tracing::instrument(name = "relational::template::list", skip_all)]
@@ -57,7 +96,10 @@ impl Persistence<Template> for TemplateRelationalPersistence {
         let rows = sqlx::query_as!(
             Template,
             r#"
-                SELECT * FROM templates
+                SELECT id, repository, git_ref, path, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM templates
             "#,
         )
         .fetch_all(&*self.db)
@@ -71,11 +113,25 @@ impl Persistence<Template> for TemplateRelationalPersistence {
         Ok(models)
     }
 
+    #[tracing::instrument(name = "relational::template::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Template>> {
+        list_page(&self.db, "templates", options).await
+    }
+
     #[tracing::instrument(name = "relational::template::get_by_id", skip_all)]
     async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Template>> {
-        let supply = sqlx::query_as!(Template, "SELECT * FROM templates WHERE id = $1", id)
-            .fetch_optional(&*self.db)
-            .await?;
+        let supply = sqlx::query_as!(
+            Template,
+            r#"
+                SELECT id, repository, git_ref, path, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM templates WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&*self.db)
+        .await?;
 
         Ok(supply)
     }
@@ -4,7 +4,10 @@ use async_trait::async_trait;
 use sqlx::PgPool;
 
 use crate::models::Workload;
-use crate::persistence::{Persistence, WorkloadPersistence};
+use crate::persistence::{
+    relational::{keep_deleted, list_page},
+    ListOptions, ListPage, Persistence, ResourceVersionConflict, WorkloadPersistence,
+};
 
 #[derive(Debug)]
 pub struct WorkloadRelationalPersistence {
@@ -25,39 +28,72 @@ impl Persistence<Workload> for WorkloadRelationalPersistence {
         let result = sqlx::query!(
             r#"
             INSERT INTO workloads
-               (id, name, team_id, template_id)
-            VALUES
-               ($1, $2, $3, $4)
+               (id, name, team_id, template_id, resource_version,
+                created_at, created_by, updated_at, updated_by, labels, annotations)
+            SELECT
+               $1, $2, $3, $4, 1, $6, $7, $8, $9, $10, $11
+            WHERE
+               $5::BIGINT = 0 OR EXISTS (SELECT 1 FROM workloads WHERE id = $1 FOR UPDATE)
             ON CONFLICT (id) DO UPDATE SET
                name = $2,
                team_id = $3,
-               template_id = $4
+               template_id = $4,
+               labels = $10,
+               annotations = $11,
+               updated_at = $8,
+               updated_by = $9,
+               resource_version = workloads.resource_version + 1
+            WHERE
+               $5::BIGINT = 0 OR workloads.resource_version = $5
             "#,
             workload.id,
             workload.name,
             workload.team_id,
             workload.template_id,
+            workload.resource_version,
+            workload.created_at,
+            workload.created_by,
+            workload.updated_at,
+            workload.updated_by,
+            &workload.labels,
+            &workload.annotations as _
         )
         .execute(&*self.db)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: workload.id.clone(),
+                resource_version: workload.resource_version,
+            }
+            .into());
+        }
+
         Ok(result.rows_affected())
     }
 
     #[tracing::instrument(name = "relational::workload::delete", skip_all)]
     async fn delete(&self, id: &str) -> anyhow::Result<u64> {
-        let result = sqlx::query!(
+        let mut transaction = self.db.begin().await?;
+
+        let workload = sqlx::query_as!(
+            Workload,
             // language=PostgreSQL
             r#"
                 DELETE FROM workloads WHERE id = $1
+                RETURNING id, name, team_id, template_id, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
             "#,
             id
         )
-        .bind(id)
-        .execute(&*self.db)
+        .fetch_optional(&mut transaction)
         .await?;
 
-        Ok(result.rows_affected())
+        let deleted_count = keep_deleted(&mut transaction, workload.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
     }
 
     #[tracing::instrument(name = "relational::workload::list", skip_all)]
@@ -65,7 +101,10 @@ impl Persistence<Workload> for WorkloadRelationalPersistence {
         let rows = sqlx::query_as!(
             Workload,
             r#"
-                SELECT * FROM workloads
+                SELECT id, name, team_id, template_id, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM workloads
             "#,
         )
         .fetch_all(&*self.db)
@@ -79,11 +118,25 @@ impl Persistence<Workload> for WorkloadRelationalPersistence {
         Ok(models)
     }
 
+    #[tracing::instrument(name = "relational::workload::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Workload>> {
+        list_page(&self.db, "workloads", options).await
+    }
+
     #[tracing::instrument(name = "relational::workload::get_by_id", skip_all)]
     async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Workload>> {
-        let supply = sqlx::query_as!(Workload, "SELECT * FROM workloads WHERE id = $1", id)
-            .fetch_optional(&*self.db)
-            .await?;
+        let supply = sqlx::query_as!(
+            Workload,
+            r#"
+                SELECT id, name, team_id, template_id, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM workloads WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&*self.db)
+        .await?;
 
         Ok(supply)
     }
@@ -96,7 +149,10 @@ impl WorkloadPersistence for WorkloadRelationalPersistence {
         let rows = sqlx::query_as!(
             Workload,
             r#"
-                SELECT * FROM workloads WHERE template_id = $1
+                SELECT id, name, team_id, template_id, labels,
+                    annotations AS "annotations: _", resource_version,
+                    created_at, created_by, updated_at, updated_by
+                FROM workloads WHERE template_id = $1
             "#,
             template_id
         )
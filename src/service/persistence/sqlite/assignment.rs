@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+use crate::models::Assignment;
+use crate::persistence::{
+    sqlite::{keep_deleted, list_page},
+    AssignmentPersistence, ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
+
+#[derive(Debug)]
+pub struct AssignmentSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+#[async_trait]
+impl Persistence<Assignment> for AssignmentSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::assignment::create", skip_all)]
+    async fn upsert(&self, assignment: &Assignment) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO assignments
+               (id, deployment_id, host_id, resource_version,
+                created_at, created_by, updated_at, updated_by)
+            SELECT
+               ?1, ?2, ?3, 1, ?5, ?6, ?7, ?8
+            WHERE
+               ?4 = 0 OR EXISTS (SELECT 1 FROM assignments WHERE id = ?1)
+            ON CONFLICT (id) DO UPDATE SET
+               deployment_id = ?2,
+               host_id = ?3,
+               updated_at = ?7,
+               updated_by = ?8,
+               resource_version = assignments.resource_version + 1
+            WHERE
+               ?4 = 0 OR assignments.resource_version = ?4
+            "#,
+        )
+        .bind(&assignment.id)
+        .bind(&assignment.deployment_id)
+        .bind(&assignment.host_id)
+        .bind(assignment.resource_version)
+        .bind(assignment.created_at)
+        .bind(&assignment.created_by)
+        .bind(assignment.updated_at)
+        .bind(&assignment.updated_by)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: assignment.id.clone(),
+                resource_version: assignment.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "sqlite::assignment::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let assignment =
+            sqlx::query_as::<_, Assignment>("DELETE FROM assignments WHERE id = ?1 RETURNING *")
+                .bind(id)
+                .fetch_optional(&mut transaction)
+                .await?;
+
+        let deleted_count = keep_deleted(&mut transaction, assignment.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "sqlite::assignment::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Assignment>> {
+        let row = sqlx::query_as::<_, Assignment>("SELECT * FROM assignments WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "sqlite::assignment::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<Assignment>> {
+        let rows = sqlx::query_as::<_, Assignment>("SELECT * FROM assignments")
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows)
+    }
+
+    #[tracing::instrument(name = "sqlite::assignment::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Assignment>> {
+        list_page::<Assignment, _>(&self.db, "assignments", options).await
+    }
+}
+
+#[async_trait]
+impl AssignmentPersistence for AssignmentSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::assignment::get_by_deployment_id", skip_all)]
+    async fn get_by_deployment_id(&self, deployment_id: &str) -> anyhow::Result<Vec<Assignment>> {
+        let rows =
+            sqlx::query_as::<_, Assignment>("SELECT * FROM assignments WHERE deployment_id = ?1")
+                .bind(deployment_id)
+                .fetch_all(&*self.db)
+                .await?;
+
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::get_assignment_fixture;
+
+    use super::*;
+    use crate::persistence::sqlite::tests::connect_with_fixtures;
+
+    #[tokio::test]
+    async fn test_create_get_delete() {
+        let assignment_persistence = AssignmentSqlitePersistence {
+            db: connect_with_fixtures().await,
+        };
+        let assignment: Assignment =
+            get_assignment_fixture(Some("sqlite-assignment-create")).into();
+
+        let created_count = assignment_persistence.upsert(&assignment).await.unwrap();
+        assert_eq!(created_count, 1);
+
+        let fetched_assignment = assignment_persistence
+            .get_by_id(&assignment.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            fetched_assignment,
+            Assignment {
+                resource_version: 1,
+                ..assignment.clone()
+            }
+        );
+
+        let deployment_assignments = assignment_persistence
+            .get_by_deployment_id(&assignment.deployment_id)
+            .await
+            .unwrap();
+        assert_eq!(deployment_assignments.len(), 1);
+
+        let deleted_count = assignment_persistence.delete(&assignment.id).await.unwrap();
+        assert_eq!(deleted_count, 1);
+
+        assert!(assignment_persistence
+            .get_by_id(&assignment.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+use crate::{
+    models::AuditEntry,
+    persistence::{AuditPersistence, AuditQuery},
+};
+
+#[derive(Debug)]
+pub struct AuditSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+#[async_trait]
+impl AuditPersistence for AuditSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::audit::insert", skip_all)]
+    async fn insert(&self, audit_entry: &AuditEntry) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO audit_entries
+               (id, actor, recorded_at, operation_id, event_type, model_type, model_id,
+                previous_model, current_model)
+            VALUES
+               (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+        )
+        .bind(&audit_entry.id)
+        .bind(&audit_entry.actor)
+        .bind(audit_entry.recorded_at)
+        .bind(&audit_entry.operation_id)
+        .bind(&audit_entry.event_type)
+        .bind(&audit_entry.model_type)
+        .bind(&audit_entry.model_id)
+        .bind(&audit_entry.previous_model)
+        .bind(&audit_entry.current_model)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // Timestamps are stored as RFC 3339 text in UTC, so comparing them as text orders them in
+    // time.
+    #[tracing::instrument(name = "sqlite::audit::query", skip_all)]
+    async fn query(&self, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>> {
+        let rows = sqlx::query_as::<_, AuditEntry>(
+            r#"
+                SELECT * FROM audit_entries
+                WHERE
+                    (?1 IS NULL OR model_type = ?1) AND
+                    (?2 IS NULL OR model_id = ?2) AND
+                    (?3 IS NULL OR actor = ?3) AND
+                    (?4 IS NULL OR recorded_at >= ?4) AND
+                    (?5 IS NULL OR recorded_at < ?5)
+                ORDER BY recorded_at DESC, id DESC
+                LIMIT ?6
+            "#,
+        )
+        .bind(&query.model_type)
+        .bind(&query.model_id)
+        .bind(&query.actor)
+        .bind(query.since)
+        .bind(query.until)
+        .bind(query.limit as i64)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{create_event, test::get_host_fixture, EventType, ModelType, OperationId};
+
+    use super::*;
+    use crate::persistence::sqlite::tests::connect;
+
+    #[tokio::test]
+    async fn test_insert_query() -> anyhow::Result<()> {
+        let audit_persistence = AuditSqlitePersistence {
+            db: connect().await,
+        };
+
+        let host = get_host_fixture(Some("audited-host"));
+        let event = create_event(
+            &Some(host.clone()),
+            &None,
+            EventType::Deleted,
+            ModelType::Host,
+            &OperationId::create(),
+        );
+        let audit_entry = AuditEntry::from_event(&event, "octocat")?;
+
+        let inserted_count = audit_persistence.insert(&audit_entry).await?;
+        assert_eq!(inserted_count, 1);
+
+        let audit_entries = audit_persistence
+            .query(&AuditQuery {
+                model_type: Some("host".to_string()),
+                model_id: Some(host.id.clone()),
+                actor: Some("octocat".to_string()),
+                since: Some(audit_entry.recorded_at),
+                limit: 10,
+                ..AuditQuery::default()
+            })
+            .await?;
+        assert_eq!(audit_entries, vec![audit_entry.clone()]);
+
+        let audit_entries = audit_persistence
+            .query(&AuditQuery {
+                model_id: Some(host.id),
+                until: Some(audit_entry.recorded_at),
+                limit: 10,
+                ..AuditQuery::default()
+            })
+            .await?;
+        assert!(audit_entries.is_empty());
+
+        Ok(())
+    }
+}
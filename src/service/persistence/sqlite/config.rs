@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+use fabriq_core::ConfigMessage;
+
+use crate::models::Config;
+use crate::persistence::{
+    sqlite::{keep_deleted, list_page},
+    ConfigPersistence, ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
+
+#[derive(Debug)]
+pub struct ConfigSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+#[async_trait]
+impl Persistence<Config> for ConfigSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::config::create", skip_all)]
+    async fn upsert(&self, config: &Config) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO configs
+               (id, owning_model, key, value, value_type, resource_version,
+                created_at, created_by, updated_at, updated_by)
+            SELECT
+               ?1, ?2, ?3, ?4, ?5, 1, ?7, ?8, ?9, ?10
+            WHERE
+               ?6 = 0 OR EXISTS (SELECT 1 FROM configs WHERE id = ?1)
+            ON CONFLICT (id) DO UPDATE SET
+               owning_model = ?2,
+               key = ?3,
+               value = ?4,
+               value_type = ?5,
+               updated_at = ?9,
+               updated_by = ?10,
+               resource_version = configs.resource_version + 1
+            WHERE
+               ?6 = 0 OR configs.resource_version = ?6
+            "#,
+        )
+        .bind(&config.id)
+        .bind(&config.owning_model)
+        .bind(&config.key)
+        .bind(&config.value)
+        .bind(config.value_type)
+        .bind(config.resource_version)
+        .bind(config.created_at)
+        .bind(&config.created_by)
+        .bind(config.updated_at)
+        .bind(&config.updated_by)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: config.id.clone(),
+                resource_version: config.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "sqlite::config::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let config = sqlx::query_as::<_, Config>("DELETE FROM configs WHERE id = ?1 RETURNING *")
+            .bind(id)
+            .fetch_optional(&mut transaction)
+            .await?;
+
+        let deleted_count = keep_deleted(&mut transaction, config.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "sqlite::config::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Config>> {
+        let row = sqlx::query_as::<_, Config>("SELECT * FROM configs WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "sqlite::config::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<Config>> {
+        let rows = sqlx::query_as::<_, Config>("SELECT * FROM configs")
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows)
+    }
+
+    #[tracing::instrument(name = "sqlite::config::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Config>> {
+        list_page::<Config, _>(&self.db, "configs", options).await
+    }
+}
+
+impl ConfigSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::config::get_by_owning_model", skip_all)]
+    async fn get_by_owning_model(&self, owning_model: &str) -> anyhow::Result<Vec<Config>> {
+        let rows = sqlx::query_as::<_, Config>("SELECT * FROM configs WHERE owning_model = ?1")
+            .bind(owning_model)
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl ConfigPersistence for ConfigSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::config::get_by_deployment_id", skip_all)]
+    async fn get_by_deployment_id(&self, deployment_id: &str) -> anyhow::Result<Vec<Config>> {
+        let owning_model = ConfigMessage::make_owning_model("deployment", deployment_id)?;
+
+        self.get_by_owning_model(&owning_model).await
+    }
+
+    #[tracing::instrument(name = "sqlite::config::get_by_host_id", skip_all)]
+    async fn get_by_host_id(&self, host_id: &str) -> anyhow::Result<Vec<Config>> {
+        let owning_model = ConfigMessage::make_owning_model("host", host_id)?;
+
+        self.get_by_owning_model(&owning_model).await
+    }
+
+    #[tracing::instrument(name = "sqlite::config::get_by_template_id", skip_all)]
+    async fn get_by_template_id(&self, template_id: &str) -> anyhow::Result<Vec<Config>> {
+        let owning_model = ConfigMessage::make_owning_model("template", template_id)?;
+
+        self.get_by_owning_model(&owning_model).await
+    }
+
+    #[tracing::instrument(name = "sqlite::config::get_by_workload_id", skip_all)]
+    async fn get_by_workload_id(&self, workload_id: &str) -> anyhow::Result<Vec<Config>> {
+        let owning_model = ConfigMessage::make_owning_model("workload", workload_id)?;
+
+        self.get_by_owning_model(&owning_model).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::{get_string_config_fixture, get_workload_fixture};
+
+    use super::*;
+    use crate::persistence::sqlite::tests::connect;
+
+    #[tokio::test]
+    async fn test_create_get_delete() {
+        let config_persistence = ConfigSqlitePersistence {
+            db: connect().await,
+        };
+        let config: Config = get_string_config_fixture().into();
+
+        let created_count = config_persistence.upsert(&config).await.unwrap();
+        assert_eq!(created_count, 1);
+
+        let fetched_config = config_persistence
+            .get_by_id(&config.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched_config.value, config.value);
+        assert_eq!(fetched_config.value_type, config.value_type);
+
+        let workload_configs = config_persistence
+            .get_by_workload_id(&get_workload_fixture(None).id)
+            .await
+            .unwrap();
+        assert_eq!(workload_configs.len(), 1);
+
+        let deleted_count = config_persistence.delete(&config.id).await.unwrap();
+        assert_eq!(deleted_count, 1);
+    }
+}
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use sqlx::{
+    types::{
+        chrono::{DateTime, Utc},
+        Json,
+    },
+    SqlitePool,
+};
+use std::sync::Arc;
+
+use crate::models::{Annotations, Deployment};
+use crate::persistence::{
+    sqlite::{keep_deleted, list_page},
+    DeploymentPersistence, ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
+
+#[derive(Debug)]
+pub struct DeploymentSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+// A deployment as stored, with its labels as a JSON array.
+#[derive(sqlx::FromRow)]
+struct DeploymentRow {
+    id: String,
+    name: String,
+    workload_id: String,
+    target_id: String,
+    template_id: Option<String>,
+    host_count: i32,
+    labels: Json<Vec<String>>,
+    annotations: Annotations,
+    resource_version: i64,
+    created_at: DateTime<Utc>,
+    created_by: String,
+    updated_at: DateTime<Utc>,
+    updated_by: String,
+}
+
+impl From<DeploymentRow> for Deployment {
+    fn from(row: DeploymentRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            workload_id: row.workload_id,
+            target_id: row.target_id,
+            template_id: row.template_id,
+            host_count: row.host_count,
+            labels: row.labels.0,
+            annotations: row.annotations,
+            resource_version: row.resource_version,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        }
+    }
+}
+
+#[async_trait]
+impl Persistence<Deployment> for DeploymentSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::deployment::create", skip_all)]
+    async fn upsert(&self, deployment: &Deployment) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO deployments
+               (id, name, workload_id, target_id, template_id, host_count, resource_version,
+                created_at, created_by, updated_at, updated_by, labels, annotations)
+            SELECT
+               ?1, ?2, ?3, ?4, ?5, ?6, 1, ?8, ?9, ?10, ?11, ?12, ?13
+            WHERE
+               ?7 = 0 OR EXISTS (SELECT 1 FROM deployments WHERE id = ?1)
+            ON CONFLICT (id) DO UPDATE SET
+               name = ?2,
+               workload_id = ?3,
+               target_id = ?4,
+               template_id = ?5,
+               host_count = ?6,
+               labels = ?12,
+               annotations = ?13,
+               updated_at = ?10,
+               updated_by = ?11,
+               resource_version = deployments.resource_version + 1
+            WHERE
+               ?7 = 0 OR deployments.resource_version = ?7
+            "#,
+        )
+        .bind(&deployment.id)
+        .bind(&deployment.name)
+        .bind(&deployment.workload_id)
+        .bind(&deployment.target_id)
+        .bind(&deployment.template_id)
+        .bind(deployment.host_count)
+        .bind(deployment.resource_version)
+        .bind(deployment.created_at)
+        .bind(&deployment.created_by)
+        .bind(deployment.updated_at)
+        .bind(&deployment.updated_by)
+        .bind(Json(&deployment.labels))
+        .bind(&deployment.annotations)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: deployment.id.clone(),
+                resource_version: deployment.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "sqlite::deployment::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let deployment =
+            sqlx::query_as::<_, DeploymentRow>("DELETE FROM deployments WHERE id = ?1 RETURNING *")
+                .bind(id)
+                .fetch_optional(&mut transaction)
+                .await?
+                .map(Deployment::from);
+
+        let deleted_count = keep_deleted(&mut transaction, deployment.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "sqlite::deployment::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Deployment>> {
+        let row = sqlx::query_as::<_, DeploymentRow>("SELECT * FROM deployments WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row.map(Deployment::from))
+    }
+
+    #[tracing::instrument(name = "sqlite::deployment::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<Deployment>> {
+        let rows = sqlx::query_as::<_, DeploymentRow>("SELECT * FROM deployments")
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(Deployment::from).collect())
+    }
+
+    #[tracing::instrument(name = "sqlite::deployment::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Deployment>> {
+        list_page::<DeploymentRow, _>(&self.db, "deployments", options).await
+    }
+}
+
+#[async_trait]
+impl DeploymentPersistence for DeploymentSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::deployment::get_by_target_id", skip_all)]
+    async fn get_by_target_id(&self, target_id: &str) -> anyhow::Result<Vec<Deployment>> {
+        let rows =
+            sqlx::query_as::<_, DeploymentRow>("SELECT * FROM deployments WHERE target_id = ?1")
+                .bind(target_id)
+                .fetch_all(&*self.db)
+                .await?;
+
+        Ok(rows.into_iter().map(Deployment::from).collect())
+    }
+
+    #[tracing::instrument(name = "sqlite::deployment::get_by_template_id", skip_all)]
+    async fn get_by_template_id(&self, template_id: &str) -> anyhow::Result<Vec<Deployment>> {
+        let rows =
+            sqlx::query_as::<_, DeploymentRow>("SELECT * FROM deployments WHERE template_id = ?1")
+                .bind(template_id)
+                .fetch_all(&*self.db)
+                .await?;
+
+        Ok(rows.into_iter().map(Deployment::from).collect())
+    }
+
+    #[tracing::instrument(name = "sqlite::deployment::get_by_workload_id", skip_all)]
+    async fn get_by_workload_id(&self, workload_id: &str) -> anyhow::Result<Vec<Deployment>> {
+        let rows =
+            sqlx::query_as::<_, DeploymentRow>("SELECT * FROM deployments WHERE workload_id = ?1")
+                .bind(workload_id)
+                .fetch_all(&*self.db)
+                .await?;
+
+        Ok(rows.into_iter().map(Deployment::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::get_deployment_fixture;
+
+    use super::*;
+    use crate::persistence::sqlite::tests::connect_with_fixtures;
+
+    #[tokio::test]
+    async fn test_create_get_delete() {
+        let deployment_persistence = DeploymentSqlitePersistence {
+            db: connect_with_fixtures().await,
+        };
+        let deployment: Deployment =
+            get_deployment_fixture(Some("sqlite-deployment-create")).into();
+
+        let created_count = deployment_persistence.upsert(&deployment).await.unwrap();
+        assert_eq!(created_count, 1);
+
+        let fetched_deployment = deployment_persistence
+            .get_by_id(&deployment.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            fetched_deployment,
+            Deployment {
+                resource_version: 1,
+                ..deployment.clone()
+            }
+        );
+
+        let target_deployments = deployment_persistence
+            .get_by_target_id(&deployment.target_id)
+            .await
+            .unwrap();
+        assert_eq!(target_deployments.len(), 2);
+
+        let deleted_count = deployment_persistence.delete(&deployment.id).await.unwrap();
+        assert_eq!(deleted_count, 1);
+
+        assert!(deployment_persistence
+            .get_by_id(&deployment.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
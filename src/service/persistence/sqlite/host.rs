@@ -0,0 +1,224 @@
+use async_trait::async_trait;
+use sqlx::{
+    types::{
+        chrono::{DateTime, Utc},
+        Json,
+    },
+    QueryBuilder, Sqlite, SqlitePool,
+};
+use std::sync::Arc;
+
+use crate::models::{Host, Target};
+use crate::persistence::{
+    sqlite::{keep_deleted, list_page, push_has_labels},
+    HostPersistence, ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
+
+#[derive(Debug)]
+pub struct HostSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+// A host as stored, with its labels as a JSON array.
+#[derive(sqlx::FromRow)]
+struct HostRow {
+    id: String,
+    labels: Json<Vec<String>>,
+    resource_version: i64,
+    created_at: DateTime<Utc>,
+    created_by: String,
+    updated_at: DateTime<Utc>,
+    updated_by: String,
+}
+
+impl From<HostRow> for Host {
+    fn from(row: HostRow) -> Self {
+        Self {
+            id: row.id,
+            labels: row.labels.0,
+            resource_version: row.resource_version,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        }
+    }
+}
+
+#[async_trait]
+impl Persistence<Host> for HostSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::host::create", skip_all)]
+    async fn upsert(&self, host: &Host) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO hosts
+               (id, labels, resource_version,
+                created_at, created_by, updated_at, updated_by)
+            SELECT
+               ?1, ?2, 1, ?4, ?5, ?6, ?7
+            WHERE
+               ?3 = 0 OR EXISTS (SELECT 1 FROM hosts WHERE id = ?1)
+            ON CONFLICT (id) DO UPDATE SET
+               labels = ?2,
+               updated_at = ?6,
+               updated_by = ?7,
+               resource_version = hosts.resource_version + 1
+            WHERE
+               ?3 = 0 OR hosts.resource_version = ?3
+            "#,
+        )
+        .bind(&host.id)
+        .bind(Json(&host.labels))
+        .bind(host.resource_version)
+        .bind(host.created_at)
+        .bind(&host.created_by)
+        .bind(host.updated_at)
+        .bind(&host.updated_by)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: host.id.clone(),
+                resource_version: host.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "sqlite::host::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let host = sqlx::query_as::<_, HostRow>("DELETE FROM hosts WHERE id = ?1 RETURNING *")
+            .bind(id)
+            .fetch_optional(&mut transaction)
+            .await?
+            .map(Host::from);
+
+        let deleted_count = keep_deleted(&mut transaction, host.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "sqlite::host::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Host>> {
+        let row = sqlx::query_as::<_, HostRow>("SELECT * FROM hosts WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row.map(Host::from))
+    }
+
+    #[tracing::instrument(name = "sqlite::host::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<Host>> {
+        let rows = sqlx::query_as::<_, HostRow>("SELECT * FROM hosts")
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(Host::from).collect())
+    }
+
+    #[tracing::instrument(name = "sqlite::host::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Host>> {
+        list_page::<HostRow, _>(&self.db, "hosts", options).await
+    }
+}
+
+#[async_trait]
+impl HostPersistence for HostSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::host::get_matching_target", skip_all)]
+    async fn get_matching_target(&self, target: &Target) -> anyhow::Result<Vec<Host>> {
+        // matches the hosts that have all of target.labels
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM hosts WHERE ");
+        push_has_labels(&mut query, &target.labels)?;
+
+        let rows = query
+            .build_query_as::<HostRow>()
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(Host::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::{get_host_fixture, get_target_fixture};
+
+    use super::*;
+    use crate::persistence::sqlite::tests::connect;
+
+    #[tokio::test]
+    async fn test_get_matching_target() {
+        let host_persistence = HostSqlitePersistence {
+            db: connect().await,
+        };
+        let host: Host = get_host_fixture(None).into();
+        host_persistence.upsert(&host).await.unwrap();
+
+        let fetched_host = host_persistence.get_by_id(&host.id).await.unwrap().unwrap();
+        assert_eq!(fetched_host.labels, host.labels);
+
+        let matching_target: Target = get_target_fixture(None).into();
+        let matching_hosts = host_persistence
+            .get_matching_target(&matching_target)
+            .await
+            .unwrap();
+        assert_eq!(matching_hosts.len(), 1);
+
+        let non_matching_target = Target {
+            id: "target-hawaii".to_owned(),
+            labels: vec!["region:eastus2".to_string(), "region:hawaii5".to_string()],
+            ..Default::default()
+        };
+        let non_matching_hosts = host_persistence
+            .get_matching_target(&non_matching_target)
+            .await
+            .unwrap();
+        assert!(non_matching_hosts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_page() {
+        let host_persistence = HostSqlitePersistence {
+            db: connect().await,
+        };
+
+        for name in ["host-list-page-a", "host-list-page-b", "host-list-page-c"] {
+            let mut host: Host = get_host_fixture(Some(name)).into();
+            host.labels.push("list-page:test".to_string());
+            host_persistence.upsert(&host).await.unwrap();
+        }
+        host_persistence
+            .upsert(&get_host_fixture(None).into())
+            .await
+            .unwrap();
+
+        let options = ListOptions {
+            labels: vec!["list-page:test".to_string()],
+            descending: true,
+            page_size: 2,
+            ..ListOptions::default()
+        };
+
+        let page = host_persistence.list_page(&options).await.unwrap();
+        let page_ids: Vec<&str> = page.models.iter().map(|host| host.id.as_str()).collect();
+        assert_eq!(page_ids, vec!["host-list-page-c", "host-list-page-b"]);
+        assert!(!page.next_page_token.is_empty());
+
+        let options = ListOptions {
+            offset: 2,
+            ..options
+        };
+
+        let page = host_persistence.list_page(&options).await.unwrap();
+        assert_eq!(page.models.len(), 1);
+        assert_eq!(page.models[0].id, "host-list-page-a");
+        assert!(page.next_page_token.is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+mod assignment;
+mod audit;
+mod config;
+mod deployment;
+mod host;
+mod role_binding;
+mod target;
+mod template;
+mod workload;
+
+pub use assignment::AssignmentSqlitePersistence;
+pub use audit::AuditSqlitePersistence;
+pub use config::ConfigSqlitePersistence;
+pub use deployment::DeploymentSqlitePersistence;
+pub use host::HostSqlitePersistence;
+pub use role_binding::RoleBindingSqlitePersistence;
+pub use target::TargetSqlitePersistence;
+pub use template::TemplateSqlitePersistence;
+pub use workload::WorkloadSqlitePersistence;
+
+use sqlx::{sqlite::SqliteRow, FromRow, QueryBuilder, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+use super::{ListOptions, ListPage, Persistable, Persistences};
+use crate::{models::DeletedModel, services::current_actor};
+
+pub fn make_persistences(db: &Arc<SqlitePool>) -> Persistences {
+    Persistences {
+        assignment: Box::new(AssignmentSqlitePersistence { db: Arc::clone(db) }),
+        audit: Box::new(AuditSqlitePersistence { db: Arc::clone(db) }),
+        config: Box::new(ConfigSqlitePersistence { db: Arc::clone(db) }),
+        deployment: Box::new(DeploymentSqlitePersistence { db: Arc::clone(db) }),
+        host: Box::new(HostSqlitePersistence { db: Arc::clone(db) }),
+        role_binding: Box::new(RoleBindingSqlitePersistence { db: Arc::clone(db) }),
+        target: Box::new(TargetSqlitePersistence { db: Arc::clone(db) }),
+        template: Box::new(TemplateSqlitePersistence { db: Arc::clone(db) }),
+        workload: Box::new(WorkloadSqlitePersistence { db: Arc::clone(db) }),
+    }
+}
+
+// SQLite has no arrays, so labels are stored as JSON arrays. Pushes a condition matching rows
+// whose labels include every one of labels.
+fn push_has_labels(query: &mut QueryBuilder<'_, Sqlite>, labels: &[String]) -> anyhow::Result<()> {
+    query
+        .push("NOT EXISTS (SELECT 1 FROM json_each(")
+        .push_bind(serde_json::to_string(labels)?)
+        .push(") AS label WHERE label.value NOT IN (SELECT value FROM json_each(labels)))");
+
+    Ok(())
+}
+
+// Fetches a page of models from table, read as Rows. Field names are checked against the model's
+// list fields before they are interpolated, values are always bound.
+pub async fn list_page<Row, Model>(
+    db: &SqlitePool,
+    table: &str,
+    options: &ListOptions,
+) -> anyhow::Result<ListPage<Model>>
+where
+    Row: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
+    Model: Persistable<Model> + From<Row>,
+{
+    options.validate::<Model>()?;
+
+    let mut query = QueryBuilder::<Sqlite>::new(format!("SELECT * FROM {table} WHERE TRUE"));
+
+    for (field, value) in &options.filters {
+        query
+            .push(format!(" AND {field} = "))
+            .push_bind(value.clone());
+    }
+
+    if !options.labels.is_empty() {
+        query.push(" AND ");
+        push_has_labels(&mut query, &options.labels)?;
+    }
+
+    let direction = if options.descending { "DESC" } else { "ASC" };
+
+    query
+        .push(format!(
+            " ORDER BY {} {direction}, id {direction}",
+            options.get_order_by()
+        ))
+        .push(" LIMIT ")
+        .push_bind((options.get_page_size() + 1) as i64)
+        .push(" OFFSET ")
+        .push_bind(options.offset as i64);
+
+    let rows = query.build_query_as::<Row>().fetch_all(db).await?;
+    let models = rows.into_iter().map(Model::from).collect();
+
+    Ok(ListPage::new(models, options))
+}
+
+// Keeps the model that a delete in transaction returned, if it deleted one, in deleted_models so
+// that it's only ever deleted along with being kept. Returns how many models were deleted.
+pub async fn keep_deleted<Model: Persistable<Model>>(
+    transaction: &mut Transaction<'_, Sqlite>,
+    model: Option<&Model>,
+) -> anyhow::Result<u64> {
+    let model = match model {
+        Some(model) => model,
+        None => return Ok(0),
+    };
+
+    let deleted_model = DeletedModel::new(model, &current_actor())?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO deleted_models
+           (model_type, model_id, deleted_at, deleted_by, model)
+        VALUES
+           (?1, ?2, ?3, ?4, ?5)
+        "#,
+    )
+    .bind(&deleted_model.model_type)
+    .bind(&deleted_model.model_id)
+    .bind(deleted_model.deleted_at)
+    .bind(&deleted_model.deleted_by)
+    .bind(&deleted_model.model)
+    .execute(&mut *transaction)
+    .await?;
+
+    Ok(1)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use fabriq_core::test::{
+        get_deployment_fixture, get_host_fixture, get_target_fixture, get_template_fixture,
+        get_workload_fixture,
+    };
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::persistence::Persistence;
+
+    // Every test gets its own in-memory database. Each connection to an in-memory database opens
+    // a different database, so the pool only has one.
+    pub async fn connect() -> Arc<SqlitePool> {
+        let db = Arc::new(
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await
+                .expect("failed to open in-memory database"),
+        );
+
+        sqlx::migrate!("./migrations/sqlite")
+            .run(&*db)
+            .await
+            .expect("failed to migrate in-memory database");
+
+        db
+    }
+
+    pub async fn connect_with_fixtures() -> Arc<SqlitePool> {
+        let db = connect().await;
+
+        HostSqlitePersistence {
+            db: Arc::clone(&db),
+        }
+        .upsert(&get_host_fixture(None).into())
+        .await
+        .unwrap();
+
+        TargetSqlitePersistence {
+            db: Arc::clone(&db),
+        }
+        .upsert(&get_target_fixture(None).into())
+        .await
+        .unwrap();
+
+        TemplateSqlitePersistence {
+            db: Arc::clone(&db),
+        }
+        .upsert(&get_template_fixture(None).into())
+        .await
+        .unwrap();
+
+        WorkloadSqlitePersistence {
+            db: Arc::clone(&db),
+        }
+        .upsert(&get_workload_fixture(None).into())
+        .await
+        .unwrap();
+
+        DeploymentSqlitePersistence {
+            db: Arc::clone(&db),
+        }
+        .upsert(&get_deployment_fixture(None).into())
+        .await
+        .unwrap();
+
+        db
+    }
+}
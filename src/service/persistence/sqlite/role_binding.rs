@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+use crate::models::RoleBinding;
+use crate::persistence::{
+    sqlite::{keep_deleted, list_page},
+    ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
+
+#[derive(Debug)]
+pub struct RoleBindingSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+#[async_trait]
+impl Persistence<RoleBinding> for RoleBindingSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::role_binding::create", skip_all)]
+    async fn upsert(&self, role_binding: &RoleBinding) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO role_bindings
+               (id, role, team_id, subject, resource_version)
+            SELECT
+               ?1, ?2, ?3, ?4, 1
+            WHERE
+               ?5 = 0 OR EXISTS (SELECT 1 FROM role_bindings WHERE id = ?1)
+            ON CONFLICT (id) DO UPDATE SET
+               role = ?2,
+               team_id = ?3,
+               subject = ?4,
+               resource_version = role_bindings.resource_version + 1
+            WHERE
+               ?5 = 0 OR role_bindings.resource_version = ?5
+            "#,
+        )
+        .bind(&role_binding.id)
+        .bind(&role_binding.role)
+        .bind(&role_binding.team_id)
+        .bind(&role_binding.subject)
+        .bind(role_binding.resource_version)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: role_binding.id.clone(),
+                resource_version: role_binding.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "sqlite::role_binding::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let role_binding =
+            sqlx::query_as::<_, RoleBinding>("DELETE FROM role_bindings WHERE id = ?1 RETURNING *")
+                .bind(id)
+                .fetch_optional(&mut transaction)
+                .await?;
+
+        let deleted_count = keep_deleted(&mut transaction, role_binding.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "sqlite::role_binding::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<RoleBinding>> {
+        let row = sqlx::query_as::<_, RoleBinding>("SELECT * FROM role_bindings WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row)
+    }
+
+    #[tracing::instrument(name = "sqlite::role_binding::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<RoleBinding>> {
+        let rows = sqlx::query_as::<_, RoleBinding>("SELECT * FROM role_bindings")
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows)
+    }
+
+    #[tracing::instrument(name = "sqlite::role_binding::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<RoleBinding>> {
+        list_page::<RoleBinding, _>(&self.db, "role_bindings", options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::get_role_binding_fixture;
+
+    use super::*;
+    use crate::persistence::sqlite::tests::connect;
+
+    #[tokio::test]
+    async fn test_resource_version_conflict() {
+        let role_binding_persistence = RoleBindingSqlitePersistence {
+            db: connect().await,
+        };
+        let role_binding: RoleBinding = get_role_binding_fixture(None).into();
+
+        role_binding_persistence
+            .upsert(&role_binding)
+            .await
+            .unwrap();
+        role_binding_persistence
+            .upsert(&role_binding)
+            .await
+            .unwrap();
+
+        let stale_role_binding = RoleBinding {
+            resource_version: 1,
+            ..role_binding.clone()
+        };
+        let err = role_binding_persistence
+            .upsert(&stale_role_binding)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<ResourceVersionConflict>().is_some());
+
+        let fetched_role_binding = role_binding_persistence
+            .get_by_id(&role_binding.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched_role_binding.resource_version, 2);
+
+        // a non-zero resource version doesn't recreate a model deleted since it was read.
+        role_binding_persistence
+            .delete(&role_binding.id)
+            .await
+            .unwrap();
+        let err = role_binding_persistence
+            .upsert(&fetched_role_binding)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<ResourceVersionConflict>().is_some());
+        assert!(role_binding_persistence
+            .get_by_id(&role_binding.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+use sqlx::{
+    types::{
+        chrono::{DateTime, Utc},
+        Json,
+    },
+    QueryBuilder, Sqlite, SqlitePool,
+};
+use std::sync::Arc;
+
+use crate::models::{Host, Target};
+use crate::persistence::{
+    sqlite::{keep_deleted, list_page, push_has_labels},
+    ListOptions, ListPage, Persistence, ResourceVersionConflict, TargetPersistence,
+};
+
+#[derive(Debug)]
+pub struct TargetSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+// A target as stored, with its labels as a JSON array.
+#[derive(sqlx::FromRow)]
+struct TargetRow {
+    id: String,
+    labels: Json<Vec<String>>,
+    resource_version: i64,
+    created_at: DateTime<Utc>,
+    created_by: String,
+    updated_at: DateTime<Utc>,
+    updated_by: String,
+}
+
+impl From<TargetRow> for Target {
+    fn from(row: TargetRow) -> Self {
+        Self {
+            id: row.id,
+            labels: row.labels.0,
+            resource_version: row.resource_version,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        }
+    }
+}
+
+#[async_trait]
+impl Persistence<Target> for TargetSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::target::create", skip_all)]
+    async fn upsert(&self, target: &Target) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO targets
+               (id, labels, resource_version,
+                created_at, created_by, updated_at, updated_by)
+            SELECT
+               ?1, ?2, 1, ?4, ?5, ?6, ?7
+            WHERE
+               ?3 = 0 OR EXISTS (SELECT 1 FROM targets WHERE id = ?1)
+            ON CONFLICT (id) DO UPDATE SET
+               labels = ?2,
+               updated_at = ?6,
+               updated_by = ?7,
+               resource_version = targets.resource_version + 1
+            WHERE
+               ?3 = 0 OR targets.resource_version = ?3
+            "#,
+        )
+        .bind(&target.id)
+        .bind(Json(&target.labels))
+        .bind(target.resource_version)
+        .bind(target.created_at)
+        .bind(&target.created_by)
+        .bind(target.updated_at)
+        .bind(&target.updated_by)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: target.id.clone(),
+                resource_version: target.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "sqlite::target::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let target =
+            sqlx::query_as::<_, TargetRow>("DELETE FROM targets WHERE id = ?1 RETURNING *")
+                .bind(id)
+                .fetch_optional(&mut transaction)
+                .await?
+                .map(Target::from);
+
+        let deleted_count = keep_deleted(&mut transaction, target.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "sqlite::target::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Target>> {
+        let row = sqlx::query_as::<_, TargetRow>("SELECT * FROM targets WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row.map(Target::from))
+    }
+
+    #[tracing::instrument(name = "sqlite::target::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<Target>> {
+        let rows = sqlx::query_as::<_, TargetRow>("SELECT * FROM targets")
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(Target::from).collect())
+    }
+
+    #[tracing::instrument(name = "sqlite::target::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Target>> {
+        list_page::<TargetRow, _>(&self.db, "targets", options).await
+    }
+}
+
+#[async_trait]
+impl TargetPersistence for TargetSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::target::get_matching_host", skip_all)]
+    async fn get_matching_host(&self, host: &Host) -> anyhow::Result<Vec<Target>> {
+        // matches the targets that have all of host.labels, as relational persistence does
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM targets WHERE ");
+        push_has_labels(&mut query, &host.labels)?;
+
+        let rows = query
+            .build_query_as::<TargetRow>()
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(Target::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::get_target_fixture;
+
+    use super::*;
+    use crate::persistence::sqlite::tests::connect;
+
+    #[tokio::test]
+    async fn test_get_matching_host() {
+        let target_persistence = TargetSqlitePersistence {
+            db: connect().await,
+        };
+        let target: Target = get_target_fixture(None).into();
+        target_persistence.upsert(&target).await.unwrap();
+
+        let matching_host = Host {
+            id: "host-eastus2".to_owned(),
+            labels: vec!["region:eastus2".to_string()],
+            ..Default::default()
+        };
+        let matching_targets = target_persistence
+            .get_matching_host(&matching_host)
+            .await
+            .unwrap();
+        assert_eq!(matching_targets.len(), 1);
+
+        let non_matching_host = Host {
+            id: "host-hawaii".to_owned(),
+            labels: vec!["region:hawaii5".to_string()],
+            ..Default::default()
+        };
+        let non_matching_targets = target_persistence
+            .get_matching_host(&non_matching_host)
+            .await
+            .unwrap();
+        assert!(non_matching_targets.is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+use sqlx::{
+    types::{
+        chrono::{DateTime, Utc},
+        Json,
+    },
+    SqlitePool,
+};
+use std::sync::Arc;
+
+use crate::models::{Annotations, Template};
+use crate::persistence::{
+    sqlite::{keep_deleted, list_page},
+    ListOptions, ListPage, Persistence, ResourceVersionConflict,
+};
+
+#[derive(Debug)]
+pub struct TemplateSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+// A template as stored, with its labels as a JSON array.
+#[derive(sqlx::FromRow)]
+struct TemplateRow {
+    id: String,
+    repository: String,
+    git_ref: String,
+    path: String,
+    labels: Json<Vec<String>>,
+    annotations: Annotations,
+    resource_version: i64,
+    created_at: DateTime<Utc>,
+    created_by: String,
+    updated_at: DateTime<Utc>,
+    updated_by: String,
+}
+
+impl From<TemplateRow> for Template {
+    fn from(row: TemplateRow) -> Self {
+        Self {
+            id: row.id,
+            repository: row.repository,
+            git_ref: row.git_ref,
+            path: row.path,
+            labels: row.labels.0,
+            annotations: row.annotations,
+            resource_version: row.resource_version,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        }
+    }
+}
+
+#[async_trait]
+impl Persistence<Template> for TemplateSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::template::create", skip_all)]
+    async fn upsert(&self, template: &Template) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO templates
+               (id, repository, git_ref, path, resource_version,
+                created_at, created_by, updated_at, updated_by, labels, annotations)
+            SELECT
+               ?1, ?2, ?3, ?4, 1, ?6, ?7, ?8, ?9, ?10, ?11
+            WHERE
+               ?5 = 0 OR EXISTS (SELECT 1 FROM templates WHERE id = ?1)
+            ON CONFLICT (id) DO UPDATE SET
+               repository = ?2,
+               git_ref = ?3,
+               path = ?4,
+               labels = ?10,
+               annotations = ?11,
+               updated_at = ?8,
+               updated_by = ?9,
+               resource_version = templates.resource_version + 1
+            WHERE
+               ?5 = 0 OR templates.resource_version = ?5
+            "#,
+        )
+        .bind(&template.id)
+        .bind(&template.repository)
+        .bind(&template.git_ref)
+        .bind(&template.path)
+        .bind(template.resource_version)
+        .bind(template.created_at)
+        .bind(&template.created_by)
+        .bind(template.updated_at)
+        .bind(&template.updated_by)
+        .bind(Json(&template.labels))
+        .bind(&template.annotations)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: template.id.clone(),
+                resource_version: template.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "sqlite::template::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let template =
+            sqlx::query_as::<_, TemplateRow>("DELETE FROM templates WHERE id = ?1 RETURNING *")
+                .bind(id)
+                .fetch_optional(&mut transaction)
+                .await?
+                .map(Template::from);
+
+        let deleted_count = keep_deleted(&mut transaction, template.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "sqlite::template::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Template>> {
+        let row = sqlx::query_as::<_, TemplateRow>("SELECT * FROM templates WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row.map(Template::from))
+    }
+
+    #[tracing::instrument(name = "sqlite::template::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<Template>> {
+        let rows = sqlx::query_as::<_, TemplateRow>("SELECT * FROM templates")
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(Template::from).collect())
+    }
+
+    #[tracing::instrument(name = "sqlite::template::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Template>> {
+        list_page::<TemplateRow, _>(&self.db, "templates", options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::get_template_fixture;
+
+    use super::*;
+    use crate::{models::DeletedModel, persistence::sqlite::tests::connect};
+
+    #[tokio::test]
+    async fn test_create_get_delete() {
+        let template_persistence = TemplateSqlitePersistence {
+            db: connect().await,
+        };
+        let template: Template = get_template_fixture(Some("sqlite-template-create")).into();
+
+        let created_count = template_persistence.upsert(&template).await.unwrap();
+        assert_eq!(created_count, 1);
+
+        let fetched_template = template_persistence
+            .get_by_id(&template.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            fetched_template,
+            Template {
+                resource_version: 1,
+                ..template.clone()
+            }
+        );
+
+        let deleted_count = template_persistence.delete(&template.id).await.unwrap();
+        assert_eq!(deleted_count, 1);
+
+        let deleted_template = sqlx::query_as::<_, DeletedModel>(
+            "SELECT * FROM deleted_models WHERE model_type = 'template' AND model_id = ?1",
+        )
+        .bind(&template.id)
+        .fetch_one(&*template_persistence.db)
+        .await
+        .unwrap();
+        assert_eq!(deleted_template.model["repository"], template.repository);
+
+        assert!(template_persistence
+            .get_by_id(&template.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
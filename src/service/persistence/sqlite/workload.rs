@@ -0,0 +1,240 @@
+use async_trait::async_trait;
+use sqlx::{
+    types::{
+        chrono::{DateTime, Utc},
+        Json,
+    },
+    SqlitePool,
+};
+use std::sync::Arc;
+
+use crate::models::{Annotations, Workload};
+use crate::persistence::{
+    sqlite::{keep_deleted, list_page},
+    ListOptions, ListPage, Persistence, ResourceVersionConflict, WorkloadPersistence,
+};
+
+#[derive(Debug)]
+pub struct WorkloadSqlitePersistence {
+    pub db: Arc<SqlitePool>,
+}
+
+// A workload as stored, with its labels as a JSON array.
+#[derive(sqlx::FromRow)]
+struct WorkloadRow {
+    id: String,
+    name: String,
+    team_id: String,
+    template_id: String,
+    labels: Json<Vec<String>>,
+    annotations: Annotations,
+    resource_version: i64,
+    created_at: DateTime<Utc>,
+    created_by: String,
+    updated_at: DateTime<Utc>,
+    updated_by: String,
+}
+
+impl From<WorkloadRow> for Workload {
+    fn from(row: WorkloadRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            team_id: row.team_id,
+            template_id: row.template_id,
+            labels: row.labels.0,
+            annotations: row.annotations,
+            resource_version: row.resource_version,
+            created_at: row.created_at,
+            created_by: row.created_by,
+            updated_at: row.updated_at,
+            updated_by: row.updated_by,
+        }
+    }
+}
+
+#[async_trait]
+impl Persistence<Workload> for WorkloadSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::workload::create", skip_all)]
+    async fn upsert(&self, workload: &Workload) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO workloads
+               (id, name, team_id, template_id, resource_version,
+                created_at, created_by, updated_at, updated_by, labels, annotations)
+            SELECT
+               ?1, ?2, ?3, ?4, 1, ?6, ?7, ?8, ?9, ?10, ?11
+            WHERE
+               ?5 = 0 OR EXISTS (SELECT 1 FROM workloads WHERE id = ?1)
+            ON CONFLICT (id) DO UPDATE SET
+               name = ?2,
+               team_id = ?3,
+               template_id = ?4,
+               labels = ?10,
+               annotations = ?11,
+               updated_at = ?8,
+               updated_by = ?9,
+               resource_version = workloads.resource_version + 1
+            WHERE
+               ?5 = 0 OR workloads.resource_version = ?5
+            "#,
+        )
+        .bind(&workload.id)
+        .bind(&workload.name)
+        .bind(&workload.team_id)
+        .bind(&workload.template_id)
+        .bind(workload.resource_version)
+        .bind(workload.created_at)
+        .bind(&workload.created_by)
+        .bind(workload.updated_at)
+        .bind(&workload.updated_by)
+        .bind(Json(&workload.labels))
+        .bind(&workload.annotations)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ResourceVersionConflict {
+                model_id: workload.id.clone(),
+                resource_version: workload.resource_version,
+            }
+            .into());
+        }
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "sqlite::workload::delete", skip_all)]
+    async fn delete(&self, id: &str) -> anyhow::Result<u64> {
+        let mut transaction = self.db.begin().await?;
+
+        let workload =
+            sqlx::query_as::<_, WorkloadRow>("DELETE FROM workloads WHERE id = ?1 RETURNING *")
+                .bind(id)
+                .fetch_optional(&mut transaction)
+                .await?
+                .map(Workload::from);
+
+        let deleted_count = keep_deleted(&mut transaction, workload.as_ref()).await?;
+        transaction.commit().await?;
+
+        Ok(deleted_count)
+    }
+
+    #[tracing::instrument(name = "sqlite::workload::get_by_id", skip_all)]
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<Workload>> {
+        let row = sqlx::query_as::<_, WorkloadRow>("SELECT * FROM workloads WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&*self.db)
+            .await?;
+
+        Ok(row.map(Workload::from))
+    }
+
+    #[tracing::instrument(name = "sqlite::workload::list", skip_all)]
+    async fn list(&self) -> anyhow::Result<Vec<Workload>> {
+        let rows = sqlx::query_as::<_, WorkloadRow>("SELECT * FROM workloads")
+            .fetch_all(&*self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(Workload::from).collect())
+    }
+
+    #[tracing::instrument(name = "sqlite::workload::list_page", skip_all)]
+    async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Workload>> {
+        list_page::<WorkloadRow, _>(&self.db, "workloads", options).await
+    }
+}
+
+#[async_trait]
+impl WorkloadPersistence for WorkloadSqlitePersistence {
+    #[tracing::instrument(name = "sqlite::workload::get_by_template_id", skip_all)]
+    async fn get_by_template_id(&self, template_id: &str) -> anyhow::Result<Vec<Workload>> {
+        let rows =
+            sqlx::query_as::<_, WorkloadRow>("SELECT * FROM workloads WHERE template_id = ?1")
+                .bind(template_id)
+                .fetch_all(&*self.db)
+                .await?;
+
+        Ok(rows.into_iter().map(Workload::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::get_workload_fixture;
+
+    use super::*;
+    use crate::persistence::sqlite::tests::connect;
+
+    #[tokio::test]
+    async fn test_create_get_delete() {
+        let workload_persistence = WorkloadSqlitePersistence {
+            db: connect().await,
+        };
+        let workload: Workload = get_workload_fixture(Some("sqlite-workload-create")).into();
+
+        let created_count = workload_persistence.upsert(&workload).await.unwrap();
+        assert_eq!(created_count, 1);
+
+        let fetched_workload = workload_persistence
+            .get_by_id(&workload.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            fetched_workload,
+            Workload {
+                resource_version: 1,
+                ..workload.clone()
+            }
+        );
+
+        let template_workloads = workload_persistence
+            .get_by_template_id(&workload.template_id)
+            .await
+            .unwrap();
+        assert_eq!(template_workloads.len(), 1);
+
+        let deleted_count = workload_persistence.delete(&workload.id).await.unwrap();
+        assert_eq!(deleted_count, 1);
+
+        assert!(workload_persistence
+            .get_by_id(&workload.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_page_by_label() {
+        let workload_persistence = WorkloadSqlitePersistence {
+            db: connect().await,
+        };
+
+        let mut unlabelled_workload: Workload =
+            get_workload_fixture(Some("sqlite-workload-unlabelled")).into();
+        unlabelled_workload.labels = vec![];
+        workload_persistence
+            .upsert(&unlabelled_workload)
+            .await
+            .unwrap();
+
+        let labelled_workload: Workload =
+            get_workload_fixture(Some("sqlite-workload-labelled")).into();
+        workload_persistence
+            .upsert(&labelled_workload)
+            .await
+            .unwrap();
+
+        let options = ListOptions {
+            labels: vec!["tier:critical".to_string()],
+            ..ListOptions::default()
+        };
+
+        let page = workload_persistence.list_page(&options).await.unwrap();
+        assert_eq!(page.models.len(), 1);
+        assert_eq!(page.models[0].id, labelled_workload.id);
+        assert_eq!(page.models[0].annotations, labelled_workload.annotations);
+    }
+}
@@ -47,6 +47,8 @@ impl Reconciler {
             model_type if model_type == ModelType::Host as i32 => {
                 self.process_host_event(event).await
             }
+            // role bindings only affect authorization, so there is nothing to reconcile.
+            model_type if model_type == ModelType::RoleBinding as i32 => Ok(()),
             model_type if model_type == ModelType::Target as i32 => {
                 self.process_target_event(event).await
             }
@@ -370,6 +372,7 @@ impl Reconciler {
                     id: AssignmentMessage::make_id(&deployment.id, &host.id),
                     deployment_id: deployment.id.clone(),
                     host_id: host.id.clone(),
+                    ..Default::default()
                 })
                 .collect();
         }
@@ -469,6 +472,7 @@ mod tests {
         let host2 = Host {
             id: "host2-id".to_owned(),
             labels: vec!["region:westus2".to_owned(), "cloud:azure".to_owned()],
+            ..Default::default()
         };
         reconciler.host_service.upsert(&host2, &None).await.unwrap();
 
@@ -529,6 +533,7 @@ mod tests {
         let host4 = Host {
             id: "host4-id".to_owned(),
             labels: vec!["region:westus2".to_owned(), "cloud:azure".to_owned()],
+            ..Default::default()
         };
 
         let operation_id = OperationId::create();
@@ -550,6 +555,7 @@ mod tests {
         let host3 = Host {
             id: "host3-id".to_owned(),
             labels: vec!["region:eastus2".to_owned(), "cloud:azure".to_owned()],
+            ..Default::default()
         };
 
         let event = create_event::<HostMessage>(
@@ -654,14 +660,17 @@ mod tests {
             Host {
                 id: "host1-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
             Host {
                 id: "host2-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
             Host {
                 id: "host3-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
         ];
 
@@ -692,16 +701,19 @@ mod tests {
             id: AssignmentMessage::make_id(&deployment.id, "host1-id"),
             deployment_id: deployment.id.to_string(),
             host_id: "host1-id".to_string(),
+            ..Default::default()
         }];
 
         let target_matching_hosts = vec![
             Host {
                 id: "host1-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
             Host {
                 id: "host2-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
         ];
 
@@ -734,11 +746,13 @@ mod tests {
                 id: AssignmentMessage::make_id(&deployment.id, "host1-id"),
                 deployment_id: deployment.id.to_string(),
                 host_id: "host1-id".to_string(),
+                ..Default::default()
             },
             Assignment {
                 id: AssignmentMessage::make_id(&deployment.id, "host2-id"),
                 deployment_id: deployment.id.to_string(),
                 host_id: "host2-id".to_string(),
+                ..Default::default()
             },
         ];
 
@@ -746,10 +760,12 @@ mod tests {
             Host {
                 id: "host1-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
             Host {
                 id: "host2-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
         ];
 
@@ -781,17 +797,20 @@ mod tests {
                 id: AssignmentMessage::make_id(&deployment.id, "host1-id"),
                 deployment_id: deployment.id.to_string(),
                 host_id: "host1-id".to_string(),
+                ..Default::default()
             },
             Assignment {
                 id: AssignmentMessage::make_id(&deployment.id, "host2-id"),
                 deployment_id: deployment.id.to_string(),
                 host_id: "host2-id".to_string(),
+                ..Default::default()
             },
         ];
 
         let target_matching_hosts = vec![Host {
             id: "host1-id".to_string(),
             labels: vec!["region:eastus2".to_string()],
+            ..Default::default()
         }];
 
         let desired_host_count = 0;
@@ -825,16 +844,19 @@ mod tests {
             id: AssignmentMessage::make_id(&deployment.id, "host1-id"),
             deployment_id: deployment.id.to_string(),
             host_id: "host1-id".to_string(),
+            ..Default::default()
         }];
 
         let target_matching_hosts = vec![
             Host {
                 id: "host1-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
             Host {
                 id: "host2-id".to_string(),
                 labels: vec!["region:eastus2".to_string()],
+                ..Default::default()
             },
         ];
 
@@ -0,0 +1,275 @@
+use fabriq_core::{Bundle, OperationId};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::{
+    models::{Config, Deployment, Host, Target, Template, Workload},
+    persistence::Persistable,
+};
+
+use super::{
+    ConfigService, DeploymentService, HostService, TargetService, TemplateService, WorkloadService,
+};
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct ImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+impl ImportSummary {
+    // Counts imported against current, returning whether it needs to be written. Models are
+    // compared as JSON without the fields the api manages, so a model imported from another
+    // installation matches however many times it has been written there.
+    fn record<Model, ModelMessage>(
+        &mut self,
+        imported: &ModelMessage,
+        current: Option<Model>,
+    ) -> anyhow::Result<bool>
+    where
+        Model: Persistable<Model> + Into<ModelMessage>,
+        ModelMessage: Serialize,
+    {
+        let current = match current {
+            Some(current) => current,
+            None => {
+                self.created += 1;
+                return Ok(true);
+            }
+        };
+
+        let comparable = |message: &ModelMessage| -> anyhow::Result<serde_json::Value> {
+            let mut json = serde_json::to_value(message)?;
+
+            if let Some(fields) = json.as_object_mut() {
+                fields.remove("resource_version");
+                fields.remove("metadata");
+            }
+
+            Ok(json)
+        };
+
+        if comparable(imported)? == comparable(&current.into())? {
+            self.unchanged += 1;
+            Ok(false)
+        } else {
+            self.updated += 1;
+            Ok(true)
+        }
+    }
+}
+
+// Imported models are always written (resource version 0) rather than checked against the
+// resource version they had in the exporting installation.
+fn imported<Model: Persistable<Model>>(mut model: Model) -> Model {
+    model.set_resource_version(0);
+    model
+}
+
+// Sorted by id so that exports of the same models are identical.
+fn sorted<Model, ModelMessage>(mut models: Vec<Model>) -> Vec<ModelMessage>
+where
+    Model: Persistable<Model> + Into<ModelMessage>,
+{
+    models.sort_by_key(|model| model.get_id());
+    models.into_iter().map(Model::into).collect()
+}
+
+#[derive(Debug)]
+pub struct AdminService {
+    pub config_service: Arc<ConfigService>,
+    pub deployment_service: Arc<DeploymentService>,
+    pub host_service: Arc<HostService>,
+    pub target_service: Arc<TargetService>,
+    pub template_service: Arc<TemplateService>,
+    pub workload_service: Arc<WorkloadService>,
+}
+
+impl AdminService {
+    #[tracing::instrument(name = "service::admin::export", skip_all)]
+    pub async fn export(&self) -> anyhow::Result<Bundle> {
+        Ok(Bundle {
+            version: Bundle::VERSION,
+            templates: sorted(self.template_service.list().await?),
+            targets: sorted(self.target_service.list().await?),
+            hosts: sorted(self.host_service.list().await?),
+            workloads: sorted(self.workload_service.list().await?),
+            deployments: sorted(self.deployment_service.list().await?),
+            configs: sorted(self.config_service.list().await?),
+        })
+    }
+
+    // Models are imported in dependency order, so that eg. a workload's template exists before the
+    // workload is upserted.
+    #[tracing::instrument(name = "service::admin::import", skip_all)]
+    pub async fn import(&self, bundle: Bundle) -> anyhow::Result<(OperationId, ImportSummary)> {
+        if bundle.version == 0 || bundle.version > Bundle::VERSION {
+            return Err(anyhow::anyhow!(
+                "bundle version {} is not supported, expected at most {}",
+                bundle.version,
+                Bundle::VERSION
+            ));
+        }
+
+        let operation_id = OperationId::create();
+        let mut summary = ImportSummary::default();
+
+        for template in bundle.templates {
+            let current = self.template_service.get_by_id(&template.id).await?;
+            if summary.record(&template, current)? {
+                let template = imported(Template::from(template));
+                self.template_service
+                    .upsert(&template, Some(operation_id.clone()))
+                    .await?;
+            }
+        }
+
+        for target in bundle.targets {
+            let current = self.target_service.get_by_id(&target.id).await?;
+            if summary.record(&target, current)? {
+                let target = imported(Target::from(target));
+                self.target_service
+                    .upsert(&target, &Some(operation_id.clone()))
+                    .await?;
+            }
+        }
+
+        for host in bundle.hosts {
+            let current = self.host_service.get_by_id(&host.id).await?;
+            if summary.record(&host, current)? {
+                let host = imported(Host::from(host));
+                self.host_service
+                    .upsert(&host, &Some(operation_id.clone()))
+                    .await?;
+            }
+        }
+
+        for workload in bundle.workloads {
+            let current = self.workload_service.get_by_id(&workload.id).await?;
+            if summary.record(&workload, current)? {
+                let workload = imported(Workload::from(workload));
+                self.workload_service
+                    .upsert(&workload, Some(operation_id.clone()))
+                    .await?;
+            }
+        }
+
+        for deployment in bundle.deployments {
+            let current = self.deployment_service.get_by_id(&deployment.id).await?;
+            if summary.record(&deployment, current)? {
+                let deployment = imported(Deployment::from(deployment));
+                self.deployment_service
+                    .upsert(&deployment, &Some(operation_id.clone()))
+                    .await?;
+            }
+        }
+
+        for config in bundle.configs {
+            let current = self.config_service.get_by_id(&config.id).await?;
+            if summary.record(&config, current)? {
+                let config = imported(Config::from(config));
+                self.config_service
+                    .upsert(&config, &Some(operation_id.clone()))
+                    .await?;
+            }
+        }
+
+        tracing::info!("bundle imported: {:?}", summary);
+
+        Ok((operation_id, summary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::{
+        get_deployment_fixture, get_host_fixture, get_keyvalue_config_fixture, get_target_fixture,
+        get_template_fixture, get_workload_fixture,
+    };
+
+    use super::*;
+    use crate::services::{make_deletion_service, DeletionService};
+
+    fn make_admin_service(deletion_service: DeletionService) -> AdminService {
+        AdminService {
+            config_service: deletion_service.config_service,
+            deployment_service: deletion_service.deployment_service,
+            host_service: deletion_service.host_service,
+            target_service: deletion_service.target_service,
+            template_service: deletion_service.template_service,
+            workload_service: deletion_service.workload_service,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_import() -> anyhow::Result<()> {
+        let source = make_admin_service(make_deletion_service());
+
+        source
+            .template_service
+            .upsert(&get_template_fixture(None).into(), None)
+            .await?;
+        source
+            .target_service
+            .upsert(&get_target_fixture(None).into(), &None)
+            .await?;
+        source
+            .host_service
+            .upsert(&get_host_fixture(None).into(), &None)
+            .await?;
+        source
+            .workload_service
+            .upsert(&get_workload_fixture(None).into(), None)
+            .await?;
+        source
+            .deployment_service
+            .upsert(&get_deployment_fixture(None).into(), &None)
+            .await?;
+        source
+            .config_service
+            .upsert(&get_keyvalue_config_fixture().into(), &None)
+            .await?;
+
+        let bundle = source.export().await?;
+        assert_eq!(bundle.version, Bundle::VERSION);
+        assert_eq!(bundle.deployments.len(), 1);
+
+        let destination = make_admin_service(make_deletion_service());
+
+        let (_, summary) = destination.import(bundle.clone()).await?;
+        assert_eq!(
+            summary,
+            ImportSummary {
+                created: 6,
+                ..ImportSummary::default()
+            }
+        );
+
+        let (_, summary) = destination.import(bundle.clone()).await?;
+        assert_eq!(
+            summary,
+            ImportSummary {
+                unchanged: 6,
+                ..ImportSummary::default()
+            }
+        );
+
+        let mut changed_bundle = bundle.clone();
+        changed_bundle.hosts[0]
+            .labels
+            .push("region:westus".to_string());
+
+        let (_, summary) = destination.import(changed_bundle).await?;
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.unchanged, 5);
+
+        let unsupported_bundle = Bundle {
+            version: Bundle::VERSION + 1,
+            ..bundle
+        };
+        assert!(destination.import(unsupported_bundle).await.is_err());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
 use std::sync::Arc;
 
-use crate::{models::Assignment, persistence::AssignmentPersistence};
+use crate::{
+    models::Assignment,
+    persistence::{AssignmentPersistence, ListOptions, ListPage, Persistable},
+};
+
+use super::current_actor;
 use fabriq_core::{
     create_event, AssignmentMessage, EventStream, EventType, ModelType, OperationId,
 };
@@ -21,18 +26,28 @@ impl AssignmentService {
     ) -> anyhow::Result<OperationId> {
         let operation_id = OperationId::unwrap_or_create(operation_id);
 
-        let affected_count = self.persistence.upsert(assignment).await?;
+        let previous_assignment = self.persistence.get_by_id(&assignment.id).await?;
+
+        let mut assignment = assignment.clone();
+        assignment.set_changed(&current_actor(), previous_assignment.as_ref());
+
+        let affected_count = self.persistence.upsert(&assignment).await?;
 
         if affected_count > 0 {
-            let create_event = create_event::<AssignmentMessage>(
-                &None,
+            let event_type = match previous_assignment {
+                Some(_) => EventType::Updated,
+                None => EventType::Created,
+            };
+
+            let upsert_event = create_event::<AssignmentMessage>(
+                &previous_assignment.map(AssignmentMessage::from),
                 &Some(assignment.clone().into()),
-                EventType::Created,
+                event_type,
                 ModelType::Assignment,
                 &operation_id,
             );
 
-            self.event_stream.send(&create_event).await?;
+            self.event_stream.send(&upsert_event).await?;
         }
 
         tracing::info!("assignment created: {:?}", assignment);
@@ -139,6 +154,11 @@ impl AssignmentService {
 
         Ok(results)
     }
+
+    #[tracing::instrument(name = "service::assignment::list_page", skip_all)]
+    pub async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Assignment>> {
+        self.persistence.list_page(options).await
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use fabriq_core::{Event, EventStream};
+use std::{future::Future, sync::Arc};
+
+use crate::{
+    models::AuditEntry,
+    persistence::{AuditPersistence, AuditQuery},
+};
+
+// Mutations made outside of a request, eg. by the reconciler, are attributed to the system.
+pub const SYSTEM_ACTOR: &str = "system";
+
+tokio::task_local! {
+    static ACTOR: String;
+}
+
+// Runs future with actor as the actor that audit entries recorded by it are attributed to.
+pub async fn with_actor<F: Future>(actor: String, future: F) -> F::Output {
+    ACTOR.scope(actor, future).await
+}
+
+// The actor of the request being served, if any.
+pub fn request_actor() -> Option<String> {
+    ACTOR.try_with(|actor| actor.clone()).ok()
+}
+
+pub fn current_actor() -> String {
+    request_actor().unwrap_or_else(|| SYSTEM_ACTOR.to_string())
+}
+
+// Events from mutations made on behalf of an actor carry them, so that consumers like gitops can
+// attribute what they do with them.
+fn with_request_actor(event: &Event) -> Event {
+    match request_actor() {
+        Some(actor) => Event {
+            actor,
+            ..event.clone()
+        },
+        None => event.clone(),
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditService {
+    pub persistence: Box<dyn AuditPersistence>,
+}
+
+impl AuditService {
+    #[tracing::instrument(name = "service::audit::record", skip_all)]
+    pub async fn record(&self, event: &Event) -> anyhow::Result<()> {
+        let audit_entry = AuditEntry::from_event(event, &current_actor())?;
+
+        self.persistence.insert(&audit_entry).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "service::audit::query", skip_all)]
+    pub async fn query(&self, query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>> {
+        self.persistence.query(query).await
+    }
+}
+
+// Every mutation made by the services is sent as an event with the model before and after it, so
+// wrapping the services' event stream audits all of them, including cascaded deletes and
+// reconciler assignments. It also stamps each event with the actor that made it.
+//
+// The mutation has already been persisted by the time its event is sent, so the event is still
+// sent on for consumers when recording it fails, but the request then fails so that a missing
+// entry is never silent. A deleted model is also kept by its delete (see DeletedModel), so it
+// outlives a failure to record its entry.
+#[derive(Debug)]
+pub struct AuditingEventStream {
+    pub event_stream: Arc<dyn EventStream>,
+    pub audit_service: Arc<AuditService>,
+}
+
+impl AuditingEventStream {
+    async fn record(&self, event: &Event) -> anyhow::Result<()> {
+        self.audit_service.record(event).await.map_err(|err| {
+            anyhow::anyhow!(
+                "failed to record audit entry for event {}: {}",
+                event.id,
+                err
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl EventStream for AuditingEventStream {
+    async fn delete(&self, event: &Event, consumer_id: &str) -> anyhow::Result<u64> {
+        self.event_stream.delete(event, consumer_id).await
+    }
+
+    async fn receive(&self, consumer_id: &str) -> anyhow::Result<Vec<Event>> {
+        self.event_stream.receive(consumer_id).await
+    }
+
+    async fn send(&self, event: &Event) -> anyhow::Result<()> {
+        let event = with_request_actor(event);
+
+        let recorded = self.record(&event).await;
+        self.event_stream.send(&event).await?;
+
+        recorded
+    }
+
+    async fn send_many(&self, events: &[Event]) -> anyhow::Result<()> {
+        let events: Vec<Event> = events.iter().map(with_request_actor).collect();
+
+        let mut recorded = Ok(());
+        for event in &events {
+            if let Err(err) = self.record(event).await {
+                recorded = Err(err);
+            }
+        }
+
+        self.event_stream.send_many(&events).await?;
+
+        recorded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::get_host_fixture;
+    use fabriq_memory_stream::MemoryEventStream;
+
+    use super::*;
+    use crate::models::Host;
+    use crate::persistence::memory::{AuditMemoryPersistence, HostMemoryPersistence};
+    use crate::services::HostService;
+
+    #[tokio::test]
+    async fn test_audit_mutations() -> anyhow::Result<()> {
+        let audit_service = Arc::new(AuditService {
+            persistence: Box::<AuditMemoryPersistence>::default(),
+        });
+
+        let event_stream = Arc::new(AuditingEventStream {
+            event_stream: Arc::new(MemoryEventStream::new()?),
+            audit_service: Arc::clone(&audit_service),
+        }) as Arc<dyn EventStream>;
+
+        let host_service = HostService {
+            persistence: Box::<HostMemoryPersistence>::default(),
+            event_stream,
+        };
+
+        let host: Host = get_host_fixture(None).into();
+
+        with_actor("octocat".to_string(), host_service.upsert(&host, &None)).await?;
+        let operation_id = host_service.delete(&host.id, None).await?;
+
+        let audit_entries = audit_service
+            .query(&AuditQuery {
+                model_type: Some("host".to_string()),
+                model_id: Some(host.id.clone()),
+                limit: 10,
+                ..AuditQuery::default()
+            })
+            .await?;
+
+        assert_eq!(audit_entries.len(), 2);
+
+        let deleted_entry = audit_entries
+            .iter()
+            .find(|audit_entry| audit_entry.event_type == "deleted")
+            .unwrap();
+        assert_eq!(deleted_entry.actor, SYSTEM_ACTOR);
+        assert_eq!(deleted_entry.operation_id, operation_id.id);
+        assert_eq!(
+            deleted_entry.previous_model.as_ref().unwrap()["id"],
+            host.id
+        );
+        assert!(deleted_entry.current_model.is_none());
+
+        let audit_entries = audit_service
+            .query(&AuditQuery {
+                actor: Some("octocat".to_string()),
+                limit: 10,
+                ..AuditQuery::default()
+            })
+            .await?;
+
+        assert_eq!(audit_entries.len(), 1);
+        assert_eq!(audit_entries[0].event_type, "created");
+        assert!(audit_entries[0].previous_model.is_none());
+
+        let events = host_service.event_stream.receive("gitops").await?;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].actor, "octocat");
+        assert!(events[1].actor.is_empty());
+
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct FailingAuditPersistence {}
+
+    #[async_trait]
+    impl AuditPersistence for FailingAuditPersistence {
+        async fn insert(&self, _audit_entry: &AuditEntry) -> anyhow::Result<u64> {
+            Err(anyhow::anyhow!("audit table is unavailable"))
+        }
+
+        async fn query(&self, _query: &AuditQuery) -> anyhow::Result<Vec<AuditEntry>> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_failure_fails_mutation() -> anyhow::Result<()> {
+        let event_stream = Arc::new(AuditingEventStream {
+            event_stream: Arc::new(MemoryEventStream::new()?),
+            audit_service: Arc::new(AuditService {
+                persistence: Box::new(FailingAuditPersistence {}),
+            }),
+        }) as Arc<dyn EventStream>;
+
+        let host_service = HostService {
+            persistence: Box::<HostMemoryPersistence>::default(),
+            event_stream,
+        };
+
+        let host: Host = get_host_fixture(None).into();
+        assert!(host_service.upsert(&host, &None).await.is_err());
+
+        // the mutation was made before the entry failed to record, so its event is still sent on.
+        assert!(host_service.get_by_id(&host.id).await?.is_some());
+
+        let events = host_service.event_stream.receive("gitops").await?;
+        assert_eq!(events.len(), 1);
+
+        Ok(())
+    }
+}
@@ -5,9 +5,11 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     models::{Config, Deployment, Workload},
-    persistence::ConfigPersistence,
+    persistence::{ConfigPersistence, Persistable},
 };
 
+use super::current_actor;
+
 #[derive(Debug)]
 pub struct ConfigService {
     pub persistence: Box<dyn ConfigPersistence>,
@@ -21,19 +23,29 @@ impl ConfigService {
         config: &Config,
         operation_id: &Option<OperationId>,
     ) -> anyhow::Result<OperationId> {
-        let affected_count = self.persistence.upsert(config).await?;
+        let previous_config = self.persistence.get_by_id(&config.id).await?;
+
+        let mut config = config.clone();
+        config.set_changed(&current_actor(), previous_config.as_ref());
+
+        let affected_count = self.persistence.upsert(&config).await?;
         let operation_id = OperationId::unwrap_or_create(operation_id);
 
         if affected_count > 0 {
-            let create_event = create_event::<ConfigMessage>(
-                &None,
+            let event_type = match previous_config {
+                Some(_) => EventType::Updated,
+                None => EventType::Created,
+            };
+
+            let upsert_event = create_event::<ConfigMessage>(
+                &previous_config.map(ConfigMessage::from),
                 &Some(config.clone().into()),
-                EventType::Created,
+                event_type,
                 ModelType::Config,
                 &operation_id,
             );
 
-            self.event_stream.send(&create_event).await?;
+            self.event_stream.send(&upsert_event).await?;
         }
 
         Ok(operation_id)
@@ -46,11 +58,21 @@ impl ConfigService {
         Ok(deployment_config)
     }
 
+    #[tracing::instrument(name = "service::config::get_by_host_id", skip_all)]
+    pub async fn get_by_host_id(&self, host_id: &str) -> anyhow::Result<Vec<Config>> {
+        self.persistence.get_by_host_id(host_id).await
+    }
+
     #[tracing::instrument(name = "service::config::get_by_id", skip_all)]
     pub async fn get_by_id(&self, config_id: &str) -> anyhow::Result<Option<Config>> {
         self.persistence.get_by_id(config_id).await
     }
 
+    #[tracing::instrument(name = "service::config::list", skip_all)]
+    pub async fn list(&self) -> anyhow::Result<Vec<Config>> {
+        self.persistence.list().await
+    }
+
     #[tracing::instrument(name = "service::config::get_by_template_id", skip_all)]
     pub async fn get_by_template_id(&self, template_id: &str) -> anyhow::Result<Vec<Config>> {
         self.persistence.get_by_template_id(template_id).await
@@ -153,6 +175,14 @@ impl ConfigService {
 
                 (vec![], vec![], template_config)
             }
+
+            // host config is layered over a deployment's by whatever renders the host, so it
+            // is returned on its own.
+            ConfigMessage::HOST_OWNER => {
+                let host_config = self.get_by_host_id(&query.model_id).await?;
+
+                (host_config, vec![], vec![])
+            }
             _ => return Err(anyhow::anyhow!("Model type not supported")),
         };
 
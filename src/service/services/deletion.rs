@@ -0,0 +1,443 @@
+use fabriq_core::{DeleteMode, ModelType, OperationId};
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+
+use super::{
+    AssignmentService, ConfigService, DeploymentService, HostService, TargetService,
+    TemplateService, WorkloadService,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModelReference {
+    pub model_type: ModelType,
+    pub id: String,
+}
+
+impl ModelReference {
+    pub fn new(model_type: ModelType, id: &str) -> Self {
+        ModelReference {
+            model_type,
+            id: id.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ModelReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.model_type.as_str_name().to_lowercase(),
+            self.id
+        )
+    }
+}
+
+// Returned by a refusing delete when other models still depend on the model.
+#[derive(Debug, Eq, PartialEq)]
+pub struct DependentsExist {
+    pub model: ModelReference,
+    pub dependents: Vec<ModelReference>,
+}
+
+impl fmt::Display for DependentsExist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dependents: Vec<String> = self
+            .dependents
+            .iter()
+            .map(|dependent| dependent.to_string())
+            .collect();
+
+        write!(
+            f,
+            "{} is still depended on by {}",
+            self.model,
+            dependents.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for DependentsExist {}
+
+type DependentsFuture<'a> = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>>;
+
+// Deletes models with respect to the models that reference them: deployments reference
+// templates, targets and workloads, and workloads reference templates. Assignments of deployments
+// to hosts and configs, which are owned by deployments, hosts, templates and workloads, never keep
+// a model from being deleted.
+#[derive(Debug)]
+pub struct DeletionService {
+    pub assignment_service: Arc<AssignmentService>,
+    pub config_service: Arc<ConfigService>,
+    pub deployment_service: Arc<DeploymentService>,
+    pub host_service: Arc<HostService>,
+    pub target_service: Arc<TargetService>,
+    pub template_service: Arc<TemplateService>,
+    pub workload_service: Arc<WorkloadService>,
+}
+
+impl DeletionService {
+    #[tracing::instrument(name = "service::deletion::delete", skip_all)]
+    pub async fn delete(
+        &self,
+        model: &ModelReference,
+        mode: DeleteMode,
+        operation_id: Option<OperationId>,
+    ) -> anyhow::Result<OperationId> {
+        let operation_id = OperationId::unwrap_or_create(&operation_id);
+
+        let models = match mode {
+            DeleteMode::Refuse => {
+                let dependents = self.get_dependents(model).await?;
+
+                if !dependents.is_empty() {
+                    return Err(DependentsExist {
+                        model: model.clone(),
+                        dependents,
+                    }
+                    .into());
+                }
+
+                vec![model.clone()]
+            }
+            DeleteMode::Cascade => {
+                let mut models = Vec::new();
+                self.collect_dependents(model.clone(), &mut models).await?;
+
+                models
+            }
+        };
+
+        for model in models {
+            for owned in self.get_owned(&model).await? {
+                tracing::info!("deleting {owned} in operation {}", operation_id.id);
+                self.delete_model(&owned, &operation_id).await?;
+            }
+
+            tracing::info!("deleting {model} in operation {}", operation_id.id);
+            self.delete_model(&model, &operation_id).await?;
+        }
+
+        Ok(operation_id)
+    }
+
+    // The models that reference model independently of it, and so keep it from being deleted
+    // unless they are deleted too. A deployment's assignments, which the reconciler manages, and
+    // the configs a model owns are part of the model instead (see get_owned).
+    #[tracing::instrument(name = "service::deletion::get_dependents", skip_all)]
+    pub async fn get_dependents(
+        &self,
+        model: &ModelReference,
+    ) -> anyhow::Result<Vec<ModelReference>> {
+        let mut dependents = Vec::new();
+
+        match model.model_type {
+            ModelType::Target => {
+                for deployment in self.deployment_service.get_by_target_id(&model.id).await? {
+                    dependents.push(ModelReference::new(ModelType::Deployment, &deployment.id));
+                }
+            }
+            ModelType::Template => {
+                for workload in self.workload_service.get_by_template_id(&model.id).await? {
+                    dependents.push(ModelReference::new(ModelType::Workload, &workload.id));
+                }
+
+                for deployment in self
+                    .deployment_service
+                    .get_by_template_id(&model.id)
+                    .await?
+                {
+                    dependents.push(ModelReference::new(ModelType::Deployment, &deployment.id));
+                }
+            }
+            ModelType::Workload => {
+                for deployment in self
+                    .deployment_service
+                    .get_by_workload_id(&model.id)
+                    .await?
+                {
+                    dependents.push(ModelReference::new(ModelType::Deployment, &deployment.id));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(dependents)
+    }
+
+    // The models deleted along with model whatever the mode: the configs it owns. Deleting a
+    // deployment already deletes its assignments and configs, while the assignments of a deleted
+    // host are moved to other hosts by the reconciler.
+    #[tracing::instrument(name = "service::deletion::get_owned", skip_all)]
+    pub async fn get_owned(&self, model: &ModelReference) -> anyhow::Result<Vec<ModelReference>> {
+        let configs = match model.model_type {
+            ModelType::Host => self.config_service.get_by_host_id(&model.id).await?,
+            ModelType::Template => self.config_service.get_by_template_id(&model.id).await?,
+            ModelType::Workload => self.config_service.get_by_workload_id(&model.id).await?,
+            _ => Vec::new(),
+        };
+
+        Ok(configs
+            .iter()
+            .map(|config| ModelReference::new(ModelType::Config, &config.id))
+            .collect())
+    }
+
+    // Appends model's transitive dependents and then model itself to models, such that every model
+    // comes after all of the models that depend on it and so can be deleted in order.
+    fn collect_dependents<'a>(
+        &'a self,
+        model: ModelReference,
+        models: &'a mut Vec<ModelReference>,
+    ) -> DependentsFuture<'a> {
+        Box::pin(async move {
+            for dependent in self.get_dependents(&model).await? {
+                // a deployment can depend on a template both directly and through its workload.
+                if !models.contains(&dependent) {
+                    self.collect_dependents(dependent, models).await?;
+                }
+            }
+
+            models.push(model);
+
+            Ok(())
+        })
+    }
+
+    async fn delete_model(
+        &self,
+        model: &ModelReference,
+        operation_id: &OperationId,
+    ) -> anyhow::Result<OperationId> {
+        let operation_id = Some(operation_id.clone());
+
+        match model.model_type {
+            ModelType::Assignment => {
+                self.assignment_service
+                    .delete(&model.id, &operation_id)
+                    .await
+            }
+            ModelType::Config => self.config_service.delete(&model.id, &operation_id).await,
+            ModelType::Deployment => {
+                self.deployment_service
+                    .delete(&model.id, &operation_id)
+                    .await
+            }
+            ModelType::Host => self.host_service.delete(&model.id, operation_id).await,
+            ModelType::Target => self.target_service.delete(&model.id, operation_id).await,
+            ModelType::Template => self.template_service.delete(&model.id, operation_id).await,
+            ModelType::Workload => self.workload_service.delete(&model.id, operation_id).await,
+            _ => Err(anyhow::anyhow!("deleting {model} is not supported")),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use fabriq_core::{
+        test::{
+            get_assignment_fixture, get_deployment_fixture, get_host_fixture, get_target_fixture,
+            get_template_fixture, get_workload_fixture,
+        },
+        ConfigMessage, ConfigValueType, EventStream,
+    };
+    use fabriq_memory_stream::MemoryEventStream;
+
+    use super::*;
+    use crate::models::{Assignment, Config, Deployment, Host, Target, Template, Workload};
+    use crate::persistence::memory::{
+        AssignmentMemoryPersistence, ConfigMemoryPersistence, DeploymentMemoryPersistence,
+        HostMemoryPersistence, MemoryPersistence, WorkloadMemoryPersistence,
+    };
+
+    pub fn make_deletion_service() -> DeletionService {
+        let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
+
+        let assignment_service = Arc::new(AssignmentService {
+            persistence: Box::<AssignmentMemoryPersistence>::default(),
+            event_stream: Arc::clone(&event_stream),
+        });
+
+        let config_service = Arc::new(ConfigService {
+            persistence: Box::<ConfigMemoryPersistence>::default(),
+            event_stream: Arc::clone(&event_stream),
+        });
+
+        let target_service = Arc::new(TargetService {
+            persistence: Box::<MemoryPersistence<Target>>::default(),
+            event_stream: Arc::clone(&event_stream),
+        });
+
+        let deployment_service = Arc::new(DeploymentService {
+            persistence: Box::<DeploymentMemoryPersistence>::default(),
+            event_stream: Arc::clone(&event_stream),
+
+            assignment_service: Arc::clone(&assignment_service),
+            config_service: Arc::clone(&config_service),
+            target_service: Arc::clone(&target_service),
+        });
+
+        let host_service = Arc::new(HostService {
+            persistence: Box::<HostMemoryPersistence>::default(),
+            event_stream: Arc::clone(&event_stream),
+        });
+
+        let template_service = Arc::new(TemplateService {
+            persistence: Box::<MemoryPersistence<Template>>::default(),
+            event_stream: Arc::clone(&event_stream),
+        });
+
+        let workload_service = Arc::new(WorkloadService {
+            persistence: Box::<WorkloadMemoryPersistence>::default(),
+            event_stream,
+
+            template_service: Arc::clone(&template_service),
+        });
+
+        DeletionService {
+            assignment_service,
+            config_service,
+            deployment_service,
+            host_service,
+            target_service,
+            template_service,
+            workload_service,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refuse_and_cascade() -> anyhow::Result<()> {
+        let deletion_service = make_deletion_service();
+
+        let template: Template = get_template_fixture(None).into();
+        deletion_service
+            .template_service
+            .upsert(&template, None)
+            .await?;
+
+        let target: Target = get_target_fixture(None).into();
+        deletion_service
+            .target_service
+            .upsert(&target, &None)
+            .await?;
+
+        let host: Host = get_host_fixture(None).into();
+        deletion_service.host_service.upsert(&host, &None).await?;
+
+        let workload: Workload = get_workload_fixture(None).into();
+        deletion_service
+            .workload_service
+            .upsert(&workload, None)
+            .await?;
+
+        let deployment: Deployment = get_deployment_fixture(None).into();
+        deletion_service
+            .deployment_service
+            .upsert(&deployment, &None)
+            .await?;
+
+        let assignment: Assignment = get_assignment_fixture(None).into();
+        deletion_service
+            .assignment_service
+            .upsert(&assignment, &None)
+            .await?;
+
+        let owning_model = ConfigMessage::make_owning_model("workload", &workload.id)?;
+        let config: Config = ConfigMessage {
+            id: ConfigMessage::make_id(&owning_model, "replicas"),
+            owning_model,
+            key: "replicas".to_string(),
+            value: "2".to_string(),
+            value_type: ConfigValueType::StringType as i32,
+            ..Default::default()
+        }
+        .into();
+        deletion_service
+            .config_service
+            .upsert(&config, &None)
+            .await?;
+
+        let owning_model = ConfigMessage::make_owning_model("host", &host.id)?;
+        let host_config: Config = ConfigMessage {
+            id: ConfigMessage::make_id(&owning_model, "region"),
+            owning_model,
+            key: "region".to_string(),
+            value: "eastus2".to_string(),
+            value_type: ConfigValueType::StringType as i32,
+            ..Default::default()
+        }
+        .into();
+        deletion_service
+            .config_service
+            .upsert(&host_config, &None)
+            .await?;
+
+        let workload_reference = ModelReference::new(ModelType::Workload, &workload.id);
+
+        let err = deletion_service
+            .delete(&workload_reference, DeleteMode::Refuse, None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<DependentsExist>(),
+            Some(&DependentsExist {
+                model: workload_reference.clone(),
+                dependents: vec![ModelReference::new(ModelType::Deployment, &deployment.id)],
+            })
+        );
+
+        // assignments and configs are part of the models they belong to.
+        let deployment_reference = ModelReference::new(ModelType::Deployment, &deployment.id);
+        assert!(deletion_service
+            .get_dependents(&deployment_reference)
+            .await?
+            .is_empty());
+
+        let host_reference = ModelReference::new(ModelType::Host, &host.id);
+        assert!(deletion_service
+            .get_dependents(&host_reference)
+            .await?
+            .is_empty());
+        assert_eq!(
+            deletion_service.get_owned(&host_reference).await?,
+            vec![ModelReference::new(ModelType::Config, &host_config.id)]
+        );
+
+        deletion_service
+            .delete(&workload_reference, DeleteMode::Cascade, None)
+            .await?;
+
+        assert!(deletion_service
+            .workload_service
+            .get_by_id(&workload.id)
+            .await?
+            .is_none());
+        assert!(deletion_service
+            .deployment_service
+            .get_by_id(&deployment.id)
+            .await?
+            .is_none());
+        assert!(deletion_service
+            .assignment_service
+            .get_by_id(&assignment.id)
+            .await?
+            .is_none());
+        assert!(deletion_service
+            .config_service
+            .get_by_id(&config.id)
+            .await?
+            .is_none());
+
+        deletion_service
+            .delete(&host_reference, DeleteMode::Refuse, None)
+            .await?;
+
+        assert!(deletion_service
+            .config_service
+            .get_by_id(&host_config.id)
+            .await?
+            .is_none());
+
+        Ok(())
+    }
+}
@@ -3,9 +3,12 @@ use fabriq_core::{
 };
 use std::sync::Arc;
 
-use crate::{models::Deployment, persistence::DeploymentPersistence};
+use crate::{
+    models::Deployment,
+    persistence::{DeploymentPersistence, ListOptions, ListPage, Persistable},
+};
 
-use super::{AssignmentService, ConfigService, TargetService};
+use super::{current_actor, AssignmentService, ConfigService, TargetService};
 
 #[derive(Debug)]
 pub struct DeploymentService {
@@ -49,19 +52,29 @@ impl DeploymentService {
             return Err(anyhow::anyhow!(message));
         }
 
-        let affected_count = self.persistence.upsert(deployment).await?;
+        let previous_deployment = self.persistence.get_by_id(&deployment.id).await?;
+
+        let mut deployment = deployment.clone();
+        deployment.set_changed(&current_actor(), previous_deployment.as_ref());
+
+        let affected_count = self.persistence.upsert(&deployment).await?;
         let operation_id = OperationId::unwrap_or_create(operation_id);
 
         if affected_count > 0 {
-            let create_event = create_event::<DeploymentMessage>(
-                &None,
+            let event_type = match previous_deployment {
+                Some(_) => EventType::Updated,
+                None => EventType::Created,
+            };
+
+            let upsert_event = create_event::<DeploymentMessage>(
+                &previous_deployment.map(DeploymentMessage::from),
                 &Some(deployment.clone().into()),
-                EventType::Created,
+                event_type,
                 ModelType::Deployment,
                 &operation_id,
             );
 
-            self.event_stream.send(&create_event).await?;
+            self.event_stream.send(&upsert_event).await?;
         }
 
         tracing::info!("deployment created: {:?}", deployment);
@@ -137,6 +150,11 @@ impl DeploymentService {
 
         Ok(results)
     }
+
+    #[tracing::instrument(name = "service::deployment::list_page", skip_all)]
+    pub async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Deployment>> {
+        self.persistence.list_page(options).await
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use fabriq_core::{cloud_event::CloudEvent, Event, EventStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{
+    fs::OpenOptions,
+    io::AsyncWriteExt,
+    sync::mpsc::{self, error::TrySendError},
+};
+
+// A destination that events leave fabriq for, as CloudEvents.
+#[derive(Debug)]
+pub enum EventExport {
+    // POSTs each event to url in the binary content mode.
+    Webhook {
+        url: String,
+        client: reqwest::Client,
+    },
+
+    // Appends each event to path in the structured content mode, one JSON document per line.
+    File {
+        path: PathBuf,
+    },
+}
+
+impl EventExport {
+    const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+    // EVENT_EXPORT_WEBHOOK_URL and EVENT_EXPORT_FILE each add an export.
+    pub fn from_env() -> anyhow::Result<Vec<EventExport>> {
+        let mut exports = vec![];
+
+        if let Ok(url) = dotenvy::var("EVENT_EXPORT_WEBHOOK_URL") {
+            exports.push(EventExport::Webhook {
+                url,
+                client: reqwest::Client::builder()
+                    .timeout(EventExport::WEBHOOK_TIMEOUT)
+                    .build()?,
+            });
+        }
+
+        if let Ok(path) = dotenvy::var("EVENT_EXPORT_FILE") {
+            exports.push(EventExport::File { path: path.into() });
+        }
+
+        Ok(exports)
+    }
+
+    fn destination(&self) -> String {
+        match self {
+            EventExport::Webhook { url, .. } => url.clone(),
+            EventExport::File { path } => path.display().to_string(),
+        }
+    }
+
+    async fn export(&self, cloud_event: &CloudEvent) -> anyhow::Result<()> {
+        match self {
+            EventExport::Webhook { url, client } => {
+                let binary_event = cloud_event.to_binary()?;
+
+                let mut request = client.post(url).body(binary_event.body);
+                for (name, value) in binary_event.headers {
+                    request = request.header(name, value);
+                }
+
+                request.send().await?.error_for_status()?;
+            }
+            EventExport::File { path } => {
+                let mut line = cloud_event.to_structured()?;
+                line.push(b'\n');
+
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await?;
+                file.write_all(&line).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Exports every event once it has been sent on, so that only events consumers will also see are
+// exported. The mutation has already been made by then, so a failed export is logged rather than
+// failing it. Events are queued for a background task to export, so that a slow destination
+// doesn't hold up mutations, and are dropped if the queue is full.
+#[derive(Debug)]
+pub struct ExportingEventStream {
+    pub event_stream: Arc<dyn EventStream>,
+
+    // the CloudEvents source of exported events, eg. the api's endpoint.
+    pub source: String,
+    queue: mpsc::Sender<CloudEvent>,
+}
+
+impl ExportingEventStream {
+    const QUEUE_CAPACITY: usize = 1024;
+
+    // Spawns the task that exports queued events, which runs until the stream is dropped.
+    pub fn new(
+        event_stream: Arc<dyn EventStream>,
+        source: String,
+        exports: Vec<EventExport>,
+    ) -> Self {
+        let (queue, mut queued) = mpsc::channel(ExportingEventStream::QUEUE_CAPACITY);
+
+        tokio::spawn(async move {
+            while let Some(cloud_event) = queued.recv().await {
+                for export in &exports {
+                    if let Err(err) = export.export(&cloud_event).await {
+                        tracing::error!(
+                            "failed to export event {} to {}: {}",
+                            cloud_event.id,
+                            export.destination(),
+                            err
+                        );
+                    }
+                }
+            }
+        });
+
+        ExportingEventStream {
+            event_stream,
+            source,
+            queue,
+        }
+    }
+
+    fn export(&self, event: &Event) {
+        let cloud_event = match CloudEvent::from_event(event, &self.source) {
+            Ok(cloud_event) => cloud_event,
+            Err(err) => {
+                tracing::error!(
+                    "failed to encode event {} as a cloud event: {}",
+                    event.id,
+                    err
+                );
+                return;
+            }
+        };
+
+        match self.queue.try_send(cloud_event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                tracing::error!("dropped event {}: the export queue is full", event.id)
+            }
+            Err(TrySendError::Closed(_)) => {
+                tracing::error!("dropped event {}: the export task has stopped", event.id)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl EventStream for ExportingEventStream {
+    async fn delete(&self, event: &Event, consumer_id: &str) -> anyhow::Result<u64> {
+        self.event_stream.delete(event, consumer_id).await
+    }
+
+    async fn receive(&self, consumer_id: &str) -> anyhow::Result<Vec<Event>> {
+        self.event_stream.receive(consumer_id).await
+    }
+
+    async fn send(&self, event: &Event) -> anyhow::Result<()> {
+        self.event_stream.send(event).await?;
+        self.export(event);
+
+        Ok(())
+    }
+
+    async fn send_many(&self, events: &[Event]) -> anyhow::Result<()> {
+        self.event_stream.send_many(events).await?;
+
+        for event in events {
+            self.export(event);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::{cloud_event::CloudEvent, test::get_host_fixture};
+    use fabriq_memory_stream::MemoryEventStream;
+
+    use super::*;
+    use crate::models::Host;
+    use crate::persistence::memory::HostMemoryPersistence;
+    use crate::services::HostService;
+
+    #[tokio::test]
+    async fn test_export_to_file() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("fabriq-events-{}.jsonl", uuid::Uuid::new_v4()));
+
+        let event_stream = Arc::new(ExportingEventStream::new(
+            Arc::new(MemoryEventStream::new()?),
+            "//fabriq.test".to_string(),
+            vec![EventExport::File { path: path.clone() }],
+        )) as Arc<dyn EventStream>;
+
+        let host_service = HostService {
+            persistence: Box::<HostMemoryPersistence>::default(),
+            event_stream,
+        };
+
+        let host: Host = get_host_fixture(None).into();
+        host_service.upsert(&host, &None).await?;
+        host_service.delete(&host.id, None).await?;
+
+        // events are exported in the background, so wait for both to be.
+        let mut exported = String::new();
+        for _ in 0..100 {
+            exported = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+            if exported.lines().count() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::fs::remove_file(&path).await?;
+
+        let cloud_events = exported
+            .lines()
+            .map(|line| CloudEvent::from_structured(line.as_bytes()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        assert_eq!(cloud_events.len(), 2);
+        assert_eq!(cloud_events[0].event_type, "cloud.fabriq.host.created");
+        assert_eq!(cloud_events[1].event_type, "cloud.fabriq.host.deleted");
+        assert_eq!(cloud_events[1].subject, Some(host.id));
+        assert_eq!(cloud_events[1].source, "//fabriq.test");
+
+        Ok(())
+    }
+}
@@ -3,9 +3,11 @@ use std::sync::Arc;
 
 use crate::{
     models::{Host, Target},
-    persistence::HostPersistence,
+    persistence::{HostPersistence, ListOptions, ListPage, Persistable},
 };
 
+use super::current_actor;
+
 #[derive(Debug)]
 pub struct HostService {
     pub persistence: Box<dyn HostPersistence>,
@@ -19,20 +21,30 @@ impl HostService {
         host: &Host,
         operation_id: &Option<OperationId>,
     ) -> anyhow::Result<OperationId> {
-        let affected_count = self.persistence.upsert(host).await?;
+        let previous_host = self.persistence.get_by_id(&host.id).await?;
+
+        let mut host = host.clone();
+        host.set_changed(&current_actor(), previous_host.as_ref());
+
+        let affected_count = self.persistence.upsert(&host).await?;
 
         let operation_id = OperationId::unwrap_or_create(operation_id);
 
         if affected_count > 0 {
-            let create_event = create_event::<HostMessage>(
-                &None,
+            let event_type = match previous_host {
+                Some(_) => EventType::Updated,
+                None => EventType::Created,
+            };
+
+            let upsert_event = create_event::<HostMessage>(
+                &previous_host.map(HostMessage::from),
                 &Some(host.clone().into()),
-                EventType::Created,
+                event_type,
                 ModelType::Host,
                 &operation_id,
             );
 
-            self.event_stream.send(&create_event).await?;
+            self.event_stream.send(&upsert_event).await?;
         }
 
         tracing::info!("host created: {:?}", host);
@@ -90,6 +102,11 @@ impl HostService {
 
         Ok(results)
     }
+
+    #[tracing::instrument(name = "service::host::list_page", skip_all)]
+    pub async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Host>> {
+        self.persistence.list_page(options).await
+    }
 }
 
 #[cfg(test)]
@@ -98,6 +115,7 @@ mod tests {
     use fabriq_memory_stream::MemoryEventStream;
 
     use crate::persistence::memory::HostMemoryPersistence;
+    use crate::services::with_actor;
 
     use super::*;
 
@@ -127,4 +145,31 @@ mod tests {
         let deleted_host_operation_id = host_service.delete(&host.id, None).await.unwrap();
         assert_eq!(deleted_host_operation_id.id.len(), 36);
     }
+
+    #[tokio::test]
+    async fn test_records_changes() -> anyhow::Result<()> {
+        let host_service = HostService {
+            persistence: Box::<HostMemoryPersistence>::default(),
+            event_stream: Arc::new(MemoryEventStream::new()?),
+        };
+
+        let host: Host = get_host_fixture(None).into();
+
+        with_actor("octocat".to_string(), host_service.upsert(&host, &None)).await?;
+        let created_host = host_service.get_by_id(&host.id).await?.unwrap();
+
+        assert_eq!(created_host.created_by, "octocat");
+        assert_eq!(created_host.updated_by, "octocat");
+        assert_eq!(created_host.created_at, created_host.updated_at);
+
+        host_service.upsert(&host, &None).await?;
+        let updated_host = host_service.get_by_id(&host.id).await?.unwrap();
+
+        assert_eq!(updated_host.created_by, "octocat");
+        assert_eq!(updated_host.created_at, created_host.created_at);
+        assert_eq!(updated_host.updated_by, "system");
+        assert!(updated_host.updated_at >= created_host.updated_at);
+
+        Ok(())
+    }
 }
@@ -1,15 +1,28 @@
+mod admin;
 mod assignment;
+mod audit;
 mod config;
+mod deletion;
 mod deployment;
+mod export;
 mod host;
+mod role_binding;
 mod target;
 mod template;
 mod workload;
 
+pub use admin::AdminService;
 pub use assignment::AssignmentService;
+pub use audit::{current_actor, with_actor, AuditService, AuditingEventStream};
 pub use config::ConfigService;
+pub use deletion::{DeletionService, DependentsExist, ModelReference};
 pub use deployment::DeploymentService;
+pub use export::{EventExport, ExportingEventStream};
 pub use host::HostService;
+pub use role_binding::RoleBindingService;
 pub use target::TargetService;
 pub use template::TemplateService;
 pub use workload::WorkloadService;
+
+#[cfg(test)]
+pub use deletion::tests::make_deletion_service;
@@ -0,0 +1,146 @@
+use fabriq_core::{
+    create_event, EventStream, EventType, ModelType, OperationId, RoleBindingMessage,
+};
+use std::sync::Arc;
+
+use crate::{
+    models::RoleBinding,
+    persistence::{ListOptions, ListPage, Persistence},
+};
+
+#[derive(Debug)]
+pub struct RoleBindingService {
+    pub persistence: Box<dyn Persistence<RoleBinding>>,
+    pub event_stream: Arc<dyn EventStream>,
+}
+
+impl RoleBindingService {
+    #[tracing::instrument(name = "service::role_binding::create", skip_all)]
+    pub async fn upsert(
+        &self,
+        role_binding: &RoleBinding,
+        operation_id: Option<OperationId>,
+    ) -> anyhow::Result<OperationId> {
+        let previous_role_binding = self.persistence.get_by_id(&role_binding.id).await?;
+        let affected_count = self.persistence.upsert(role_binding).await?;
+
+        let operation_id = OperationId::unwrap_or_create(&operation_id);
+
+        if affected_count > 0 {
+            let event_type = match previous_role_binding {
+                Some(_) => EventType::Updated,
+                None => EventType::Created,
+            };
+
+            let upsert_event = create_event::<RoleBindingMessage>(
+                &previous_role_binding.map(RoleBindingMessage::from),
+                &Some(role_binding.clone().into()),
+                event_type,
+                ModelType::RoleBinding,
+                &operation_id,
+            );
+
+            self.event_stream.send(&upsert_event).await?;
+        }
+
+        tracing::info!("role binding created: {:?}", role_binding);
+
+        Ok(operation_id)
+    }
+
+    #[tracing::instrument(name = "service::role_binding::get_by_id", skip_all)]
+    pub async fn get_by_id(&self, role_binding_id: &str) -> anyhow::Result<Option<RoleBinding>> {
+        self.persistence.get_by_id(role_binding_id).await
+    }
+
+    #[tracing::instrument(name = "service::role_binding::delete", skip_all)]
+    pub async fn delete(
+        &self,
+        role_binding_id: &str,
+        operation_id: Option<OperationId>,
+    ) -> anyhow::Result<OperationId> {
+        let role_binding = match self.get_by_id(role_binding_id).await? {
+            Some(role_binding) => role_binding,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Role binding id {role_binding_id} not found"
+                ))
+            }
+        };
+
+        let deleted_count = self.persistence.delete(role_binding_id).await?;
+
+        if deleted_count == 0 {
+            return Err(anyhow::anyhow!(
+                "Role binding id {role_binding_id} not found"
+            ));
+        }
+
+        let operation_id = OperationId::unwrap_or_create(&operation_id);
+        let delete_event = create_event::<RoleBindingMessage>(
+            &Some(role_binding.clone().into()),
+            &None,
+            EventType::Deleted,
+            ModelType::RoleBinding,
+            &operation_id,
+        );
+
+        self.event_stream.send(&delete_event).await?;
+
+        tracing::info!("role binding deleted: {:?}", role_binding);
+
+        Ok(operation_id)
+    }
+
+    #[tracing::instrument(name = "service::role_binding::list", skip_all)]
+    pub async fn list(&self) -> anyhow::Result<Vec<RoleBinding>> {
+        let results = self.persistence.list().await?;
+
+        Ok(results)
+    }
+
+    #[tracing::instrument(name = "service::role_binding::list_page", skip_all)]
+    pub async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<RoleBinding>> {
+        self.persistence.list_page(options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fabriq_core::test::get_role_binding_fixture;
+    use fabriq_memory_stream::MemoryEventStream;
+
+    use super::*;
+    use crate::persistence::memory::MemoryPersistence;
+
+    #[tokio::test]
+    async fn test_create_get_delete() {
+        let role_binding_persistence = MemoryPersistence::<RoleBinding>::default();
+        let event_stream = Arc::new(MemoryEventStream::new().unwrap()) as Arc<dyn EventStream>;
+
+        let role_binding_service = RoleBindingService {
+            persistence: Box::new(role_binding_persistence),
+            event_stream,
+        };
+
+        let role_binding: RoleBinding = get_role_binding_fixture(None).into();
+
+        let create_operation_id = role_binding_service
+            .upsert(&role_binding, None)
+            .await
+            .unwrap();
+        assert_eq!(create_operation_id.id.len(), 36);
+
+        let role_bindings = role_binding_service.list().await.unwrap();
+        assert_eq!(role_bindings.len(), 1);
+        assert_eq!(role_bindings[0].id, role_binding.id);
+
+        let delete_operation_id = role_binding_service
+            .delete(&role_binding.id, Some(create_operation_id))
+            .await
+            .unwrap();
+        assert_eq!(delete_operation_id.id.len(), 36);
+
+        assert!(role_binding_service.list().await.unwrap().is_empty());
+    }
+}
@@ -3,9 +3,11 @@ use std::sync::Arc;
 
 use crate::{
     models::{Host, Target},
-    persistence::Persistence,
+    persistence::{ListOptions, ListPage, Persistable, Persistence},
 };
 
+use super::current_actor;
+
 #[derive(Debug)]
 pub struct TargetService {
     pub persistence: Box<dyn Persistence<Target>>,
@@ -19,20 +21,30 @@ impl TargetService {
         target: &Target,
         operation_id: &Option<OperationId>,
     ) -> anyhow::Result<OperationId> {
-        let affected_count = self.persistence.upsert(target).await?;
+        let previous_target = self.persistence.get_by_id(&target.id).await?;
+
+        let mut target = target.clone();
+        target.set_changed(&current_actor(), previous_target.as_ref());
+
+        let affected_count = self.persistence.upsert(&target).await?;
 
         let operation_id = OperationId::unwrap_or_create(operation_id);
 
         if affected_count > 0 {
-            let create_event = create_event::<TargetMessage>(
-                &None,
+            let event_type = match previous_target {
+                Some(_) => EventType::Updated,
+                None => EventType::Created,
+            };
+
+            let upsert_event = create_event::<TargetMessage>(
+                &previous_target.map(TargetMessage::from),
                 &Some(target.clone().into()),
-                EventType::Created,
+                event_type,
                 ModelType::Target,
                 &operation_id,
             );
 
-            self.event_stream.send(&create_event).await?;
+            self.event_stream.send(&upsert_event).await?;
         }
 
         tracing::info!("target created: {:?}", target);
@@ -85,6 +97,11 @@ impl TargetService {
         Ok(results)
     }
 
+    #[tracing::instrument(name = "service::target::list_page", skip_all)]
+    pub async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Target>> {
+        self.persistence.list_page(options).await
+    }
+
     #[tracing::instrument(name = "service::target::get_matching_host", skip_all)]
     pub async fn get_matching_host(&self, host: &Host) -> anyhow::Result<Vec<Target>> {
         // TODO: Naive implementation, use proper query
@@ -161,6 +178,7 @@ mod tests {
         let non_matching_target: Target = Target {
             id: "westus2".to_owned(),
             labels: vec!["location:westus2".to_string()],
+            ..Default::default()
         };
 
         target_service
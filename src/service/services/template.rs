@@ -1,7 +1,12 @@
 use fabriq_core::{create_event, EventStream, EventType, ModelType, OperationId, TemplateMessage};
 use std::sync::Arc;
 
-use crate::{models::Template, persistence::Persistence};
+use crate::{
+    models::Template,
+    persistence::{ListOptions, ListPage, Persistable, Persistence},
+};
+
+use super::current_actor;
 
 #[derive(Debug)]
 pub struct TemplateService {
@@ -16,20 +21,30 @@ impl TemplateService {
         template: &Template,
         operation_id: Option<OperationId>,
     ) -> anyhow::Result<OperationId> {
-        let affected_count = self.persistence.upsert(template).await?;
+        let previous_template = self.persistence.get_by_id(&template.id).await?;
+
+        let mut template = template.clone();
+        template.set_changed(&current_actor(), previous_template.as_ref());
+
+        let affected_count = self.persistence.upsert(&template).await?;
 
         let operation_id = OperationId::unwrap_or_create(&operation_id);
 
         if affected_count > 0 {
-            let create_event = create_event::<TemplateMessage>(
-                &None,
+            let event_type = match previous_template {
+                Some(_) => EventType::Updated,
+                None => EventType::Created,
+            };
+
+            let upsert_event = create_event::<TemplateMessage>(
+                &previous_template.map(TemplateMessage::from),
                 &Some(template.clone().into()),
-                EventType::Created,
+                event_type,
                 ModelType::Template,
                 &operation_id,
             );
 
-            self.event_stream.send(&create_event).await?;
+            self.event_stream.send(&upsert_event).await?;
         }
 
         tracing::info!("template created: {:?}", template);
@@ -81,6 +96,11 @@ impl TemplateService {
 
         Ok(results)
     }
+
+    #[tracing::instrument(name = "service::template::list_page", skip_all)]
+    pub async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Template>> {
+        self.persistence.list_page(options).await
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,12 @@
 use fabriq_core::{create_event, EventStream, EventType, ModelType, OperationId, WorkloadMessage};
 use std::sync::Arc;
 
-use crate::{models::Workload, persistence::WorkloadPersistence};
+use crate::{
+    models::Workload,
+    persistence::{ListOptions, ListPage, Persistable, WorkloadPersistence},
+};
+
+use super::current_actor;
 
 use super::TemplateService;
 
@@ -57,19 +62,29 @@ impl WorkloadService {
             ));
         }
 
-        let affected_count = self.persistence.upsert(workload).await?;
+        let previous_workload = self.persistence.get_by_id(&workload.id).await?;
+
+        let mut workload = workload.clone();
+        workload.set_changed(&current_actor(), previous_workload.as_ref());
+
+        let affected_count = self.persistence.upsert(&workload).await?;
         let operation_id = OperationId::unwrap_or_create(&operation_id);
 
         if affected_count > 0 {
-            let create_event = create_event::<WorkloadMessage>(
-                &None,
+            let event_type = match previous_workload {
+                Some(_) => EventType::Updated,
+                None => EventType::Created,
+            };
+
+            let upsert_event = create_event::<WorkloadMessage>(
+                &previous_workload.map(WorkloadMessage::from),
                 &Some(workload.clone().into()),
-                EventType::Created,
+                event_type,
                 ModelType::Workload,
                 &operation_id,
             );
 
-            self.event_stream.send(&create_event).await?;
+            self.event_stream.send(&upsert_event).await?;
         }
 
         tracing::info!("workload created: {:?}", workload);
@@ -122,6 +137,11 @@ impl WorkloadService {
         Ok(results)
     }
 
+    #[tracing::instrument(name = "service::workload::list_page", skip_all)]
+    pub async fn list_page(&self, options: &ListOptions) -> anyhow::Result<ListPage<Workload>> {
+        self.persistence.list_page(options).await
+    }
+
     #[tracing::instrument(name = "service::workload::get_by_template_id", skip_all)]
     pub async fn get_by_template_id(&self, template_id: &str) -> anyhow::Result<Vec<Workload>> {
         self.persistence.get_by_template_id(template_id).await